@@ -1,6 +1,6 @@
 use std::borrow::Cow;
 
-use crate::{Cookie, SameSite, Expiration};
+use crate::{Cookie, SameSite, Priority, Expiration, ValidationError};
 
 /// Structure that follows the builder pattern for building `Cookie` structs.
 ///
@@ -53,6 +53,25 @@ impl<'c> CookieBuilder<'c> {
         CookieBuilder { cookie: Cookie::new(name, value) }
     }
 
+    /// Sets the value of the cookie being built to the string form of `value`,
+    /// as returned by its [`ToString`] implementation. The value can be read
+    /// back with [`Cookie::value_as()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Cookie;
+    ///
+    /// let c = Cookie::build("visits", "").typed_value(42).finish();
+    /// assert_eq!(c.value(), "42");
+    /// assert_eq!(c.value_as::<u32>(), Ok(42));
+    /// ```
+    #[inline]
+    pub fn typed_value<V: ToString>(mut self, value: V) -> Self {
+        self.cookie.set_value(value.to_string());
+        self
+    }
+
     /// Sets the `expires` field in the cookie being built.
     ///
     /// # Example
@@ -82,6 +101,29 @@ impl<'c> CookieBuilder<'c> {
         self
     }
 
+    /// Sets the `expires` field in the cookie being built to `duration` from
+    /// now. See [`Cookie::set_expires_in()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Cookie;
+    /// use cookie::time::{Duration, OffsetDateTime};
+    ///
+    /// let c = Cookie::build("foo", "bar")
+    ///     .expires_in(Duration::days(30))
+    ///     .finish();
+    ///
+    /// let expires = c.expires_datetime().unwrap();
+    /// assert!(expires > OffsetDateTime::now_utc() + Duration::days(29));
+    /// assert_eq!(c.max_age(), None);
+    /// ```
+    #[inline]
+    pub fn expires_in(mut self, duration: time::Duration) -> Self {
+        self.cookie.set_expires_in(duration);
+        self
+    }
+
     /// Sets the `max_age` field in the cookie being built.
     ///
     /// # Example
@@ -160,6 +202,35 @@ impl<'c> CookieBuilder<'c> {
         self
     }
 
+    /// Marks the cookie being built `Secure` if `condition` is `true` and
+    /// leaves it unchanged otherwise. Unlike `secure(false)`, which explicitly
+    /// disables the `Secure` flag written for `SameSite=None` and `Partitioned`
+    /// cookies, a `false` condition leaves the `secure` field as it was.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, SameSite};
+    ///
+    /// let tls = false;
+    /// let c = Cookie::build("foo", "bar")
+    ///     .secure_if(tls)
+    ///     .finish();
+    ///
+    /// assert_eq!(c.secure(), None);
+    ///
+    /// let c = Cookie::build("foo", "bar")
+    ///     .same_site(SameSite::None)
+    ///     .secure_if(tls)
+    ///     .finish();
+    ///
+    /// assert_eq!(c.to_string(), "foo=bar; SameSite=None; Secure");
+    /// ```
+    #[inline]
+    pub fn secure_if(self, condition: bool) -> Self {
+        self.when(condition, |builder| builder.secure(true))
+    }
+
     /// Sets the `http_only` field in the cookie being built.
     ///
     /// # Example
@@ -179,6 +250,31 @@ impl<'c> CookieBuilder<'c> {
         self
     }
 
+    /// Marks the cookie being built `HttpOnly` if `condition` is `true` and
+    /// leaves it unchanged otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Cookie;
+    ///
+    /// let c = Cookie::build("foo", "bar")
+    ///     .http_only_if(true)
+    ///     .finish();
+    ///
+    /// assert_eq!(c.http_only(), Some(true));
+    ///
+    /// let c = Cookie::build("foo", "bar")
+    ///     .http_only_if(false)
+    ///     .finish();
+    ///
+    /// assert_eq!(c.http_only(), None);
+    /// ```
+    #[inline]
+    pub fn http_only_if(self, condition: bool) -> Self {
+        self.when(condition, |builder| builder.http_only(true))
+    }
+
     /// Sets the `same_site` field in the cookie being built.
     ///
     /// # Example
@@ -198,6 +294,156 @@ impl<'c> CookieBuilder<'c> {
         self
     }
 
+    /// Sets the `partitioned` field in the cookie being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Cookie;
+    ///
+    /// let c = Cookie::build("foo", "bar")
+    ///     .partitioned(true)
+    ///     .finish();
+    ///
+    /// assert_eq!(c.partitioned(), Some(true));
+    /// assert_eq!(c.to_string(), "foo=bar; Secure; Partitioned");
+    /// ```
+    #[inline]
+    pub fn partitioned(mut self, value: bool) -> Self {
+        self.cookie.set_partitioned(value);
+        self
+    }
+
+    /// Sets the `priority` field in the cookie being built.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, Priority};
+    ///
+    /// let c = Cookie::build("foo", "bar")
+    ///     .priority(Priority::High)
+    ///     .finish();
+    ///
+    /// assert_eq!(c.priority(), Some(Priority::High));
+    /// assert_eq!(c.to_string(), "foo=bar; Priority=High");
+    /// ```
+    #[inline]
+    pub fn priority(mut self, value: Priority) -> Self {
+        self.cookie.set_priority(value);
+        self
+    }
+
+    /// Sets the attribute named `name`, one this crate doesn't otherwise model,
+    /// to `value` in the cookie being built. See [`Cookie::set_extension()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Cookie;
+    ///
+    /// let c = Cookie::build("foo", "bar")
+    ///     .extension("X-Scope", Some("app"))
+    ///     .finish();
+    ///
+    /// assert_eq!(c.to_string(), "foo=bar; X-Scope=app");
+    /// ```
+    #[inline]
+    pub fn extension<N, V>(mut self, name: N, value: Option<V>) -> Self
+        where N: Into<Cow<'c, str>>, V: Into<Cow<'c, str>>
+    {
+        self.cookie.set_extension(name, value);
+        self
+    }
+
+    /// Prefixes the name of the cookie being built with `__Host-`, unless it is
+    /// already prefixed, and sets the attributes browsers require of such
+    /// cookies: the cookie is marked `Secure`, its `Path` is set to `/`, and
+    /// its `Domain` is unset. See [`Cookie::validate()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Cookie;
+    ///
+    /// let c = Cookie::build("foo", "bar")
+    ///     .domain("crates.io")
+    ///     .host_prefix()
+    ///     .finish();
+    ///
+    /// assert_eq!(c.name(), "__Host-foo");
+    /// assert_eq!(c.to_string(), "__Host-foo=bar; Secure; Path=/");
+    /// assert!(c.validate().is_ok());
+    /// ```
+    pub fn host_prefix(mut self) -> Self {
+        self.prefix_name("__Host-");
+        self.cookie.set_secure(true);
+        self.cookie.set_path("/");
+        self.cookie.unset_domain();
+        self
+    }
+
+    /// Prefixes the name of the cookie being built with `__Secure-`, unless it
+    /// is already prefixed, and marks the cookie `Secure`, as browsers require
+    /// of such cookies. See [`Cookie::validate()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Cookie;
+    ///
+    /// let c = Cookie::build("foo", "bar")
+    ///     .path("/app")
+    ///     .secure_prefix()
+    ///     .finish();
+    ///
+    /// assert_eq!(c.name(), "__Secure-foo");
+    /// assert_eq!(c.to_string(), "__Secure-foo=bar; Secure; Path=/app");
+    /// assert!(c.validate().is_ok());
+    /// ```
+    pub fn secure_prefix(mut self) -> Self {
+        self.prefix_name("__Secure-");
+        self.cookie.set_secure(true);
+        self
+    }
+
+    /// Prefixes the name of the cookie being built with `prefix` unless it
+    /// already starts with `prefix`.
+    fn prefix_name(&mut self, prefix: &str) {
+        if !self.cookie.name().starts_with(prefix) {
+            let name = format!("{}{}", prefix, self.cookie.name());
+            self.cookie.set_name(name);
+        }
+    }
+
+    /// Applies `f` to the builder if `condition` is `true` and returns the
+    /// builder unchanged otherwise, so that the cookie being built can depend
+    /// on a condition without breaking the chain of builder calls.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, SameSite};
+    ///
+    /// let production = true;
+    /// let c = Cookie::build("session", "abc")
+    ///     .path("/")
+    ///     .when(production, |c| c.domain("example.com").same_site(SameSite::Strict))
+    ///     .when(!production, |c| c.same_site(SameSite::Lax))
+    ///     .finish();
+    ///
+    /// assert_eq!(c.to_string(), "session=abc; SameSite=Strict; Path=/; Domain=example.com");
+    /// ```
+    #[inline]
+    pub fn when<F>(self, condition: bool, f: F) -> Self
+        where F: FnOnce(Self) -> Self
+    {
+        match condition {
+            true => f(self),
+            false => self,
+        }
+    }
+
     /// Makes the cookie being built 'permanent' by extending its expiration and
     /// max age 20 years into the future.
     ///
@@ -243,4 +489,33 @@ impl<'c> CookieBuilder<'c> {
     pub fn finish(self) -> Cookie<'c> {
         self.cookie
     }
+
+    /// Finishes building and returns the built `Cookie` if it passes
+    /// [`Cookie::validate()`], or the [`ValidationError`] otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, SameSite, ValidationError};
+    ///
+    /// let c = Cookie::build("foo", "bar")
+    ///     .same_site(SameSite::None)
+    ///     .finish_checked()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(c.to_string(), "foo=bar; SameSite=None; Secure");
+    ///
+    /// let error = Cookie::build("foo", "bar")
+    ///     .same_site(SameSite::None)
+    ///     .secure(false)
+    ///     .finish_checked()
+    ///     .unwrap_err();
+    ///
+    /// assert_eq!(error, ValidationError::InsecureSameSiteNone);
+    /// ```
+    #[inline]
+    pub fn finish_checked(self) -> Result<Cookie<'c>, ValidationError> {
+        self.cookie.validate()?;
+        Ok(self.cookie)
+    }
 }
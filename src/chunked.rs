@@ -0,0 +1,377 @@
+use std::borrow::{Borrow, BorrowMut};
+
+#[cfg(feature = "private")] use crate::secure::PrivateJar;
+#[cfg(feature = "signed")] use crate::secure::SignedJar;
+#[cfg(any(feature = "private", feature = "signed"))] use crate::secure::Key;
+use crate::{Cookie, CookieJar};
+
+/// The default maximum length, in bytes, of the value of each chunk. This
+/// leaves room for the chunk's name and attributes within the 4096 byte limit
+/// on the size of a cookie imposed by browsers.
+const DEFAULT_CHUNK_LEN: usize = 3800;
+
+/// The separator between the chunk count and the data in the first chunk.
+const COUNT_SEPARATOR: char = '.';
+
+/// How a `ChunkedJar` protects values before splitting them.
+enum Seal {
+    /// Values are stored as-is.
+    Plain,
+    /// Values are signed as if by a `SignedJar`.
+    #[cfg(feature = "signed")]
+    Signed(SignedJar<()>),
+    /// Values are encrypted as if by a `PrivateJar`.
+    #[cfg(feature = "private")]
+    Private(PrivateJar<()>),
+}
+
+/// A child cookie jar that splits large values across several cookies.
+///
+/// Browsers reject cookies larger than about 4KiB. A _chunked_ child jar
+/// stores the value of each cookie added to it across cookies named `name.0`,
+/// `name.1`, and so on, each holding at most
+/// [`chunk_len`](ChunkedJar::with_chunk_len()) bytes of the value, and
+/// reassembles the value when the cookie is retrieved. The first chunk records
+/// the number of chunks. Chunks have the same attributes as the original
+/// cookie.
+///
+/// With the `signed` or `private` features, values can be signed
+/// ([`ChunkedJar::with_signing()`]) or encrypted
+/// ([`ChunkedJar::with_encryption()`]) before they are split, so that the
+/// signature or authentication tag covers the whole reassembled value: values
+/// with missing, reordered, or tampered with chunks are rejected.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{CookieJar, Cookie};
+///
+/// let mut jar = CookieJar::new();
+/// jar.chunked_mut().with_chunk_len(4).add(Cookie::new("name", "0123456789"));
+///
+/// assert_eq!(jar.get("name.0").unwrap().value(), "3.0123");
+/// assert_eq!(jar.get("name.1").unwrap().value(), "4567");
+/// assert_eq!(jar.get("name.2").unwrap().value(), "89");
+/// assert_eq!(jar.chunked().get("name").unwrap().value(), "0123456789");
+/// ```
+pub struct ChunkedJar<J> {
+    parent: J,
+    chunk_len: usize,
+    seal: Seal,
+}
+
+impl<J> ChunkedJar<J> {
+    /// Creates a new child `ChunkedJar` with parent `parent`. This method is
+    /// typically called indirectly via the `chunked{_mut}` methods of
+    /// `CookieJar`.
+    pub(crate) fn new(parent: J) -> ChunkedJar<J> {
+        ChunkedJar { parent, chunk_len: DEFAULT_CHUNK_LEN, seal: Seal::Plain }
+    }
+
+    /// Sets the maximum length, in bytes, of the value of each chunk to
+    /// `chunk_len`. The default is 3800 bytes. The first chunk additionally
+    /// holds the number of chunks. Values are only split at UTF-8 character
+    /// boundaries, so a chunk holds at least one character.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_len` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie};
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.chunked_mut().with_chunk_len(1000).add(Cookie::new("name", "a".repeat(2500)));
+    /// assert!(jar.get("name.2").is_some());
+    /// assert!(jar.get("name.3").is_none());
+    /// ```
+    pub fn with_chunk_len(mut self, chunk_len: usize) -> Self {
+        assert!(chunk_len > 0, "chunk length must be non-zero");
+        self.chunk_len = chunk_len;
+        self
+    }
+
+    /// Signs values with `key`, as if by a [`SignedJar`], before they are split
+    /// and verifies reassembled values before they are returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.chunked_mut().with_signing(&key).add(Cookie::new("name", "value"));
+    ///
+    /// assert_eq!(jar.chunked().with_signing(&key).get("name").unwrap().value(), "value");
+    /// assert!(jar.chunked().with_signing(&Key::generate()).get("name").is_none());
+    /// ```
+    #[cfg(feature = "signed")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+    pub fn with_signing(mut self, key: &Key) -> Self {
+        self.seal = Seal::Signed(SignedJar::new((), key));
+        self
+    }
+
+    /// Encrypts values with `key`, as if by a [`PrivateJar`], before they are
+    /// split and authenticates and decrypts reassembled values before they are
+    /// returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.chunked_mut().with_encryption(&key).add(Cookie::new("name", "value"));
+    ///
+    /// assert!(!jar.get("name.0").unwrap().value().contains("value"));
+    /// assert_eq!(jar.chunked().with_encryption(&key).get("name").unwrap().value(), "value");
+    /// ```
+    #[cfg(feature = "private")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "private")))]
+    pub fn with_encryption(mut self, key: &Key) -> Self {
+        self.seal = Seal::Private(PrivateJar::new((), key));
+        self
+    }
+
+    /// Returns `value`, the value of the cookie named `name`, signed or
+    /// encrypted as configured.
+    #[cfg_attr(not(any(feature = "private", feature = "signed")), allow(unused_variables))]
+    fn seal(&self, name: &str, value: &str) -> String {
+        match self.seal {
+            Seal::Plain => value.to_string(),
+            #[cfg(feature = "signed")]
+            Seal::Signed(ref jar) => jar.sign(name, value, None, None),
+            #[cfg(feature = "private")]
+            Seal::Private(ref jar) => jar.seal(name, value),
+        }
+    }
+
+    /// Returns the original value of `value`, the reassembled value of the
+    /// cookie named `name`, if it verifies or decrypts as configured.
+    #[cfg_attr(not(any(feature = "private", feature = "signed")), allow(unused_variables))]
+    fn open(&self, name: &str, value: String) -> Option<String> {
+        match self.seal {
+            Seal::Plain => Some(value),
+            #[cfg(feature = "signed")]
+            Seal::Signed(ref jar) => jar._verify(name, &value).ok(),
+            #[cfg(feature = "private")]
+            Seal::Private(ref jar) => jar.unseal(name, &value).ok(),
+        }
+    }
+
+    /// Splits `value` into chunks of at most `self.chunk_len` bytes, or one
+    /// character if a character is longer. There is always at least one chunk.
+    fn split<'v>(&self, mut value: &'v str) -> Vec<&'v str> {
+        let mut chunks = vec![];
+        loop {
+            let mut end = value.len().min(self.chunk_len);
+            while !value.is_char_boundary(end) {
+                end -= 1;
+            }
+
+            if end == 0 && !value.is_empty() {
+                end = value.chars().next().map_or(0, char::len_utf8);
+            }
+
+            let (chunk, rest) = value.split_at(end);
+            chunks.push(chunk);
+            if rest.is_empty() {
+                return chunks;
+            }
+
+            value = rest;
+        }
+    }
+}
+
+/// Returns the name of the `i`th chunk of the cookie named `name`.
+fn chunk_name(name: &str, i: usize) -> String {
+    format!("{}.{}", name, i)
+}
+
+/// Returns the number of chunks of the cookie named `name` in `jar`, as
+/// recorded in the first chunk, along with the data in the first chunk.
+fn first_chunk<'j>(jar: &'j CookieJar, name: &str) -> Option<(usize, &'j Cookie<'static>)> {
+    let first = jar.get(&chunk_name(name, 0))?;
+    let (count, _) = first.value().split_once(COUNT_SEPARATOR)?;
+    Some((count.parse().ok().filter(|&n| n > 0)?, first))
+}
+
+impl<J: Borrow<CookieJar>> ChunkedJar<J> {
+    /// Returns the `Cookie` named `name` reassembled from its chunks in the
+    /// parent jar, with the attributes of the first chunk. If any chunk cannot
+    /// be found, or the reassembled value fails to verify or decrypt, `None` is
+    /// returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie};
+    ///
+    /// let mut jar = CookieJar::new();
+    /// assert!(jar.chunked().get("name").is_none());
+    ///
+    /// jar.chunked_mut().add(Cookie::new("name", "x".repeat(5000)));
+    /// assert_eq!(jar.chunked().get("name").unwrap().value().len(), 5000);
+    ///
+    /// jar.remove(Cookie::named("name.1"));
+    /// assert!(jar.chunked().get("name").is_none());
+    /// ```
+    pub fn get(&self, name: &str) -> Option<Cookie<'static>> {
+        let jar = self.parent.borrow();
+        let (count, first) = first_chunk(jar, name)?;
+        let mut value = first.value().split_once(COUNT_SEPARATOR)?.1.to_string();
+        for i in 1..count {
+            value.push_str(jar.get(&chunk_name(name, i))?.value());
+        }
+
+        let mut cookie = first.clone();
+        cookie.set_name(name.to_string());
+        cookie.set_value(self.open(name, value)?);
+        Some(cookie)
+    }
+}
+
+impl<J: BorrowMut<CookieJar>> ChunkedJar<J> {
+    /// Adds `cookie` to the parent jar, split into chunks. The value is signed
+    /// or encrypted first, if configured. Chunks left over from a previous,
+    /// longer value of the cookie are removed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie};
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.chunked_mut().add(Cookie::new("name", "x".repeat(5000)));
+    /// assert_eq!(jar.iter().count(), 2);
+    ///
+    /// jar.chunked_mut().add(Cookie::new("name", "short"));
+    /// assert_eq!(jar.iter().count(), 1);
+    /// assert_eq!(jar.chunked().get("name").unwrap().value(), "short");
+    /// ```
+    pub fn add(&mut self, cookie: Cookie<'static>) {
+        let sealed = self.seal(cookie.name(), cookie.value());
+        let chunks = self.split(&sealed);
+        let stale = first_chunk(self.parent.borrow(), cookie.name()).map_or(0, |(n, _)| n);
+
+        let mut chunk_cookies = vec![];
+        for (i, chunk) in chunks.iter().enumerate() {
+            let mut chunk_cookie = cookie.clone();
+            chunk_cookie.set_name(chunk_name(cookie.name(), i));
+            let value = match i {
+                0 => format!("{}{}{}", chunks.len(), COUNT_SEPARATOR, chunk),
+                _ => chunk.to_string(),
+            };
+
+            chunk_cookie.set_value(value);
+
+            chunk_cookies.push(chunk_cookie);
+        }
+
+        let jar = self.parent.borrow_mut();
+        for i in chunks.len()..stale {
+            let mut stale_cookie = cookie.clone();
+            stale_cookie.set_name(chunk_name(cookie.name(), i));
+            jar.remove(stale_cookie);
+        }
+
+        chunk_cookies.into_iter().for_each(|c| jar.add(c));
+    }
+
+    /// Removes every chunk of `cookie` from the parent jar.
+    ///
+    /// For correct removal, the passed in `cookie` must contain the same `path`
+    /// and `domain` as the cookie that was initially set.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie};
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.chunked_mut().add(Cookie::new("name", "x".repeat(5000)));
+    /// jar.chunked_mut().remove(Cookie::named("name"));
+    /// assert!(jar.chunked().get("name").is_none());
+    /// assert_eq!(jar.iter().count(), 0);
+    /// ```
+    pub fn remove(&mut self, cookie: Cookie<'static>) {
+        let count = first_chunk(self.parent.borrow(), cookie.name()).map_or(1, |(n, _)| n);
+        let jar = self.parent.borrow_mut();
+        for i in 0..count {
+            let mut chunk_cookie = cookie.clone();
+            chunk_cookie.set_name(chunk_name(cookie.name(), i));
+            jar.remove(chunk_cookie);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{CookieJar, Cookie};
+
+    #[test]
+    fn chunking() {
+        let mut jar = CookieJar::new();
+        let mut chunked = jar.chunked_mut().with_chunk_len(3);
+        chunked.add(Cookie::build("empty", "").path("/").finish());
+        chunked.add(Cookie::new("ascii", "abcdefg"));
+        chunked.add(Cookie::new("unicode", "aéb€"));
+        assert_eq!(chunked.get("empty").unwrap().value(), "");
+        assert_eq!(chunked.get("empty").unwrap().path(), Some("/"));
+        assert_eq!(chunked.get("ascii").unwrap().value(), "abcdefg");
+        assert_eq!(chunked.get("unicode").unwrap().value(), "aéb€");
+
+        assert_eq!(jar.get("empty.0").unwrap().value(), "1.");
+        assert_eq!(jar.get("empty.0").unwrap().path(), Some("/"));
+        assert_eq!(jar.get("ascii.2").unwrap().value(), "g");
+        let unicode: Vec<_> = (0..3).map(|i| jar.get(&format!("unicode.{}", i))).collect();
+        let unicode: Vec<_> = unicode.iter().map(|c| c.unwrap().value()).collect();
+        assert_eq!(unicode, ["3.aé", "b", "€"]);
+
+        // Malformed first chunks are rejected.
+        for value in &["abc", "0.abc", "x.abc", "2.abc"] {
+            jar.add(Cookie::new("bad.0", *value));
+            assert!(jar.chunked().get("bad").is_none());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "signed")]
+    fn signed_chunks() {
+        let key = crate::Key::generate();
+        let mut jar = CookieJar::new();
+        let value = "0123456789".repeat(1000);
+        jar.chunked_mut().with_signing(&key).add(Cookie::new("name", value.clone()));
+        assert_eq!(jar.chunked().with_signing(&key).get("name").unwrap().value(), value);
+
+        // Swapping chunks breaks the signature over the reassembled value.
+        let (one, two) = (jar.get("name.1").unwrap().clone(), jar.get("name.2").unwrap().clone());
+        jar.add(Cookie::new("name.1", two.value().to_string()));
+        jar.add(Cookie::new("name.2", one.value().to_string()));
+        assert!(jar.chunked().with_signing(&key).get("name").is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "private")]
+    fn private_chunks() {
+        let key = crate::Key::generate();
+        let mut jar = CookieJar::new();
+        let value = "0123456789".repeat(1000);
+        jar.chunked_mut().with_encryption(&key).add(Cookie::new("name", value.clone()));
+        assert_eq!(jar.chunked().with_encryption(&key).get("name").unwrap().value(), value);
+        assert!(jar.chunked().get("name").unwrap().value() != value);
+
+        // Dropping the last chunk fails to authenticate.
+        let first = jar.get("name.0").unwrap().value().to_string();
+        let (count, data) = first.split_once('.').unwrap();
+        let count: usize = count.parse().unwrap();
+        jar.add(Cookie::new("name.0", format!("{}.{}", count - 1, data)));
+        assert!(jar.chunked().with_encryption(&key).get("name").is_none());
+    }
+}
@@ -20,19 +20,28 @@ use std::fmt;
 /// initally or passing `None` to [`Cookie::set_same_site()`]), then the cookie
 /// will be sent as normal.
 ///
+/// A `SameSite` attribute with any other value is parsed as `SameSite::Other`
+/// and written out again as it was received, so that values defined by future
+/// revisions of the draft survive a round trip through this library. Since
+/// variants may be added, this enum is `#[non_exhaustive]`.
+///
 /// **Note:** This cookie attribute is an [HTTP draft]! Its meaning and
 /// definition are subject to change.
 ///
 /// [`Cookie::set_same_site()`]: crate::Cookie::set_same_site()
 /// [HTTP draft]: https://tools.ietf.org/html/draft-west-cookie-incrementalism-00
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum SameSite {
     /// The "Strict" `SameSite` attribute.
     Strict,
     /// The "Lax" `SameSite` attribute.
     Lax,
     /// The "None" `SameSite` attribute.
-    None
+    None,
+    /// A `SameSite` attribute with a value this library doesn't recognize, as
+    /// written.
+    Other(String),
 }
 
 impl SameSite {
@@ -52,7 +61,7 @@ impl SameSite {
     pub fn is_strict(&self) -> bool {
         match *self {
             SameSite::Strict => true,
-            SameSite::Lax | SameSite::None => false,
+            SameSite::Lax | SameSite::None | SameSite::Other(_) => false,
         }
     }
 
@@ -72,7 +81,7 @@ impl SameSite {
     pub fn is_lax(&self) -> bool {
         match *self {
             SameSite::Lax => true,
-            SameSite::Strict | SameSite::None => false,
+            SameSite::Strict | SameSite::None | SameSite::Other(_) => false,
         }
     }
 
@@ -92,7 +101,7 @@ impl SameSite {
     pub fn is_none(&self) -> bool {
         match *self {
             SameSite::None => true,
-            SameSite::Lax | SameSite::Strict => false
+            SameSite::Lax | SameSite::Strict | SameSite::Other(_) => false,
         }
     }
 }
@@ -103,6 +112,40 @@ impl fmt::Display for SameSite {
             SameSite::Strict => write!(f, "Strict"),
             SameSite::Lax => write!(f, "Lax"),
             SameSite::None => write!(f, "None"),
+            SameSite::Other(ref value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// The `Priority` cookie attribute.
+///
+/// The `Priority` attribute is a Chrome-specific hint used when the browser
+/// evicts cookies to stay within its per-domain limit: cookies with a "Low"
+/// priority are evicted before those with a "Medium" priority, which in turn
+/// are evicted before those with a "High" priority. Cookies without a
+/// `Priority` attribute are treated as "Medium".
+///
+/// **Note:** This cookie attribute is a [nonstandard proposal]! It is ignored
+/// by browsers other than Chrome, and its meaning and definition are subject
+/// to change.
+///
+/// [nonstandard proposal]: https://tools.ietf.org/html/draft-west-cookie-priority-00
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Priority {
+    /// The "Low" `Priority` attribute.
+    Low,
+    /// The "Medium" `Priority` attribute.
+    Medium,
+    /// The "High" `Priority` attribute.
+    High,
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Priority::Low => write!(f, "Low"),
+            Priority::Medium => write!(f, "Medium"),
+            Priority::High => write!(f, "High"),
         }
     }
 }
@@ -0,0 +1,210 @@
+/// A Base64 alphabet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Alphabet {
+    /// The standard alphabet, whose last two characters are `+` and `/`.
+    Standard,
+    /// The URL-safe alphabet, whose last two characters are `-` and `_`.
+    UrlSafe,
+}
+
+impl Alphabet {
+    /// Returns the characters of this alphabet, in order of their values.
+    fn chars(self) -> &'static [u8; 64] {
+        match self {
+            Alphabet::Standard => b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/",
+            Alphabet::UrlSafe => b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_",
+        }
+    }
+}
+
+/// A Base64 implementation. The one used by the crate is [`Engine`]: the
+/// `base64` crate when the `base64` feature is enabled and [`Builtin`]
+/// otherwise. Implementations must agree on every input.
+pub(crate) trait Encoding {
+    /// Appends `bytes`, encoded as Base64 with `alphabet`, to `out`. If `pad`,
+    /// the output is padded with `=` to a multiple of four characters.
+    fn encode_into(out: &mut String, bytes: &[u8], alphabet: Alphabet, pad: bool);
+
+    /// Decodes the unpadded Base64 string `s` with `alphabet`, or returns
+    /// `None` if it isn't such a string. Non-zero trailing bits are ignored.
+    fn decode(s: &str, alphabet: Alphabet) -> Option<Vec<u8>>;
+}
+
+/// The dependency-free implementation.
+#[cfg_attr(feature = "base64", allow(dead_code))]
+pub(crate) struct Builtin;
+
+/// The implementation backed by the `base64` crate.
+#[cfg(feature = "base64")]
+pub(crate) struct Base64Crate;
+
+/// The implementation used by the crate.
+#[cfg(not(feature = "base64"))]
+pub(crate) type Engine = Builtin;
+
+/// The implementation used by the crate.
+#[cfg(feature = "base64")]
+pub(crate) type Engine = Base64Crate;
+
+impl Encoding for Builtin {
+    fn encode_into(out: &mut String, bytes: &[u8], alphabet: Alphabet, pad: bool) {
+        let chars = alphabet.chars();
+        for chunk in bytes.chunks(3) {
+            let n = chunk.iter().enumerate()
+                .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+
+            for i in 0..4 {
+                match i <= chunk.len() {
+                    true => out.push(chars[(n >> (18 - 6 * i) & 0x3F) as usize] as char),
+                    false if pad => out.push('='),
+                    false => break,
+                }
+            }
+        }
+    }
+
+    fn decode(s: &str, alphabet: Alphabet) -> Option<Vec<u8>> {
+        let chars = alphabet.chars();
+        let mut bytes = Vec::with_capacity(s.len() * 3 / 4);
+        for chunk in s.as_bytes().chunks(4) {
+            if chunk.len() == 1 {
+                return None;
+            }
+
+            let mut n = 0u32;
+            for (i, &c) in chunk.iter().enumerate() {
+                let sextet = chars.iter().position(|&b| b == c)? as u32;
+                n |= sextet << (18 - 6 * i);
+            }
+
+            bytes.extend_from_slice(&n.to_be_bytes()[1..chunk.len()]);
+        }
+
+        Some(bytes)
+    }
+}
+
+#[cfg(feature = "base64")]
+impl Encoding for Base64Crate {
+    fn encode_into(out: &mut String, bytes: &[u8], alphabet: Alphabet, pad: bool) {
+        base64::encode_config_buf(bytes, Base64Crate::config(alphabet, pad), out)
+    }
+
+    fn decode(s: &str, alphabet: Alphabet) -> Option<Vec<u8>> {
+        // The crate accepts padding regardless of the configuration.
+        if s.contains('=') {
+            return None;
+        }
+
+        let config = Base64Crate::config(alphabet, false).decode_allow_trailing_bits(true);
+        base64::decode_config(s, config).ok()
+    }
+}
+
+#[cfg(feature = "base64")]
+impl Base64Crate {
+    fn config(alphabet: Alphabet, pad: bool) -> base64::Config {
+        let charset = match alphabet {
+            Alphabet::Standard => base64::CharacterSet::Standard,
+            Alphabet::UrlSafe => base64::CharacterSet::UrlSafe,
+        };
+
+        base64::Config::new(charset, pad)
+    }
+}
+
+/// Appends `bytes`, encoded as Base64 with `alphabet` by [`Engine`], to `out`.
+/// If `pad`, the output is padded with `=` to a multiple of four characters.
+pub(crate) fn encode_into(out: &mut String, bytes: &[u8], alphabet: Alphabet, pad: bool) {
+    Engine::encode_into(out, bytes, alphabet, pad)
+}
+
+/// Decodes the unpadded Base64 string `s` with `alphabet` by [`Engine`], or
+/// returns `None` if it isn't such a string.
+pub(crate) fn decode(s: &str, alphabet: Alphabet) -> Option<Vec<u8>> {
+    Engine::decode(s, alphabet)
+}
+
+/// Returns the byte offset of the first character that keeps `s` from being
+/// unpadded Base64 with `alphabet`, or `None` if `s` is such a string.
+pub(crate) fn invalid_at(s: &str, alphabet: Alphabet) -> Option<usize> {
+    match s.bytes().position(|c| !alphabet.chars().contains(&c)) {
+        Some(i) => Some(i),
+        None if s.len() % 4 == 1 => Some(s.len() - 1),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decode, encode_into, invalid_at, Alphabet, Builtin, Encoding};
+
+    fn roundtrip<E: Encoding>() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for len in 0..bytes.len() {
+            for &alphabet in &[Alphabet::Standard, Alphabet::UrlSafe] {
+                let mut padded = String::new();
+                E::encode_into(&mut padded, &bytes[..len], alphabet, true);
+                assert_eq!(padded.len() % 4, 0);
+
+                let mut unpadded = String::new();
+                E::encode_into(&mut unpadded, &bytes[..len], alphabet, false);
+                assert_eq!(unpadded, padded.trim_end_matches('='));
+                assert_eq!(E::decode(&unpadded, alphabet).as_deref(), Some(&bytes[..len]));
+                assert_eq!(invalid_at(&unpadded, alphabet), None);
+            }
+        }
+    }
+
+    fn invalid<E: Encoding>() {
+        let mut encoded = String::new();
+        E::encode_into(&mut encoded, &[0xfb, 0xff, 0x00], Alphabet::UrlSafe, false);
+        assert_eq!(encoded, "-_8A");
+        assert_eq!(E::decode("+/8A", Alphabet::UrlSafe), None);
+        assert_eq!(E::decode("-_8A", Alphabet::Standard), None);
+        assert_eq!(E::decode("AB", Alphabet::Standard).unwrap(), [0x00]);
+
+        for &(s, at) in &[("A", 0), ("AAAAA", 4), ("AA==", 2), ("AA-_", 2), ("A A", 1)] {
+            assert_eq!(E::decode(s, Alphabet::Standard), None);
+            assert_eq!(invalid_at(s, Alphabet::Standard), Some(at));
+        }
+    }
+
+    #[test]
+    fn builtin() {
+        roundtrip::<Builtin>();
+        invalid::<Builtin>();
+    }
+
+    #[test]
+    #[cfg(feature = "base64")]
+    fn base64_crate() {
+        use super::Base64Crate;
+
+        roundtrip::<Base64Crate>();
+        invalid::<Base64Crate>();
+
+        // The implementations agree on every string of up to four characters.
+        let chars = "AB/+_-=. ";
+        let mut strings = vec![String::new()];
+        for _ in 0..4 {
+            strings = strings.iter()
+                .flat_map(|s| chars.chars().map(move |c| format!("{}{}", s, c)))
+                .collect();
+
+            for s in &strings {
+                for &alphabet in &[Alphabet::Standard, Alphabet::UrlSafe] {
+                    assert_eq!(Base64Crate::decode(s, alphabet), Builtin::decode(s, alphabet));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn engine() {
+        let mut encoded = String::new();
+        encode_into(&mut encoded, b"cookie", Alphabet::UrlSafe, false);
+        assert_eq!(encoded, "Y29va2ll");
+        assert_eq!(decode(&encoded, Alphabet::UrlSafe).unwrap(), b"cookie");
+    }
+}
@@ -0,0 +1,378 @@
+use crate::SameSite;
+
+/// An attribute of a cookie written out in a `Set-Cookie` header.
+///
+/// Used to choose the order attributes are written in with
+/// [`DisplayOptions::order()`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[non_exhaustive]
+pub enum Attribute {
+    /// The `HttpOnly` attribute.
+    HttpOnly,
+    /// The `SameSite` attribute.
+    SameSite,
+    /// The `Secure` attribute.
+    Secure,
+    /// The `Partitioned` attribute.
+    Partitioned,
+    /// The `Path` attribute.
+    Path,
+    /// The `Domain` attribute.
+    Domain,
+    /// The `Max-Age` attribute.
+    MaxAge,
+    /// The `Expires` attribute.
+    Expires,
+    /// The `Priority` attribute.
+    Priority,
+    /// Every attribute this crate doesn't otherwise model, in order. See
+    /// [`Cookie::extensions()`](crate::Cookie::extensions()).
+    Extensions,
+}
+
+/// The order attributes are written in by default.
+const DEFAULT_ORDER: [Attribute; 10] = [
+    Attribute::HttpOnly, Attribute::SameSite, Attribute::Secure, Attribute::Partitioned,
+    Attribute::Path, Attribute::Domain, Attribute::MaxAge, Attribute::Expires,
+    Attribute::Priority, Attribute::Extensions,
+];
+
+/// Options controlling how a cookie is written out as a `Set-Cookie` header
+/// value by [`Cookie::display_with()`](crate::Cookie::display_with()).
+///
+/// The default options, those of the [`Display`](std::fmt::Display)
+/// implementation of `Cookie`, write attributes in the order `HttpOnly`,
+/// `SameSite`, `Secure`, `Partitioned`, `Path`, `Domain`, `Max-Age`,
+/// `Expires`, `Priority`, then any others, write `SameSite` values
+/// capitalized, separate attributes with `"; "`, write obsolete RFC 2109
+/// and RFC 2965 attributes, and write `Max-Age` and `Expires` only as set.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{Attribute, Cookie, DisplayOptions, SameSite};
+///
+/// let c = Cookie::build("name", "value")
+///     .path("/")
+///     .same_site(SameSite::Lax)
+///     .http_only(true)
+///     .finish();
+///
+/// assert_eq!(c.to_string(), "name=value; HttpOnly; SameSite=Lax; Path=/");
+///
+/// let options = DisplayOptions::new()
+///     .order(&[Attribute::Path])
+///     .lowercase_same_site(true)
+///     .compact(true);
+///
+/// assert_eq!(c.display_with(&options).to_string(), "name=value;Path=/;HttpOnly;SameSite=lax");
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct DisplayOptions {
+    order: [Attribute; 10],
+    lowercase_same_site: bool,
+    compact: bool,
+    obsolete: bool,
+    dual_expiration: bool,
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        DisplayOptions {
+            order: DEFAULT_ORDER,
+            lowercase_same_site: false,
+            compact: false,
+            obsolete: true,
+            dual_expiration: false,
+        }
+    }
+}
+
+impl DisplayOptions {
+    /// Returns the default options, those of the
+    /// [`Display`](std::fmt::Display) implementation of `Cookie`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, DisplayOptions};
+    ///
+    /// let c = Cookie::build("name", "value").secure(true).path("/").finish();
+    /// assert_eq!(c.display_with(&DisplayOptions::new()).to_string(), c.to_string());
+    /// ```
+    pub fn new() -> DisplayOptions {
+        DisplayOptions::default()
+    }
+
+    /// Sets the order attributes are written in: the attributes in `order`
+    /// are written first, in order, followed by the rest in the default order.
+    /// Repeated attributes are only written once.
+    ///
+    /// A `Secure` attribute added because the cookie is `SameSite=None` or
+    /// `Partitioned` is written in the position of [`Attribute::Secure`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Attribute, Cookie, DisplayOptions};
+    ///
+    /// let c = Cookie::build("name", "value")
+    ///     .domain("crates.io")
+    ///     .path("/")
+    ///     .secure(true)
+    ///     .extension("X-Flag", None::<&str>)
+    ///     .finish();
+    ///
+    /// let options = DisplayOptions::new()
+    ///     .order(&[Attribute::Domain, Attribute::Path, Attribute::Extensions]);
+    ///
+    /// assert_eq!(c.display_with(&options).to_string(),
+    ///     "name=value; Domain=crates.io; Path=/; X-Flag; Secure");
+    /// ```
+    pub fn order(mut self, order: &[Attribute]) -> Self {
+        let mut len = 0;
+        for attribute in order.iter().chain(DEFAULT_ORDER.iter()) {
+            if !self.order[..len].contains(attribute) {
+                self.order[len] = *attribute;
+                len += 1;
+            }
+        }
+
+        self
+    }
+
+    /// Sets whether `SameSite` values are written in lowercase, as in
+    /// `SameSite=lax`, instead of capitalized. Disabled by default.
+    /// [`SameSite::Other`] values are always written as they are.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, DisplayOptions, SameSite};
+    ///
+    /// let c = Cookie::build("name", "value").same_site(SameSite::Strict).finish();
+    /// let options = DisplayOptions::new().lowercase_same_site(true);
+    /// assert_eq!(c.display_with(&options).to_string(), "name=value; SameSite=strict");
+    /// ```
+    pub fn lowercase_same_site(mut self, lowercase: bool) -> Self {
+        self.lowercase_same_site = lowercase;
+        self
+    }
+
+    /// Sets whether attributes are separated by `";"` instead of `"; "`.
+    /// Disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, DisplayOptions};
+    ///
+    /// let c = Cookie::build("name", "value").secure(true).path("/").finish();
+    /// let options = DisplayOptions::new().compact(true);
+    /// assert_eq!(c.display_with(&options).to_string(), "name=value;Secure;Path=/");
+    /// ```
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+
+    /// Sets whether the obsolete RFC 2109 and RFC 2965 attributes among a
+    /// cookie's extensions, its
+    /// [`obsolete_attributes()`](crate::Cookie::obsolete_attributes()), are
+    /// written out. Enabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, DisplayOptions};
+    ///
+    /// let c = Cookie::parse("name=value; Version=1; Comment=hi; X-Flag").unwrap();
+    /// assert_eq!(c.to_string(), "name=value; Version=1; Comment=hi; X-Flag");
+    ///
+    /// let options = DisplayOptions::new().obsolete_attributes(false);
+    /// assert_eq!(c.display_with(&options).to_string(), "name=value; X-Flag");
+    /// ```
+    pub fn obsolete_attributes(mut self, obsolete: bool) -> Self {
+        self.obsolete = obsolete;
+        self
+    }
+
+    /// Sets whether both `Max-Age` and `Expires` are written when only one of
+    /// them is set, for clients, such as old versions of Internet Explorer,
+    /// that ignore `Max-Age`. Disabled by default.
+    ///
+    /// When enabled, a cookie with only a max-age is also written with an
+    /// `Expires` of the current time plus its max-age, and a cookie with only
+    /// an expiration date is also written with a `Max-Age` of the whole seconds
+    /// from the current time until that date, or `0` if it has passed. Cookies
+    /// with both or neither are written as they are.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, DisplayOptions};
+    /// use cookie::time::{Duration, OffsetDateTime};
+    ///
+    /// let c = Cookie::build("name", "value").max_age(Duration::days(30)).finish();
+    /// assert_eq!(c.to_string(), "name=value; Max-Age=2592000");
+    ///
+    /// let options = DisplayOptions::new().dual_expiration(true);
+    /// let string = c.display_with(&options).to_string();
+    /// assert!(string.starts_with("name=value; Max-Age=2592000; Expires="));
+    ///
+    /// let expires = Cookie::parse(string).unwrap().expires_datetime().unwrap();
+    /// let expected = OffsetDateTime::now_utc() + Duration::days(30);
+    /// assert!((expected - expires).abs() < Duration::minutes(1));
+    ///
+    /// let c = Cookie::build("name", "value")
+    ///     .expires(OffsetDateTime::now_utc() - Duration::days(1))
+    ///     .finish();
+    ///
+    /// let string = c.display_with(&options).to_string();
+    /// assert!(string.starts_with("name=value; Max-Age=0; Expires="));
+    /// ```
+    pub fn dual_expiration(mut self, dual: bool) -> Self {
+        self.dual_expiration = dual;
+        self
+    }
+
+    /// The attributes in the order they're written in.
+    pub(crate) fn attributes(&self) -> &[Attribute] {
+        &self.order
+    }
+
+    /// Whether obsolete RFC 2109 and RFC 2965 attributes are written.
+    pub(crate) fn obsolete(&self) -> bool {
+        self.obsolete
+    }
+
+    /// Whether both `Max-Age` and `Expires` are written when one is set.
+    pub(crate) fn writes_dual_expiration(&self) -> bool {
+        self.dual_expiration
+    }
+
+    /// The separator written before each attribute.
+    pub(crate) fn separator(&self) -> &'static str {
+        match self.compact {
+            true => ";",
+            false => "; ",
+        }
+    }
+
+    /// The `SameSite` value `same_site` as written. Unrecognized values are
+    /// written as they are.
+    pub(crate) fn same_site_str<'a>(&self, same_site: &'a SameSite) -> &'a str {
+        match (same_site, self.lowercase_same_site) {
+            (SameSite::Strict, false) => "Strict",
+            (SameSite::Lax, false) => "Lax",
+            (SameSite::None, false) => "None",
+            (SameSite::Strict, true) => "strict",
+            (SameSite::Lax, true) => "lax",
+            (SameSite::None, true) => "none",
+            (SameSite::Other(value), _) => value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Attribute, Cookie, DisplayOptions, Priority, SameSite};
+    use time::{Duration, macros::datetime};
+
+    #[test]
+    fn display_options() {
+        let cookie = Cookie::build("name", "value")
+            .http_only(true)
+            .same_site(SameSite::None)
+            .partitioned(true)
+            .path("/")
+            .domain("a.com")
+            .max_age(Duration::seconds(10))
+            .expires(datetime!(2015-10-21 7:28 UTC))
+            .priority(Priority::High)
+            .extension("X-Flag", None::<&str>)
+            .finish();
+
+        let default = cookie.display_with(&DisplayOptions::new()).to_string();
+        assert_eq!(default, cookie.to_string());
+        assert_eq!(default, "name=value; HttpOnly; SameSite=None; Secure; Partitioned; \
+            Path=/; Domain=a.com; Max-Age=10; Expires=Wed, 21 Oct 2015 07:28:00 GMT; \
+            Priority=High; X-Flag");
+
+        let mut reversed = [
+            Attribute::Extensions, Attribute::Priority, Attribute::Expires, Attribute::MaxAge,
+            Attribute::Domain, Attribute::Path, Attribute::Partitioned, Attribute::Secure,
+            Attribute::SameSite, Attribute::HttpOnly,
+        ];
+
+        let options = DisplayOptions::new().order(&reversed).lowercase_same_site(true);
+        assert_eq!(cookie.display_with(&options).to_string(), "name=value; X-Flag; \
+            Priority=High; Expires=Wed, 21 Oct 2015 07:28:00 GMT; Max-Age=10; Domain=a.com; \
+            Path=/; Partitioned; Secure; SameSite=none; HttpOnly");
+
+        reversed.reverse();
+        assert_eq!(DisplayOptions::new().order(&reversed), DisplayOptions::new());
+        assert_eq!(DisplayOptions::new().order(&[]), DisplayOptions::new());
+        let repeated = [Attribute::Path, Attribute::Secure, Attribute::Path];
+        assert_eq!(DisplayOptions::new().order(&repeated).attributes()[..3],
+            [Attribute::Path, Attribute::Secure, Attribute::HttpOnly]);
+
+        // Options apply to stripped and encoded displays, too.
+        let options = DisplayOptions::new().compact(true).order(&[Attribute::MaxAge]);
+        let display = cookie.display_with(&options);
+        assert_eq!(display.stripped().to_string(), "name=value");
+        let cookie = Cookie::build("a b", "c").max_age(Duration::ZERO).secure(true).finish();
+        let display = cookie.display_with(&options);
+        assert!(display.to_string().starts_with("a b=c;Max-Age=0;Secure"));
+
+        #[cfg(feature = "percent-encode")]
+        assert_eq!(display.encoded().to_string(), "a%20b=c;Max-Age=0;Secure");
+
+        let cookie = Cookie::parse("a=b; Discard; Path=/; X=y; version=1").unwrap();
+        let options = DisplayOptions::new().obsolete_attributes(false);
+        assert_eq!(cookie.display_with(&options).to_string(), "a=b; Path=/; X=y");
+        assert_eq!(cookie.to_string(), "a=b; Path=/; Discard; X=y; version=1");
+    }
+
+    #[test]
+    fn dual_expiration() {
+        use time::OffsetDateTime;
+
+        let options = DisplayOptions::new().dual_expiration(true);
+        let expires_in = |cookie: &Cookie| {
+            let parsed = Cookie::parse(cookie.display_with(&options).to_string()).unwrap();
+            let max_age = parsed.max_age().unwrap();
+            let expires = parsed.expires_datetime().unwrap() - OffsetDateTime::now_utc();
+            (max_age, expires)
+        };
+
+        let cookie = Cookie::build("a", "b").max_age(Duration::hours(1)).finish();
+        let (max_age, expires) = expires_in(&cookie);
+        assert_eq!(max_age, Duration::hours(1));
+        assert!((expires - max_age).abs() < Duration::seconds(5));
+
+        let in_a_day = OffsetDateTime::now_utc() + Duration::days(1);
+        let cookie = Cookie::build("a", "b").expires(in_a_day).finish();
+        let (max_age, expires) = expires_in(&cookie);
+        assert!((Duration::days(1) - max_age).abs() < Duration::seconds(5));
+        assert!((expires - max_age).abs() < Duration::seconds(5));
+
+        // Values that can't be written as dates are clamped.
+        let cookie = Cookie::build("a", "b").max_age(Duration::MAX).finish();
+        let string = cookie.display_with(&options).to_string();
+        assert!(string.ends_with("Expires=Fri, 31 Dec 9999 23:59:59 GMT"));
+        let cookie = Cookie::build("a", "b").max_age(Duration::MIN).finish();
+        let string = cookie.display_with(&options).to_string();
+        assert!(string.ends_with("Expires=Mon, 01 Jan 1601 00:00:00 GMT"));
+
+        // Cookies with both or neither are written as they are.
+        let cookie = Cookie::build("a", "b")
+            .max_age(Duration::ZERO)
+            .expires(datetime!(2015-10-21 7:28 UTC))
+            .finish();
+
+        assert_eq!(cookie.display_with(&options).to_string(), cookie.to_string());
+        let cookie = Cookie::build("a", "b").expires(None).path("/").finish();
+        assert_eq!(cookie.display_with(&options).to_string(), "a=b; Path=/");
+    }
+}
@@ -1,11 +1,16 @@
 use std::collections::HashSet;
 
-#[cfg(feature = "signed")] use crate::secure::SignedJar;
+#[cfg(feature = "signed")] use crate::secure::{ClaimsJar, CookieSigner, SignedJar};
 #[cfg(feature = "private")] use crate::secure::PrivateJar;
-#[cfg(any(feature = "signed", feature = "private"))] use crate::secure::Key;
+#[cfg(all(feature = "private", feature = "signed"))] use crate::secure::SecureJar;
+#[cfg(any(feature = "signed", feature = "private"))] use crate::secure::{Key, Keyring};
 
 use crate::delta::DeltaCookie;
-use crate::Cookie;
+use crate::{ChunkedJar, Cookie, PrefixedJar, SizeError, Utf8Policy};
+
+/// The length, in bytes, of the randomness in a session token.
+#[cfg(any(feature = "signed", feature = "private"))]
+const SESSION_TOKEN_LEN: usize = 32;
 
 /// A collection of cookies that tracks its modifications.
 ///
@@ -80,8 +85,8 @@ use crate::Cookie;
 /// ```
 #[derive(Default, Debug, Clone)]
 pub struct CookieJar {
-    original_cookies: HashSet<DeltaCookie>,
-    delta_cookies: HashSet<DeltaCookie>,
+    pub(crate) original_cookies: HashSet<DeltaCookie>,
+    pub(crate) delta_cookies: HashSet<DeltaCookie>,
 }
 
 impl CookieJar {
@@ -149,6 +154,89 @@ impl CookieJar {
         self.original_cookies.replace(DeltaCookie::added(cookie));
     }
 
+    /// Parses the request `Cookie` header value `header`, such as
+    /// `name=value; other=thing`, and adds each of its cookies to this jar as
+    /// an "original" cookie via [`add_original`](#method.add_original).
+    /// Malformed pairs in `header` are skipped, while pairs without a name are
+    /// added as nameless cookies; see [`Cookie::parse()`]. Does not perform any
+    /// percent-decoding.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::CookieJar;
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.parse_original("name=value; second=two%20; =; nameless");
+    ///
+    /// assert_eq!(jar.get("name").map(|c| c.value()), Some("value"));
+    /// assert_eq!(jar.get("second").map(|c| c.value()), Some("two%20"));
+    /// assert_eq!(jar.get("").map(|c| c.value()), Some("nameless"));
+    /// assert_eq!(jar.iter().count(), 3);
+    /// assert_eq!(jar.delta().count(), 0);
+    /// ```
+    pub fn parse_original(&mut self, header: &str) {
+        for cookie in Cookie::split_parse(header).flatten() {
+            self.add_original(cookie.into_owned());
+        }
+    }
+
+    /// Parses the raw request `Cookie` header value bytes `header`, such as
+    /// `name=value; other=thing`, and adds each of its cookies to this jar as
+    /// an "original" cookie via [`add_original`](#method.add_original). Bytes
+    /// that aren't valid UTF-8 are handled according to `policy`, pair by
+    /// pair: with [`Utf8Policy::Strict`], only the pairs with invalid UTF-8 are
+    /// skipped. Malformed pairs are skipped. Does not perform any
+    /// percent-decoding.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Utf8Policy};
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.parse_original_bytes(b"name=value; bad=\xff; other=two", Utf8Policy::Strict);
+    /// assert_eq!(jar.get("name").map(|c| c.value()), Some("value"));
+    /// assert_eq!(jar.get("other").map(|c| c.value()), Some("two"));
+    /// assert!(jar.get("bad").is_none());
+    ///
+    /// jar.parse_original_bytes(b"bad=\xff", Utf8Policy::Lossy);
+    /// assert_eq!(jar.get("bad").map(|c| c.value()), Some("\u{fffd}"));
+    /// ```
+    pub fn parse_original_bytes(&mut self, header: &[u8], policy: Utf8Policy) {
+        for pair in header.split(|&byte| byte == b';') {
+            if let Ok(cookie) = Cookie::parse_bytes(pair, policy) {
+                self.add_original(cookie.into_owned());
+            }
+        }
+    }
+
+    /// Parses the request `Cookie` header value `header`, such as
+    /// `name=value; other=thing`, where the name and value fields are
+    /// percent-encoded, and adds each of its cookies to this jar as an
+    /// "original" cookie via [`add_original`](#method.add_original). Malformed
+    /// pairs in `header` are skipped. Percent-decodes the name/value fields.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::CookieJar;
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.parse_original_encoded("name=value; second=two%20; =");
+    ///
+    /// assert_eq!(jar.get("second").map(|c| c.value()), Some("two "));
+    /// assert_eq!(jar.iter().count(), 2);
+    /// assert_eq!(jar.delta().count(), 0);
+    /// ```
+    #[cfg(feature = "percent-encode")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "percent-encode")))]
+    pub fn parse_original_encoded(&mut self, header: &str) {
+        for cookie in Cookie::split_parse_encoded(header).flatten() {
+            self.add_original(cookie.into_owned());
+        }
+    }
+
     /// Adds `cookie` to this jar. If a cookie with the same name already
     /// exists, it is replaced with `cookie`.
     ///
@@ -170,6 +258,43 @@ impl CookieJar {
         self.delta_cookies.replace(DeltaCookie::added(cookie));
     }
 
+    /// Adds a session token cookie named `name` to this jar and returns the
+    /// token. The token is 32 bytes (256 bits) of cryptographically secure
+    /// randomness, generated as if by [`Cookie::random()`]. The cookie is
+    /// `HttpOnly`, `Secure`, `SameSite=Lax`, and has the `Path` `/`. If a
+    /// cookie with the same name already exists, it is replaced.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, SameSite};
+    ///
+    /// let mut jar = CookieJar::new();
+    /// let token = jar.add_session_token("session");
+    ///
+    /// let cookie = jar.get("session").unwrap();
+    /// assert_eq!(cookie.value(), token);
+    /// assert_eq!(cookie.http_only(), Some(true));
+    /// assert_eq!(cookie.secure(), Some(true));
+    /// assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+    /// assert_eq!(cookie.path(), Some("/"));
+    /// ```
+    #[cfg(any(feature = "private", feature = "signed"))]
+    #[cfg_attr(all(nightly, doc), doc(cfg(any(feature = "private", feature = "signed"))))]
+    pub fn add_session_token<N>(&mut self, name: N) -> String
+        where N: Into<std::borrow::Cow<'static, str>>
+    {
+        let mut cookie = Cookie::random(name, SESSION_TOKEN_LEN);
+        cookie.set_http_only(true);
+        cookie.set_secure(true);
+        cookie.set_same_site(crate::SameSite::Lax);
+        cookie.set_path("/");
+
+        let token = cookie.value().to_string();
+        self.add(cookie);
+        token
+    }
+
     /// Removes `cookie` from this jar. If an _original_ cookie with the same
     /// name as `cookie` is present in the jar, a _removal_ cookie will be
     /// present in the `delta` computation. To properly generate the removal
@@ -336,10 +461,58 @@ impl CookieJar {
     /// // Delta contains two new cookies ("new", "yac") and a removal ("name").
     /// assert_eq!(jar.delta().count(), 3);
     /// ```
-    pub fn delta(&self) -> Delta {
+    pub fn delta(&self) -> Delta<'_> {
         Delta { iter: self.delta_cookies.iter() }
     }
 
+    /// Returns an iterator over the removal cookies in this jar's
+    /// [delta](#method.delta): the changes that remove a cookie from the
+    /// client, as opposed to those that add or update one. A delta cookie is a
+    /// removal cookie if [`Cookie::is_removal()`] returns `true`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie};
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.add_original(Cookie::new("name", "value"));
+    /// jar.add_original(Cookie::new("second", "two"));
+    ///
+    /// jar.add(Cookie::new("new", "third"));
+    /// jar.add(Cookie::new("second", "updated"));
+    /// jar.remove(Cookie::named("name"));
+    ///
+    /// let removals: Vec<_> = jar.removals().map(|c| c.name()).collect();
+    /// assert_eq!(removals, ["name"]);
+    /// assert_eq!(jar.delta().count(), 3);
+    /// ```
+    pub fn removals(&self) -> impl Iterator<Item = &Cookie<'static>> + '_ {
+        self.delta().filter(|cookie| cookie.is_removal())
+    }
+
+    /// Checks that every cookie in this jar's [delta](#method.delta), when
+    /// serialized for a `Set-Cookie` header, is no larger than `limit` bytes.
+    /// If any is larger, returns a [`SizeError`] for one of them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, DEFAULT_SIZE_LIMIT};
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.add_original(Cookie::new("original", "v".repeat(DEFAULT_SIZE_LIMIT)));
+    /// jar.add(Cookie::new("name", "value"));
+    /// assert!(jar.check_size(DEFAULT_SIZE_LIMIT).is_ok());
+    ///
+    /// jar.add(Cookie::new("large", "v".repeat(DEFAULT_SIZE_LIMIT)));
+    /// let error = jar.check_size(DEFAULT_SIZE_LIMIT).unwrap_err();
+    /// assert_eq!(error.name(), "large");
+    /// ```
+    pub fn check_size(&self, limit: usize) -> Result<(), SizeError> {
+        self.delta().try_for_each(|cookie| cookie.check_size(limit))
+    }
+
     /// Returns an iterator over all of the cookies present in this jar.
     ///
     /// # Example
@@ -370,13 +543,101 @@ impl CookieJar {
     ///     }
     /// }
     /// ```
-    pub fn iter(&self) -> Iter {
+    pub fn iter(&self) -> Iter<'_> {
         Iter {
             delta_cookies: self.delta_cookies.iter()
                 .chain(self.original_cookies.difference(&self.delta_cookies)),
         }
     }
 
+    /// Returns a read-only `ChunkedJar` with `self` as its parent jar that
+    /// reassembles cookies retrieved from the child jar from their chunks.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar};
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.chunked_mut().add(Cookie::new("large", "x".repeat(10000)));
+    ///
+    /// // The value is split across several cookies...
+    /// assert_eq!(jar.iter().count(), 3);
+    ///
+    /// // ...and reassembled through the child jar.
+    /// assert_eq!(jar.chunked().get("large").unwrap().value(), "x".repeat(10000));
+    /// ```
+    pub fn chunked(&self) -> ChunkedJar<&Self> {
+        ChunkedJar::new(self)
+    }
+
+    /// Returns a read/write `ChunkedJar` with `self` as its parent jar that
+    /// splits cookies added to the child jar into chunks and reassembles
+    /// cookies retrieved from the child jar from their chunks.
+    ///
+    /// Any modifications to the child jar will be reflected on the parent jar,
+    /// and any retrievals from the child jar will be made from the parent jar.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar};
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.chunked_mut().add(Cookie::new("large", "x".repeat(10000)));
+    ///
+    /// // Remove every chunk using the child jar.
+    /// jar.chunked_mut().remove(Cookie::named("large"));
+    /// assert_eq!(jar.iter().count(), 0);
+    /// ```
+    pub fn chunked_mut(&mut self) -> ChunkedJar<&mut Self> {
+        ChunkedJar::new(self)
+    }
+
+    /// Returns a read-only `PrefixedJar` with `self` as its parent jar that
+    /// retrieves the cookies whose names start with `prefix`, with the prefix
+    /// stripped from their names. Any retrievals from the child jar will be
+    /// made from the parent jar.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar};
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.add(Cookie::new("myapp_session", "abc"));
+    ///
+    /// let cookie = jar.prefixed("myapp_").get("session").unwrap();
+    /// assert_eq!(cookie.name(), "session");
+    /// assert_eq!(cookie.value(), "abc");
+    /// ```
+    pub fn prefixed(&self, prefix: &str) -> PrefixedJar<&Self> {
+        PrefixedJar::new(self, prefix)
+    }
+
+    /// Returns a read/write `PrefixedJar` with `self` as its parent jar that
+    /// prepends `prefix` to the names of cookies added to or removed from the
+    /// child jar and strips it from the names of cookies retrieved from it.
+    ///
+    /// Any modifications to the child jar will be reflected on the parent jar,
+    /// and any retrievals from the child jar will be made from the parent jar.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar};
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.prefixed_mut("myapp_").add(Cookie::new("session", "abc"));
+    /// assert_eq!(jar.get("myapp_session").unwrap().value(), "abc");
+    ///
+    /// jar.prefixed_mut("myapp_").remove(Cookie::named("session"));
+    /// assert!(jar.get("myapp_session").is_none());
+    /// ```
+    pub fn prefixed_mut(&mut self, prefix: &str) -> PrefixedJar<&mut Self> {
+        PrefixedJar::new(self, prefix)
+    }
+
     /// Returns a read-only `PrivateJar` with `self` as its parent jar using the
     /// key `key` to verify/decrypt cookies retrieved from the child jar. Any
     /// retrievals from the child jar will be made from the parent jar.
@@ -439,6 +700,169 @@ impl CookieJar {
         PrivateJar::new(self, key)
     }
 
+    /// Returns a read-only `PrivateJar` with `self` as its parent jar that
+    /// verifies/decrypts cookies retrieved from the child jar with each key in
+    /// `keys`, in order, succeeding if any key decrypts. This allows encryption
+    /// keys to be rotated while cookies encrypted with previous keys remain
+    /// readable.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keys` is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar, Key};
+    ///
+    /// let (old_key, new_key) = (Key::generate(), Key::generate());
+    ///
+    /// // A cookie encrypted with a key that has since been rotated out.
+    /// let mut jar = CookieJar::new();
+    /// jar.private_mut(&old_key).add(Cookie::new("private", "text"));
+    /// assert!(jar.private(&new_key).get("private").is_none());
+    ///
+    /// // Decrypts with the old key, which is now a fallback.
+    /// let private = jar.private_with_keys(&[new_key, old_key]);
+    /// assert_eq!(private.get("private").unwrap().value(), "text");
+    /// ```
+    #[cfg(feature = "private")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "private")))]
+    pub fn private_with_keys<'a>(&'a self, keys: &[Key]) -> PrivateJar<&'a Self> {
+        PrivateJar::with_keys(self, keys)
+    }
+
+    /// Returns a read/write `PrivateJar` with `self` as its parent jar that
+    /// encrypts cookies added to the child jar with the primary key, `keys[0]`,
+    /// and verifies/decrypts cookies retrieved from the child jar with each key
+    /// in `keys`, in order, succeeding if any key decrypts.
+    ///
+    /// Any modifications to the child jar will be reflected on the parent jar,
+    /// and any retrievals from the child jar will be made from the parent jar.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keys` is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar, Key};
+    ///
+    /// let (old_key, new_key) = (Key::generate(), Key::generate());
+    /// let keys = [new_key.clone(), old_key.clone()];
+    ///
+    /// // New cookies are encrypted with the primary key.
+    /// let mut jar = CookieJar::new();
+    /// jar.private_with_keys_mut(&keys).add(Cookie::new("private", "text"));
+    /// assert_eq!(jar.private(&new_key).get("private").unwrap().value(), "text");
+    /// assert!(jar.private(&old_key).get("private").is_none());
+    /// ```
+    #[cfg(feature = "private")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "private")))]
+    pub fn private_with_keys_mut<'a>(&'a mut self, keys: &[Key]) -> PrivateJar<&'a mut Self> {
+        PrivateJar::with_keys(self, keys)
+    }
+
+    /// Returns a read-only `PrivateJar` with `self` as its parent jar that
+    /// verifies/decrypts cookies retrieved from the child jar with the key in
+    /// `keyring` identified by the key ID embedded in the cookie's value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar, Key, Keyring};
+    ///
+    /// let mut keyring = Keyring::new("k1", Key::generate());
+    /// let mut jar = CookieJar::new();
+    /// jar.private_with_keyring_mut(&keyring).add(Cookie::new("private", "text"));
+    ///
+    /// keyring.add("k2", Key::generate());
+    /// let private = jar.private_with_keyring(&keyring);
+    /// assert_eq!(private.get("private").unwrap().value(), "text");
+    /// ```
+    #[cfg(feature = "private")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "private")))]
+    pub fn private_with_keyring<'a>(&'a self, keyring: &Keyring) -> PrivateJar<&'a Self> {
+        PrivateJar::with_keyring(self, keyring)
+    }
+
+    /// Returns a read/write `PrivateJar` with `self` as its parent jar that
+    /// encrypts cookies added to the child jar with the primary key in
+    /// `keyring`, embedding its key ID in the cookie's value, and
+    /// verifies/decrypts cookies retrieved from the child jar with the
+    /// identified key.
+    ///
+    /// Any modifications to the child jar will be reflected on the parent jar,
+    /// and any retrievals from the child jar will be made from the parent jar.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar, Key, Keyring};
+    ///
+    /// let keyring = Keyring::new("k1", Key::generate());
+    /// let mut jar = CookieJar::new();
+    /// jar.private_with_keyring_mut(&keyring).add(Cookie::new("private", "text"));
+    /// assert!(jar.get("private").unwrap().value().starts_with("v2:k1$"));
+    /// ```
+    #[cfg(feature = "private")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "private")))]
+    pub fn private_with_keyring_mut<'a>(&'a mut self, keyring: &Keyring) -> PrivateJar<&'a mut Self> {
+        PrivateJar::with_keyring(self, keyring)
+    }
+
+    /// Returns a read-only `PrivateJar` with `self` as its parent jar using the
+    /// key `key` to verify/decrypt cookies retrieved from the child jar that
+    /// were encrypted with the context `context`. See
+    /// [`PrivateJar::with_context()`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.private_with_context_mut(&key, b"agent").add(Cookie::new("private", "text"));
+    ///
+    /// let private = jar.private_with_context(&key, b"agent");
+    /// assert_eq!(private.get("private").unwrap().value(), "text");
+    /// assert!(jar.private_with_context(&key, b"other").get("private").is_none());
+    /// ```
+    #[cfg(feature = "private")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "private")))]
+    pub fn private_with_context<'a>(&'a self, key: &Key, context: &[u8]) -> PrivateJar<&'a Self> {
+        PrivateJar::new(self, key).with_context(context)
+    }
+
+    /// Returns a read/write `PrivateJar` with `self` as its parent jar using
+    /// the key `key` to encrypt and decrypt cookies with the context `context`.
+    /// See [`PrivateJar::with_context()`] for details.
+    ///
+    /// Any modifications to the child jar will be reflected on the parent jar,
+    /// and any retrievals from the child jar will be made from the parent jar.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.private_with_context_mut(&key, b"agent").add(Cookie::new("private", "text"));
+    /// assert!(jar.private(&key).get("private").is_none());
+    /// ```
+    #[cfg(feature = "private")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "private")))]
+    pub fn private_with_context_mut<'a>(
+        &'a mut self,
+        key: &Key,
+        context: &[u8]
+    ) -> PrivateJar<&'a mut Self> {
+        PrivateJar::new(self, key).with_context(context)
+    }
+
     /// Returns a read-only `SignedJar` with `self` as its parent jar using the
     /// key `key` to verify cookies retrieved from the child jar. Any retrievals
     /// from the child jar will be made from the parent jar.
@@ -500,6 +924,434 @@ impl CookieJar {
     pub fn signed_mut<'a>(&'a mut self, key: &Key) -> SignedJar<&'a mut Self> {
         SignedJar::new(self, key)
     }
+
+    /// Returns a read-only `SignedJar` with `self` as its parent jar that
+    /// verifies cookies retrieved from the child jar with each key in `keys`,
+    /// in order, succeeding if any key verifies. This allows signing keys to be
+    /// rotated without invalidating cookies signed with previous keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keys` is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar, Key};
+    ///
+    /// let (old_key, new_key) = (Key::generate(), Key::generate());
+    ///
+    /// // A cookie signed with a key that has since been rotated out.
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_mut(&old_key).add(Cookie::new("signed", "text"));
+    /// assert!(jar.signed(&new_key).get("signed").is_none());
+    ///
+    /// // Verifies with the old key, which is now a fallback.
+    /// let signed = jar.signed_with_keys(&[new_key, old_key]);
+    /// assert_eq!(signed.get("signed").unwrap().value(), "text");
+    /// ```
+    #[cfg(feature = "signed")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+    pub fn signed_with_keys<'a>(&'a self, keys: &[Key]) -> SignedJar<&'a Self> {
+        SignedJar::with_keys(self, keys)
+    }
+
+    /// Returns a read/write `SignedJar` with `self` as its parent jar that
+    /// signs cookies added to the child jar with the primary key, `keys[0]`,
+    /// and verifies cookies retrieved from the child jar with each key in
+    /// `keys`, in order, succeeding if any key verifies.
+    ///
+    /// Any modifications to the child jar will be reflected on the parent jar,
+    /// and any retrievals from the child jar will be made from the parent jar.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keys` is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar, Key};
+    ///
+    /// let (old_key, new_key) = (Key::generate(), Key::generate());
+    /// let keys = [new_key.clone(), old_key.clone()];
+    ///
+    /// // New cookies are signed with the primary key.
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_with_keys_mut(&keys).add(Cookie::new("signed", "text"));
+    /// assert_eq!(jar.signed(&new_key).get("signed").unwrap().value(), "text");
+    /// assert!(jar.signed(&old_key).get("signed").is_none());
+    /// ```
+    #[cfg(feature = "signed")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+    pub fn signed_with_keys_mut<'a>(&'a mut self, keys: &[Key]) -> SignedJar<&'a mut Self> {
+        SignedJar::with_keys(self, keys)
+    }
+
+    /// Returns a read-only `SignedJar` with `self` as its parent jar that
+    /// verifies cookies retrieved from the child jar with the key in `keyring`
+    /// identified by the key ID embedded in the cookie's value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar, Key, Keyring};
+    ///
+    /// let mut keyring = Keyring::new("k1", Key::generate());
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_with_keyring_mut(&keyring).add(Cookie::new("signed", "text"));
+    ///
+    /// keyring.add("k2", Key::generate());
+    /// let signed = jar.signed_with_keyring(&keyring);
+    /// assert_eq!(signed.get("signed").unwrap().value(), "text");
+    /// ```
+    #[cfg(feature = "signed")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+    pub fn signed_with_keyring<'a>(&'a self, keyring: &Keyring) -> SignedJar<&'a Self> {
+        SignedJar::with_keyring(self, keyring)
+    }
+
+    /// Returns a read/write `SignedJar` with `self` as its parent jar that
+    /// signs cookies added to the child jar with the primary key in `keyring`,
+    /// embedding its key ID in the cookie's value, and verifies cookies
+    /// retrieved from the child jar with the identified key.
+    ///
+    /// Any modifications to the child jar will be reflected on the parent jar,
+    /// and any retrievals from the child jar will be made from the parent jar.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar, Key, Keyring};
+    ///
+    /// let keyring = Keyring::new("k1", Key::generate());
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_with_keyring_mut(&keyring).add(Cookie::new("signed", "text"));
+    /// assert!(jar.get("signed").unwrap().value().starts_with("v2:k1$"));
+    /// ```
+    #[cfg(feature = "signed")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+    pub fn signed_with_keyring_mut<'a>(&'a mut self, keyring: &Keyring) -> SignedJar<&'a mut Self> {
+        SignedJar::with_keyring(self, keyring)
+    }
+
+    /// Returns a read-only `SignedJar` with `self` as its parent jar using the
+    /// key `key` to verify cookies retrieved from the child jar that were
+    /// signed with the context `context`. See [`SignedJar::with_context()`]
+    /// for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_with_context_mut(&key, b"agent").add(Cookie::new("signed", "text"));
+    ///
+    /// let signed = jar.signed_with_context(&key, b"agent");
+    /// assert_eq!(signed.get("signed").unwrap().value(), "text");
+    /// assert!(jar.signed_with_context(&key, b"other").get("signed").is_none());
+    /// ```
+    #[cfg(feature = "signed")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+    pub fn signed_with_context<'a>(&'a self, key: &Key, context: &[u8]) -> SignedJar<&'a Self> {
+        SignedJar::new(self, key).with_context(context)
+    }
+
+    /// Returns a read/write `SignedJar` with `self` as its parent jar using the
+    /// key `key` to sign and verify cookies with the context `context`. See
+    /// [`SignedJar::with_context()`] for details.
+    ///
+    /// Any modifications to the child jar will be reflected on the parent jar,
+    /// and any retrievals from the child jar will be made from the parent jar.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_with_context_mut(&key, b"agent").add(Cookie::new("signed", "text"));
+    /// assert!(jar.signed(&key).get("signed").is_none());
+    /// ```
+    #[cfg(feature = "signed")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+    pub fn signed_with_context_mut<'a>(
+        &'a mut self,
+        key: &Key,
+        context: &[u8]
+    ) -> SignedJar<&'a mut Self> {
+        SignedJar::new(self, key).with_context(context)
+    }
+
+    /// Returns a read-only `SignedJar` with `self` as its parent jar that signs
+    /// and verifies cookies with the custom [`CookieSigner`] `signer`. Any
+    /// retrievals from the child jar will be made from the parent jar.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar, HmacSigner, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_mut(&key).add(Cookie::new("signed", "text"));
+    ///
+    /// let signed = jar.signed_with_signer(HmacSigner::new(&key));
+    /// assert_eq!(signed.get("signed").unwrap().value(), "text");
+    /// ```
+    #[cfg(feature = "signed")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+    pub fn signed_with_signer<S: CookieSigner>(&self, signer: S) -> SignedJar<&Self, S> {
+        SignedJar::with_signer(self, signer)
+    }
+
+    /// Returns a read/write `SignedJar` with `self` as its parent jar that
+    /// signs and verifies cookies with the custom [`CookieSigner`] `signer`.
+    ///
+    /// Any modifications to the child jar will be reflected on the parent jar,
+    /// and any retrievals from the child jar will be made from the parent jar.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar, HmacSigner, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_with_signer_mut(HmacSigner::new(&key)).add(Cookie::new("signed", "text"));
+    /// assert_eq!(jar.signed(&key).get("signed").unwrap().value(), "text");
+    /// ```
+    #[cfg(feature = "signed")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+    pub fn signed_with_signer_mut<S: CookieSigner>(
+        &mut self,
+        signer: S
+    ) -> SignedJar<&mut Self, S> {
+        SignedJar::with_signer(self, signer)
+    }
+
+    /// Returns a read-only `ClaimsJar` with `self` as its parent jar using the
+    /// key `key` to verify claims retrieved from the child jar. Any retrievals
+    /// from the child jar will be made from the parent jar.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Claims, Cookie, CookieJar, Key};
+    /// use cookie::time::Duration;
+    ///
+    /// // Generate a secure key.
+    /// let key = Key::generate();
+    ///
+    /// // Add a cookie holding signed claims.
+    /// let mut jar = CookieJar::new();
+    /// let claims = Claims::new().with("user", "1234").with_expiry(Duration::hours(1));
+    /// jar.claims_mut(&key).add(Cookie::named("session"), &claims);
+    ///
+    /// // The claims can be verified through the child jar.
+    /// let session = jar.claims(&key).get("session").unwrap();
+    /// assert_eq!(session.get("user"), Some("1234"));
+    /// ```
+    #[cfg(feature = "signed")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+    pub fn claims<'a>(&'a self, key: &Key) -> ClaimsJar<&'a Self> {
+        ClaimsJar::new(self, key)
+    }
+
+    /// Returns a read/write `ClaimsJar` with `self` as its parent jar using the
+    /// key `key` to sign and verify claims added to and retrieved from the
+    /// child jar.
+    ///
+    /// Any modifications to the child jar will be reflected on the parent jar,
+    /// and any retrievals from the child jar will be made from the parent jar.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Claims, Cookie, CookieJar, Key};
+    ///
+    /// // Generate a secure key.
+    /// let key = Key::generate();
+    ///
+    /// // Add a cookie holding signed claims.
+    /// let mut jar = CookieJar::new();
+    /// jar.claims_mut(&key).add(Cookie::named("session"), &Claims::new().with("user", "1"));
+    ///
+    /// // Remove a cookie using the child jar.
+    /// jar.claims_mut(&key).remove(Cookie::named("session"));
+    /// ```
+    #[cfg(feature = "signed")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+    pub fn claims_mut<'a>(&'a mut self, key: &Key) -> ClaimsJar<&'a mut Self> {
+        ClaimsJar::new(self, key)
+    }
+
+    /// Returns a read-only `SecureJar` with `self` as its parent jar using the
+    /// key `key` to verify and decrypt cookies retrieved from the child jar.
+    /// Any retrievals from the child jar will be made from the parent jar.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar, Key};
+    ///
+    /// // Generate a secure key.
+    /// let key = Key::generate();
+    ///
+    /// // Add an encrypted, then signed, cookie.
+    /// let mut jar = CookieJar::new();
+    /// jar.secure_mut(&key).add(Cookie::new("secure", "text"));
+    ///
+    /// // The cookie's contents are encrypted.
+    /// assert!(!jar.get("secure").unwrap().value().contains("text"));
+    ///
+    /// // They can be verified and decrypted through the child jar.
+    /// assert_eq!(jar.secure(&key).get("secure").unwrap().value(), "text");
+    ///
+    /// // A tampered with cookie does not validate but still exists.
+    /// let mut cookie = jar.get("secure").unwrap().clone();
+    /// jar.add(Cookie::new("secure", cookie.value().to_string() + "!"));
+    /// assert!(jar.secure(&key).get("secure").is_none());
+    /// assert!(jar.get("secure").is_some());
+    /// ```
+    #[cfg(all(feature = "private", feature = "signed"))]
+    #[cfg_attr(all(nightly, doc), doc(cfg(all(feature = "private", feature = "signed"))))]
+    pub fn secure<'a>(&'a self, key: &Key) -> SecureJar<&'a Self> {
+        SecureJar::new(self, key)
+    }
+
+    /// Returns a read/write `SecureJar` with `self` as its parent jar using the
+    /// key `key` to encrypt and sign, and to verify and decrypt, cookies
+    /// added to and retrieved from the child jar.
+    ///
+    /// Any modifications to the child jar will be reflected on the parent jar,
+    /// and any retrievals from the child jar will be made from the parent jar.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar, Key};
+    ///
+    /// // Generate a secure key.
+    /// let key = Key::generate();
+    ///
+    /// // Add an encrypted, then signed, cookie.
+    /// let mut jar = CookieJar::new();
+    /// jar.secure_mut(&key).add(Cookie::new("secure", "text"));
+    ///
+    /// // Remove a cookie using the child jar.
+    /// jar.secure_mut(&key).remove(Cookie::named("secure"));
+    /// ```
+    #[cfg(all(feature = "private", feature = "signed"))]
+    #[cfg_attr(all(nightly, doc), doc(cfg(all(feature = "private", feature = "signed"))))]
+    pub fn secure_mut<'a>(&'a mut self, key: &Key) -> SecureJar<&'a mut Self> {
+        SecureJar::new(self, key)
+    }
+
+    /// Returns a read-only `SecureJar` with `self` as its parent jar that
+    /// verifies and decrypts cookies retrieved from the child jar with each key
+    /// in `keys`, in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keys` is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar, Key};
+    ///
+    /// let (old_key, new_key) = (Key::generate(), Key::generate());
+    /// let mut jar = CookieJar::new();
+    /// jar.secure_mut(&old_key).add(Cookie::new("secure", "text"));
+    ///
+    /// let secure = jar.secure_with_keys(&[new_key, old_key]);
+    /// assert_eq!(secure.get("secure").unwrap().value(), "text");
+    /// ```
+    #[cfg(all(feature = "private", feature = "signed"))]
+    #[cfg_attr(all(nightly, doc), doc(cfg(all(feature = "private", feature = "signed"))))]
+    pub fn secure_with_keys<'a>(&'a self, keys: &[Key]) -> SecureJar<&'a Self> {
+        SecureJar::with_keys(self, keys)
+    }
+
+    /// Returns a read/write `SecureJar` with `self` as its parent jar that
+    /// encrypts and signs cookies added to the child jar with the primary key,
+    /// `keys[0]`, and verifies and decrypts cookies retrieved from the child
+    /// jar with each key in `keys`, in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keys` is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar, Key};
+    ///
+    /// let (old_key, new_key) = (Key::generate(), Key::generate());
+    /// let keys = [new_key.clone(), old_key.clone()];
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.secure_with_keys_mut(&keys).add(Cookie::new("secure", "text"));
+    /// assert_eq!(jar.secure(&new_key).get("secure").unwrap().value(), "text");
+    /// assert!(jar.secure(&old_key).get("secure").is_none());
+    /// ```
+    #[cfg(all(feature = "private", feature = "signed"))]
+    #[cfg_attr(all(nightly, doc), doc(cfg(all(feature = "private", feature = "signed"))))]
+    pub fn secure_with_keys_mut<'a>(&'a mut self, keys: &[Key]) -> SecureJar<&'a mut Self> {
+        SecureJar::with_keys(self, keys)
+    }
+
+    /// Returns a read-only `SecureJar` with `self` as its parent jar that
+    /// verifies and decrypts cookies retrieved from the child jar with the key
+    /// in `keyring` identified by the key ID embedded in the cookie's value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar, Key, Keyring};
+    ///
+    /// let mut keyring = Keyring::new("k1", Key::generate());
+    /// let mut jar = CookieJar::new();
+    /// jar.secure_with_keyring_mut(&keyring).add(Cookie::new("secure", "text"));
+    ///
+    /// keyring.add("k2", Key::generate());
+    /// let secure = jar.secure_with_keyring(&keyring);
+    /// assert_eq!(secure.get("secure").unwrap().value(), "text");
+    /// ```
+    #[cfg(all(feature = "private", feature = "signed"))]
+    #[cfg_attr(all(nightly, doc), doc(cfg(all(feature = "private", feature = "signed"))))]
+    pub fn secure_with_keyring<'a>(&'a self, keyring: &Keyring) -> SecureJar<&'a Self> {
+        SecureJar::with_keyring(self, keyring)
+    }
+
+    /// Returns a read/write `SecureJar` with `self` as its parent jar that
+    /// encrypts and signs cookies added to the child jar with the primary key
+    /// in `keyring`, and verifies and decrypts cookies retrieved from the child
+    /// jar with the key identified by the key ID embedded in the cookie's
+    /// value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, CookieJar, Key, Keyring};
+    ///
+    /// let keyring = Keyring::new("k1", Key::generate());
+    /// let mut jar = CookieJar::new();
+    /// jar.secure_with_keyring_mut(&keyring).add(Cookie::new("secure", "text"));
+    /// assert!(jar.get("secure").unwrap().value().starts_with("v2:k1$"));
+    /// ```
+    #[cfg(all(feature = "private", feature = "signed"))]
+    #[cfg_attr(all(nightly, doc), doc(cfg(all(feature = "private", feature = "signed"))))]
+    pub fn secure_with_keyring_mut<'a>(
+        &'a mut self,
+        keyring: &Keyring
+    ) -> SecureJar<&'a mut Self> {
+        SecureJar::with_keyring(self, keyring)
+    }
 }
 
 use std::collections::hash_set::Iter as HashSetIter;
@@ -530,13 +1382,9 @@ impl<'a> Iterator for Iter<'a> {
     type Item = &'a Cookie<'static>;
 
     fn next(&mut self) -> Option<&'a Cookie<'static>> {
-        for cookie in self.delta_cookies.by_ref() {
-            if !cookie.removed {
-                return Some(&*cookie);
-            }
-        }
-
-        None
+        self.delta_cookies.by_ref()
+            .find(|c| !c.removed)
+            .map(|c| &c.cookie)
     }
 }
 
@@ -647,6 +1495,51 @@ mod test {
         assert_eq!(jar.get("original_a").unwrap().value(), "av2");
     }
 
+    #[test]
+    fn parse_original() {
+        let mut jar = CookieJar::new();
+        jar.add(Cookie::new("b", "added"));
+        jar.parse_original(" a=1;; b=2 ; =3; c=3; a=4; d");
+        assert_eq!(jar.get("a").unwrap().value(), "4");
+        assert_eq!(jar.get("b").unwrap().value(), "added");
+        assert_eq!(jar.get("c").unwrap().value(), "3");
+        assert_eq!(jar.get("").unwrap().value(), "d");
+        assert_eq!(jar.iter().count(), 4);
+        assert_eq!(jar.delta().count(), 1);
+
+        jar.parse_original(" ; =");
+        assert_eq!(jar.iter().count(), 4);
+
+        #[cfg(feature = "percent-encode")] {
+            jar.parse_original_encoded("e%3B=%20; f=%FF");
+            assert_eq!(jar.get("e;").unwrap().value(), " ");
+            assert!(jar.get("f").is_none());
+        }
+    }
+
+    #[test]
+    fn removals() {
+        use time::Duration;
+
+        let mut jar = CookieJar::new();
+        jar.add_original(Cookie::new("a", "1"));
+        jar.add_original(Cookie::new("b", "2"));
+        jar.add(Cookie::new("c", "3"));
+        jar.add(Cookie::build("d", "").max_age(Duration::seconds(-1)).finish());
+        jar.add(Cookie::build("e", "5").max_age(Duration::seconds(1)).finish());
+        jar.remove(Cookie::named("a"));
+        jar.remove(Cookie::named("c"));
+
+        let mut removals: Vec<_> = jar.removals().map(|c| c.name()).collect();
+        removals.sort_unstable();
+        assert_eq!(removals, ["a", "d"]);
+
+        jar.add(Cookie::new("a", "again"));
+        assert_eq!(jar.removals().map(|c| c.name()).collect::<Vec<_>>(), ["d"]);
+        jar.reset_delta();
+        assert_eq!(jar.removals().count(), 0);
+    }
+
     #[test]
     fn empty_delta() {
         let mut jar = CookieJar::new();
@@ -725,4 +1618,23 @@ mod test {
         assert_eq!(jar.delta().filter(|c| c.value().is_empty()).count(), 1);
         assert_eq!(jar.delta().filter(|c| c.path() == Some("/")).count(), 1);
     }
+
+    #[test]
+    #[cfg(any(feature = "signed", feature = "private"))]
+    fn session_token() {
+        use base64::URL_SAFE_NO_PAD;
+
+        let mut jar = CookieJar::new();
+        let token = jar.add_session_token("session");
+        assert_eq!(base64::decode_config(&token, URL_SAFE_NO_PAD).unwrap().len(), 32);
+        assert_ne!(jar.add_session_token("session"), token);
+        assert_eq!(jar.iter().count(), 1);
+
+        assert_eq!(Cookie::random("a", 0).value(), "");
+        for n in 1..40 {
+            let value = Cookie::random("a", n).value().to_string();
+            assert_eq!(base64::decode_config(&value, URL_SAFE_NO_PAD).unwrap().len(), n);
+            assert!(value.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_'));
+        }
+    }
 }
@@ -48,7 +48,8 @@
 //!
 //! * **`key-expansion`**
 //!
-//!   Enables _key expansion_ or _key derivation_ via [`Key::derive_from()`].
+//!   Enables _key expansion_ or _key derivation_ via [`Key::derive_from()`]
+//!   and the configurable [`KeyDerivation`].
 //!
 //!   When this feature is enabled, and either `signed` or `private` are _also_
 //!   enabled, the [`Key::derive_from()`] method is available. The method can be
@@ -61,6 +62,54 @@
 //!   A meta-feature that simultaneously enables `signed`, `private`, and
 //!   `key-expansion`.
 //!
+//!   When both `signed` and `private` are enabled, the [`CookieJar::secure()`]
+//!   method and [`SecureJar`] type are also available. The jar encrypts and
+//!   then signs cookies as they are added to the parent jar. Keys can also be
+//!   stored at rest encrypted with a passphrase via [`Key::save_encrypted()`]
+//!   and [`Key::load_encrypted()`].
+//!
+//! * **`async`**
+//!
+//!   Enables loading keys asynchronously via a [`KeyProvider`].
+//!
+//!   When this feature is enabled, and either `signed` or `private` are _also_
+//!   enabled, the [`KeyProvider`] trait and the `SignedJar::with_provider()`
+//!   and `PrivateJar::with_provider()` methods are available. The methods fetch
+//!   keys, for instance from a secrets manager, each time a child jar is
+//!   created, so that rotated keys are picked up without a restart.
+//!
+//! * **`zeroize`**
+//!
+//!   Wipes key material when it is dropped via [`zeroize`](https://docs.rs/zeroize).
+//!
+//!   When this feature is enabled, and either `signed` or `private` are _also_
+//!   enabled, the raw bytes of a [`Key`] and of the key copies held by child
+//!   jars and signers are overwritten with zeros when dropped. See [`Key`] for
+//!   what is and isn't covered.
+//!
+//! * **`base64`**
+//!
+//!   Uses the [`base64`](https://docs.rs/base64) crate for Base64 encoding.
+//!
+//!   Binary values, as written by [`Cookie::new_binary()`], and structured
+//!   field byte sequences are otherwise encoded and decoded without any
+//!   dependency. This feature lets projects that already depend on `base64`
+//!   use a single implementation. It is enabled by `signed` and `private`,
+//!   which depend on the crate for keys and cookie values.
+//!
+//! * **`serde`**
+//!
+//!   Enables serialization and deserialization of [`Cookie`], [`CookieJar`],
+//!   [`SameSite`], [`Priority`], and [`Expiration`], as well as `Claims` when
+//!   `signed` is also enabled, via [`serde`](https://serde.rs).
+//!
+//!   When this feature is enabled, the types implement `Serialize` and
+//!   `Deserialize`. A `Cookie` is serialized as a struct of its name, value,
+//!   and every attribute, including its expiration and unknown attributes. A
+//!   `CookieJar` is serialized with its delta, so that which cookies are
+//!   original and which were added or removed is preserved; the
+//!   [`cookies_only`] module serializes just the cookies in a jar instead.
+//!
 //! You can enable features via `Cargo.toml`:
 //!
 //! ```toml
@@ -81,12 +130,23 @@ mod jar;
 mod delta;
 mod draft;
 mod expiration;
+mod chunked;
+mod prefixed;
+mod split;
+mod sub_values;
+mod structured;
+mod format;
+mod size;
+mod validation;
+mod encoding;
+#[cfg(feature = "serde")] mod serialization;
 
 #[cfg(any(feature = "private", feature = "signed"))] #[macro_use] mod secure;
 #[cfg(any(feature = "private", feature = "signed"))] pub use secure::*;
 
 use std::borrow::Cow;
 use std::fmt;
+use std::io;
 use std::str::FromStr;
 
 #[allow(unused_imports, deprecated)]
@@ -97,11 +157,23 @@ use percent_encoding::{AsciiSet, percent_encode as encode};
 use time::{Duration, OffsetDateTime, UtcOffset, macros::datetime};
 
 use crate::parse::parse_cookie;
-pub use crate::parse::ParseError;
+use crate::encoding::Alphabet;
+pub use crate::parse::{DuplicatePolicy, ParseError, ParseOptions, SplitCookies, Utf8Policy};
+pub use crate::parse::ValueRecovery;
+pub use crate::parse::MAX_AGE_LIMIT;
 pub use crate::builder::CookieBuilder;
 pub use crate::jar::{CookieJar, Delta, Iter};
 pub use crate::draft::*;
 pub use crate::expiration::*;
+pub use crate::chunked::ChunkedJar;
+pub use crate::prefixed::PrefixedJar;
+pub use crate::split::SetCookieSplitter;
+pub use crate::sub_values::SubValues;
+pub use crate::structured::{StructuredDictionary, StructuredFieldError, StructuredItem};
+pub use crate::format::{Attribute, DisplayOptions};
+pub use crate::size::{SizeError, DEFAULT_SIZE_LIMIT};
+pub use crate::validation::{FormatError, NameError, ValidationError};
+#[cfg(feature = "serde")] pub use crate::serialization::cookies_only;
 
 #[derive(Debug, Clone)]
 enum CookieStr<'c> {
@@ -126,7 +198,7 @@ impl<'c> CookieStr<'c> {
                     converting indexed str to str! (This is a module invariant.)");
                 &s[i..j]
             },
-            CookieStr::Concrete(ref cstr) => &*cstr,
+            CookieStr::Concrete(ref cstr) => cstr,
         }
     }
 
@@ -203,6 +275,17 @@ pub struct Cookie<'c> {
     http_only: Option<bool>,
     /// The draft `SameSite` attribute.
     same_site: Option<SameSite>,
+    /// Whether this cookie was marked Partitioned.
+    partitioned: Option<bool>,
+    /// The nonstandard `Priority` attribute.
+    priority: Option<Priority>,
+    /// Attributes this crate doesn't recognize, each a name and an optional
+    /// value, in order.
+    extensions: Vec<(CookieStr<'c>, Option<CookieStr<'c>>)>,
+    /// Whether the value is wrapped in double quotes.
+    quoted: bool,
+    /// Whether the `Domain` is written with a leading dot.
+    domain_dot: bool,
 }
 
 impl<'c> Cookie<'c> {
@@ -231,9 +314,43 @@ impl<'c> Cookie<'c> {
             secure: None,
             http_only: None,
             same_site: None,
+            partitioned: None,
+            priority: None,
+            extensions: vec![],
+            quoted: false,
+            domain_dot: false,
         }
     }
 
+    /// Creates a new `Cookie` with the given name and value if `name` is a
+    /// valid cookie name, or returns a [`NameError`] describing why it isn't.
+    ///
+    /// A valid name is a non-empty RFC 6265 `token`: it must not contain
+    /// separators such as `=`, `;`, or `,`, whitespace, control characters, or
+    /// non-ASCII characters. [`Cookie::new()`] accepts any name, which can
+    /// produce headers that servers misparse or reject. The value is not
+    /// validated.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, NameError};
+    ///
+    /// let cookie = Cookie::try_new("name", "value").unwrap();
+    /// assert_eq!(cookie.name_value(), ("name", "value"));
+    ///
+    /// let error = Cookie::try_new("na;me", "value").unwrap_err();
+    /// assert_eq!(error, NameError::InvalidCharacter(';', 2));
+    /// ```
+    pub fn try_new<N, V>(name: N, value: V) -> Result<Self, NameError>
+        where N: Into<Cow<'c, str>>,
+              V: Into<Cow<'c, str>>
+    {
+        let name = name.into();
+        NameError::check(&name)?;
+        Ok(Cookie::new(name, value))
+    }
+
     /// Creates a new `Cookie` with the given name and an empty value.
     ///
     /// # Example
@@ -251,6 +368,51 @@ impl<'c> Cookie<'c> {
         Cookie::new(name, "")
     }
 
+    /// Creates a new `Cookie` with the given name and a value of `n_bytes`
+    /// bytes from a cryptographically secure random number generator, encoded
+    /// as URL-safe, unpadded Base64. Such a value is suitable as a session ID or
+    /// CSRF token: 16 bytes (128 bits) or more is recommended.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Cookie;
+    ///
+    /// let c = Cookie::random("session", 32);
+    /// assert_eq!(c.name(), "session");
+    /// assert_eq!(c.value().len(), 43);
+    /// assert_ne!(c.value(), Cookie::random("session", 32).value());
+    /// ```
+    #[cfg(any(feature = "private", feature = "signed"))]
+    #[cfg_attr(all(nightly, doc), doc(cfg(any(feature = "private", feature = "signed"))))]
+    pub fn random<N>(name: N, n_bytes: usize) -> Cookie<'c>
+        where N: Into<Cow<'c, str>>
+    {
+        Cookie::new(name, crate::secure::random_value(n_bytes))
+    }
+
+    /// Creates a new `Cookie` with the given name and the binary value `value`,
+    /// encoded as URL-safe, unpadded Base64. The bytes can be read back with
+    /// [`Cookie::value_bytes()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Cookie;
+    ///
+    /// let c = Cookie::new_binary("token", &[0xfb, 0xff, 0x00]);
+    /// assert_eq!(c.name(), "token");
+    /// assert_eq!(c.value(), "-_8A");
+    /// assert_eq!(c.value_bytes().unwrap(), [0xfb, 0xff, 0x00]);
+    /// ```
+    pub fn new_binary<N>(name: N, value: &[u8]) -> Cookie<'c>
+        where N: Into<Cow<'c, str>>
+    {
+        let mut encoded = String::new();
+        crate::encoding::encode_into(&mut encoded, value, Alphabet::UrlSafe, false);
+        Cookie::new(name, encoded)
+    }
+
     /// Creates a new `CookieBuilder` instance from the given key and value
     /// strings.
     ///
@@ -272,6 +434,10 @@ impl<'c> Cookie<'c> {
     /// Parses a `Cookie` from the given HTTP cookie header value string. Does
     /// not perform any percent-decoding.
     ///
+    /// As browsers do, a cookie of the form `=value` or, without an `=`, of the
+    /// form `value` is parsed as a _nameless_ cookie: one with an empty name.
+    /// [`Cookie::parse_strict()`] rejects these.
+    ///
     /// # Example
     ///
     /// ```
@@ -280,11 +446,51 @@ impl<'c> Cookie<'c> {
     /// let c = Cookie::parse("foo=bar%20baz; HttpOnly").unwrap();
     /// assert_eq!(c.name_value(), ("foo", "bar%20baz"));
     /// assert_eq!(c.http_only(), Some(true));
+    ///
+    /// let c = Cookie::parse("bar; Path=/").unwrap();
+    /// assert_eq!(c.name_value(), ("", "bar"));
+    /// assert_eq!(c.to_string(), "=bar; Path=/");
     /// ```
     pub fn parse<S>(s: S) -> Result<Cookie<'c>, ParseError>
         where S: Into<Cow<'c, str>>
     {
-        parse_cookie(s, false)
+        parse_cookie(s, &ParseOptions::new())
+    }
+
+    /// Parses a `Cookie` from the given HTTP cookie header value string,
+    /// rejecting strings that don't conform to the `Set-Cookie` grammar of
+    /// [RFC 6265, Section 4.1.1] instead of tolerating them as
+    /// [`Cookie::parse()`] does. Does not perform any percent-decoding.
+    ///
+    /// In particular, the cookie's name must be a non-empty token, so nameless
+    /// cookies are rejected, its value must consist of `cookie-octet`s,
+    /// optionally quoted, and each attribute must follow a `; `, be
+    /// well-formed, and appear at most once. Control characters are never
+    /// accepted. Unknown attributes whose names are tokens and, as written by
+    /// removal cookies, a `Max-Age` of `0` are accepted.
+    ///
+    /// [RFC 6265, Section 4.1.1]: https://datatracker.ietf.org/doc/html/rfc6265#section-4.1.1
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::{Cookie, ParseError};
+    ///
+    /// let c = Cookie::parse_strict("foo=bar; Path=/; HttpOnly").unwrap();
+    /// assert_eq!(c.name_value(), ("foo", "bar"));
+    /// assert_eq!(c.http_only(), Some(true));
+    ///
+    /// assert!(Cookie::parse("foo=bar baz").is_ok());
+    /// let error = Cookie::parse_strict("foo=bar baz").unwrap_err();
+    /// assert_eq!(error, ParseError::InvalidValue { at: 7, len: 1 });
+    ///
+    /// let error = Cookie::parse_strict("foo=bar; Path=/; Path=/a").unwrap_err();
+    /// assert_eq!(error, ParseError::DuplicateAttribute { at: 16, len: 8 });
+    /// ```
+    pub fn parse_strict<S>(s: S) -> Result<Cookie<'c>, ParseError>
+        where S: Into<Cow<'c, str>>
+    {
+        parse_cookie(s, &ParseOptions::new().strict(true))
     }
 
     /// Parses a `Cookie` from the given HTTP cookie header value string where
@@ -305,7 +511,162 @@ impl<'c> Cookie<'c> {
     pub fn parse_encoded<S>(s: S) -> Result<Cookie<'c>, ParseError>
         where S: Into<Cow<'c, str>>
     {
-        parse_cookie(s, true)
+        parse_cookie(s, &ParseOptions::new().percent_decode(true))
+    }
+
+    /// Parses a `Cookie` from the given HTTP cookie header value string with
+    /// the parsing options `options`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::{Cookie, ParseOptions};
+    ///
+    /// let options = ParseOptions::new().strict(true);
+    /// let c = Cookie::parse_with("foo=bar; HttpOnly; Priority=High", &options).unwrap();
+    /// assert_eq!(c.http_only(), Some(true));
+    /// assert_eq!(c.to_string(), "foo=bar; HttpOnly; Priority=High");
+    ///
+    /// assert!(Cookie::parse_with("foo=bar;HttpOnly", &options).is_err());
+    /// ```
+    pub fn parse_with<S>(s: S, options: &ParseOptions) -> Result<Cookie<'c>, ParseError>
+        where S: Into<Cow<'c, str>>
+    {
+        parse_cookie(s, options)
+    }
+
+    /// Parses a `Cookie` from the given raw HTTP cookie header value bytes,
+    /// which aren't guaranteed to be valid UTF-8. Bytes that aren't valid UTF-8
+    /// are handled according to `policy`. Does not perform any
+    /// percent-decoding.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::{Cookie, ParseError, Utf8Policy};
+    ///
+    /// let c = Cookie::parse_bytes(b"foo=bar; HttpOnly", Utf8Policy::Strict).unwrap();
+    /// assert_eq!(c.name_value(), ("foo", "bar"));
+    /// assert_eq!(c.http_only(), Some(true));
+    ///
+    /// let bytes = b"foo=b\xffr; HttpOnly";
+    /// let c = Cookie::parse_bytes(bytes, Utf8Policy::Lossy).unwrap();
+    /// assert_eq!(c.name_value(), ("foo", "b\u{fffd}r"));
+    ///
+    /// let error = Cookie::parse_bytes(bytes, Utf8Policy::Strict).unwrap_err();
+    /// assert!(matches!(error, ParseError::Utf8Error(_)));
+    /// ```
+    pub fn parse_bytes(bytes: &'c [u8], policy: Utf8Policy) -> Result<Cookie<'c>, ParseError> {
+        match policy {
+            Utf8Policy::Strict => Cookie::parse(std::str::from_utf8(bytes)?),
+            Utf8Policy::Lossy => Cookie::parse(String::from_utf8_lossy(bytes)),
+        }
+    }
+
+    /// Parses a `Cookie` from the given HTTP cookie header value string without
+    /// allocating. Does not perform any percent-decoding.
+    ///
+    /// The name, value, and attributes of the returned cookie are stored as
+    /// ranges into `s`; they can be retrieved as slices of `s` with the `_raw`
+    /// accessors such as [`Cookie::name_raw()`], while `s` itself is available
+    /// via [`Cookie::raw_str()`]. To avoid allocating, unknown attributes and
+    /// unrecognized `SameSite` values are not preserved. Otherwise, this method
+    /// parses as [`Cookie::parse()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    ///
+    /// let header = "foo=bar; Path=/; Domain=.example.com; Priority=High";
+    /// let c = Cookie::parse_borrowed(header).unwrap();
+    /// assert_eq!(c.name_raw(), Some("foo"));
+    /// assert_eq!(c.value_raw(), Some("bar"));
+    /// assert_eq!(c.path_raw(), Some("/"));
+    /// assert_eq!(c.domain_raw(), Some("example.com"));
+    /// assert_eq!(c.raw_str(), Some(header));
+    /// assert_eq!(c.extensions().count(), 0);
+    /// ```
+    pub fn parse_borrowed(s: &'c str) -> Result<Cookie<'c>, ParseError> {
+        parse_cookie(s, &ParseOptions::new().preserve_unknown(false))
+    }
+
+    /// Parses each `Set-Cookie` header value string in `headers` as a
+    /// `Cookie`, returning an iterator over the results in the same order.
+    /// Does not perform any percent-decoding.
+    ///
+    /// A header that fails to parse yields an `Err` but does not stop the
+    /// iterator: the remaining headers are still parsed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::{Cookie, ParseError};
+    ///
+    /// let headers = ["a=b; Path=/", "; Secure", "c=d; Secure"];
+    /// let results: Vec<_> = Cookie::parse_all(headers.iter().copied()).collect();
+    /// assert_eq!(results.len(), 3);
+    /// assert_eq!(results[0].as_ref().unwrap().path(), Some("/"));
+    /// assert_eq!(results[1], Err(ParseError::MissingPair));
+    /// assert_eq!(results[2].as_ref().unwrap().secure(), Some(true));
+    ///
+    /// let (cookies, errors): (Vec<_>, Vec<_>) = Cookie::parse_all(headers.iter().copied())
+    ///     .partition(Result::is_ok);
+    ///
+    /// assert_eq!((cookies.len(), errors.len()), (2, 1));
+    /// ```
+    pub fn parse_all<'a, I>(headers: I) -> impl Iterator<Item = Result<Cookie<'a>, ParseError>>
+        where I: IntoIterator<Item = &'a str>
+    {
+        headers.into_iter().map(Cookie::parse)
+    }
+
+    /// Parses the cookies in the given request `Cookie` header value string,
+    /// such as `name=value; other=thing`, returning an iterator over the
+    /// results. Does not perform any percent-decoding. See [`SplitCookies`]
+    /// for details on how the string is split.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    ///
+    /// let cookies: Vec<_> = Cookie::split_parse("name=value; other=key%20value; ")
+    ///     .collect::<Result<_, _>>()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cookies.len(), 2);
+    /// assert_eq!(cookies[0].name_value(), ("name", "value"));
+    /// assert_eq!(cookies[1].name_value(), ("other", "key%20value"));
+    /// ```
+    pub fn split_parse<S>(string: S) -> SplitCookies<'c>
+        where S: Into<Cow<'c, str>>
+    {
+        SplitCookies::new(string.into(), ParseOptions::new())
+    }
+
+    /// Parses the cookies in the given request `Cookie` header value string,
+    /// such as `name=value; other=thing`, where the name and value fields are
+    /// percent-encoded, returning an iterator over the results. Percent-decodes
+    /// the name/value fields.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    ///
+    /// let cookies: Vec<_> = Cookie::split_parse_encoded("name=value; other=key%20value")
+    ///     .collect::<Result<_, _>>()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(cookies[1].name_value(), ("other", "key value"));
+    /// ```
+    #[cfg(feature = "percent-encode")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "percent-encode")))]
+    pub fn split_parse_encoded<S>(string: S) -> SplitCookies<'c>
+        where S: Into<Cow<'c, str>>
+    {
+        SplitCookies::new(string.into(), ParseOptions::new().percent_decode(true))
     }
 
     /// Converts `self` into a `Cookie` with a static lifetime with as few
@@ -332,6 +693,13 @@ impl<'c> Cookie<'c> {
             secure: self.secure,
             http_only: self.http_only,
             same_site: self.same_site,
+            partitioned: self.partitioned,
+            priority: self.priority,
+            extensions: self.extensions.into_iter()
+                .map(|(name, value)| (name.into_owned(), value.map(|v| v.into_owned())))
+                .collect(),
+            quoted: self.quoted,
+            domain_dot: self.domain_dot,
         }
     }
 
@@ -365,6 +733,114 @@ impl<'c> Cookie<'c> {
         self.value.to_str(self.cookie_string.as_ref())
     }
 
+    /// Returns whether the value of `self` is wrapped in double quotes, either
+    /// because it was parsed with quotes or because it was set via
+    /// [`Cookie::set_quoted_value()`]. Quoted values are written out with
+    /// their quotes; the quotes are never part of [`Cookie::value()`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    ///
+    /// let c = Cookie::parse("name=\"a value\"; Path=/").unwrap();
+    /// assert_eq!(c.value(), "a value");
+    /// assert!(c.is_quoted());
+    /// assert_eq!(c.to_string(), "name=\"a value\"; Path=/");
+    ///
+    /// assert!(!Cookie::parse("name=value").unwrap().is_quoted());
+    /// ```
+    #[inline]
+    pub fn is_quoted(&self) -> bool {
+        self.quoted
+    }
+
+    /// Parses the value of `self` as a `T` via [`FromStr`], returning the
+    /// error of `T`'s `FromStr` implementation if the value can't be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    ///
+    /// let c = Cookie::new("visits", "42");
+    /// assert_eq!(c.value_as::<u32>(), Ok(42));
+    /// assert!(c.value_as::<bool>().is_err());
+    ///
+    /// let c = Cookie::new("visits", "");
+    /// assert!(c.value_as::<u32>().is_err());
+    /// ```
+    #[inline]
+    pub fn value_as<T: FromStr>(&self) -> Result<T, T::Err> {
+        self.value().parse()
+    }
+
+    /// Decodes the value of `self` as URL-safe, unpadded Base64, as written by
+    /// [`Cookie::new_binary()`], returning the decoded bytes. Returns `None` if
+    /// the value isn't valid URL-safe, unpadded Base64.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Cookie;
+    ///
+    /// let c = Cookie::parse("token=-_8A; Path=/").unwrap();
+    /// assert_eq!(c.value_bytes().unwrap(), [0xfb, 0xff, 0x00]);
+    ///
+    /// let c = Cookie::new("token", "+/8A");
+    /// assert!(c.value_bytes().is_none());
+    /// ```
+    pub fn value_bytes(&self) -> Option<Vec<u8>> {
+        crate::encoding::decode(self.value(), Alphabet::UrlSafe)
+    }
+
+    /// Parses the value of `self` as an [RFC 8941] structured field item, as
+    /// written by [`StructuredItem::serialize()`]. The double quotes around a
+    /// [quoted](Cookie::is_quoted()) value are parsed as those of a string.
+    ///
+    /// [RFC 8941]: https://datatracker.ietf.org/doc/html/rfc8941
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, StructuredItem};
+    ///
+    /// let c = Cookie::parse("visits=42; Path=/").unwrap();
+    /// assert_eq!(c.structured_item(), Ok(StructuredItem::Integer(42)));
+    ///
+    /// let c = Cookie::parse("greeting=\"hello, world\"").unwrap();
+    /// assert_eq!(c.structured_item().unwrap().as_str(), Some("hello, world"));
+    ///
+    /// let c = Cookie::new("bad", "1.2.3");
+    /// assert!(c.structured_item().is_err());
+    /// ```
+    pub fn structured_item(&self) -> Result<StructuredItem, StructuredFieldError> {
+        match self.quoted {
+            true => StructuredItem::parse(&format!("\"{}\"", self.value())),
+            false => StructuredItem::parse(self.value()),
+        }
+    }
+
+    /// Parses the value of `self` as an [RFC 8941] structured field
+    /// dictionary, as written by [`StructuredDictionary::serialize()`].
+    ///
+    /// [RFC 8941]: https://datatracker.ietf.org/doc/html/rfc8941
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, StructuredItem};
+    ///
+    /// let c = Cookie::parse("prefs=theme=dark,beta,font=1.5; Path=/").unwrap();
+    /// let prefs = c.structured_dictionary().unwrap();
+    /// assert_eq!(prefs.get("theme").and_then(|t| t.as_str()), Some("dark"));
+    /// assert_eq!(prefs.get("beta"), Some(&StructuredItem::Boolean(true)));
+    /// assert_eq!(prefs.get("font").and_then(|f| f.as_decimal()), Some(1.5));
+    /// ```
+    pub fn structured_dictionary(&self) -> Result<StructuredDictionary, StructuredFieldError> {
+        StructuredDictionary::parse(self.value())
+    }
+
     /// Returns the name and value of `self` as a tuple of `(name, value)`.
     ///
     /// # Example
@@ -453,10 +929,67 @@ impl<'c> Cookie<'c> {
     ///
     /// let c = Cookie::parse("name=value; SameSite=Lax").unwrap();
     /// assert_eq!(c.same_site(), Some(SameSite::Lax));
+    ///
+    /// let c = Cookie::parse("name=value; SameSite=Sometimes").unwrap();
+    /// assert_eq!(c.same_site(), Some(SameSite::Other("Sometimes".into())));
+    /// assert_eq!(c.to_string(), "name=value; SameSite=Sometimes");
     /// ```
     #[inline]
     pub fn same_site(&self) -> Option<SameSite> {
-        self.same_site
+        self.same_site.clone()
+    }
+
+    /// Returns whether this cookie was marked `Partitioned` or not. Returns
+    /// `Some(true)` when the cookie was explicitly set (manually or parsed) as
+    /// `Partitioned`, `Some(false)` when `partitioned` was manually set to
+    /// `false`, and `None` otherwise.
+    ///
+    /// Partitioned cookies, as specified by the [CHIPS draft], are stored by
+    /// browsers separately for each top-level site they are set under, so that
+    /// they remain available to embedded, third-party content.
+    ///
+    /// [CHIPS draft]: https://datatracker.ietf.org/doc/html/draft-cutler-httpbis-partitioned-cookies
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    ///
+    /// let c = Cookie::parse("name=value; Partitioned").unwrap();
+    /// assert_eq!(c.partitioned(), Some(true));
+    ///
+    /// let mut c = Cookie::new("name", "value");
+    /// assert_eq!(c.partitioned(), None);
+    ///
+    /// // An explicitly set "false" value.
+    /// c.set_partitioned(false);
+    /// assert_eq!(c.partitioned(), Some(false));
+    ///
+    /// // An explicitly set "true" value.
+    /// c.set_partitioned(true);
+    /// assert_eq!(c.partitioned(), Some(true));
+    /// ```
+    #[inline]
+    pub fn partitioned(&self) -> Option<bool> {
+        self.partitioned
+    }
+
+    /// Returns the `Priority` attribute of this cookie if one was specified.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::{Cookie, Priority};
+    ///
+    /// let c = Cookie::parse("name=value; Priority=High").unwrap();
+    /// assert_eq!(c.priority(), Some(Priority::High));
+    ///
+    /// let c = Cookie::parse("name=value").unwrap();
+    /// assert_eq!(c.priority(), None);
+    /// ```
+    #[inline]
+    pub fn priority(&self) -> Option<Priority> {
+        self.priority
     }
 
     /// Returns the specified max-age of the cookie if one was specified.
@@ -477,6 +1010,31 @@ impl<'c> Cookie<'c> {
         self.max_age
     }
 
+    /// Returns the max-age of the cookie that browsers will apply, if one was
+    /// specified: the specified max-age clamped to be between zero and
+    /// [`MAX_AGE_LIMIT`], beyond which browsers cap a cookie's lifetime.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::{Cookie, MAX_AGE_LIMIT};
+    /// use cookie::time::Duration;
+    ///
+    /// let c = Cookie::parse("name=value; Max-Age=3600").unwrap();
+    /// assert_eq!(c.effective_max_age(), Some(Duration::hours(1)));
+    ///
+    /// let c = Cookie::parse("name=value; Max-Age=100000000").unwrap();
+    /// assert_eq!(c.max_age(), Some(Duration::seconds(100000000)));
+    /// assert_eq!(c.effective_max_age(), Some(MAX_AGE_LIMIT));
+    ///
+    /// let c = Cookie::build("name", "value").max_age(Duration::hours(-1)).finish();
+    /// assert_eq!(c.effective_max_age(), Some(Duration::ZERO));
+    /// ```
+    #[inline]
+    pub fn effective_max_age(&self) -> Option<Duration> {
+        self.max_age().map(|age| age.max(Duration::ZERO).min(MAX_AGE_LIMIT))
+    }
+
     /// Returns the `Path` of the cookie if one was specified.
     ///
     /// # Example
@@ -522,6 +1080,30 @@ impl<'c> Cookie<'c> {
         }
     }
 
+    /// Returns whether the `Domain` of `self` is written out with a leading
+    /// dot: either because it was parsed with one, as in
+    /// `Domain=.example.com`, or because it was requested via
+    /// [`Cookie::set_domain_leading_dot()`]. As RFC 6265 requires, the dot is
+    /// stripped from [`Cookie::domain()`] when parsing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    ///
+    /// let c = Cookie::parse("name=value; Domain=.crates.io").unwrap();
+    /// assert_eq!(c.domain(), Some("crates.io"));
+    /// assert!(c.domain_leading_dot());
+    /// assert_eq!(c.to_string(), "name=value; Domain=.crates.io");
+    ///
+    /// let c = Cookie::parse("name=value; Domain=crates.io").unwrap();
+    /// assert!(!c.domain_leading_dot());
+    /// ```
+    #[inline]
+    pub fn domain_leading_dot(&self) -> bool {
+        self.domain_dot
+    }
+
     /// Returns the [`Expiration`] of the cookie if one was specified.
     ///
     /// # Example
@@ -570,17 +1152,110 @@ impl<'c> Cookie<'c> {
         self.expires.and_then(|e| e.datetime())
     }
 
-    /// Sets the name of `self` to `name`.
+    /// Returns the moment `self` expires if it was received at `now`, or
+    /// `None` if it's a session cookie. As in RFC 6265, Section 5.3, `Max-Age`
+    /// takes precedence over `Expires`: with a `Max-Age`, the cookie expires
+    /// that long after `now`, or at `now` if the max-age isn't positive.
+    /// Otherwise, it expires at its `Expires` date-time.
+    ///
+    /// A max-age too large to add to `now` results in the latest date that can
+    /// be written out, the end of the year 9999.
     ///
     /// # Example
     ///
     /// ```
     /// use cookie::Cookie;
+    /// use cookie::time::{Duration, macros::datetime};
     ///
-    /// let mut c = Cookie::new("name", "value");
-    /// assert_eq!(c.name(), "name");
+    /// let now = datetime!(2015-10-21 7:28 UTC);
     ///
-    /// c.set_name("foo");
+    /// let c = Cookie::parse("name=value; Expires=Wed, 21 Oct 2015 08:28:00 GMT").unwrap();
+    /// assert_eq!(c.effective_expiration(now), Some(now + Duration::hours(1)));
+    ///
+    /// let c = Cookie::parse("name=value; Max-Age=60; Expires=Wed, 21 Oct 2015 08:28:00 GMT");
+    /// assert_eq!(c.unwrap().effective_expiration(now), Some(now + Duration::minutes(1)));
+    ///
+    /// let c = Cookie::parse("name=value; Max-Age=-1").unwrap();
+    /// assert_eq!(c.effective_expiration(now), Some(now));
+    ///
+    /// let c = Cookie::parse("name=value").unwrap();
+    /// assert_eq!(c.effective_expiration(now), None);
+    /// ```
+    pub fn effective_expiration(&self, now: OffsetDateTime) -> Option<OffsetDateTime> {
+        match self.max_age() {
+            Some(max_age) if max_age <= Duration::ZERO => Some(now),
+            Some(max_age) => {
+                let latest = datetime!(9999-12-31 23:59:59 UTC);
+                Some(now.checked_add(max_age).map_or(latest, |expires| expires.min(latest)))
+            }
+            None => self.expires_datetime(),
+        }
+    }
+
+    /// Returns an iterator over the attributes of `self` that this crate
+    /// doesn't recognize, such as vendor extensions, in the order they appear.
+    /// Each is a name and, if the attribute has one, a value.
+    ///
+    /// Unknown attributes are kept when a cookie is parsed, unless disabled via
+    /// [`ParseOptions::preserve_unknown()`], and are written out again, after
+    /// all known attributes, when the cookie is displayed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    ///
+    /// let c = Cookie::parse("name=value; X-Scope=app; Path=/; X-Flag").unwrap();
+    /// let extensions: Vec<_> = c.extensions().collect();
+    /// assert_eq!(extensions, [("X-Scope", Some("app")), ("X-Flag", None)]);
+    /// assert_eq!(c.to_string(), "name=value; Path=/; X-Scope=app; X-Flag");
+    ///
+    /// let c = Cookie::new("name", "value");
+    /// assert_eq!(c.extensions().count(), 0);
+    /// ```
+    pub fn extensions(&self) -> impl Iterator<Item = (&str, Option<&str>)> + '_ {
+        let string = self.cookie_string.as_ref();
+        self.extensions.iter().map(move |(name, value)| {
+            (name.to_str(string), value.as_ref().map(|v| v.to_str(string)))
+        })
+    }
+
+    /// Returns an iterator over the [extensions](Cookie::extensions()) of
+    /// `self` that are attributes defined by the obsolete RFC 2109 and RFC
+    /// 2965: `Version`, `Comment`, `CommentURL`, and `Discard`, matched
+    /// case-insensitively. Each is a name and, if the attribute has one, a
+    /// value.
+    ///
+    /// Browsers ignore these attributes, but they still appear in older
+    /// traffic. Like other extensions, they're kept when a cookie is parsed
+    /// and written out again when it's displayed; see
+    /// [`DisplayOptions::obsolete_attributes()`] to omit them instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    ///
+    /// let c = Cookie::parse("name=value; Version=1; X-Scope=app; Discard").unwrap();
+    /// let obsolete: Vec<_> = c.obsolete_attributes().collect();
+    /// assert_eq!(obsolete, [("Version", Some("1")), ("Discard", None)]);
+    /// assert_eq!(c.to_string(), "name=value; Version=1; X-Scope=app; Discard");
+    /// ```
+    pub fn obsolete_attributes(&self) -> impl Iterator<Item = (&str, Option<&str>)> + '_ {
+        self.extensions().filter(|(name, _)| crate::parse::is_obsolete_attribute(name))
+    }
+
+    /// Sets the name of `self` to `name`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    ///
+    /// let mut c = Cookie::new("name", "value");
+    /// assert_eq!(c.name(), "name");
+    ///
+    /// c.set_name("foo");
     /// assert_eq!(c.name(), "foo");
     /// ```
     pub fn set_name<N: Into<Cow<'c, str>>>(&mut self, name: N) {
@@ -601,7 +1276,33 @@ impl<'c> Cookie<'c> {
     /// assert_eq!(c.value(), "bar");
     /// ```
     pub fn set_value<V: Into<Cow<'c, str>>>(&mut self, value: V) {
-        self.value = CookieStr::Concrete(value.into())
+        self.value = CookieStr::Concrete(value.into());
+        self.quoted = false;
+    }
+
+    /// Sets the value of `self` to `value`, to be wrapped in double quotes
+    /// when `self` is written out, as RFC 6265 allows. The quotes are not part
+    /// of [`Cookie::value()`]. Setting the value with [`Cookie::set_value()`]
+    /// removes the quotes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    ///
+    /// let mut c = Cookie::new("name", "value");
+    /// c.set_quoted_value("a, b");
+    /// assert_eq!(c.value(), "a, b");
+    /// assert!(c.is_quoted());
+    /// assert_eq!(c.to_string(), "name=\"a, b\"");
+    ///
+    /// c.set_value("bar");
+    /// assert!(!c.is_quoted());
+    /// assert_eq!(c.to_string(), "name=bar");
+    /// ```
+    pub fn set_quoted_value<V: Into<Cow<'c, str>>>(&mut self, value: V) {
+        self.value = CookieStr::Concrete(value.into());
+        self.quoted = true;
     }
 
     /// Sets the value of `http_only` in `self` to `value`.  If `value` is
@@ -693,6 +1394,60 @@ impl<'c> Cookie<'c> {
         self.same_site = value.into();
     }
 
+    /// Sets the value of `partitioned` in `self` to `value`. If `value` is
+    /// `None`, the field is unset. Browsers reject partitioned cookies that
+    /// aren't `Secure`, so if `value` is `true`, the "Secure" flag will be set
+    /// when the cookie is written out unless `secure` is explicitly set to
+    /// `false` via [`Cookie::set_secure()`] or the equivalent builder method.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    ///
+    /// let mut c = Cookie::new("name", "value");
+    /// assert_eq!(c.partitioned(), None);
+    ///
+    /// c.set_partitioned(true);
+    /// assert_eq!(c.partitioned(), Some(true));
+    /// assert_eq!(c.to_string(), "name=value; Secure; Partitioned");
+    ///
+    /// c.set_secure(false);
+    /// assert_eq!(c.to_string(), "name=value; Partitioned");
+    ///
+    /// c.set_partitioned(None);
+    /// assert_eq!(c.partitioned(), None);
+    /// assert_eq!(c.to_string(), "name=value");
+    /// ```
+    #[inline]
+    pub fn set_partitioned<T: Into<Option<bool>>>(&mut self, value: T) {
+        self.partitioned = value.into();
+    }
+
+    /// Sets the value of `priority` in `self` to `value`. If `value` is
+    /// `None`, the field is unset.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::{Cookie, Priority};
+    ///
+    /// let mut c = Cookie::new("name", "value");
+    /// assert_eq!(c.priority(), None);
+    ///
+    /// c.set_priority(Priority::Low);
+    /// assert_eq!(c.priority(), Some(Priority::Low));
+    /// assert_eq!(c.to_string(), "name=value; Priority=Low");
+    ///
+    /// c.set_priority(None);
+    /// assert_eq!(c.priority(), None);
+    /// assert_eq!(c.to_string(), "name=value");
+    /// ```
+    #[inline]
+    pub fn set_priority<T: Into<Option<Priority>>>(&mut self, value: T) {
+        self.priority = value.into();
+    }
+
     /// Sets the value of `max_age` in `self` to `value`. If `value` is `None`,
     /// the field is unset.
     ///
@@ -771,6 +1526,29 @@ impl<'c> Cookie<'c> {
     /// ```
     pub fn set_domain<D: Into<Cow<'c, str>>>(&mut self, domain: D) {
         self.domain = Some(CookieStr::Concrete(domain.into()));
+        self.domain_dot = false;
+    }
+
+    /// Sets whether the `Domain` of `self` is written out with a leading dot,
+    /// as in `Domain=.example.com`, for compatibility with legacy clients.
+    /// RFC 6265 clients ignore the leading dot. The dot is never part of
+    /// [`Cookie::domain()`]. Setting or unsetting the domain resets this to
+    /// `false`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    ///
+    /// let mut c = Cookie::build("name", "value").domain("crates.io").finish();
+    /// assert_eq!(c.to_string(), "name=value; Domain=crates.io");
+    ///
+    /// c.set_domain_leading_dot(true);
+    /// assert_eq!(c.domain(), Some("crates.io"));
+    /// assert_eq!(c.to_string(), "name=value; Domain=.crates.io");
+    /// ```
+    pub fn set_domain_leading_dot(&mut self, value: bool) {
+        self.domain_dot = value;
     }
 
     /// Unsets the `domain` of `self`.
@@ -791,6 +1569,7 @@ impl<'c> Cookie<'c> {
     /// ```
     pub fn unset_domain(&mut self) {
         self.domain = None;
+        self.domain_dot = false;
     }
 
     /// Sets the expires field of `self` to `time`. If `time` is `None`, an
@@ -823,6 +1602,34 @@ impl<'c> Cookie<'c> {
             .map(|time| std::cmp::min(time, MAX_DATETIME)));
     }
 
+    /// Sets the `expires` field of `self` to `duration` from now. Unlike
+    /// [`Cookie::set_max_age()`], which clients apply from the time they
+    /// receive the cookie, the expiration date is fixed when this is called.
+    /// As with [`Cookie::set_expires()`], dates past the year 9999 are
+    /// clamped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    /// use cookie::time::{Duration, OffsetDateTime};
+    ///
+    /// let mut c = Cookie::new("name", "value");
+    /// c.set_expires_in(Duration::days(30));
+    ///
+    /// let expires = c.expires_datetime().unwrap();
+    /// let expected = OffsetDateTime::now_utc() + Duration::days(30);
+    /// assert!((expected - expires).abs() < Duration::minutes(1));
+    /// assert_eq!(c.max_age(), None);
+    /// ```
+    pub fn set_expires_in(&mut self, duration: Duration) {
+        let now = OffsetDateTime::now_utc();
+        self.set_expires(now.checked_add(duration).unwrap_or(match duration.is_negative() {
+            true => datetime!(1601-01-01 0:00 UTC),
+            false => datetime!(9999-12-31 23:59:59 UTC),
+        }));
+    }
+
     /// Unsets the `expires` of `self`.
     ///
     /// # Example
@@ -843,6 +1650,53 @@ impl<'c> Cookie<'c> {
         self.expires = None;
     }
 
+    /// Sets the attribute of `self` named `name`, one this crate doesn't
+    /// otherwise model, such as `X-Scope`, to `value`, replacing any existing
+    /// [extensions](Cookie::extensions()) of the same name, compared
+    /// case-insensitively. The attribute is written as `name=value` or, if
+    /// `value` is `None`, as a flag: `name`.
+    ///
+    /// Neither `name` nor `value` is validated, and `name` should not be that
+    /// of an attribute this crate models, such as `Path`: use the dedicated
+    /// setter instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    ///
+    /// let mut c = Cookie::new("name", "value");
+    /// c.set_extension("X-Scope", Some("app"));
+    /// c.set_extension("X-Flag", None::<&str>);
+    /// c.set_extension("x-scope", Some("api"));
+    /// assert_eq!(c.to_string(), "name=value; X-Flag; x-scope=api");
+    /// ```
+    pub fn set_extension<N, V>(&mut self, name: N, value: Option<V>)
+        where N: Into<Cow<'c, str>>, V: Into<Cow<'c, str>>
+    {
+        let name = name.into();
+        self.unset_extension(&name);
+        let value = value.map(|v| CookieStr::Concrete(v.into()));
+        self.extensions.push((CookieStr::Concrete(name), value));
+    }
+
+    /// Removes the [extensions](Cookie::extensions()) of `self` named `name`,
+    /// compared case-insensitively.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    ///
+    /// let mut c = Cookie::parse("name=value; X-Scope=app; X-Flag").unwrap();
+    /// c.unset_extension("x-scope");
+    /// assert_eq!(c.to_string(), "name=value; X-Flag");
+    /// ```
+    pub fn unset_extension(&mut self, name: &str) {
+        let string = self.cookie_string.as_ref();
+        self.extensions.retain(|(n, _)| !n.to_str(string).eq_ignore_ascii_case(name));
+    }
+
     /// Makes `self` a "permanent" cookie by extending its expiration and max
     /// age 20 years into the future.
     ///
@@ -896,41 +1750,284 @@ impl<'c> Cookie<'c> {
         self.set_expires(OffsetDateTime::now_utc() - Duration::days(365));
     }
 
-    fn fmt_parameters(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if let Some(true) = self.http_only() {
-            write!(f, "; HttpOnly")?;
+    /// Returns `true` if `self` is a removal cookie: a cookie that, when sent
+    /// to a client, removes the cookie of the same name there, such as those
+    /// made by [`Cookie::make_removal()`].
+    ///
+    /// A cookie is a removal cookie if its `Max-Age` is zero or negative or,
+    /// if it has no `Max-Age`, its `Expires` date-time is in the past. As
+    /// clients do, `Max-Age` takes precedence over `Expires`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Cookie;
+    /// use cookie::time::{Duration, OffsetDateTime};
+    ///
+    /// let mut c = Cookie::new("name", "value");
+    /// assert!(!c.is_removal());
+    ///
+    /// c.make_removal();
+    /// assert!(c.is_removal());
+    ///
+    /// let c = Cookie::parse("name=value; Expires=Wed, 21 Oct 2015 07:28:00 GMT").unwrap();
+    /// assert!(c.is_removal());
+    ///
+    /// let mut c = Cookie::new("name", "value");
+    /// c.set_expires(OffsetDateTime::now_utc() - Duration::days(1));
+    /// c.set_max_age(Duration::days(1));
+    /// assert!(!c.is_removal());
+    /// ```
+    pub fn is_removal(&self) -> bool {
+        match (self.max_age(), self.expires_datetime()) {
+            (Some(max_age), _) => max_age <= Duration::ZERO,
+            (None, Some(expires)) => expires <= OffsetDateTime::now_utc(),
+            (None, None) => false,
         }
+    }
+
+    /// Returns the canonical form of `self`: an owned `Cookie` that is written
+    /// out the same way as every other cookie with the same meaning, making it
+    /// suitable, via its [`Display`](fmt::Display) implementation, as a cache
+    /// key or for deduplication.
+    ///
+    /// In the canonical form:
+    ///
+    ///   * The name and value are trimmed of whitespace and the value is
+    ///     unquoted unless it is itself wrapped in double quotes.
+    ///   * The domain is lowercased, without leading dots, and removed if
+    ///     empty. The path is removed if it doesn't start with a `/`, as
+    ///     browsers ignore such paths.
+    ///   * The expiration is represented once: by `Max-Age`, in whole,
+    ///     non-negative seconds, if it is set, otherwise by an `Expires`
+    ///     date-time in UTC truncated to whole seconds and, as earlier ones
+    ///     can't be parsed, no earlier than 1601. Session expiration is
+    ///     represented by neither.
+    ///   * `Secure`, `HttpOnly`, and `Partitioned` are either set or unset, and
+    ///     `Secure` is set whenever it would be written out. `Secure` remains
+    ///     explicitly unset only for `SameSite=None` or `Partitioned` cookies
+    ///     that would otherwise be written out as `Secure`.
+    ///   * `SameSite` values are recognized case-insensitively.
+    ///   * Unknown attributes are trimmed, and those without a name or with
+    ///     the name of an attribute this crate models are removed.
+    ///
+    /// The canonical form round-trips: parsing the canonical cookie as written
+    /// out yields a cookie equal to it, in every attribute, whose canonical
+    /// form is written out identically. This is guaranteed when the canonical
+    /// cookie passes both [`Cookie::validate()`] and
+    /// [`Cookie::to_string_checked()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, SameSite};
+    ///
+    /// let a = Cookie::parse("id=\"42\"; Domain=.Example.COM; SameSite=lax; Secure").unwrap();
+    /// let b = Cookie::build("id", "42")
+    ///     .domain("example.com")
+    ///     .same_site(SameSite::Other("LAX".into()))
+    ///     .secure(true)
+    ///     .http_only(false)
+    ///     .finish();
+    ///
+    /// assert_ne!(a.to_string(), b.to_string());
+    /// assert_eq!(a.canonicalize().to_string(), b.canonicalize().to_string());
+    ///
+    /// let c = a.canonicalize();
+    /// assert_eq!(c.to_string(), "id=42; SameSite=Lax; Secure; Domain=example.com");
+    /// assert_eq!(Cookie::parse(c.to_string()).unwrap(), c);
+    /// ```
+    pub fn canonicalize(&self) -> Cookie<'static> {
+        let owned = |s: &str| CookieStr::Concrete(Cow::Owned(s.to_string()));
+        let value = self.value().trim();
+        let max_age = self.max_age().map(|age| Duration::seconds(age.whole_seconds().max(0)));
+        let expires = match max_age {
+            Some(_) => None,
+            None => self.expires_datetime()
+                .and_then(|time| OffsetDateTime::from_unix_timestamp(time.unix_timestamp()).ok())
+                .map(|time| Expiration::DateTime(time.max(datetime!(1601-01-01 0:00 UTC)))),
+        };
 
-        if let Some(same_site) = self.same_site() {
-            write!(f, "; SameSite={}", same_site)?;
+        let same_site = match self.same_site {
+            Some(SameSite::Other(ref value)) => crate::parse::parse_same_site(value.trim()),
+            ref same_site => same_site.clone(),
+        };
 
-            if same_site.is_none() && self.secure().is_none() {
-                write!(f, "; Secure")?;
-            }
-        }
+        let mut cookie = Cookie {
+            cookie_string: None,
+            name: owned(self.name().trim()),
+            value: owned(value),
+            expires,
+            max_age,
+            domain: self.domain()
+                .map(|domain| domain.trim().trim_start_matches('.').to_ascii_lowercase())
+                .filter(|domain| !domain.is_empty())
+                .map(|domain| CookieStr::Concrete(Cow::Owned(domain))),
+            path: self.path().map(str::trim).filter(|path| path.starts_with('/')).map(owned),
+            secure: self.secure,
+            http_only: self.http_only.filter(|&http_only| http_only),
+            same_site,
+            partitioned: self.partitioned.filter(|&partitioned| partitioned),
+            priority: self.priority,
+            extensions: self.extensions()
+                .map(|(name, value)| (name.trim(), value.map(str::trim)))
+                .filter(|(name, _)| {
+                    !name.is_empty() && crate::parse::attribute_name(name).is_none()
+                })
+                .map(|(name, value)| (owned(name), value.map(owned)))
+                .collect(),
+            quoted: value.len() >= 2 && value.starts_with('"') && value.ends_with('"'),
+            domain_dot: false,
+        };
+
+        // `Secure` is only written out implicitly if it isn't explicitly unset.
+        let implicit = matches!(cookie.same_site, Some(SameSite::None))
+            || cookie.partitioned == Some(true);
+
+        cookie.secure = match cookie.writes_secure() {
+            true => Some(true),
+            false if implicit => Some(false),
+            false => None,
+        };
+
+        cookie
+    }
 
-        if let Some(true) = self.secure() {
-            write!(f, "; Secure")?;
+    fn fmt_name_value<W, N, V>(&self, f: &mut W, name: N, value: V) -> fmt::Result
+        where W: fmt::Write, N: fmt::Display, V: fmt::Display
+    {
+        match self.quoted {
+            true => write!(f, "{}=\"{}\"", name, value),
+            false => write!(f, "{}={}", name, value),
         }
+    }
 
-        if let Some(path) = self.path() {
-            write!(f, "; Path={}", path)?;
+    fn fmt_parameters<W>(&self, f: &mut W, options: &DisplayOptions) -> fmt::Result
+        where W: fmt::Write
+    {
+        let sep = options.separator();
+        let (max_age, expires) = self.written_expiration(options);
+        for attribute in options.attributes() {
+            match attribute {
+                Attribute::HttpOnly => if let Some(true) = self.http_only() {
+                    write!(f, "{}HttpOnly", sep)?;
+                },
+                Attribute::SameSite => if let Some(ref same_site) = self.same_site {
+                    write!(f, "{}SameSite={}", sep, options.same_site_str(same_site))?;
+                },
+                Attribute::Secure => if self.writes_secure() {
+                    write!(f, "{}Secure", sep)?;
+                },
+                Attribute::Partitioned => if let Some(true) = self.partitioned() {
+                    write!(f, "{}Partitioned", sep)?;
+                },
+                Attribute::Path => if let Some(path) = self.path() {
+                    write!(f, "{}Path={}", sep, path)?;
+                },
+                Attribute::Domain => if let Some(domain) = self.domain() {
+                    match self.domain_dot && !domain.starts_with('.') {
+                        true => write!(f, "{}Domain=.{}", sep, domain)?,
+                        false => write!(f, "{}Domain={}", sep, domain)?,
+                    }
+                },
+                Attribute::MaxAge => if let Some(max_age) = max_age {
+                    write!(f, "{}Max-Age={}", sep, max_age.whole_seconds())?;
+                },
+                Attribute::Expires => if let Some(time) = expires {
+                    write!(f, "{}Expires=", sep)?;
+                    crate::parse::write_date(f, time.to_offset(UtcOffset::UTC))?;
+                },
+                Attribute::Priority => if let Some(priority) = self.priority() {
+                    write!(f, "{}Priority={}", sep, priority)?;
+                },
+                Attribute::Extensions => for (name, value) in self.extensions() {
+                    if !options.obsolete() && crate::parse::is_obsolete_attribute(name) {
+                        continue;
+                    }
+
+                    write!(f, "{}{}", sep, name)?;
+                    if let Some(value) = value {
+                        write!(f, "={}", value)?;
+                    }
+                },
+            }
         }
 
-        if let Some(domain) = self.domain() {
-            write!(f, "; Domain={}", domain)?;
+        Ok(())
+    }
+
+    /// Returns the `Max-Age` and `Expires` written out with `options`: as they
+    /// are set or, with [`DisplayOptions::dual_expiration()`], each computed
+    /// from the other, relative to now, if only one of them is set.
+    fn written_expiration(
+        &self,
+        options: &DisplayOptions
+    ) -> (Option<Duration>, Option<OffsetDateTime>) {
+        let (max_age, expires) = (self.max_age(), self.expires_datetime());
+        if !options.writes_dual_expiration() {
+            return (max_age, expires);
         }
 
-        if let Some(max_age) = self.max_age() {
-            write!(f, "; Max-Age={}", max_age.whole_seconds())?;
+        match (max_age, expires) {
+            (Some(max_age), None) => {
+                // Keep the date within the years that are written and parsed.
+                let earliest = datetime!(1601-01-01 0:00 UTC);
+                let latest = datetime!(9999-12-31 23:59:59 UTC);
+                let expires = OffsetDateTime::now_utc().checked_add(max_age)
+                    .unwrap_or(if max_age.is_negative() { earliest } else { latest })
+                    .clamp(earliest, latest);
+
+                (Some(max_age), Some(expires))
+            }
+            (None, Some(expires)) => {
+                let max_age = (expires - OffsetDateTime::now_utc()).max(Duration::ZERO);
+                (Some(max_age), Some(expires))
+            }
+            both => both,
         }
+    }
 
-        if let Some(time) = self.expires_datetime() {
-            let time = time.to_offset(UtcOffset::UTC);
-            write!(f, "; Expires={}", time.format(&crate::parse::FMT1).map_err(|_| fmt::Error)?)?;
+    /// Returns whether the "Secure" flag is written out: if `secure` is set,
+    /// or, if it's unset, if `self` is `SameSite=None` or `Partitioned`.
+    fn writes_secure(&self) -> bool {
+        match self.secure() {
+            Some(secure) => secure,
+            None => matches!(self.same_site, Some(SameSite::None))
+                || self.partitioned() == Some(true),
         }
+    }
 
-        Ok(())
+    /// Returns the raw string `self` was originally parsed from. If `self` was
+    /// not originally parsed from a borrowed raw string, returns `None`.
+    ///
+    /// The string is returned as it was parsed, even if `self` has since been
+    /// modified. Like [`Cookie::name_raw()`], the returned string has the same
+    /// lifetime as the originally parsed string, which may outlive `self`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cookie::Cookie;
+    ///
+    /// let cookie_string = String::from("foo=bar; Path=/");
+    ///
+    /// // `c` will be dropped at the end of the scope, but `raw` will live on
+    /// let raw = {
+    ///     let mut c = Cookie::parse(cookie_string.as_str()).unwrap();
+    ///     c.set_value("baz");
+    ///     c.raw_str()
+    /// };
+    ///
+    /// assert_eq!(raw, Some("foo=bar; Path=/"));
+    /// assert!(Cookie::parse(cookie_string.clone()).unwrap().raw_str().is_none());
+    /// assert!(Cookie::new("foo", "bar").raw_str().is_none());
+    /// ```
+    #[inline]
+    pub fn raw_str(&self) -> Option<&'c str> {
+        match self.cookie_string {
+            Some(Cow::Borrowed(s)) => Some(s),
+            _ => None,
+        }
     }
 
     /// Returns the name of `self` as a string slice of the raw string `self`
@@ -1083,6 +2180,37 @@ impl<'c> Cookie<'c> {
         Display::new_encoded(self)
     }
 
+    /// Wraps `self` in an encoded [`Display`] that, unlike
+    /// [`Cookie::encoded()`], percent-encodes the name and value of the wrapped
+    /// `Cookie` exactly as JavaScript's `encodeURIComponent` does: every
+    /// character except ASCII alphanumerics and `- _ . ! ~ * ' ( )` is
+    /// encoded.
+    ///
+    /// Values written this way are decoded by `decodeURIComponent` in frontend
+    /// code reading `document.cookie`, and values written by
+    /// `encodeURIComponent` are decoded by [`Cookie::parse_encoded()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Cookie;
+    ///
+    /// let c = Cookie::build("my name", "a&b+c=(d)").secure(true).finish();
+    /// assert_eq!(c.encoded().to_string(), "my%20name=a&b+c%3D(d); Secure");
+    /// assert_eq!(c.encoded_component().to_string(), "my%20name=a%26b%2Bc%3D(d); Secure");
+    ///
+    /// let c = Cookie::new("data", "{\"k\":[1,2]}&x=$+#");
+    /// let encoded = c.encoded_component().to_string();
+    /// assert_eq!(encoded, "data=%7B%22k%22%3A%5B1%2C2%5D%7D%26x%3D%24%2B%23");
+    /// assert_eq!(Cookie::parse_encoded(encoded).unwrap().value(), c.value());
+    /// ```
+    #[cfg(feature = "percent-encode")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "percent-encode")))]
+    #[inline(always)]
+    pub fn encoded_component<'a>(&'a self) -> Display<'a, 'c> {
+        Display::new_encoded(self).encoded_component()
+    }
+
     /// Wraps `self` in a stripped `Display`]: a cost-free wrapper around
     /// `Cookie` whose [`fmt::Display`] implementation prints only the `name`
     /// and `value` of the wrapped `Cookie`.
@@ -1106,6 +2234,262 @@ assert_eq!(&c.stripped().encoded().to_string(), "key%3F=value");
     pub fn stripped<'a>(&'a self) -> Display<'a, 'c> {
         Display::new_stripped(self)
     }
+
+    /// Wraps `self` in a `Display` whose [`fmt::Display`] implementation
+    /// writes `self` as a `Set-Cookie` header value formatted according to
+    /// `options`: a cost-free wrapper around `Cookie`.
+    ///
+    /// The returned structure can be chained with [`Display::encoded()`] to
+    /// encode the name and value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Attribute, Cookie, DisplayOptions};
+    ///
+    /// let c = Cookie::build("name", "value").secure(true).path("/").finish();
+    /// assert_eq!(c.to_string(), "name=value; Secure; Path=/");
+    ///
+    /// let options = DisplayOptions::new().order(&[Attribute::Path]).compact(true);
+    /// assert_eq!(c.display_with(&options).to_string(), "name=value;Path=/;Secure");
+    /// ```
+    #[inline(always)]
+    pub fn display_with<'a>(&'a self, options: &DisplayOptions) -> Display<'a, 'c> {
+        Display::new_with(self, *options)
+    }
+
+    /// Returns the `name=value` pair of `self` as sent by a client in a request
+    /// `Cookie` header, without any of the cookie's attributes. Does not
+    /// perform any percent-encoding.
+    ///
+    /// In contrast, the [`fmt::Display`] implementation of `Cookie` and
+    /// [`to_string()`](ToString::to_string) produce the full `Set-Cookie` form,
+    /// attributes included, as sent by a server in a response. This method is
+    /// equivalent to `self.stripped().to_string()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Cookie;
+    ///
+    /// let c = Cookie::build("name", "value").secure(true).path("/").finish();
+    /// assert_eq!(c.to_request_string(), "name=value");
+    /// assert_eq!(c.to_string(), "name=value; Secure; Path=/");
+    /// ```
+    pub fn to_request_string(&self) -> String {
+        self.stripped().to_string()
+    }
+
+    /// Returns `self` formatted as a `Set-Cookie` header value, as by the
+    /// [`fmt::Display`] implementation of `Cookie`, or a [`FormatError`] if
+    /// the result would be malformed.
+    ///
+    /// The name must be a valid cookie name; see [`Cookie::try_new()`]. The
+    /// value, path, domain, and unknown attributes must not contain control
+    /// characters such as `\r` and `\n`, which could split the header, or
+    /// `;`, which would start a new attribute. A quoted value must not contain
+    /// a `"`, and the name of an unknown attribute must not contain a `=`. The
+    /// [`fmt::Display`] implementation writes such cookies out regardless.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, FormatError};
+    ///
+    /// let c = Cookie::build("name", "value").path("/").finish();
+    /// assert_eq!(c.to_string_checked().unwrap(), "name=value; Path=/");
+    ///
+    /// let c = Cookie::new("name", "value\r\nLocation: /");
+    /// assert_eq!(c.to_string_checked(), Err(FormatError::InvalidValue('\r', 5)));
+    /// ```
+    pub fn to_string_checked(&self) -> Result<String, FormatError> {
+        NameError::check(self.name())?;
+        FormatError::check_value(self.value(), self.quoted)?;
+        if let Some(path) = self.path() {
+            FormatError::check_attribute(Attribute::Path, path)?;
+        }
+
+        if let Some(domain) = self.domain() {
+            FormatError::check_attribute(Attribute::Domain, domain)?;
+        }
+
+        if let Some(SameSite::Other(ref same_site)) = self.same_site {
+            FormatError::check_attribute(Attribute::SameSite, same_site)?;
+        }
+
+        for (name, value) in self.extensions() {
+            if name.contains('=') {
+                return Err(FormatError::InvalidAttribute(Attribute::Extensions, '='));
+            }
+
+            FormatError::check_attribute(Attribute::Extensions, name)?;
+            FormatError::check_attribute(Attribute::Extensions, value.unwrap_or(""))?;
+        }
+
+        Ok(self.to_string())
+    }
+
+    /// Writes `self`, formatted as a `Set-Cookie` header value exactly as by
+    /// the [`fmt::Display`] implementation of `Cookie`, into `out`. No
+    /// intermediate strings are allocated, including for the `Expires` date.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Cookie;
+    ///
+    /// let c = Cookie::build("name", "value").path("/").finish();
+    ///
+    /// let mut header = String::from("Set-Cookie: ");
+    /// c.write_to(&mut header).unwrap();
+    /// assert_eq!(header, "Set-Cookie: name=value; Path=/");
+    /// ```
+    pub fn write_to<W: fmt::Write>(&self, out: &mut W) -> fmt::Result {
+        self.fmt_name_value(out, self.name(), self.value())?;
+        self.fmt_parameters(out, &DisplayOptions::new())
+    }
+
+    /// Writes `self`, formatted as a `Set-Cookie` header value exactly as by
+    /// the [`fmt::Display`] implementation of `Cookie`, into the writer `out`.
+    /// No intermediate strings are allocated, including for the `Expires` date.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Cookie;
+    ///
+    /// let c = Cookie::build("name", "value").path("/").finish();
+    ///
+    /// let mut buffer: Vec<u8> = Vec::new();
+    /// c.write_io(&mut buffer).unwrap();
+    /// assert_eq!(buffer, b"name=value; Path=/");
+    /// ```
+    pub fn write_io<W: io::Write>(&self, out: &mut W) -> io::Result<()> {
+        write!(out, "{}", self)
+    }
+
+    /// Returns the length, in bytes, of `self` when serialized for a
+    /// `Set-Cookie` header, that is, of `self.to_string()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Cookie;
+    ///
+    /// let c = Cookie::build("name", "value").path("/").finish();
+    /// assert_eq!(c.serialized_len(), "name=value; Path=/".len());
+    /// ```
+    pub fn serialized_len(&self) -> usize {
+        struct Counter(usize);
+
+        impl fmt::Write for Counter {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0 += s.len();
+                Ok(())
+            }
+        }
+
+        let mut counter = Counter(0);
+        self.write_to(&mut counter).expect("counting never fails");
+        counter.0
+    }
+
+    /// Checks that `self`, when serialized for a `Set-Cookie` header, is no
+    /// larger than `limit` bytes, returning a [`SizeError`] if it is.
+    /// Browsers silently truncate or drop cookies larger than
+    /// [`DEFAULT_SIZE_LIMIT`] bytes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, DEFAULT_SIZE_LIMIT};
+    ///
+    /// let c = Cookie::new("name", "value");
+    /// assert!(c.check_size(DEFAULT_SIZE_LIMIT).is_ok());
+    /// assert!(c.check_size(10).is_ok());
+    ///
+    /// let c = Cookie::new("name", "v".repeat(DEFAULT_SIZE_LIMIT));
+    /// let error = c.check_size(DEFAULT_SIZE_LIMIT).unwrap_err();
+    /// assert_eq!(error.size(), DEFAULT_SIZE_LIMIT + 5);
+    /// ```
+    pub fn check_size(&self, limit: usize) -> Result<(), SizeError> {
+        match self.serialized_len() {
+            size if size > limit => Err(SizeError::new(self.name(), size, limit)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that browsers won't reject `self` when it is sent in a
+    /// `Set-Cookie` header, returning a [`ValidationError`] if they would.
+    ///
+    /// Browsers silently drop cookies that are `SameSite=None` or
+    /// `Partitioned` but not `Secure`. Unless `secure` is unset, the "Secure"
+    /// flag is added to such cookies when they are written out, so this method
+    /// only rejects cookies where `secure` was explicitly set to `false`.
+    ///
+    /// Browsers also drop cookies whose names start with one of the following
+    /// prefixes, compared case-insensitively, unless they meet its
+    /// requirements:
+    ///
+    ///   * `__Secure-`: the cookie must be `Secure`.
+    ///   * `__Host-`: the cookie must be `Secure`, must have a `Path` of `/`,
+    ///     and must not have a `Domain`.
+    ///
+    /// See [`CookieBuilder::host_prefix()`] and
+    /// [`CookieBuilder::secure_prefix()`] to build such cookies.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, SameSite, ValidationError};
+    ///
+    /// let mut c = Cookie::build("name", "value").same_site(SameSite::None).finish();
+    /// assert!(c.validate().is_ok());
+    /// assert_eq!(c.to_string(), "name=value; SameSite=None; Secure");
+    ///
+    /// c.set_secure(false);
+    /// assert_eq!(c.validate(), Err(ValidationError::InsecureSameSiteNone));
+    ///
+    /// let c = Cookie::parse("__Host-name=value; Secure; Path=/").unwrap();
+    /// assert!(c.validate().is_ok());
+    ///
+    /// let c = Cookie::parse("__Host-name=value; Secure; Path=/; Domain=a.com").unwrap();
+    /// assert_eq!(c.validate(), Err(ValidationError::InvalidHostPrefix));
+    ///
+    /// let c = Cookie::parse("__Secure-name=value; Path=/").unwrap();
+    /// assert_eq!(c.validate(), Err(ValidationError::InvalidSecurePrefix));
+    /// ```
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        let same_site_none = matches!(self.same_site, Some(SameSite::None));
+        let partitioned = self.partitioned() == Some(true);
+        if self.secure() == Some(false) {
+            if same_site_none {
+                return Err(ValidationError::InsecureSameSiteNone);
+            }
+
+            if partitioned {
+                return Err(ValidationError::InsecurePartitioned);
+            }
+        }
+
+        let secure = self.writes_secure();
+
+        let has_prefix = |prefix: &str| {
+            let name = self.name().as_bytes();
+            matches!(name.get(..prefix.len()), Some(p) if p.eq_ignore_ascii_case(prefix.as_bytes()))
+        };
+
+        if has_prefix("__Secure-") && !secure {
+            return Err(ValidationError::InvalidSecurePrefix);
+        }
+
+        let host_only = self.path() == Some("/") && self.domain().is_none();
+        if has_prefix("__Host-") && !(secure && host_only) {
+            return Err(ValidationError::InvalidHostPrefix);
+        }
+
+        Ok(())
+    }
 }
 
 /// https://url.spec.whatwg.org/#fragment-percent-encode-set
@@ -1131,6 +2515,15 @@ const USERINFO_ENCODE_SET: &AsciiSet = &PATH_ENCODE_SET
     .add(b'|')
     .add(b'%');
 
+/// The set of characters encoded by JavaScript's `encodeURIComponent`: all but
+/// ASCII alphanumerics and `-_.!~*'()`.
+#[cfg(feature = "percent-encode")]
+const COMPONENT_ENCODE_SET: &AsciiSet = &USERINFO_ENCODE_SET
+    .add(b'$')
+    .add(b'&')
+    .add(b'+')
+    .add(b',');
+
 /// Wrapper around `Cookie` whose `Display` implementation either
 /// percent-encodes the cookie's name and value, skips displaying the cookie's
 /// parameters (only displaying it's name and value), or both.
@@ -1156,41 +2549,50 @@ assert_eq!(&c.encoded().stripped().to_string(), "my%20name=this%3B%20value%25%3F
 pub struct Display<'a, 'c: 'a> {
     cookie: &'a Cookie<'c>,
     #[cfg(feature = "percent-encode")]
-    encode: bool,
+    encode: Option<&'static AsciiSet>,
     strip: bool,
+    options: DisplayOptions,
 }
 
 impl<'a, 'c: 'a> fmt::Display for Display<'a, 'c> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         #[cfg(feature = "percent-encode")] {
-            if self.encode {
-                let name = encode(self.cookie.name().as_bytes(), USERINFO_ENCODE_SET);
-                let value = encode(self.cookie.value().as_bytes(), USERINFO_ENCODE_SET);
-                write!(f, "{}={}", name, value)?;
+            if let Some(set) = self.encode {
+                let name = encode(self.cookie.name().as_bytes(), set);
+                let value = encode(self.cookie.value().as_bytes(), set);
+                self.cookie.fmt_name_value(f, name, value)?;
             } else {
-                write!(f, "{}={}", self.cookie.name(), self.cookie.value())?;
+                self.cookie.fmt_name_value(f, self.cookie.name(), self.cookie.value())?;
             }
         }
 
         #[cfg(not(feature = "percent-encode"))] {
-            write!(f, "{}={}", self.cookie.name(), self.cookie.value())?;
+            self.cookie.fmt_name_value(f, self.cookie.name(), self.cookie.value())?;
         }
 
         match self.strip {
             true => Ok(()),
-            false => self.cookie.fmt_parameters(f)
+            false => self.cookie.fmt_parameters(f, &self.options)
         }
     }
 }
 
 impl<'a, 'c> Display<'a, 'c> {
+    fn new_with(cookie: &'a Cookie<'c>, options: DisplayOptions) -> Self {
+        Display {
+            cookie, options,
+            strip: false,
+            #[cfg(feature = "percent-encode")] encode: None,
+        }
+    }
+
     #[cfg(feature = "percent-encode")]
     fn new_encoded(cookie: &'a Cookie<'c>) -> Self {
-        Display { cookie, strip: false, encode: true }
+        Display::new_with(cookie, DisplayOptions::new()).encoded()
     }
 
     fn new_stripped(cookie: &'a Cookie<'c>) -> Self {
-        Display { cookie, strip: true, #[cfg(feature = "percent-encode")] encode: false }
+        Display::new_with(cookie, DisplayOptions::new()).stripped()
     }
 
     /// Percent-encode the name and value pair.
@@ -1198,7 +2600,17 @@ impl<'a, 'c> Display<'a, 'c> {
     #[cfg(feature = "percent-encode")]
     #[cfg_attr(all(nightly, doc), doc(cfg(feature = "percent-encode")))]
     pub fn encoded(mut self) -> Self {
-        self.encode = true;
+        self.encode = Some(USERINFO_ENCODE_SET);
+        self
+    }
+
+    /// Percent-encode the name and value pair exactly as JavaScript's
+    /// `encodeURIComponent` does. See [`Cookie::encoded_component()`].
+    #[inline]
+    #[cfg(feature = "percent-encode")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "percent-encode")))]
+    pub fn encoded_component(mut self) -> Self {
+        self.encode = Some(COMPONENT_ENCODE_SET);
         self
     }
 
@@ -1228,8 +2640,7 @@ impl<'c> fmt::Display for Cookie<'c> {
     /// assert_eq!(&cookie.to_string(), "foo=bar; Path=/");
     /// ```
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}={}", self.name(), self.value())?;
-        self.fmt_parameters(f)
+        self.write_to(f)
     }
 }
 
@@ -1247,6 +2658,8 @@ impl<'a, 'b> PartialEq<Cookie<'b>> for Cookie<'a> {
             && self.value() == other.value()
             && self.http_only() == other.http_only()
             && self.secure() == other.secure()
+            && self.partitioned() == other.partitioned()
+            && self.priority() == other.priority()
             && self.max_age() == other.max_age()
             && self.expires() == other.expires();
 
@@ -1272,7 +2685,7 @@ impl<'a, 'b> PartialEq<Cookie<'b>> for Cookie<'a> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Cookie, SameSite, parse::parse_date};
+    use crate::{Cookie, Priority, SameSite, parse::parse_date};
     use time::{Duration, OffsetDateTime};
 
     #[test]
@@ -1329,6 +2742,109 @@ mod tests {
         assert_eq!(&cookie.to_string(), "foo=bar; SameSite=None");
         cookie.set_secure(true);
         assert_eq!(&cookie.to_string(), "foo=bar; SameSite=None; Secure");
+
+        let mut cookie = Cookie::build("foo", "bar")
+            .partitioned(true).finish();
+        assert_eq!(&cookie.to_string(), "foo=bar; Secure; Partitioned");
+        cookie.set_same_site(SameSite::None);
+        assert_eq!(&cookie.to_string(), "foo=bar; SameSite=None; Secure; Partitioned");
+        cookie.set_secure(false);
+        assert_eq!(&cookie.to_string(), "foo=bar; SameSite=None; Partitioned");
+        cookie.set_secure(true);
+        cookie.set_partitioned(false);
+        assert_eq!(&cookie.to_string(), "foo=bar; SameSite=None; Secure");
+
+        let mut cookie = Cookie::build("foo", "bar")
+            .priority(Priority::Medium).path("/").finish();
+        assert_eq!(&cookie.to_string(), "foo=bar; Path=/; Priority=Medium");
+        cookie.set_priority(None);
+        assert_eq!(&cookie.to_string(), "foo=bar; Path=/");
+    }
+
+    #[test]
+    fn format_request() {
+        let cookie = Cookie::parse("foo=bar baz; Path=/; HttpOnly; Priority=High").unwrap();
+        assert_eq!(cookie.to_request_string(), "foo=bar baz");
+        assert_eq!(Cookie::new("foo", "").to_request_string(), "foo=");
+
+        let cookie = Cookie::build("foo", "bar").max_age(Duration::ZERO).finish();
+        assert_eq!(cookie.to_request_string(), "foo=bar");
+        assert_eq!(cookie.to_string(), "foo=bar; Max-Age=0");
+    }
+
+    #[test]
+    fn format_writers() {
+        let expires = time::macros::datetime!(2015-10-21 7:28 UTC);
+        let cookie = Cookie::build("foo", "bar")
+            .expires(expires)
+            .same_site(SameSite::None)
+            .path("/")
+            .finish();
+
+        let expected = "foo=bar; SameSite=None; Secure; Path=/; \
+            Expires=Wed, 21 Oct 2015 07:28:00 GMT";
+
+        let mut string = String::new();
+        cookie.write_to(&mut string).unwrap();
+        assert_eq!(string, expected);
+        assert_eq!(cookie.to_string(), expected);
+        assert_eq!(cookie.serialized_len(), expected.len());
+
+        let mut buffer = vec![];
+        cookie.write_io(&mut buffer).unwrap();
+        cookie.write_io(&mut buffer).unwrap();
+        assert_eq!(buffer, format!("{}{}", expected, expected).as_bytes());
+
+        let mut full: &mut [u8] = &mut [0; 8];
+        assert!(cookie.write_io(&mut full).is_err());
+    }
+
+    #[test]
+    fn format_quoted() {
+        let cookie = Cookie::parse("foo=\"bar baz\"; Path=/").unwrap();
+        assert_eq!(cookie.value(), "bar baz");
+        assert_eq!(cookie.to_string(), "foo=\"bar baz\"; Path=/");
+        assert_eq!(cookie.to_request_string(), "foo=\"bar baz\"");
+        assert_eq!(cookie.serialized_len(), cookie.to_string().len());
+        assert!(cookie.into_owned().is_quoted());
+
+        let cookie = Cookie::parse("foo=\"\"").unwrap();
+        assert_eq!((cookie.value(), cookie.is_quoted()), ("", true));
+        assert_eq!(cookie.to_string(), "foo=\"\"");
+
+        for unquoted in ["foo=\"bar", "foo=bar\"", "foo=\""].iter() {
+            let cookie = Cookie::parse(*unquoted).unwrap();
+            assert!(!cookie.is_quoted());
+            assert_eq!(&cookie.to_string(), unquoted);
+        }
+
+        let mut cookie = Cookie::new("foo", "bar");
+        cookie.set_quoted_value("a, b");
+        assert_eq!(cookie, Cookie::new("foo", "a, b"));
+        assert_eq!(cookie.to_string(), "foo=\"a, b\"");
+
+        #[cfg(feature = "percent-encode")]
+        assert_eq!(cookie.encoded().to_string(), "foo=\"a,%20b\"");
+    }
+
+    #[test]
+    fn format_extensions() {
+        let mut cookie = Cookie::parse("foo=bar; X-Scope=app; Path=/; X-Flag; x-flag=1").unwrap();
+        assert_eq!(&cookie.to_string(), "foo=bar; Path=/; X-Scope=app; X-Flag; x-flag=1");
+
+        cookie.set_extension("x-scope", Some(String::from("api")));
+        cookie.set_extension("X-Other", None::<&str>);
+        let expected = "foo=bar; Path=/; X-Flag; x-flag=1; x-scope=api; X-Other";
+        assert_eq!(&cookie.to_string(), expected);
+        cookie.unset_extension("X-FLAG");
+        cookie.unset_extension("missing");
+        let cookie = cookie.into_owned();
+        assert_eq!(&cookie.to_string(), "foo=bar; Path=/; x-scope=api; X-Other");
+        assert_eq!(cookie.extensions().collect::<Vec<_>>(),
+            [("x-scope", Some("api")), ("X-Other", None)]);
+
+        let cookie = Cookie::parse(cookie.to_string()).unwrap();
+        assert_eq!(cookie.extensions().count(), 2);
     }
 
     #[test]
@@ -1399,4 +2915,147 @@ mod tests {
         let cookie = Cookie::parse_encoded(cookie_str).unwrap();
         assert_eq!(cookie.name_value(), ("foo !?=", "bar;; a"));
     }
+
+    #[test]
+    #[cfg(feature = "percent-encode")]
+    fn format_encoded_component() {
+        // Every printable ASCII character and a few others, as encoded by
+        // `encodeURIComponent` in a browser.
+        let value = " !\"#$%&'()*+,-./09:;<=>?@AZ[\\]^_`az{|}~é日😀";
+        let expected = "%20!%22%23%24%25%26'()*%2B%2C-.%2F09%3A%3B%3C%3D%3E%3F%40AZ%5B%5C%5D\
+            %5E_%60az%7B%7C%7D~%C3%A9%E6%97%A5%F0%9F%98%80";
+
+        let cookie = Cookie::build("a b", value).path("/").finish();
+        assert_eq!(cookie.encoded_component().to_string(), format!("a%20b={}; Path=/", expected));
+        assert_eq!(cookie.encoded().encoded_component().stripped().to_string(),
+            format!("a%20b={}", expected));
+
+        let parsed = Cookie::parse_encoded(cookie.encoded_component().to_string()).unwrap();
+        assert_eq!(parsed.name_value(), ("a b", value));
+
+        // The control characters, too.
+        let cookie = Cookie::new("c", "\u{0}\t\u{7f}");
+        assert_eq!(cookie.encoded_component().to_string(), "c=%00%09%7F");
+    }
+
+    #[test]
+    fn canonicalize() {
+        use crate::Expiration;
+        use time::macros::datetime;
+
+        // Every attribute a canonical cookie holds, for exact comparisons.
+        let parts = |c: &Cookie| format!("{:?} {:?}", (
+            c.name_value(), c.is_quoted(), c.expires(), c.max_age(), c.domain(),
+            c.domain_leading_dot(), c.path(),
+        ), (
+            c.secure(), c.http_only(), c.same_site(), c.partitioned(), c.priority(),
+            c.extensions().collect::<Vec<_>>(),
+        ));
+
+        let expires = datetime!(2015-10-21 7:28:00.5 +2);
+        let cookies = [
+            Cookie::new(" a ", " b "),
+            Cookie::new("a", "\"b\""),
+            Cookie::new("a", "\"b"),
+            Cookie::new("", "nameless"),
+            Cookie::parse("a=\"b c\"; Domain=..A.com; Path=/x ; Max-Age=-5").unwrap(),
+            Cookie::parse("a=b; Path=x; Domain=; Expires=Wed, 21 Oct 2015 07:28:00 GMT").unwrap(),
+            Cookie::build("a", "b").expires(expires).finish(),
+            Cookie::build("a", "b").expires(expires).max_age(Duration::milliseconds(1500)).finish(),
+            Cookie::build("a", "b").expires(Expiration::Session).finish(),
+            Cookie::build("a", "b").expires(datetime!(1500-01-01 0:00 UTC)).finish(),
+            Cookie::build("a", "b").secure(false).http_only(false).partitioned(false).finish(),
+            Cookie::build("a", "b").same_site(SameSite::None).finish(),
+            Cookie::build("a", "b").same_site(SameSite::None).secure(false).finish(),
+            Cookie::build("a", "b").partitioned(true).secure(false).finish(),
+            Cookie::build("a", "b").same_site(SameSite::Other(" NONE ".into())).finish(),
+            Cookie::build("a", "b").same_site(SameSite::Other("Sometimes".into())).finish(),
+            Cookie::build("a", "b").same_site(SameSite::Other(" ".into())).finish(),
+            Cookie::build("a", "b").priority(Priority::Low).http_only(true).finish(),
+            Cookie::build("a", "b").extension(" X ", Some(" y ")).extension("", Some("z"))
+                .extension("path", Some("/")).extension("Flag", None::<&str>)
+                .extension("Empty", Some("")).finish(),
+            Cookie::parse("a=b; Version=1; X=1; x=2; Secure; Priority=High").unwrap(),
+        ];
+
+        for cookie in cookies.iter() {
+            let canonical = cookie.canonicalize();
+            let string = canonical.to_string();
+            assert_eq!(parts(&canonical.canonicalize()), parts(&canonical));
+            if canonical.validate().is_err() {
+                continue;
+            }
+
+            let parsed = Cookie::parse(string.clone()).unwrap();
+            assert_eq!(parsed, canonical, "{}", string);
+            assert_eq!(parts(&parsed), parts(&canonical), "{}", string);
+            assert_eq!(parsed.canonicalize().to_string(), string);
+            assert_eq!(parsed.to_string(), string);
+        }
+
+        let canonical = |c: &Cookie| c.canonicalize().to_string();
+        assert_eq!(canonical(&cookies[0]), "a=b");
+        assert_eq!(canonical(&cookies[1]), "a=\"\"b\"\"");
+        assert_eq!(canonical(&cookies[4]), "a=b c; Path=/x; Domain=a.com; Max-Age=0");
+        assert_eq!(canonical(&cookies[5]), "a=b; Expires=Wed, 21 Oct 2015 07:28:00 GMT");
+        assert_eq!(canonical(&cookies[6]), "a=b; Expires=Wed, 21 Oct 2015 05:28:00 GMT");
+        assert_eq!(canonical(&cookies[7]), "a=b; Max-Age=1");
+        assert_eq!(canonical(&cookies[8]), "a=b");
+        assert_eq!(canonical(&cookies[9]), "a=b; Expires=Mon, 01 Jan 1601 00:00:00 GMT");
+        assert_eq!(canonical(&cookies[10]), "a=b");
+        assert_eq!(cookies[12].canonicalize().secure(), Some(false));
+        assert_eq!(canonical(&cookies[12]), "a=b; SameSite=None");
+        assert_eq!(canonical(&cookies[14]), canonical(&cookies[11]));
+        assert_eq!(canonical(&cookies[16]), "a=b");
+        assert_eq!(canonical(&cookies[18]), "a=b; X=y; Flag; Empty=");
+    }
+
+    #[test]
+    fn effective_expiration() {
+        use time::macros::datetime;
+
+        let now = datetime!(2015-10-21 7:28 UTC);
+        let expires = datetime!(2015-10-21 8:28 +02:00);
+        let expiration = |max_age: Option<Duration>, expires: Option<OffsetDateTime>| {
+            let mut cookie = Cookie::new("a", "b");
+            cookie.max_age = max_age;
+            cookie.expires = expires.map(Into::into);
+            cookie.effective_expiration(now)
+        };
+
+        assert_eq!(expiration(None, None), None);
+        assert_eq!(expiration(None, Some(expires)), Some(expires));
+        assert_eq!(expiration(Some(Duration::seconds(1)), None), Some(now + Duration::seconds(1)));
+        let max_age = Some(Duration::days(1));
+        assert_eq!(expiration(max_age, Some(expires)), Some(now + Duration::days(1)));
+        assert_eq!(expiration(Some(Duration::ZERO), Some(expires)), Some(now));
+        assert_eq!(expiration(Some(Duration::MIN), None), Some(now));
+        assert_eq!(expiration(Some(Duration::MAX), None), Some(datetime!(9999-12-31 23:59:59 UTC)));
+
+        // Session cookies never expire, unless they have a `Max-Age`.
+        let mut cookie = Cookie::build("a", "b").expires(None).finish();
+        assert_eq!(cookie.effective_expiration(now), None);
+        cookie.set_max_age(Duration::hours(1));
+        assert_eq!(cookie.effective_expiration(now), Some(now + Duration::hours(1)));
+
+        // Expired and removal cookies expire no later than now.
+        let mut cookie = Cookie::new("a", "b");
+        cookie.make_removal();
+        let now = OffsetDateTime::now_utc();
+        assert!(cookie.effective_expiration(now).unwrap() <= now);
+    }
+
+    #[test]
+    fn expires_in() {
+        let cookie = Cookie::build("a", "b").expires_in(Duration::hours(-1)).finish();
+        assert!(cookie.is_removal());
+        let expected = OffsetDateTime::now_utc() - Duration::hours(1);
+        assert!((expected - cookie.expires_datetime().unwrap()).abs() < Duration::minutes(1));
+
+        let cookie = Cookie::build("a", "b").expires_in(Duration::MAX).finish();
+        assert_eq!(cookie.expires_datetime().unwrap().year(), 9999);
+        let cookie = Cookie::build("a", "b").expires_in(Duration::MIN).finish();
+        assert!(cookie.to_string().ends_with("Expires=Mon, 01 Jan 1601 00:00:00 GMT"));
+        assert_eq!(Cookie::parse(cookie.to_string()).unwrap(), cookie);
+    }
 }
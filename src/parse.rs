@@ -3,34 +3,72 @@ use std::error::Error;
 use std::convert::{From, TryFrom};
 use std::str::Utf8Error;
 use std::fmt;
+use std::ops::Range;
 
 #[allow(unused_imports, deprecated)]
 use std::ascii::AsciiExt;
 
 #[cfg(feature = "percent-encode")]
 use percent_encoding::percent_decode;
-use time::{PrimitiveDateTime, Duration, OffsetDateTime};
+use time::{Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, Time};
 use time::{parsing::Parsable, macros::format_description, format_description::FormatItem};
 
-use crate::{Cookie, SameSite, CookieStr};
+use crate::{Cookie, SameSite, Priority, CookieStr};
+use crate::encoding::Alphabet;
 
-// The three formats spec'd in http://tools.ietf.org/html/rfc2616#section-3.3.1.
-// Additional ones as encountered in the real world.
+// The preferred format of dates spec'd in RFC 7231 section 7.1.1.1, which is
+// the only one accepted by strict parsing and the one dates are written in.
+// Others are parsed with the RFC 6265 algorithm; see `parse_cookie_date()`.
 pub static FMT1: &[FormatItem<'_>] = format_description!("[weekday repr:short], [day] [month repr:short] [year padding:none] [hour]:[minute]:[second] GMT");
-pub static FMT2: &[FormatItem<'_>] = format_description!("[weekday], [day]-[month repr:short]-[year repr:last_two] [hour]:[minute]:[second] GMT");
-pub static FMT3: &[FormatItem<'_>] = format_description!("[weekday repr:short] [month repr:short] [day padding:space] [hour]:[minute]:[second] [year padding:none]");
-pub static FMT4: &[FormatItem<'_>] = format_description!("[weekday repr:short], [day]-[month repr:short]-[year padding:none] [hour]:[minute]:[second] GMT");
 
 /// Enum corresponding to a parsing error.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 #[non_exhaustive]
 pub enum ParseError {
-    /// The cookie did not contain a name/value pair.
+    /// The cookie did not contain a name/value pair: it was empty or, in strict
+    /// parsing, it had no `=`.
     MissingPair,
-    /// The cookie's name was empty.
+    /// The cookie's name was empty and, unless parsing strictly, so was its
+    /// value.
     EmptyName,
     /// Decoding the cookie's name or value resulted in invalid UTF-8.
     Utf8Error(Utf8Error),
+    /// In strict parsing, the cookie's name is not a valid token. The
+    /// disallowed character is at byte offset `at` and is `len` bytes long.
+    InvalidName {
+        /// The byte offset of the disallowed character.
+        at: usize,
+        /// The length, in bytes, of the disallowed character.
+        len: usize,
+    },
+    /// In strict parsing, or with [`ValueRecovery::Reject`], the cookie's
+    /// value contains a disallowed character, at byte offset `at` and `len`
+    /// bytes long. With [`ParseOptions::binary_value()`], the character at
+    /// `at` keeps the value from being URL-safe, unpadded Base64.
+    InvalidValue {
+        /// The byte offset of the disallowed character.
+        at: usize,
+        /// The length, in bytes, of the disallowed character.
+        len: usize,
+    },
+    /// In strict parsing, an attribute is malformed or has an invalid value.
+    /// The attribute, as written between `;`s, starts at byte offset `at`
+    /// and is `len` bytes long.
+    InvalidAttribute {
+        /// The byte offset of the attribute.
+        at: usize,
+        /// The length, in bytes, of the attribute.
+        len: usize,
+    },
+    /// In strict parsing, or with [`DuplicatePolicy::Reject`], an attribute
+    /// appears more than once. The repeated attribute, as written between
+    /// `;`s, starts at byte offset `at` and is `len` bytes long.
+    DuplicateAttribute {
+        /// The byte offset of the repeated attribute.
+        at: usize,
+        /// The length, in bytes, of the repeated attribute.
+        len: usize,
+    },
 }
 
 impl ParseError {
@@ -42,13 +80,66 @@ impl ParseError {
             ParseError::Utf8Error(_) => {
                 "decoding the cookie's name or value resulted in invalid UTF-8"
             }
+            ParseError::InvalidName { .. } => "the cookie's name is not a valid token",
+            ParseError::InvalidValue { .. } => "the cookie's value contains an invalid character",
+            ParseError::InvalidAttribute { .. } => "the cookie has a malformed attribute",
+            ParseError::DuplicateAttribute { .. } => "the cookie has a duplicate attribute",
         }
     }
+
+    /// Returns the byte range of the parsed string that caused this error, if
+    /// this error is associated with one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Cookie;
+    ///
+    /// let string = "foo=bar; Path=/; Max-Age=soon";
+    /// let error = Cookie::parse_strict(string).unwrap_err();
+    /// assert_eq!(error.span(), Some(16..29));
+    /// assert_eq!(&string[error.span().unwrap()], " Max-Age=soon");
+    ///
+    /// let error = Cookie::parse_strict("foo").unwrap_err();
+    /// assert_eq!(error.span(), None);
+    /// ```
+    pub fn span(&self) -> Option<Range<usize>> {
+        match *self {
+            ParseError::InvalidName { at, len }
+            | ParseError::InvalidValue { at, len }
+            | ParseError::InvalidAttribute { at, len }
+            | ParseError::DuplicateAttribute { at, len } => Some(at..(at + len)),
+            _ => None,
+        }
+    }
+
+    /// Returns the slice of `string`, the string that failed to parse, that
+    /// caused this error, if this error is associated with one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Cookie;
+    ///
+    /// let string = "foo=bar; Secure; HttpOnly; secure";
+    /// let error = Cookie::parse_strict(string).unwrap_err();
+    /// assert_eq!(error.offending(string), Some(" secure"));
+    ///
+    /// let string = "fo o=bar";
+    /// let error = Cookie::parse_strict(string).unwrap_err();
+    /// assert_eq!(error.offending(string), Some(" "));
+    /// ```
+    pub fn offending<'a>(&self, string: &'a str) -> Option<&'a str> {
+        string.get(self.span()?)
+    }
 }
 
 impl fmt::Display for ParseError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.as_str())
+        match self.span() {
+            Some(span) => write!(f, "{} at byte {}", self.as_str(), span.start),
+            None => write!(f, "{}", self.as_str()),
+        }
     }
 }
 
@@ -64,6 +155,317 @@ impl Error for ParseError {
     }
 }
 
+/// The longest lifetime browsers allow a cookie: Chrome clamps `Max-Age` and
+/// `Expires` to 400 days in the future.
+///
+/// See [`ParseOptions::max_age_limit()`] and [`Cookie::effective_max_age()`].
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::MAX_AGE_LIMIT;
+/// use cookie::time::Duration;
+///
+/// assert_eq!(MAX_AGE_LIMIT, Duration::days(400));
+/// ```
+pub const MAX_AGE_LIMIT: Duration = Duration::days(400);
+
+/// Which value of an attribute that appears more than once in a cookie string
+/// is used when parsing with [`ParseOptions`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum DuplicatePolicy {
+    /// The last valid occurrence of the attribute is used, as browsers do.
+    /// This is the default.
+    LastWins,
+    /// The first valid occurrence of the attribute is used.
+    FirstWins,
+    /// The cookie fails to parse with [`ParseError::DuplicateAttribute`] if
+    /// an attribute appears again after a valid occurrence.
+    Reject,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for DuplicatePolicy {
+    fn default() -> Self {
+        DuplicatePolicy::LastWins
+    }
+}
+
+/// How a cookie value that illegally contains a `;`, a `,`, or another
+/// character outside the `cookie-octet` grammar of RFC 6265 is handled when
+/// parsing with [`ParseOptions`].
+///
+/// A `;` ends the name/value pair, so a value such as `a;b` is read as the
+/// value `a` followed by an attribute `b`. When recovering values, each
+/// following segment is instead read as part of the value if it continues it:
+/// if it contains no `=`, doesn't start with whitespace, and isn't the name of
+/// a standard attribute. In `token=a;b;c; Path=/`, for instance, the value
+/// continues up to the last such boundary, `a;b;c`, while in
+/// `name=value; X-Flag` or `name=value;Secure`, the value is `value`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum ValueRecovery {
+    /// The value ends at the first `;`, and any other characters are kept.
+    /// This is the default.
+    Truncate,
+    /// The value continues over the segments that continue it, as described
+    /// above, and any other characters are kept.
+    Extend,
+    /// The cookie fails to parse with [`ParseError::InvalidValue`], pointing
+    /// at the first disallowed character, if the value, including segments
+    /// that continue it, contains a character that isn't a `cookie-octet`.
+    Reject,
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for ValueRecovery {
+    fn default() -> Self {
+        ValueRecovery::Truncate
+    }
+}
+
+/// How bytes that aren't valid UTF-8 are handled when parsing a cookie from
+/// raw header bytes with [`Cookie::parse_bytes()`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum Utf8Policy {
+    /// Bytes that aren't valid UTF-8 are an error: [`ParseError::Utf8Error`].
+    Strict,
+    /// Invalid UTF-8 sequences are replaced with `U+FFFD REPLACEMENT
+    /// CHARACTER`, as by [`String::from_utf8_lossy()`].
+    Lossy,
+}
+
+/// Options controlling how a cookie string is parsed by
+/// [`Cookie::parse_with()`].
+///
+/// The default options are those of [`Cookie::parse()`]: no percent-decoding,
+/// lenient parsing, the last occurrence of a duplicate attribute winning,
+/// unknown attributes preserved, and values ending at the first `;`.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{Cookie, DuplicatePolicy, ParseOptions};
+///
+/// let options = ParseOptions::new()
+///     .duplicates(DuplicatePolicy::FirstWins)
+///     .preserve_unknown(false);
+///
+/// let c = Cookie::parse_with("name=value; Path=/a; Path=/b; X-Flag", &options).unwrap();
+/// assert_eq!(c.path(), Some("/a"));
+/// assert_eq!(c.to_string(), "name=value; Path=/a");
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ParseOptions {
+    decode: bool,
+    strict: bool,
+    duplicates: DuplicatePolicy,
+    preserve_unknown: bool,
+    max_age_limit: Option<Duration>,
+    value_recovery: ValueRecovery,
+    binary_value: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            decode: false,
+            strict: false,
+            duplicates: DuplicatePolicy::default(),
+            preserve_unknown: true,
+            max_age_limit: None,
+            value_recovery: ValueRecovery::default(),
+            binary_value: false,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Returns the default options, those of [`Cookie::parse()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, ParseOptions};
+    ///
+    /// let c = Cookie::parse_with("foo=bar%20baz; HttpOnly", &ParseOptions::new()).unwrap();
+    /// assert_eq!(c, Cookie::parse("foo=bar%20baz; HttpOnly").unwrap());
+    /// ```
+    pub fn new() -> ParseOptions {
+        ParseOptions::default()
+    }
+
+    /// Sets whether the cookie's name and value are percent-decoded, as by
+    /// [`Cookie::parse_encoded()`]. Disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, ParseOptions};
+    ///
+    /// let options = ParseOptions::new().percent_decode(true);
+    /// let c = Cookie::parse_with("foo=bar%20baz", &options).unwrap();
+    /// assert_eq!(c.value(), "bar baz");
+    /// ```
+    #[cfg(feature = "percent-encode")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "percent-encode")))]
+    pub fn percent_decode(mut self, decode: bool) -> Self {
+        self.decode = decode;
+        self
+    }
+
+    /// Sets whether strings that don't conform to the `Set-Cookie` grammar
+    /// are rejected, as by [`Cookie::parse_strict()`]. Strict parsing always
+    /// rejects duplicate attributes. Disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, ParseError, ParseOptions};
+    ///
+    /// let options = ParseOptions::new().strict(true);
+    /// let error = Cookie::parse_with("foo=bar;HttpOnly", &options).unwrap_err();
+    /// assert_eq!(error, ParseError::InvalidAttribute { at: 8, len: 8 });
+    /// ```
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Sets which occurrence of an attribute that appears more than once is
+    /// used. Occurrences with invalid values are ignored, and only the
+    /// attributes this crate recognizes are affected: repeated
+    /// [extensions](Cookie::extensions()) are all kept. The default is
+    /// [`DuplicatePolicy::LastWins`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, DuplicatePolicy, ParseError, ParseOptions};
+    ///
+    /// let cookie = "name=value; Max-Age=10; Max-Age=x; Max-Age=20";
+    /// let c = Cookie::parse_with(cookie, &ParseOptions::new()).unwrap();
+    /// assert_eq!(c.max_age().unwrap().whole_seconds(), 20);
+    ///
+    /// let options = ParseOptions::new().duplicates(DuplicatePolicy::FirstWins);
+    /// let c = Cookie::parse_with(cookie, &options).unwrap();
+    /// assert_eq!(c.max_age().unwrap().whole_seconds(), 10);
+    ///
+    /// let options = ParseOptions::new().duplicates(DuplicatePolicy::Reject);
+    /// let error = Cookie::parse_with(cookie, &options).unwrap_err();
+    /// assert_eq!(error.offending(cookie), Some(" Max-Age=x"));
+    /// ```
+    pub fn duplicates(mut self, policy: DuplicatePolicy) -> Self {
+        self.duplicates = policy;
+        self
+    }
+
+    /// Sets whether attributes this crate doesn't recognize are kept in the
+    /// parsed cookie, in order, as its [extensions](Cookie::extensions()), and
+    /// written out again when the cookie is displayed, as are unrecognized
+    /// `SameSite` values as [`SameSite::Other`]. Enabled by default; when
+    /// disabled, unknown attributes and `SameSite` values are discarded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, ParseOptions};
+    ///
+    /// let cookie = "name=value; Secure; X-Scope=app; X-Flag";
+    /// let c = Cookie::parse_with(cookie, &ParseOptions::new()).unwrap();
+    /// assert_eq!(c.to_string(), "name=value; Secure; X-Scope=app; X-Flag");
+    ///
+    /// let options = ParseOptions::new().preserve_unknown(false);
+    /// let c = Cookie::parse_with(cookie, &options).unwrap();
+    /// assert_eq!(c.to_string(), "name=value; Secure");
+    /// ```
+    pub fn preserve_unknown(mut self, preserve: bool) -> Self {
+        self.preserve_unknown = preserve;
+        self
+    }
+
+    /// Sets the limit, if any, that parsed `Max-Age` values are clamped to, as
+    /// browsers do. [`MAX_AGE_LIMIT`] is the limit Chrome enforces. By default,
+    /// there is no limit, and values too large to represent are clamped to
+    /// the largest representable duration.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, ParseOptions, MAX_AGE_LIMIT};
+    /// use cookie::time::Duration;
+    ///
+    /// let cookie = "name=value; Max-Age=100000000";
+    /// let c = Cookie::parse(cookie).unwrap();
+    /// assert_eq!(c.max_age(), Some(Duration::seconds(100000000)));
+    ///
+    /// let options = ParseOptions::new().max_age_limit(MAX_AGE_LIMIT);
+    /// let c = Cookie::parse_with(cookie, &options).unwrap();
+    /// assert_eq!(c.max_age(), Some(Duration::days(400)));
+    ///
+    /// let options = ParseOptions::new().max_age_limit(Duration::hours(1));
+    /// let c = Cookie::parse_with("name=value; Max-Age=60", &options).unwrap();
+    /// assert_eq!(c.max_age(), Some(Duration::minutes(1)));
+    /// ```
+    pub fn max_age_limit<L: Into<Option<Duration>>>(mut self, limit: L) -> Self {
+        self.max_age_limit = limit.into();
+        self
+    }
+
+    /// Sets how a value that illegally contains a `;`, a `,`, or another
+    /// character that isn't allowed in a cookie value is handled. The default
+    /// is [`ValueRecovery::Truncate`]. Strict parsing always rejects such
+    /// values.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, ParseError, ParseOptions, ValueRecovery};
+    ///
+    /// let cookie = "token=a,b;c; Path=/";
+    /// let c = Cookie::parse_with(cookie, &ParseOptions::new()).unwrap();
+    /// assert_eq!(c.value(), "a,b");
+    ///
+    /// let options = ParseOptions::new().value_recovery(ValueRecovery::Extend);
+    /// let c = Cookie::parse_with(cookie, &options).unwrap();
+    /// assert_eq!(c.value(), "a,b;c");
+    /// assert_eq!(c.path(), Some("/"));
+    ///
+    /// let options = ParseOptions::new().value_recovery(ValueRecovery::Reject);
+    /// let error = Cookie::parse_with(cookie, &options).unwrap_err();
+    /// assert_eq!(error, ParseError::InvalidValue { at: 7, len: 1 });
+    /// assert_eq!(error.offending(cookie), Some(","));
+    /// ```
+    pub fn value_recovery(mut self, recovery: ValueRecovery) -> Self {
+        self.value_recovery = recovery;
+        self
+    }
+
+    /// Sets whether the cookie's value must be URL-safe, unpadded Base64, as
+    /// written by [`Cookie::new_binary()`], so that
+    /// [`Cookie::value_bytes()`] succeeds on every parsed cookie. The value is
+    /// checked before any percent-decoding. Disabled by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, ParseError, ParseOptions};
+    ///
+    /// let options = ParseOptions::new().binary_value(true);
+    /// let c = Cookie::parse_with("token=-_8A; Path=/", &options).unwrap();
+    /// assert_eq!(c.value_bytes().unwrap(), [0xfb, 0xff, 0x00]);
+    ///
+    /// let error = Cookie::parse_with("token=+/8A", &options).unwrap_err();
+    /// assert_eq!(error, ParseError::InvalidValue { at: 6, len: 1 });
+    /// ```
+    pub fn binary_value(mut self, binary: bool) -> Self {
+        self.binary_value = binary;
+        self
+    }
+}
+
 fn indexes_of(needle: &str, haystack: &str) -> Option<(usize, usize)> {
     let haystack_start = haystack.as_ptr() as usize;
     let needle_start = needle.as_ptr() as usize;
@@ -117,27 +519,119 @@ fn trim_quotes(s: &str) -> &str {
     }
 }
 
+/// Returns the lowercase name of the standard attribute `key`, compared
+/// case-insensitively, or `None` if `key` isn't a standard attribute.
+pub(crate) fn attribute_name(key: &str) -> Option<&'static str> {
+    const NAMES: [&str; 9] = [
+        "secure", "httponly", "partitioned", "max-age", "domain", "path", "samesite", "expires",
+        "priority",
+    ];
+
+    NAMES.iter().copied().find(|name| key.eq_ignore_ascii_case(name))
+}
+
+/// Returns `true` if `segment`, as written between `;`s after a cookie's
+/// name/value pair, continues its value when recovering values: it contains no
+/// `=`, doesn't start with whitespace, and isn't a standard attribute.
+fn continues_value(segment: &str) -> bool {
+    !segment.is_empty()
+        && !segment.contains('=')
+        && !segment.starts_with(char::is_whitespace)
+        && attribute_name(segment.trim()).is_none()
+}
+
+/// Parses the value of a `SameSite` attribute, compared case-insensitively.
+/// Values we don't recognize, which future drafts may define, are kept as
+/// `SameSite::Other` so that they're written out again as received.
+pub(crate) fn parse_same_site(value: &str) -> Option<SameSite> {
+    match known_same_site(value) {
+        Some(same_site) => Some(same_site),
+        None if !value.is_empty() => Some(SameSite::Other(value.to_string())),
+        None => None,
+    }
+}
+
+/// Parses the value of a `SameSite` attribute if it is one we recognize.
+fn known_same_site(value: &str) -> Option<SameSite> {
+    if value.eq_ignore_ascii_case("strict") {
+        Some(SameSite::Strict)
+    } else if value.eq_ignore_ascii_case("lax") {
+        Some(SameSite::Lax)
+    } else if value.eq_ignore_ascii_case("none") {
+        Some(SameSite::None)
+    } else {
+        None
+    }
+}
+
+/// Parses the value of a `Priority` attribute, compared case-insensitively.
+fn parse_priority(value: &str) -> Option<Priority> {
+    if value.eq_ignore_ascii_case("low") {
+        Some(Priority::Low)
+    } else if value.eq_ignore_ascii_case("medium") {
+        Some(Priority::Medium)
+    } else if value.eq_ignore_ascii_case("high") {
+        Some(Priority::High)
+    } else {
+        None
+    }
+}
+
 // This function does the real parsing but _does not_ set the `cookie_string` in
 // the returned cookie object. This only exists so that the borrow to `s` is
 // returned at the end of the call, allowing the `cookie_string` field to be
 // set in the outer `parse` function.
-fn parse_inner<'c>(s: &str, decode: bool) -> Result<Cookie<'c>, ParseError> {
-    let mut attributes = s.split(';');
+fn parse_inner<'c>(s: &str, options: &ParseOptions) -> Result<Cookie<'c>, ParseError> {
+    if options.strict {
+        check_strict(s)?;
+    }
+
+    // Find the end of the name/value pair: the first `;` or, when recovering
+    // values, the last `;` before a segment that doesn't continue the value.
+    let mut end = s.find(';').unwrap_or(s.len());
+    if options.value_recovery != ValueRecovery::Truncate {
+        while end < s.len() {
+            let next = s[(end + 1)..].find(';').map_or(s.len(), |i| end + 1 + i);
+            if !continues_value(&s[(end + 1)..next]) {
+                break;
+            }
+
+            end = next;
+        }
+    }
 
     // Determine the name = val.
-    let key_value = attributes.next().expect("first str::split().next() returns Some");
+    // As in browsers, a pair without an `=` is a nameless cookie: its value is
+    // the entire pair. Strict parsing has already rejected these above.
+    let key_value = &s[..end];
+    let attributes = s[end..].split(';').skip(1);
     let (name, value) = match key_value.find('=') {
-        Some(i) => {
-            let (key, value) = (key_value[..i].trim(), key_value[(i + 1)..].trim());
-            (key, trim_quotes(value).trim())
-        },
-        None => return Err(ParseError::MissingPair)
+        Some(i) => (key_value[..i].trim(), key_value[(i + 1)..].trim()),
+        None if key_value.trim().is_empty() => return Err(ParseError::MissingPair),
+        None => (&key_value[..0], key_value.trim()),
     };
 
-    if name.is_empty() {
+    let unquoted = trim_quotes(value);
+    let (value, quoted) = (unquoted.trim(), unquoted.len() != value.len());
+    if name.is_empty() && value.is_empty() && !quoted {
         return Err(ParseError::EmptyName);
     }
 
+    if options.value_recovery == ValueRecovery::Reject {
+        if let Some((i, c)) = value.char_indices().find(|&(_, c)| !is_cookie_octet(c)) {
+            let at = indexes_of(value, s).expect("value sub").0 + i;
+            return Err(ParseError::InvalidValue { at, len: c.len_utf8() });
+        }
+    }
+
+    if options.binary_value {
+        if let Some(i) = crate::encoding::invalid_at(value, Alphabet::UrlSafe) {
+            let at = indexes_of(value, s).expect("value sub").0 + i;
+            let len = value[i..].chars().next().map_or(1, char::len_utf8);
+            return Err(ParseError::InvalidValue { at, len });
+        }
+    }
+
     // If there is nothing to decode, or we're not decoding, use indexes.
     let indexed_names = |s, name, value| {
         let name_indexes = indexes_of(name, s).expect("name sub");
@@ -149,7 +643,7 @@ fn parse_inner<'c>(s: &str, decode: bool) -> Result<Cookie<'c>, ParseError> {
 
     // Create a cookie with all of the defaults. We'll fill things in while we
     // iterate through the parameters below.
-    let (name, value) = if decode {
+    let (name, value) = if options.decode {
         match name_val_decoded(name, value)? {
             Some((name, value)) => (name, value),
             None => indexed_names(s, name, value)
@@ -167,40 +661,69 @@ fn parse_inner<'c>(s: &str, decode: bool) -> Result<Cookie<'c>, ParseError> {
         path: None,
         secure: None,
         http_only: None,
-        same_site: None
+        same_site: None,
+        partitioned: None,
+        priority: None,
+        extensions: vec![],
+        quoted,
+        domain_dot: false,
     };
 
+    // The names of the attributes that have been set so far. Only tracked, and
+    // thus only allocated, when the last occurrence of an attribute doesn't win.
+    let track_seen = options.duplicates != DuplicatePolicy::LastWins;
+    let mut seen: Vec<&'static str> = vec![];
     for attr in attributes {
         let (key, value) = match attr.find('=') {
             Some(i) => (attr[..i].trim(), Some(attr[(i + 1)..].trim())),
             None => (attr.trim(), None),
         };
 
-        match (&*key.to_ascii_lowercase(), value) {
+        // Only recognized attributes are duplicates: empty segments and
+        // extensions are never tracked, so they may repeat.
+        let name = attribute_name(key);
+        if track_seen && matches!(name, Some(name) if seen.contains(&name)) {
+            match options.duplicates {
+                DuplicatePolicy::Reject => {
+                    let (at, end) = indexes_of(attr, s).expect("attribute sub");
+                    return Err(ParseError::DuplicateAttribute { at, len: end - at });
+                }
+                _ => continue,
+            }
+        }
+
+        match (name.unwrap_or(""), value) {
             ("secure", _) => cookie.secure = Some(true),
             ("httponly", _) => cookie.http_only = Some(true),
+            ("partitioned", _) => cookie.partitioned = Some(true),
             ("max-age", Some(mut v)) => cookie.max_age = {
                 let is_negative = v.starts_with('-');
                 if is_negative {
                     v = &v[1..];
                 }
 
-                if !v.chars().all(|d| d.is_digit(10)) {
+                if v.is_empty() || !v.chars().all(|d| d.is_ascii_digit()) {
                     continue
                 }
 
                 // From RFC 6265 5.2.2: neg values indicate that the earliest
                 // expiration should be used, so set the max age to 0 seconds.
-                if is_negative {
-                    Some(Duration::ZERO)
+                let max_age = if is_negative {
+                    Duration::ZERO
                 } else {
-                    Some(v.parse::<i64>()
+                    v.parse::<i64>()
                         .map(Duration::seconds)
-                        .unwrap_or_else(|_| Duration::seconds(i64::max_value())))
+                        .unwrap_or_else(|_| Duration::seconds(i64::MAX))
+                };
+
+                match options.max_age_limit {
+                    Some(limit) => Some(max_age.min(limit)),
+                    None => Some(max_age),
                 }
             },
             ("domain", Some(mut domain)) if !domain.is_empty() => {
-                if domain.starts_with('.') {
+                cookie.domain_dot = domain.starts_with('.');
+                if cookie.domain_dot {
                     domain = &domain[1..];
                 }
 
@@ -211,32 +734,36 @@ fn parse_inner<'c>(s: &str, decode: bool) -> Result<Cookie<'c>, ParseError> {
                 let (i, j) = indexes_of(v, s).expect("path sub");
                 cookie.path = Some(CookieStr::Indexed(i, j));
             }
-            ("samesite", Some(v)) => {
-                if v.eq_ignore_ascii_case("strict") {
-                    cookie.same_site = Some(SameSite::Strict);
-                } else if v.eq_ignore_ascii_case("lax") {
-                    cookie.same_site = Some(SameSite::Lax);
-                } else if v.eq_ignore_ascii_case("none") {
-                    cookie.same_site = Some(SameSite::None);
-                } else {
-                    // We do nothing here, for now. When/if the `SameSite`
-                    // attribute becomes standard, the spec says that we should
-                    // ignore this cookie, i.e, fail to parse it, when an
-                    // invalid value is passed in. The draft is at
-                    // http://httpwg.org/http-extensions/draft-ietf-httpbis-cookie-same-site.html.
-                }
-            }
+            // An unrecognized value never overrides a recognized one and, since
+            // it must be allocated, is only kept when preserving unknowns.
+            ("samesite", Some(v)) => match known_same_site(v) {
+                Some(same_site) => cookie.same_site = Some(same_site),
+                None if v.is_empty() || !options.preserve_unknown => continue,
+                None => match cookie.same_site {
+                    Some(SameSite::Strict | SameSite::Lax | SameSite::None) => continue,
+                    _ => cookie.same_site = Some(SameSite::Other(v.to_string())),
+                },
+            },
             ("expires", Some(v)) => {
-                let tm = parse_date(v, &FMT1)
-                    .or_else(|_| parse_date(v, &FMT2))
-                    .or_else(|_| parse_date(v, &FMT3))
-                    .or_else(|_| parse_date(v, &FMT4));
-                    // .or_else(|_| parse_date(v, &FMT5));
-
-                if let Ok(time) = tm {
-                    cookie.expires = Some(time.into())
+                match parse_cookie_date(v) {
+                    Some(time) => cookie.expires = Some(time.into()),
+                    None => continue,
                 }
             }
+            ("priority", Some(v)) => match parse_priority(v) {
+                Some(priority) => cookie.priority = Some(priority),
+                None => continue,
+            },
+            ("max-age" | "domain" | "path" | "samesite" | "expires" | "priority", _) => continue,
+            _ if options.preserve_unknown && !key.is_empty() => {
+                let (i, j) = indexes_of(key, s).expect("attribute name sub");
+                let value = value.map(|v| {
+                    let (i, j) = indexes_of(v, s).expect("attribute value sub");
+                    CookieStr::Indexed(i, j)
+                });
+
+                cookie.extensions.push((CookieStr::Indexed(i, j), value));
+            }
             _ => {
                 // We're going to be permissive here. If we have no idea what
                 // this is, then it's something nonstandard. We're not going to
@@ -244,22 +771,198 @@ fn parse_inner<'c>(s: &str, decode: bool) -> Result<Cookie<'c>, ParseError> {
                 // going to emit an error.
             }
         }
+
+        if let (true, Some(name)) = (track_seen, name) {
+            seen.push(name);
+        }
     }
 
     Ok(cookie)
 }
 
-pub(crate) fn parse_cookie<'c, S>(cow: S, decode: bool) -> Result<Cookie<'c>, ParseError>
+pub(crate) fn parse_cookie<'c, S>(cow: S, options: &ParseOptions) -> Result<Cookie<'c>, ParseError>
     where S: Into<Cow<'c, str>>
 {
     let s = cow.into();
-    let mut cookie = parse_inner(&s, decode)?;
+    let mut cookie = parse_inner(&s, options)?;
     cookie.cookie_string = Some(s);
     Ok(cookie)
 }
 
+/// Iterator over the cookies in a request `Cookie` header value.
+///
+/// The header value is split at each `;`; each segment is then parsed as a
+/// single cookie with the parsing options of the iterator. Segments that are
+/// empty or contain only whitespace, as produced by a trailing or doubled `;`,
+/// are skipped, and whitespace around names and values is trimmed. Since each
+/// segment is parsed independently, a malformed segment yields an `Err` without
+/// affecting the segments around it. A segment without a name yields a nameless
+/// cookie; see [`Cookie::parse()`].
+///
+/// Returned by [`Cookie::split_parse()`] and [`Cookie::split_parse_encoded()`].
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{Cookie, ParseError};
+///
+/// let mut cookies = Cookie::split_parse("name=value;; other =thing ; bare; =;");
+/// assert_eq!(cookies.next().unwrap().unwrap().name_value(), ("name", "value"));
+/// assert_eq!(cookies.next().unwrap().unwrap().name_value(), ("other", "thing"));
+/// assert_eq!(cookies.next().unwrap().unwrap().name_value(), ("", "bare"));
+/// assert_eq!(cookies.next().unwrap().unwrap_err(), ParseError::EmptyName);
+/// assert!(cookies.next().is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct SplitCookies<'c> {
+    string: Cow<'c, str>,
+    last: usize,
+    options: ParseOptions,
+}
+
+impl<'c> SplitCookies<'c> {
+    /// Creates a new iterator over the cookies in `string`, each parsed with
+    /// `options`.
+    pub(crate) fn new(string: Cow<'c, str>, options: ParseOptions) -> SplitCookies<'c> {
+        SplitCookies { string, last: 0, options }
+    }
+}
+
+impl<'c> Iterator for SplitCookies<'c> {
+    type Item = Result<Cookie<'c>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.last < self.string.len() {
+            let i = self.last;
+            let j = self.string[i..].find(';').map(|k| i + k).unwrap_or(self.string.len());
+            self.last = j + 1;
+            if self.string[i..j].trim().is_empty() {
+                continue;
+            }
+
+            return Some(match self.string {
+                Cow::Borrowed(s) => parse_cookie(&s[i..j], &self.options),
+                Cow::Owned(ref s) => parse_cookie(s[i..j].to_owned(), &self.options),
+            });
+        }
+
+        None
+    }
+}
+
+/// Returns `true` if `c` is a `token` character as defined in RFC 2616: any
+/// ASCII character except controls and separators.
+pub(crate) fn is_token_char(c: char) -> bool {
+    c.is_ascii() && !c.is_ascii_control() && !"()<>@,;:\\\"/[]?={} \t".contains(c)
+}
+
+/// Returns `true` if `name` is the name of an attribute defined by the obsolete
+/// RFC 2109 or RFC 2965, and since removed: `Version`, `Comment`, `CommentURL`,
+/// or `Discard`. Compared case-insensitively.
+pub(crate) fn is_obsolete_attribute(name: &str) -> bool {
+    ["Version", "Comment", "CommentURL", "Discard"].iter()
+        .any(|obsolete| obsolete.eq_ignore_ascii_case(name))
+}
+
+/// Returns `true` if `c` is a `cookie-octet` as defined in RFC 6265.
+fn is_cookie_octet(c: char) -> bool {
+    matches!(c, '\x21' | '\x23'..='\x2B' | '\x2D'..='\x3A' | '\x3C'..='\x5B' | '\x5D'..='\x7E')
+}
+
+/// Returns `true` if `domain` is a valid `Domain` attribute value: a host name,
+/// optionally with a leading `.`.
+fn is_domain(domain: &str) -> bool {
+    let domain = domain.strip_prefix('.').unwrap_or(domain);
+    domain.split('.').all(|label| {
+        !label.is_empty()
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    })
+}
+
+/// Checks that `s` is a `set-cookie-string` as defined in RFC 6265, Section
+/// 4.1.1. Unknown `extension-av` attributes are permitted if their names are
+/// tokens. As written by removal cookies, a `Max-Age` of `0` is accepted.
+fn check_strict(s: &str) -> Result<(), ParseError> {
+    let mut attributes = s.split(';');
+    let pair = attributes.next().expect("first str::split().next() returns Some");
+    let (name, value) = pair.split_once('=').ok_or(ParseError::MissingPair)?;
+    if name.is_empty() {
+        return Err(ParseError::EmptyName);
+    }
+
+    // Returns the byte offset and length of the first character in `part`, a
+    // substring of `s`, that doesn't satisfy `valid`.
+    let invalid_char = |part: &str, valid: fn(char) -> bool| {
+        let start = indexes_of(part, s).expect("part sub").0;
+        part.char_indices()
+            .find(|&(_, c)| !valid(c))
+            .map(|(i, c)| (start + i, c.len_utf8()))
+    };
+
+    if let Some((at, len)) = invalid_char(name, is_token_char) {
+        return Err(ParseError::InvalidName { at, len });
+    }
+
+    let unquoted = value.strip_prefix('"').and_then(|v| v.strip_suffix('"'));
+    if let Some((at, len)) = invalid_char(unquoted.unwrap_or(value), is_cookie_octet) {
+        return Err(ParseError::InvalidValue { at, len });
+    }
+
+    let mut seen: Vec<String> = vec![];
+    for segment in attributes {
+        let (at, end) = indexes_of(segment, s).expect("attribute sub");
+        let (invalid, duplicate) = (
+            ParseError::InvalidAttribute { at, len: end - at },
+            ParseError::DuplicateAttribute { at, len: end - at },
+        );
+
+        let attr = segment.strip_prefix(' ').ok_or(invalid)?;
+        if !attr.chars().all(|c| c.is_ascii() && !c.is_ascii_control()) {
+            return Err(invalid);
+        }
+
+        let (key, value) = match attr.split_once('=') {
+            Some((key, value)) => (key.to_ascii_lowercase(), Some(value)),
+            None => (attr.to_ascii_lowercase(), None),
+        };
+
+        let valid = match (&*key, value) {
+            _ if key.is_empty() || !key.chars().all(is_token_char) => false,
+            ("secure" | "httponly" | "partitioned", v) => v.is_none(),
+            ("expires", Some(v)) => parse_date(v, &FMT1).is_ok(),
+            ("max-age", Some(v)) => {
+                let digits = !v.is_empty() && v.chars().all(|c| c.is_ascii_digit());
+                v == "0" || (digits && !v.starts_with('0'))
+            }
+            ("domain", Some(v)) => is_domain(v),
+            ("path", Some(v)) => !v.is_empty(),
+            ("samesite", Some(v)) => {
+                ["strict", "lax", "none"].iter().any(|s| v.eq_ignore_ascii_case(s))
+            }
+            ("priority", Some(v)) => parse_priority(v).is_some(),
+            ("expires" | "max-age" | "domain" | "path" | "samesite" | "priority", None) => false,
+            _ => true,
+        };
+
+        if !valid {
+            return Err(invalid);
+        }
+
+        if seen.contains(&key) {
+            return Err(duplicate);
+        }
+
+        seen.push(key);
+    }
+
+    Ok(())
+}
+
 pub(crate) fn parse_date(s: &str, format: &impl Parsable) -> Result<OffsetDateTime, time::Error> {
     // Parse. Handle "abbreviated" dates like Chromium. See cookie#162.
+    #[allow(deprecated)]
     let mut date = format.parse(s.as_bytes())?;
     if let Some(y) = date.year().or_else(|| date.year_last_two().map(|v| v as i32)) {
         let offset = match y {
@@ -274,10 +977,112 @@ pub(crate) fn parse_date(s: &str, format: &impl Parsable) -> Result<OffsetDateTi
     Ok(PrimitiveDateTime::try_from(date)?.assume_utc())
 }
 
+/// Parses the `Expires` date `s` with the cookie-date algorithm of RFC 6265,
+/// Section 5.1.1, as browsers do, returning `None` if `s` isn't a valid date.
+///
+/// Dates are split into tokens at any delimiter; the first tokens that look
+/// like a time, day of month, month, and year, in that order of precedence,
+/// are used, and everything else, such as the weekday, is ignored. Like in
+/// Chromium, two-digit years from 69 to 99 are mapped to 1969 to 1999 and the
+/// rest to 2000 to 2068.
+pub(crate) fn parse_cookie_date(s: &str) -> Option<OffsetDateTime> {
+    const MONTHS: [&str; 12] = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"
+    ];
+
+    // Splits `token` into the value of its leading digits, if there are `min`
+    // to `max` of them, and the rest of `token`.
+    fn digits(token: &str, min: usize, max: usize) -> Option<(u32, &str)> {
+        let end = token.find(|c: char| !c.is_ascii_digit()).unwrap_or(token.len());
+        match end < min || end > max {
+            true => None,
+            false => Some((token[..end].parse().ok()?, &token[end..])),
+        }
+    }
+
+    // Parses a `time` token: `hh:mm:ss` with one or two digits per field,
+    // optionally followed by a non-digit and anything else.
+    fn time(token: &str) -> Option<(u32, u32, u32)> {
+        let (hour, rest) = digits(token, 1, 2)?;
+        let (minute, rest) = digits(rest.strip_prefix(':')?, 1, 2)?;
+        let (second, _) = digits(rest.strip_prefix(':')?, 1, 2)?;
+        Some((hour, minute, second))
+    }
+
+    let is_delimiter = |c: char| {
+        matches!(c, '\x09' | '\x20'..='\x2F' | '\x3B'..='\x40' | '\x5B'..='\x60' | '\x7B'..='\x7E')
+    };
+
+    let (mut hms, mut day, mut month, mut year) = (None, None, None, None);
+    for token in s.split(is_delimiter).filter(|t| !t.is_empty()) {
+        if hms.is_none() {
+            if let Some(t) = time(token) {
+                hms = Some(t);
+                continue;
+            }
+        }
+
+        if day.is_none() {
+            if let Some((d, _)) = digits(token, 1, 2) {
+                day = Some(d);
+                continue;
+            }
+        }
+
+        if month.is_none() {
+            let prefix = token.get(..3).unwrap_or("");
+            if let Some(i) = MONTHS.iter().position(|m| m.eq_ignore_ascii_case(prefix)) {
+                month = Some(i as u8 + 1);
+                continue;
+            }
+        }
+
+        if year.is_none() {
+            if let Some((y, _)) = digits(token, 2, 4) {
+                year = Some(y as i32);
+            }
+        }
+    }
+
+    let year = match year? {
+        y @ 0..=68 => y + 2000,
+        y @ 69..=99 => y + 1900,
+        y => y,
+    };
+
+    let (hour, minute, second) = hms?;
+    if year < 1601 || hour > 23 || minute > 59 || second > 59 {
+        return None;
+    }
+
+    let month = Month::try_from(month?).ok()?;
+    let date = Date::from_calendar_date(year, month, u8::try_from(day?).ok()?).ok()?;
+    let time = Time::from_hms(hour as u8, minute as u8, second as u8).ok()?;
+    Some(PrimitiveDateTime::new(date, time).assume_utc())
+}
+
+/// Writes `date` into `out` in the format of `FMT1` without allocating. The
+/// date is expected to be in UTC.
+pub(crate) fn write_date<W: fmt::Write>(out: &mut W, date: OffsetDateTime) -> fmt::Result {
+    const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"
+    ];
+
+    write!(out, "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+        WEEKDAYS[date.weekday().number_days_from_monday() as usize],
+        date.day(),
+        MONTHS[date.month() as usize - 1],
+        date.year(),
+        date.hour(),
+        date.minute(),
+        date.second())
+}
+
 #[cfg(test)]
 mod tests {
     use super::parse_date;
-    use crate::{Cookie, SameSite};
+    use crate::{Cookie, Priority, SameSite};
     use time::Duration;
 
     macro_rules! assert_eq_parse {
@@ -332,13 +1137,346 @@ mod tests {
         assert_eq_parse!("foo=bar; SameSITE=none", expected);
         assert_eq_parse!("foo=bar; SameSite=NOne", expected);
         assert_eq_parse!("foo=bar; SameSite=nOne", expected);
+
+        let other = |s| Cookie::parse(s).unwrap().same_site();
+        let expected = Some(SameSite::Other("Sometimes".into()));
+        assert_eq!(other("foo=bar; SameSite=Sometimes"), expected);
+        assert_eq!(other("foo=bar; SAMESITE = Sometimes "), expected);
+        assert_eq!(other("foo=bar; SameSite=Other; SameSite=Sometimes"), expected);
+        assert_eq!(other("foo=bar; SameSite=Lax; SameSite=Sometimes"), Some(SameSite::Lax));
+        assert_eq!(other("foo=bar; SameSite=Sometimes; SameSite=Lax"), Some(SameSite::Lax));
+        assert_ne!(other("foo=bar; SameSite=sometimes"), expected);
+
+        let cookie = Cookie::parse("foo=bar; SameSite=Lax; SameSite=").unwrap();
+        assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+        let cookie = Cookie::parse("foo=bar; SameSite=Sometimes; Path=/").unwrap();
+        assert_eq!(cookie.to_string(), "foo=bar; SameSite=Sometimes; Path=/");
+        let cookie = cookie.into_owned();
+        assert_eq!(Cookie::parse(cookie.to_string()).unwrap().same_site(), cookie.same_site());
+    }
+
+    #[test]
+    fn parse_strict() {
+        use crate::ParseError::*;
+
+        let strict = |s| Cookie::parse_strict(s).map(|c| c.to_string());
+        let valid = "foo=bar; Secure; HttpOnly; SameSite=Lax; Partitioned; Path=/a; \
+            Domain=.example.com; Max-Age=60; Expires=Wed, 21 Oct 2015 07:28:00 GMT";
+        assert_eq!(Cookie::parse_strict(valid).unwrap(), Cookie::parse(valid).unwrap());
+        assert_eq!(strict("foo=\"bar\"").unwrap(), "foo=\"bar\"");
+        assert_eq!(strict("foo=; Max-Age=0; Ext=x").unwrap(), "foo=; Max-Age=0; Ext=x");
+        assert_eq!(strict("foo=bar; secure; PATH=/").unwrap(), "foo=bar; Secure; Path=/");
+
+        let mut removal = Cookie::build("foo", "").path("/").domain("example.com")
+            .same_site(SameSite::Strict).secure(true).http_only(true).finish();
+        removal.make_removal();
+        assert!(Cookie::parse_strict(removal.to_string()).is_ok());
+
+        // Attribute errors are for the last attribute in each string.
+        let last = |s: &str| {
+            let at = s.rfind(';').unwrap() + 1;
+            (at, s.len() - at)
+        };
+
+        let attribute = |s| InvalidAttribute { at: last(s).0, len: last(s).1 };
+        let duplicate = |s| DuplicateAttribute { at: last(s).0, len: last(s).1 };
+        let name = |at, len| InvalidName { at, len };
+        let value = |at, len| InvalidValue { at, len };
+        for (s, error) in &[
+            ("foo", MissingPair),
+            ("=bar", EmptyName),
+            ("fo o=bar", name(2, 1)),
+            ("foo@=bar", name(3, 1)),
+            (" foo=bar", name(0, 1)),
+            ("foo =bar", name(3, 1)),
+            ("fóo=bar", name(1, 2)),
+            ("foo= bar", value(4, 1)),
+            ("foo=b,ar", value(5, 1)),
+            ("foo=b\\ar", value(5, 1)),
+            ("foo=\"bar", value(4, 1)),
+            ("foo=b\"a\"r", value(5, 1)),
+            ("foo=bár", value(5, 2)),
+            ("foo=b\x07ar", value(5, 1)),
+            ("foo=bar;Secure", attribute("foo=bar;Secure")),
+            ("foo=bar;  Secure", attribute("foo=bar;  Secure")),
+            ("foo=bar; ", attribute("foo=bar; ")),
+            ("foo=bar;", attribute("foo=bar;")),
+            ("foo=bar; =x", attribute("foo=bar; =x")),
+            ("foo=bar; Secure=yes", attribute("foo=bar; Secure=yes")),
+            ("foo=bar; HttpOnly=", attribute("foo=bar; HttpOnly=")),
+            ("foo=bar; Path", attribute("foo=bar; Path")),
+            ("foo=bar; Path=", attribute("foo=bar; Path=")),
+            ("foo=bar; Path=/\x01", attribute("foo=bar; Path=/\x01")),
+            ("foo=bar; Max-Age=-1", attribute("foo=bar; Max-Age=-1")),
+            ("foo=bar; Max-Age=01", attribute("foo=bar; Max-Age=01")),
+            ("foo=bar; Max-Age=1s", attribute("foo=bar; Max-Age=1s")),
+            ("foo=bar; Max-Age=", attribute("foo=bar; Max-Age=")),
+            ("foo=bar; Domain=", attribute("foo=bar; Domain=")),
+            ("foo=bar; Domain=a..b", attribute("foo=bar; Domain=a..b")),
+            ("foo=bar; Domain=-a.b", attribute("foo=bar; Domain=-a.b")),
+            ("foo=bar; Domain=a_b.c", attribute("foo=bar; Domain=a_b.c")),
+            ("foo=bar; SameSite=Sometimes", attribute("foo=bar; SameSite=Sometimes")),
+            ("foo=bar; Secure; Expires=Wed, 21-Oct-15 07:28:00 GMT",
+                attribute("foo=bar; Secure; Expires=Wed, 21-Oct-15 07:28:00 GMT")),
+            ("foo=bar; Secure; secure", duplicate("foo=bar; Secure; secure")),
+            ("foo=bar; Path=/; Max-Age=1; path=/a",
+                duplicate("foo=bar; Path=/; Max-Age=1; path=/a")),
+            ("foo=bar; Ext=a; ext=b", duplicate("foo=bar; Ext=a; ext=b")),
+        ] {
+            assert_eq!(Cookie::parse_strict(*s), Err(*error), "{:?}", s);
+            assert!(Cookie::parse(*s).is_ok());
+        }
+
+        let string = "foo=bár; Path=/";
+        let error = Cookie::parse_strict(string).unwrap_err();
+        assert_eq!(error.offending(string), Some("á"));
+        assert_eq!(error.to_string(), "the cookie's value contains an invalid character at byte 5");
+        assert_eq!(MissingPair.to_string(), "the cookie is missing a name/value pair");
+    }
+
+    #[test]
+    fn parse_options() {
+        use crate::{DuplicatePolicy, ParseError, ParseOptions, ValueRecovery};
+
+        let first = ParseOptions::new().duplicates(DuplicatePolicy::FirstWins);
+        let cookie = "foo=bar; Path=/a; Domain=; Domain=a.com; SameSite=; SameSite=Lax; \
+            path=/b; Domain=b.com; SameSite=Strict; Expires=x; Max-Age=1; Max-Age=2";
+        let expected = Cookie::build("foo", "bar").path("/a").domain("a.com")
+            .same_site(SameSite::Lax).max_age(Duration::seconds(1)).finish();
+        assert_eq!(Cookie::parse_with(cookie, &first).unwrap(), expected);
+
+        let expected = Cookie::build("foo", "bar").path("/b").domain("b.com")
+            .same_site(SameSite::Strict).max_age(Duration::seconds(2)).finish();
+        assert_eq!(Cookie::parse_with(cookie, &ParseOptions::new()).unwrap(), expected);
+
+        // Only repeats of valid occurrences are rejected.
+        let reject = ParseOptions::new().duplicates(DuplicatePolicy::Reject);
+        let error = Cookie::parse_with(cookie, &reject).unwrap_err();
+        assert_eq!(error, ParseError::DuplicateAttribute { at: 65, len: 8 });
+        assert_eq!(error.offending(cookie), Some(" path=/b"));
+        let cookie = "foo=bar; Domain=; Domain=a.com; SameSite=; SameSite=Lax; \
+            Max-Age=x; Max-Age=1";
+        let expected = Cookie::build("foo", "bar").domain("a.com")
+            .same_site(SameSite::Lax).max_age(Duration::seconds(1)).finish();
+        assert_eq!(Cookie::parse_with(cookie, &reject).unwrap(), expected);
+        assert!(Cookie::parse_with("foo=bar; Secure; secure", &reject).is_err());
+
+        // Empty segments and extensions may repeat under every policy.
+        use DuplicatePolicy::*;
+        for &policy in &[LastWins, FirstWins, Reject] {
+            let options = ParseOptions::new().duplicates(policy);
+            let parsed = Cookie::parse_with("foo=bar; Secure;;; ;", &options).unwrap();
+            assert_eq!(parsed.to_string(), "foo=bar; Secure");
+            let parsed = Cookie::parse_with("foo=bar; Foo; foo; Foo=1; Path=/", &options).unwrap();
+            assert_eq!(parsed.to_string(), "foo=bar; Path=/; Foo; foo; Foo=1");
+            let parsed = Cookie::parse_with("foo=bar;; Foo; Foo", &options.preserve_unknown(false));
+            assert_eq!(parsed.unwrap().to_string(), "foo=bar");
+        }
+
+        // Unknown attributes are kept in order, as written, when enabled.
+        let preserve = ParseOptions::new().preserve_unknown(true);
+        let cookie = "foo=bar;X-Scope = app; Secure; x-flag;; Ext=a=b; Path=/; Ext=c; =d;";
+        let parsed = Cookie::parse_with(cookie, &preserve).unwrap();
+        let expected = "foo=bar; Secure; Path=/; X-Scope=app; x-flag; Ext=a=b; Ext=c";
+        assert_eq!(parsed.to_string(), expected);
+        assert_eq!(parsed.into_owned().to_string(), expected);
+        let parsed = Cookie::parse_with(cookie, &preserve.duplicates(DuplicatePolicy::FirstWins));
+        assert_eq!(parsed.unwrap().to_string(), expected);
+        let discard = ParseOptions::new().preserve_unknown(false);
+        let parsed = Cookie::parse_with(cookie, &discard).unwrap();
+        assert_eq!(parsed.to_string(), "foo=bar; Secure; Path=/");
+        assert_eq!(parsed.extensions().count(), 0);
+        let expected = "foo=bar; Secure; Path=/; X-Scope=app; x-flag; Ext=a=b; Ext=c";
+        assert_eq!(Cookie::parse(cookie).unwrap().to_string(), expected);
+
+        let strict = ParseOptions::new().strict(true);
+        assert!(Cookie::parse_with("foo=bar; Secure", &strict).is_ok());
+        assert!(Cookie::parse_with("foo=bar; Secure; Secure", &strict).is_err());
+        let lenient = strict.duplicates(DuplicatePolicy::FirstWins);
+        assert!(Cookie::parse_with("foo=bar; Path=/; Path=/", &lenient).is_err());
+
+        // Values continue over segments without `=` only when recovering them.
+        let extend = ParseOptions::new().value_recovery(ValueRecovery::Extend);
+        let cookie = "foo=a;b;\"c\";secure;d; X-Flag;e;f=g;h";
+        let parsed = Cookie::parse_with(cookie, &extend).unwrap();
+        assert_eq!(parsed.value(), "a;b;\"c\"");
+        assert_eq!(parsed.to_string(), "foo=a;b;\"c\"; Secure; d; X-Flag; e; f=g; h");
+        assert_eq!(parsed.into_owned().value(), "a;b;\"c\"");
+        let parsed = Cookie::parse_with(cookie, &ParseOptions::new()).unwrap();
+        assert_eq!(parsed.to_string(), "foo=a; Secure; b; \"c\"; d; X-Flag; e; f=g; h");
+        let parsed = Cookie::parse_with("a;b ;c ; Path=/", &extend).unwrap();
+        assert_eq!(parsed.name_value(), ("", "a;b ;c"));
+        assert_eq!(parsed.path(), Some("/"));
+        assert_eq!(Cookie::parse_with("foo=\"a;b\"", &extend).unwrap().value(), "a;b");
+        assert_eq!(Cookie::parse_with("foo=;;", &extend).unwrap().value(), "");
+
+        let reject = ParseOptions::new().value_recovery(ValueRecovery::Reject);
+        let error = |cookie| Cookie::parse_with(cookie, &reject).unwrap_err();
+        assert_eq!(error("foo=a;b; Path=/"), ParseError::InvalidValue { at: 5, len: 1 });
+        assert_eq!(error("foo= a b"), ParseError::InvalidValue { at: 6, len: 1 });
+        assert_eq!(error("foo=\"a\\b\""), ParseError::InvalidValue { at: 6, len: 1 });
+        assert_eq!(error("foo=é,"), ParseError::InvalidValue { at: 4, len: 2 });
+        assert_eq!(error("foo=a,b"), ParseError::InvalidValue { at: 5, len: 1 });
+        let parsed = Cookie::parse_with("foo = \"a\" ;Secure; X-Flag", &reject).unwrap();
+        assert_eq!(parsed.to_string(), "foo=\"a\"; Secure; X-Flag");
+        let strict = strict.value_recovery(ValueRecovery::Extend);
+        assert!(Cookie::parse_with("foo=a;b", &strict).is_err());
+
+        let binary = ParseOptions::new().binary_value(true);
+        let error = |cookie| Cookie::parse_with(cookie, &binary).unwrap_err();
+        let parsed = Cookie::parse_with("foo=\"-_8A\"; Path=/", &binary).unwrap();
+        assert_eq!(parsed.value_bytes().unwrap(), [0xfb, 0xff, 0x00]);
+        assert_eq!(Cookie::parse_with("foo=", &binary).unwrap().value_bytes().unwrap(), []);
+        assert_eq!(error("foo=AAAAA"), ParseError::InvalidValue { at: 8, len: 1 });
+        assert_eq!(error("foo=AA=="), ParseError::InvalidValue { at: 6, len: 1 });
+        assert_eq!(error("foo=AAé"), ParseError::InvalidValue { at: 6, len: 2 });
+        assert_eq!(error("foo=\"A+\""), ParseError::InvalidValue { at: 6, len: 1 });
+
+        #[cfg(feature = "percent-encode")] {
+            let decode = ParseOptions::new().percent_decode(true);
+            let parsed = Cookie::parse_with("foo%20=bar%20; Path=/a%20", &decode).unwrap();
+            assert_eq!(parsed.name_value(), ("foo ", "bar "));
+            assert_eq!(parsed.path(), Some("/a%20"));
+        }
+    }
+
+    #[test]
+    fn split_parse() {
+        use crate::ParseError;
+
+        fn split(header: &str) -> Vec<Result<(String, String), ParseError>> {
+            let pairs = Cookie::split_parse(header)
+                .map(|r| r.map(|c| (c.name().to_string(), c.value().to_string())));
+
+            let owned: Vec<_> = Cookie::split_parse(header.to_string())
+                .map(|r| r.map(|c| (c.name().to_string(), c.value().to_string())))
+                .collect();
+
+            let pairs: Vec<_> = pairs.collect();
+            assert_eq!(pairs, owned);
+            pairs
+        }
+
+        let pair = |name: &str, value: &str| Ok((name.to_string(), value.to_string()));
+        assert!(split("").is_empty());
+        assert!(split(" ;; ;\t").is_empty());
+        assert_eq!(split("a=b"), [pair("a", "b")]);
+        assert_eq!(split(" a = b ;c=d;;e=\"f g\"; "), [pair("a", "b"), pair("c", "d"),
+            pair("e", "f g")]);
+        assert_eq!(split("a=b=c; d="), [pair("a", "b=c"), pair("d", "")]);
+        assert_eq!(split("a; =b; c=d"), [pair("", "a"), pair("", "b"), pair("c", "d")]);
+        assert_eq!(split("a=b; =; c=d"), [pair("a", "b"), Err(ParseError::EmptyName),
+            pair("c", "d")]);
+
+        #[cfg(feature = "percent-encode")] {
+            let cookie = Cookie::split_parse_encoded("a%20=b%3B; c=d").next().unwrap();
+            assert_eq!(cookie.unwrap().name_value(), ("a ", "b;"));
+        }
+    }
+
+    #[test]
+    fn parse_bytes() {
+        use crate::{ParseError, Utf8Policy::*};
+
+        let c = Cookie::parse_bytes(b"foo=bar; Path=/", Strict).unwrap();
+        assert_eq!(c, Cookie::build("foo", "bar").path("/").finish());
+        assert_eq!(c.name_raw(), Some("foo"));
+        assert_eq!(Cookie::parse_bytes(b"foo=bar; Path=/", Lossy).unwrap(), c);
+
+        let c = Cookie::parse_bytes(b"f\xc3\xb6o=\xe2\x82\xac; Path=/\xff", Strict);
+        assert!(matches!(c, Err(ParseError::Utf8Error(_))));
+        let c = Cookie::parse_bytes(b"f\xc3\xb6o=\xe2\x82; Path=/\xff", Lossy).unwrap();
+        assert_eq!(c.name_value(), ("f\u{f6}o", "\u{fffd}"));
+        assert_eq!(c.path(), Some("/\u{fffd}"));
+        assert!(c.into_owned().name_raw().is_none());
+
+        assert!(Cookie::parse_bytes(b"\xff", Strict).is_err());
+        let c = Cookie::parse_bytes(b"=\xff", Lossy).unwrap();
+        assert_eq!(c.name_value(), ("", "\u{fffd}"));
+        assert_eq!(Cookie::parse_bytes(b"=", Lossy).unwrap_err(), ParseError::EmptyName);
+    }
+
+    #[test]
+    fn parse_partitioned() {
+        let expected = Cookie::build("foo", "bar")
+            .secure(true)
+            .partitioned(true)
+            .finish();
+
+        assert_eq_parse!("foo=bar; Secure; Partitioned", expected);
+        assert_eq_parse!("foo=bar; partitioned; secure", expected);
+        assert_eq_parse!("foo=bar; Secure; PARTITIONED=yes", expected);
+        assert_ne_parse!("foo=bar; Secure", expected);
+        assert_ne_parse!("foo=bar; Secure; Partition", expected);
+
+        let cookie = Cookie::parse("foo=bar; Partitioned").unwrap();
+        assert_eq!(Cookie::parse(cookie.to_string()).unwrap().partitioned(), Some(true));
+    }
+
+    #[test]
+    fn parse_priority() {
+        let expected = Cookie::build("foo", "bar")
+            .priority(Priority::High)
+            .finish();
+
+        assert_eq_parse!("foo=bar; Priority=High", expected);
+        assert_eq_parse!("foo=bar; priority=high", expected);
+        assert_eq_parse!("foo=bar; PRIORITY = HIGH", expected);
+        assert_eq_parse!("foo=bar; Priority=Low; Priority=High", expected);
+        assert_eq_parse!("foo=bar; Priority=High; Priority=Highest", expected);
+        assert_ne_parse!("foo=bar; Priority=Low", expected);
+        assert_ne_parse!("foo=bar; Priority", expected);
+
+        let cookie = Cookie::parse("foo=bar; Priority=medium; Priority=urgent").unwrap();
+        assert_eq!(cookie.priority(), Some(Priority::Medium));
+        assert_eq!(cookie.extensions().count(), 0);
+        assert_eq!(cookie.to_string(), "foo=bar; Priority=Medium");
+        assert_eq!(Cookie::parse(cookie.to_string()).unwrap().priority(), Some(Priority::Medium));
+
+        assert!(Cookie::parse_strict("foo=bar; Priority=low").is_ok());
+        assert!(Cookie::parse_strict("foo=bar; Priority=urgent").is_err());
+        assert!(Cookie::parse_strict("foo=bar; Priority").is_err());
+    }
+
+    #[test]
+    fn parse_nameless() {
+        use crate::ParseError;
+
+        let expected = Cookie::new("", "bar");
+        assert_eq_parse!("=bar", expected);
+        assert_eq_parse!(" = bar ", expected);
+        assert_eq_parse!("bar", expected);
+        assert_eq_parse!(" bar ; Ignored", expected);
+        assert_ne_parse!("bar=", expected);
+
+        let cookie = Cookie::parse("a b; Path=/").unwrap();
+        assert_eq!(cookie.name_value(), ("", "a b"));
+        assert_eq!(cookie.name_raw(), Some(""));
+        assert_eq!(cookie.path(), Some("/"));
+        assert_eq!(cookie.to_string(), "=a b; Path=/");
+        assert_eq!(Cookie::parse(cookie.to_string()).unwrap(), cookie);
+
+        let cookie = Cookie::parse("\"bar\"").unwrap();
+        assert_eq!(cookie.name_value(), ("", "bar"));
+        assert!(cookie.is_quoted());
+        assert_eq!(Cookie::parse("=\"\"").unwrap().name_value(), ("", ""));
+
+        assert_eq!(Cookie::parse("=").unwrap_err(), ParseError::EmptyName);
+        assert_eq!(Cookie::parse("  ; Secure").unwrap_err(), ParseError::MissingPair);
+        assert_eq!(Cookie::parse_strict("bar").unwrap_err(), ParseError::MissingPair);
+        assert_eq!(Cookie::parse_strict("=bar").unwrap_err(), ParseError::EmptyName);
+
+        #[cfg(feature = "percent-encode")] {
+            let cookie = Cookie::parse_encoded("a%20b; Secure").unwrap();
+            assert_eq!(cookie.name_value(), ("", "a b"));
+        }
     }
 
     #[test]
     fn parse() {
-        assert!(Cookie::parse("bar").is_err());
-        assert!(Cookie::parse("=bar").is_err());
-        assert!(Cookie::parse(" =bar").is_err());
+        assert!(Cookie::parse("").is_err());
+        assert!(Cookie::parse(" =").is_err());
+        assert!(Cookie::parse("; Path=/").is_err());
         assert!(Cookie::parse("foo=").is_ok());
 
         let expected = Cookie::build("foo", "bar=baz").finish();
@@ -495,12 +1633,25 @@ mod tests {
 
         let cookie_str = "foo=bar; expires=Sun Nov  6 08:49:37 1994";
         Cookie::parse(cookie_str).unwrap().expires_datetime().unwrap();
+
+        // RFC 850 dates with four-digit years and asctime dates with unpadded
+        // days, as written by some legacy servers.
+        let expected = parse_date("Sun, 06 Nov 1994 08:49:37 GMT", &super::FMT1).unwrap();
+        for date in &["Sunday, 06-Nov-1994 08:49:37 GMT", "Sun Nov 6 08:49:37 1994",
+            "Sun Nov 06 08:49:37 1994", "Sun, 06-Nov-94 08:49:37 GMT"]
+        {
+            let cookie = Cookie::parse(format!("foo=bar; expires={}", date)).unwrap();
+            assert_eq!(cookie.expires_datetime(), Some(expected), "{:?}", date);
+        }
+
+        let cookie = Cookie::parse("foo=bar; expires=Sun Nov 16 08:49:37 1994").unwrap();
+        assert_eq!(cookie.expires_datetime().unwrap().day(), 16);
     }
 
     #[test]
     fn parse_very_large_max_ages() {
         let mut expected = Cookie::build("foo", "bar")
-            .max_age(Duration::seconds(i64::max_value()))
+            .max_age(Duration::seconds(i64::MAX))
             .finish();
 
         let string = format!("foo=bar; Max-Age={}", 1u128 << 100);
@@ -512,11 +1663,11 @@ mod tests {
         let string = format!("foo=bar; Max-Age=-{}", 1u128 << 100);
         assert_eq_parse!(&string, expected);
 
-        let string = format!("foo=bar; Max-Age=-{}", i64::max_value());
+        let string = format!("foo=bar; Max-Age=-{}", i64::MAX);
         assert_eq_parse!(&string, expected);
 
-        let string = format!("foo=bar; Max-Age={}", i64::max_value());
-        expected.set_max_age(Duration::seconds(i64::max_value()));
+        let string = format!("foo=bar; Max-Age={}", i64::MAX);
+        expected.set_max_age(Duration::seconds(i64::MAX));
         assert_eq_parse!(&string, expected);
     }
 
@@ -547,4 +1698,193 @@ mod tests {
         let too_many_seconds = (max_seconds as u64) + 1;
         assert_eq_parse!(format!(" foo=bar; Max-Age={:?}", too_many_seconds), expected);
     }
+
+    #[test]
+    fn parse_borrowed() {
+        use crate::{DuplicatePolicy, ParseOptions};
+
+        let header = "foo=bar; MAX-AGE=10; path=/a; Domain=.b.com; SameSite=Lax; Ext=x";
+        let cookie = Cookie::parse_borrowed(header).unwrap();
+        assert_eq!(cookie.raw_str(), Some(header));
+        assert_eq!(cookie.name_raw(), Some("foo"));
+        assert_eq!(cookie.value_raw(), Some("bar"));
+        assert_eq!(cookie.path_raw(), Some("/a"));
+        assert_eq!(cookie.domain_raw(), Some("b.com"));
+        assert_eq!(cookie.max_age(), Some(Duration::seconds(10)));
+        assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+        assert_eq!(cookie.extensions().count(), 0);
+
+        let cookie = Cookie::parse_borrowed("foo=bar; SameSite=Lax; SameSite=Weird").unwrap();
+        assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+        let cookie = Cookie::parse_borrowed("foo=bar; SameSite=Weird").unwrap();
+        assert_eq!(cookie.same_site(), None);
+
+        let cookie = Cookie::parse(header.to_string()).unwrap();
+        assert!(cookie.raw_str().is_none());
+        assert_eq!(cookie.extensions().count(), 1);
+
+        // Attribute names are matched case-insensitively for either policy,
+        // while extensions are never duplicates.
+        let header = "foo=bar; path=/a; PATH=/b; Ext=1; EXT=2";
+        let options = ParseOptions::new().duplicates(DuplicatePolicy::FirstWins);
+        let cookie = Cookie::parse_with(header, &options).unwrap();
+        assert_eq!(cookie.path(), Some("/a"));
+        let extensions = [("Ext", Some("1")), ("EXT", Some("2"))];
+        assert_eq!(cookie.extensions().collect::<Vec<_>>(), extensions);
+        assert_eq!(Cookie::parse(header).unwrap().path(), Some("/b"));
+    }
+
+    #[test]
+    fn parse_all() {
+        use crate::ParseError;
+
+        let headers = vec!["a=b", "", "c=d; Max-Age=10", "=", "f=g"];
+        let results: Vec<_> = Cookie::parse_all(headers).collect();
+        assert_eq!(results, [
+            Ok(Cookie::new("a", "b")),
+            Err(ParseError::MissingPair),
+            Ok(Cookie::build("c", "d").max_age(Duration::seconds(10)).finish()),
+            Err(ParseError::EmptyName),
+            Ok(Cookie::new("f", "g")),
+        ]);
+
+        assert_eq!(Cookie::parse_all(vec![]).count(), 0);
+    }
+
+    #[test]
+    fn write_date() {
+        use time::macros::datetime;
+
+        let mut date = datetime!(1601-01-01 0:00 UTC);
+        while date.year() < 3000 {
+            let mut written = String::new();
+            super::write_date(&mut written, date).unwrap();
+            assert_eq!(written, date.format(&super::FMT1).unwrap());
+            date += Duration::seconds(86_399 * 7 + 3_601);
+        }
+    }
+
+    #[test]
+    fn parse_obsolete_attributes() {
+        let string = "foo=bar; Version=1; Comment=\"a comment\"; Path=/; \
+            CommentURL=\"http://a.com/c\"; Discard; X-Scope=app";
+        let obsolete = [
+            ("Version", Some("1")),
+            ("Comment", Some("\"a comment\"")),
+            ("CommentURL", Some("\"http://a.com/c\"")),
+            ("Discard", None),
+        ];
+
+        let cookie = Cookie::parse(string).unwrap();
+        assert_eq!(cookie.path(), Some("/"));
+        assert_eq!(cookie.obsolete_attributes().collect::<Vec<_>>(), obsolete);
+        assert_eq!(cookie.extensions().count(), 5);
+        assert_eq!(cookie.to_string(), "foo=bar; Path=/; Version=1; Comment=\"a comment\"; \
+            CommentURL=\"http://a.com/c\"; Discard; X-Scope=app");
+
+        let cookie = cookie.into_owned();
+        assert_eq!(cookie.obsolete_attributes().collect::<Vec<_>>(), obsolete);
+
+        let cookie = Cookie::parse("foo=bar; version=1; DISCARD").unwrap();
+        assert_eq!(cookie.obsolete_attributes().count(), 2);
+        assert!(Cookie::parse_strict("foo=bar; Version=1; Comment=a comment; Discard").is_ok());
+        assert!(Cookie::parse_strict("foo=bar; Version=1; version=1").is_err());
+        assert_eq!(Cookie::parse("foo=bar; Path=/; Versions=1").unwrap()
+            .obsolete_attributes().count(), 0);
+    }
+
+    #[test]
+    fn parse_cookie_dates() {
+        use super::parse_cookie_date;
+
+        let expected = parse_date("Mon, 01 Jan 2024 00:00:00 GMT", &super::FMT1).unwrap();
+        let dates = [
+            "Mon, 01-Jan-24 00:00:00 GMT",
+            "Mon, 01 Jan 2024 00:00:00 GMT",
+            "Monday, 01-Jan-2024 00:00:00 GMT",
+            "Mon Jan  1 00:00:00 2024",
+            "1 JANUARY 2024 0:0:0",
+            "2024 Jan 1 00:00:00",
+            "00:00:00 01/jan/24",
+            "Mon,01-Jan-2024 00:00:00 UTC",
+            "Tue, 01-Jan-24 00:00:00 GMT",
+            "01 Jan 24 00:00:00 GMT+01:00",
+        ];
+
+        for date in dates.iter() {
+            assert_eq!(parse_cookie_date(date), Some(expected), "{:?}", date);
+        }
+
+        assert_eq!(parse_cookie_date("1 Jan 69 0:0:0").unwrap().year(), 1969);
+        assert_eq!(parse_cookie_date("1 Jan 70 0:0:0").unwrap().year(), 1970);
+        assert_eq!(parse_cookie_date("1 Jan 00 0:0:0").unwrap().year(), 2000);
+        assert_eq!(parse_cookie_date("1 Jan 1601 0:0:0").unwrap().year(), 1601);
+        assert_eq!(parse_cookie_date("29 Feb 2024 23:59:59").unwrap().day(), 29);
+
+        let invalid = [
+            "", "garbage", "Mon, 01-Jan-24 GMT", "Mon, Jan 2024 00:00:00", "01 2024 00:00:00",
+            "01 Jan 00:00:00", "32 Jan 2024 00:00:00", "1 Jan 1600 0:0:0", "1 Jan 2024 24:0:0",
+            "1 Jan 2024 0:60:0", "1 Jan 2024 0:0:60", "30 Feb 2024 0:0:0", "1 Ja 2024 0:0:0",
+            "1 Jan 2 0:0:0", "1 Jan 20245 0:0:0", "1 Jan 2024 000:0:0",
+        ];
+
+        for date in invalid.iter() {
+            assert_eq!(parse_cookie_date(date), None, "{:?}", date);
+        }
+
+        let cookie = Cookie::parse("foo=bar; Expires=Mon, 01-Jan-24 00:00:00 GMT").unwrap();
+        assert_eq!(cookie.expires_datetime(), Some(expected));
+        let cookie = Cookie::parse("foo=bar; Expires=Mon, 01-Jan-24").unwrap();
+        assert_eq!(cookie.expires(), None);
+    }
+
+    #[test]
+    fn parse_max_age_limit() {
+        use crate::{ParseOptions, MAX_AGE_LIMIT};
+
+        let limited = ParseOptions::new().max_age_limit(MAX_AGE_LIMIT);
+        let max_age = |s: &str, options: &ParseOptions| {
+            let string = format!("foo=bar; Max-Age={}", s);
+            Cookie::parse_with(string, options).unwrap().max_age()
+        };
+
+        let huge = format!("{}", 1u128 << 100);
+        assert_eq!(max_age(&huge, &ParseOptions::new()), Some(Duration::seconds(i64::MAX)));
+        assert_eq!(max_age(&huge, &limited), Some(MAX_AGE_LIMIT));
+        assert_eq!(max_age("34560000", &limited), Some(MAX_AGE_LIMIT));
+        assert_eq!(max_age("34559999", &limited), Some(MAX_AGE_LIMIT - Duration::SECOND));
+        assert_eq!(max_age("-10", &limited), Some(Duration::ZERO));
+        assert_eq!(max_age("1x", &limited), None);
+        assert_eq!(max_age("", &ParseOptions::new()), None);
+        assert_eq!(max_age("-", &ParseOptions::new()), None);
+        assert_eq!(max_age(" ", &limited), None);
+        assert_eq!(max_age("10", &limited.max_age_limit(None)), Some(Duration::seconds(10)));
+    }
+
+    #[test]
+    fn parse_domain_leading_dot() {
+        let mut cookie = Cookie::parse("foo=bar; Domain=.Example.com; Path=/").unwrap();
+        assert_eq!(cookie.domain(), Some("Example.com"));
+        assert!(cookie.domain_leading_dot());
+        assert_eq!(cookie.to_string(), "foo=bar; Path=/; Domain=.Example.com");
+        assert_eq!(cookie, Cookie::build("foo", "bar").domain("example.com").path("/").finish());
+        assert!(cookie.clone().into_owned().domain_leading_dot());
+
+        cookie.set_domain_leading_dot(false);
+        assert_eq!(cookie.to_string(), "foo=bar; Path=/; Domain=Example.com");
+        cookie.set_domain_leading_dot(true);
+        cookie.set_domain("example.org");
+        assert_eq!(cookie.to_string(), "foo=bar; Path=/; Domain=example.org");
+
+        // The last domain wins, along with whether it had a leading dot.
+        let cookie = Cookie::parse("foo=bar; Domain=.a.com; Domain=b.com").unwrap();
+        assert!(!cookie.domain_leading_dot());
+        let cookie = Cookie::parse("foo=bar; Domain=a.com; Domain=.b.com; Domain=").unwrap();
+        assert_eq!(cookie.to_string(), "foo=bar; Domain=.b.com");
+
+        // A domain set with a dot isn't written with two.
+        let mut cookie = Cookie::build("foo", "bar").domain(".a.com").finish();
+        cookie.set_domain_leading_dot(true);
+        assert_eq!(cookie.to_string(), "foo=bar; Domain=.a.com");
+    }
 }
@@ -0,0 +1,210 @@
+use std::borrow::{Borrow, BorrowMut};
+
+use crate::{Cookie, CookieJar};
+
+/// A child cookie jar that namespaces its cookies with a name prefix.
+///
+/// A _prefixed_ child jar prepends its prefix to the name of every cookie
+/// added to or removed through it, and only retrieves cookies whose names
+/// start with the prefix, returning them with the prefix stripped. This allows
+/// several applications sharing a domain to keep their cookies apart without
+/// spreading the prefix across application code.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{CookieJar, Cookie};
+///
+/// let mut jar = CookieJar::new();
+/// jar.prefixed_mut("myapp_").add(Cookie::new("session", "abc"));
+///
+/// assert_eq!(jar.get("myapp_session").unwrap().value(), "abc");
+/// assert_eq!(jar.prefixed("myapp_").get("session").unwrap().name(), "session");
+/// assert!(jar.prefixed("other_").get("session").is_none());
+/// ```
+pub struct PrefixedJar<J> {
+    parent: J,
+    prefix: String,
+}
+
+impl<J> PrefixedJar<J> {
+    /// Creates a new child `PrefixedJar` with parent `parent` and prefix
+    /// `prefix`. This method is typically called indirectly via the
+    /// `prefixed{_mut}` methods of `CookieJar`.
+    pub(crate) fn new(parent: J, prefix: &str) -> PrefixedJar<J> {
+        PrefixedJar { parent, prefix: prefix.to_string() }
+    }
+
+    /// Returns the prefix of this jar.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::CookieJar;
+    ///
+    /// let jar = CookieJar::new();
+    /// assert_eq!(jar.prefixed("myapp_").prefix(), "myapp_");
+    /// ```
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Returns `cookie` with its name prefixed.
+    fn prefixed(&self, mut cookie: Cookie<'static>) -> Cookie<'static> {
+        cookie.set_name(format!("{}{}", self.prefix, cookie.name()));
+        cookie
+    }
+
+    /// Returns a copy of `cookie`, a cookie in the parent jar, with the prefix
+    /// stripped from its name, or `None` if its name isn't prefixed.
+    fn stripped(&self, cookie: &Cookie<'static>) -> Option<Cookie<'static>> {
+        let name = cookie.name().strip_prefix(&*self.prefix)?.to_string();
+        let mut cookie = cookie.clone();
+        cookie.set_name(name);
+        Some(cookie)
+    }
+}
+
+impl<J: Borrow<CookieJar>> PrefixedJar<J> {
+    /// Returns the `Cookie` in the parent jar named `name` with this jar's
+    /// prefix, with the prefix stripped from its name. If the cookie cannot be
+    /// found, `None` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie};
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.add(Cookie::new("myapp_name", "value"));
+    /// jar.add(Cookie::new("name", "other"));
+    ///
+    /// let cookie = jar.prefixed("myapp_").get("name").unwrap();
+    /// assert_eq!((cookie.name(), cookie.value()), ("name", "value"));
+    /// assert!(jar.prefixed("myapp_").get("myapp_name").is_none());
+    /// ```
+    pub fn get(&self, name: &str) -> Option<Cookie<'static>> {
+        let cookie = self.parent.borrow().get(&format!("{}{}", self.prefix, name))?;
+        self.stripped(cookie)
+    }
+
+    /// Returns an iterator over every cookie in the parent jar whose name
+    /// starts with this jar's prefix, with the prefix stripped from its name,
+    /// in no particular order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie};
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.prefixed_mut("a_").add(Cookie::new("one", "1"));
+    /// jar.prefixed_mut("a_").add(Cookie::new("two", "2"));
+    /// jar.prefixed_mut("b_").add(Cookie::new("one", "3"));
+    ///
+    /// let prefixed = jar.prefixed("a_");
+    /// let mut names: Vec<_> = prefixed.iter().map(|c| c.name().to_string()).collect();
+    /// names.sort();
+    /// assert_eq!(names, ["one", "two"]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = Cookie<'static>> + '_ {
+        self.parent.borrow().iter().filter_map(move |cookie| self.stripped(cookie))
+    }
+}
+
+impl<J: BorrowMut<CookieJar>> PrefixedJar<J> {
+    /// Adds `cookie` to the parent jar, its name prefixed with this jar's
+    /// prefix.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie};
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.prefixed_mut("myapp_").add(Cookie::new("name", "value"));
+    ///
+    /// assert_eq!(jar.get("myapp_name").unwrap().value(), "value");
+    /// assert_eq!(jar.delta().count(), 1);
+    /// ```
+    pub fn add(&mut self, cookie: Cookie<'static>) {
+        let cookie = self.prefixed(cookie);
+        self.parent.borrow_mut().add(cookie);
+    }
+
+    /// Adds an "original" `cookie` to the parent jar, its name prefixed with
+    /// this jar's prefix. For accurate `delta` computation, this method should
+    /// be used to add cookies that were received from the client.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie};
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.prefixed_mut("myapp_").add_original(Cookie::new("name", "value"));
+    ///
+    /// assert_eq!(jar.get("myapp_name").unwrap().value(), "value");
+    /// assert_eq!(jar.delta().count(), 0);
+    /// ```
+    pub fn add_original(&mut self, cookie: Cookie<'static>) {
+        let cookie = self.prefixed(cookie);
+        self.parent.borrow_mut().add_original(cookie);
+    }
+
+    /// Removes `cookie`, its name prefixed with this jar's prefix, from the
+    /// parent jar.
+    ///
+    /// For correct removal, the passed in `cookie` must contain the same `path`
+    /// and `domain` as the cookie that was initially set.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie};
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.add(Cookie::new("name", "value"));
+    /// jar.prefixed_mut("myapp_").add(Cookie::new("name", "value"));
+    ///
+    /// jar.prefixed_mut("myapp_").remove(Cookie::named("name"));
+    /// assert!(jar.get("myapp_name").is_none());
+    /// assert!(jar.get("name").is_some());
+    /// ```
+    pub fn remove(&mut self, cookie: Cookie<'static>) {
+        let cookie = self.prefixed(cookie);
+        self.parent.borrow_mut().remove(cookie);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{CookieJar, Cookie};
+
+    #[test]
+    fn prefixing() {
+        let mut jar = CookieJar::new();
+        let mut prefixed = jar.prefixed_mut("app_");
+        prefixed.add(Cookie::build("name", "value").path("/").finish());
+        prefixed.add(Cookie::new("other", "value"));
+        assert_eq!(prefixed.get("name").unwrap().path(), Some("/"));
+        assert_eq!(prefixed.iter().count(), 2);
+
+        prefixed.remove(Cookie::named("other"));
+        assert!(prefixed.get("other").is_none());
+        assert_eq!(jar.iter().count(), 1);
+        assert_eq!(jar.get("app_name").unwrap().path(), Some("/"));
+
+        // Only prefixed names are visible; the empty prefix shows every cookie.
+        jar.add(Cookie::new("app_", "empty"));
+        jar.add(Cookie::new("apple", "value"));
+        assert_eq!(jar.prefixed("app_").get("").unwrap().value(), "empty");
+        assert!(jar.prefixed("app_").get("le").is_none());
+        assert_eq!(jar.prefixed("").iter().count(), jar.iter().count());
+        assert_eq!(jar.prefixed("a").get("pp_name").unwrap().name(), "pp_name");
+
+        let mut names: Vec<_> = jar.prefixed("app").iter().map(|c| c.name().to_string()).collect();
+        names.sort();
+        assert_eq!(names, ["_", "_name", "le"]);
+    }
+}
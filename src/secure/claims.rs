@@ -0,0 +1,518 @@
+use std::borrow::{Borrow, BorrowMut};
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+
+use time::{Duration, OffsetDateTime, PrimitiveDateTime};
+
+use crate::secure::{Key, SignedJar, VerificationError};
+use crate::secure::base64::{self, URL_SAFE_NO_PAD};
+use crate::{Cookie, CookieJar};
+
+/// The context claims are signed with, so that values signed by other jars
+/// with the same key are never mistaken for claims.
+const CLAIMS_CONTEXT: &[u8] = b"COOKIE;CLAIMS";
+
+/// The separator between the fields of an encoded set of claims.
+const FIELD_SEPARATOR: char = '.';
+
+/// A set of named claims with an issue time and an optional expiry, stored in
+/// a cookie by a [`ClaimsJar`].
+///
+/// Claims are string-valued and ordered by name. The issue time, or `iat`, is
+/// set when the claims are created and the expiry, or `exp`, via
+/// [`Claims::with_expiry()`]. Both are kept to the second.
+///
+/// With the `serde` feature, claims implement `Serialize` and `Deserialize` as
+/// a struct with the fields `iat` and `exp`, Unix timestamps in seconds, the
+/// latter optional, and `claims`, a sequence of `(name, value)` pairs.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::Claims;
+/// use cookie::time::Duration;
+///
+/// let claims = Claims::new()
+///     .with("user", "1234")
+///     .with("role", "admin")
+///     .with_expiry(Duration::hours(1));
+///
+/// assert_eq!(claims.get("user"), Some("1234"));
+/// assert!(!claims.is_expired());
+/// ```
+#[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Claims {
+    pub(crate) issued_at: OffsetDateTime,
+    pub(crate) expires: Option<OffsetDateTime>,
+    pub(crate) claims: BTreeMap<String, String>,
+}
+
+impl Claims {
+    /// Creates an empty set of claims issued now and without an expiry.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Claims;
+    ///
+    /// let claims = Claims::new();
+    /// assert_eq!(claims.iter().count(), 0);
+    /// assert!(claims.expires().is_none());
+    /// ```
+    pub fn new() -> Claims {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        Claims {
+            issued_at: OffsetDateTime::from_unix_timestamp(now).expect("current time"),
+            expires: None,
+            claims: BTreeMap::new(),
+        }
+    }
+
+    /// Sets the claims to expire `duration` after they were issued. Expired
+    /// claims are not returned by a [`ClaimsJar`]. An expiry beyond the range
+    /// of `OffsetDateTime` is capped to the latest or earliest representable
+    /// second.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Claims;
+    /// use cookie::time::Duration;
+    ///
+    /// let claims = Claims::new().with_expiry(Duration::minutes(30));
+    /// assert_eq!(claims.expires(), Some(claims.issued_at() + Duration::minutes(30)));
+    ///
+    /// let claims = Claims::new().with_expiry(Duration::MAX);
+    /// assert!(!claims.is_expired());
+    /// ```
+    pub fn with_expiry(mut self, duration: Duration) -> Self {
+        let min = PrimitiveDateTime::MIN.assume_utc().unix_timestamp();
+        let max = PrimitiveDateTime::MAX.assume_utc().unix_timestamp();
+        let expires = self.issued_at.unix_timestamp()
+            .checked_add(duration.whole_seconds())
+            .map_or(if duration.is_negative() { min } else { max }, |e| e.clamp(min, max));
+
+        self.expires = Some(OffsetDateTime::from_unix_timestamp(expires).expect("in range"));
+        self
+    }
+
+    /// Sets the claim named `name` to `value`, replacing any previous value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Claims;
+    ///
+    /// let claims = Claims::new().with("user", "1234").with("user", "5678");
+    /// assert_eq!(claims.get("user"), Some("5678"));
+    /// ```
+    pub fn with(mut self, name: &str, value: &str) -> Self {
+        self.insert(name, value);
+        self
+    }
+
+    /// Sets the claim named `name` to `value`, returning the previous value,
+    /// if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Claims;
+    ///
+    /// let mut claims = Claims::new();
+    /// assert_eq!(claims.insert("user", "1234"), None);
+    /// assert_eq!(claims.insert("user", "5678"), Some("1234".into()));
+    /// ```
+    pub fn insert(&mut self, name: &str, value: &str) -> Option<String> {
+        self.claims.insert(name.to_string(), value.to_string())
+    }
+
+    /// Removes the claim named `name`, returning its value, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Claims;
+    ///
+    /// let mut claims = Claims::new().with("user", "1234");
+    /// assert_eq!(claims.remove("user"), Some("1234".into()));
+    /// assert_eq!(claims.get("user"), None);
+    /// ```
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        self.claims.remove(name)
+    }
+
+    /// Returns the value of the claim named `name`, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Claims;
+    ///
+    /// let claims = Claims::new().with("user", "1234");
+    /// assert_eq!(claims.get("user"), Some("1234"));
+    /// assert_eq!(claims.get("role"), None);
+    /// ```
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.claims.get(name).map(|value| value.as_str())
+    }
+
+    /// Returns an iterator over the name and value of every claim, in order of
+    /// name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Claims;
+    ///
+    /// let claims = Claims::new().with("b", "2").with("a", "1");
+    /// let claims: Vec<_> = claims.iter().collect();
+    /// assert_eq!(claims, [("a", "1"), ("b", "2")]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.claims.iter().map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// Returns the time the claims were issued, to the second.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Claims;
+    /// use cookie::time::OffsetDateTime;
+    ///
+    /// let claims = Claims::new();
+    /// assert!(claims.issued_at() <= OffsetDateTime::now_utc());
+    /// ```
+    pub fn issued_at(&self) -> OffsetDateTime {
+        self.issued_at
+    }
+
+    /// Returns the time the claims expire, if they do.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Claims;
+    /// use cookie::time::Duration;
+    ///
+    /// assert!(Claims::new().expires().is_none());
+    /// assert!(Claims::new().with_expiry(Duration::hours(1)).expires().is_some());
+    /// ```
+    pub fn expires(&self) -> Option<OffsetDateTime> {
+        self.expires
+    }
+
+    /// Returns `true` if the claims have an expiry that has passed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Claims;
+    /// use cookie::time::Duration;
+    ///
+    /// assert!(!Claims::new().is_expired());
+    /// assert!(!Claims::new().with_expiry(Duration::hours(1)).is_expired());
+    /// assert!(Claims::new().with_expiry(Duration::hours(-1)).is_expired());
+    /// ```
+    pub fn is_expired(&self) -> bool {
+        matches!(self.expires, Some(expires) if OffsetDateTime::now_utc() > expires)
+    }
+
+    /// Encodes the claims as `iat.exp.claims`, where `exp` is empty if there
+    /// is no expiry and `claims` is the URL-safe, unpadded Base64 encoding of
+    /// each name and value, length-prefixed.
+    fn encode(&self) -> String {
+        let mut data = vec![];
+        for string in self.claims.iter().flat_map(|(n, v)| vec![n, v]) {
+            data.extend_from_slice(&(string.len() as u32).to_be_bytes());
+            data.extend_from_slice(string.as_bytes());
+        }
+
+        let expires = self.expires.map(|e| e.unix_timestamp().to_string()).unwrap_or_default();
+        format!("{}{sep}{}{sep}{}", self.issued_at.unix_timestamp(), expires,
+            base64::encode_config(&data, URL_SAFE_NO_PAD), sep = FIELD_SEPARATOR)
+    }
+
+    /// Decodes claims encoded by [`Claims::encode()`].
+    fn decode(encoded: &str) -> Option<Claims> {
+        let timestamp = |s: &str| OffsetDateTime::from_unix_timestamp(s.parse().ok()?).ok();
+
+        let mut fields = encoded.splitn(3, FIELD_SEPARATOR);
+        let issued_at = timestamp(fields.next()?)?;
+        let expires = match fields.next()? {
+            "" => None,
+            expires => Some(timestamp(expires)?),
+        };
+
+        // Splits a length-prefixed string from the front of `data`.
+        fn split_string(data: &[u8]) -> Option<(String, &[u8])> {
+            let len = u32::from_be_bytes(data.get(..4)?.try_into().ok()?) as usize;
+            let string = data.get(4..)?.get(..len)?.to_vec();
+            Some((String::from_utf8(string).ok()?, &data[(4 + len)..]))
+        }
+
+        let data = base64::decode_config(fields.next()?, URL_SAFE_NO_PAD).ok()?;
+        let (mut claims, mut rest) = (BTreeMap::new(), &data[..]);
+        while !rest.is_empty() {
+            let (name, data) = split_string(rest)?;
+            let (value, data) = split_string(data)?;
+            claims.insert(name, value);
+            rest = data;
+        }
+
+        Some(Claims { issued_at, expires, claims })
+    }
+}
+
+impl Default for Claims {
+    fn default() -> Self {
+        Claims::new()
+    }
+}
+
+/// A child cookie jar that stores signed [`Claims`].
+///
+/// A _claims_ child jar stores a set of claims in a cookie, encoded and signed
+/// as a unit with the jar's key, alongside the time the claims were issued and
+/// their expiry. Claims retrieved from the jar are verified, and claims that
+/// have expired are rejected. This covers stateless sessions holding a few
+/// fields and an expiry. Like a [`SignedJar`], the claims are visible to
+/// clients in their encoded form but cannot be tampered with or fabricated.
+///
+/// Claims are signed with a context distinct from that of any other jar, so
+/// values signed by a `SignedJar` with the same key are not accepted as
+/// claims.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{Claims, CookieJar, Cookie, Key};
+/// use cookie::time::Duration;
+///
+/// let key = Key::generate();
+/// let mut jar = CookieJar::new();
+/// let claims = Claims::new().with("user", "1234").with_expiry(Duration::hours(1));
+/// jar.claims_mut(&key).add(Cookie::named("session"), &claims);
+///
+/// let session = jar.claims(&key).get("session").unwrap();
+/// assert_eq!(session.get("user"), Some("1234"));
+/// assert_eq!(session, claims);
+/// ```
+#[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+pub struct ClaimsJar<J> {
+    parent: J,
+    signed: SignedJar<()>,
+}
+
+impl<J> ClaimsJar<J> {
+    /// Creates a new child `ClaimsJar` with parent `parent` and key `key`. This
+    /// method is typically called indirectly via the `claims{_mut}` methods of
+    /// `CookieJar`.
+    pub(crate) fn new(parent: J, key: &Key) -> ClaimsJar<J> {
+        let signed = SignedJar::new((), key).with_name_binding(true).with_context(CLAIMS_CONTEXT);
+        ClaimsJar { parent, signed }
+    }
+
+    /// Verifies and decodes the claims in `cookie`, returning them if they
+    /// verify and have not expired, or a [`VerificationError`] describing why
+    /// not otherwise. Values that verify but are not claims are rejected with
+    /// [`VerificationError::UnknownFormat`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Claims, CookieJar, Cookie, Key, VerificationError};
+    /// use cookie::time::Duration;
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// let claims = Claims::new().with_expiry(Duration::hours(-1));
+    /// jar.claims_mut(&key).add(Cookie::named("session"), &claims);
+    ///
+    /// let cookie = jar.get("session").cloned().unwrap();
+    /// let error = jar.claims(&key).verify_result(cookie).unwrap_err();
+    /// assert_eq!(error, VerificationError::Expired);
+    /// ```
+    pub fn verify_result(&self, cookie: Cookie<'static>) -> Result<Claims, VerificationError> {
+        let value = self.signed._verify(cookie.name(), cookie.value())?;
+        let claims = Claims::decode(&value).ok_or(VerificationError::UnknownFormat)?;
+        match claims.is_expired() {
+            true => Err(VerificationError::Expired),
+            false => Ok(claims),
+        }
+    }
+}
+
+impl<J: Borrow<CookieJar>> ClaimsJar<J> {
+    /// Returns the claims stored in the `Cookie` inside this jar with the name
+    /// `name`, if the cookie exists, verifies, and the claims have not expired.
+    /// Otherwise, `None` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Claims, CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// assert!(jar.claims(&key).get("session").is_none());
+    ///
+    /// jar.claims_mut(&key).add(Cookie::named("session"), &Claims::new().with("user", "1"));
+    /// assert_eq!(jar.claims(&key).get("session").unwrap().get("user"), Some("1"));
+    /// assert!(jar.claims(&Key::generate()).get("session").is_none());
+    /// ```
+    pub fn get(&self, name: &str) -> Option<Claims> {
+        self.get_result(name).ok()
+    }
+
+    /// Returns the claims stored in the `Cookie` inside this jar with the name
+    /// `name`. If the cookie cannot be found, returns
+    /// [`VerificationError::NotFound`]. If the cookie fails to verify or the
+    /// claims have expired, returns a [`VerificationError`] describing why.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Claims, CookieJar, Cookie, Key, VerificationError};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// assert_eq!(jar.claims(&key).get_result("session"), Err(VerificationError::NotFound));
+    ///
+    /// jar.signed_mut(&key).add(Cookie::new("session", "user=1"));
+    /// let error = jar.claims(&key).get_result("session").unwrap_err();
+    /// assert_eq!(error, VerificationError::BadSignature);
+    /// ```
+    pub fn get_result(&self, name: &str) -> Result<Claims, VerificationError> {
+        let cookie = self.parent.borrow().get(name).ok_or(VerificationError::NotFound)?;
+        self.verify_result(cookie.clone())
+    }
+}
+
+impl<J: BorrowMut<CookieJar>> ClaimsJar<J> {
+    /// Adds `cookie` to the parent jar with `claims`, encoded and signed, in
+    /// place of the cookie's value. If `cookie` has no expiration and the
+    /// claims expire, the cookie's `Expires` attribute is set to the claims'
+    /// expiry.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Claims, CookieJar, Cookie, Key};
+    /// use cookie::time::Duration;
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// let claims = Claims::new().with("user", "1234").with_expiry(Duration::hours(1));
+    /// jar.claims_mut(&key).add(Cookie::named("session"), &claims);
+    ///
+    /// let cookie = jar.get("session").unwrap();
+    /// assert_eq!(cookie.expires_datetime(), claims.expires());
+    /// assert_eq!(jar.claims(&key).get("session").unwrap(), claims);
+    /// ```
+    pub fn add(&mut self, mut cookie: Cookie<'static>, claims: &Claims) {
+        let value = self.signed.sign(cookie.name(), &claims.encode(), None, None);
+        cookie.set_value(value);
+        if let (None, Some(expires)) = (cookie.expires(), claims.expires()) {
+            cookie.set_expires(expires);
+        }
+
+        self.parent.borrow_mut().add(cookie);
+    }
+
+    /// Removes `cookie` from the parent jar.
+    ///
+    /// For correct removal, the passed in `cookie` must contain the same `path`
+    /// and `domain` as the cookie that was initially set.
+    ///
+    /// This is identical to [`CookieJar::remove()`]. See the method's
+    /// documentation for more details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Claims, CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// let mut claims_jar = jar.claims_mut(&key);
+    ///
+    /// claims_jar.add(Cookie::named("session"), &Claims::new());
+    /// assert!(claims_jar.get("session").is_some());
+    ///
+    /// claims_jar.remove(Cookie::named("session"));
+    /// assert!(claims_jar.get("session").is_none());
+    /// ```
+    pub fn remove(&mut self, cookie: Cookie<'static>) {
+        self.parent.borrow_mut().remove(cookie);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Claims;
+    use crate::{CookieJar, Cookie, Key, VerificationError};
+    use time::{Duration, OffsetDateTime};
+
+    #[test]
+    fn encoding() {
+        let claims = Claims::new()
+            .with("user", "1234")
+            .with("", "")
+            .with("a.b", "\u{fbff}\0=")
+            .with_expiry(Duration::minutes(5));
+
+        let encoded = claims.encode();
+        assert!(encoded.starts_with(&format!("{}.", claims.issued_at().unix_timestamp())));
+        assert_eq!(Claims::decode(&encoded), Some(claims));
+
+        for duration in &[Duration::MAX, Duration::MIN, Duration::seconds(i64::MAX)] {
+            let claims = Claims::new().with_expiry(*duration);
+            assert_eq!(claims.is_expired(), duration.is_negative());
+            assert_eq!(Claims::decode(&claims.encode()), Some(claims));
+        }
+
+        let claims = Claims::new();
+        assert!(claims.encode().ends_with(".."));
+        assert_eq!(Claims::decode(&claims.encode()), Some(claims));
+
+        for bad in &["", "1", "1.2", "x..", "1.x.", "1..!", "1..AAAA", "1..AAAAAQ"] {
+            assert_eq!(Claims::decode(bad), None, "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn claims_jar() {
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        let claims = Claims::new().with("user", "1234").with_expiry(Duration::hours(1));
+        jar.claims_mut(&key).add(Cookie::build("session", "").path("/").finish(), &claims);
+
+        let cookie = jar.get("session").unwrap().clone();
+        assert_eq!(cookie.path(), Some("/"));
+        assert_eq!(cookie.expires_datetime(), claims.expires());
+        assert_eq!(jar.claims(&key).get("session"), Some(claims));
+
+        // A cookie's own expiration is kept.
+        let expires = OffsetDateTime::now_utc() + Duration::days(1);
+        let cookie = Cookie::build("long", "").expires(expires).finish();
+        jar.claims_mut(&key).add(cookie, &Claims::new().with_expiry(Duration::hours(1)));
+        assert_eq!(jar.get("long").unwrap().expires_datetime(), Some(expires));
+
+        // Expired claims are rejected.
+        let expired = Claims::new().with_expiry(Duration::seconds(-1));
+        jar.claims_mut(&key).add(Cookie::named("old"), &expired);
+        assert_eq!(jar.claims(&key).get_result("old"), Err(VerificationError::Expired));
+
+        // Claims are bound to the cookie's name and aren't plain signed values.
+        jar.add(Cookie::new("moved", jar.get("session").unwrap().value().to_string()));
+        assert_eq!(jar.claims(&key).get_result("moved"), Err(VerificationError::BadSignature));
+        assert!(jar.signed(&key).get("session").is_none());
+
+        jar.signed_mut(&key).with_name_binding(true).with_context(b"COOKIE;CLAIMS")
+            .add(Cookie::new("other", "not claims"));
+        assert_eq!(jar.claims(&key).get_result("other"), Err(VerificationError::UnknownFormat));
+    }
+}
@@ -0,0 +1,318 @@
+//! Compatibility with Django signed values and signed cookies.
+//!
+//! Django's `TimestampSigner` produces values of the form
+//! `value:timestamp:signature`, where `timestamp` is the signing time in
+//! seconds, Base62 encoded, and `signature` is the URL-safe, unpadded Base64
+//! HMAC-SHA256 of `value:timestamp`, keyed with a key derived from the
+//! project's `SECRET_KEY` and a salt. [`DjangoSigner::sign()`] and
+//! [`DjangoSigner::unsign()`] produce and verify such values, as set and read
+//! by Django's `HttpResponse.set_signed_cookie()` and
+//! `HttpRequest.get_signed_cookie()` when used with a signer created by
+//! [`DjangoSigner::for_cookie()`]. [`DjangoSigner::dumps()`] and
+//! [`DjangoSigner::loads()`] additionally encode the value as in
+//! `django.core.signing.dumps()` and `loads()`.
+//!
+//! Only the SHA-256 algorithm, the default since Django 3.1, is supported.
+//! Compressed values, produced by `dumps(..., compress=True)`, are rejected.
+//!
+//! # Example
+//!
+//! ```rust
+//! use cookie::compat::django::DjangoSigner;
+//! use cookie::time::Duration;
+//!
+//! let signer = DjangoSigner::for_cookie("django-secret-key", "session", "");
+//! let signed = signer.sign("value");
+//! assert!(signed.starts_with("value:"));
+//!
+//! let value = signer.unsign(&signed, Some(Duration::hours(1))).unwrap();
+//! assert_eq!(value, "value");
+//! ```
+
+use hmac::{Hmac, Mac, digest::KeyInit};
+use sha2::{Digest, Sha256};
+use time::{Duration, OffsetDateTime};
+
+use crate::secure::VerificationError;
+use crate::secure::base64::{self, URL_SAFE_NO_PAD};
+use crate::secure::key::wipe;
+
+/// The separator between the parts of a signed value.
+const SEPARATOR: char = ':';
+
+/// The prefix of compressed `dumps()` payloads.
+const COMPRESSED_PREFIX: char = '.';
+
+/// The alphabet of Django's Base62 encoding, used for timestamps.
+const BASE62: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// The salt used by `django.core.signing.dumps()` and `loads()` by default.
+pub const DEFAULT_SALT: &str = "django.core.signing";
+
+/// The prefix Django adds to the salt of signed cookies.
+pub const COOKIE_SALT_PREFIX: &str = "django.http.cookies";
+
+/// A signer and verifier of values in the format of Django's
+/// `TimestampSigner`.
+#[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+pub struct DjangoSigner {
+    key: [u8; 32],
+}
+
+impl DjangoSigner {
+    /// Creates a new `DjangoSigner` for the project secret `secret_key` and
+    /// the salt `salt`, as in `TimestampSigner(key=secret_key, salt=salt)`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::compat::django::{DjangoSigner, DEFAULT_SALT};
+    ///
+    /// let signer = DjangoSigner::new("django-secret-key", DEFAULT_SALT);
+    /// ```
+    pub fn new(secret_key: &str, salt: &str) -> DjangoSigner {
+        // Django's `salted_hmac` keys the HMAC with `sha256(salt + secret)`.
+        let digest = Sha256::new()
+            .chain_update(salt)
+            .chain_update("signer")
+            .chain_update(secret_key)
+            .finalize();
+
+        let mut key = [0; 32];
+        key.copy_from_slice(&digest);
+        DjangoSigner { key }
+    }
+
+    /// Creates a new `DjangoSigner` for the signed cookie named `name`, as
+    /// set with `response.set_signed_cookie(name, value, salt=salt)`. Django's
+    /// default `salt` is the empty string.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::compat::django::DjangoSigner;
+    ///
+    /// let signer = DjangoSigner::for_cookie("django-secret-key", "session", "");
+    /// ```
+    pub fn for_cookie(secret_key: &str, name: &str, salt: &str) -> DjangoSigner {
+        let salt = format!("{}{}{}", COOKIE_SALT_PREFIX, name, salt);
+        DjangoSigner::new(secret_key, &salt)
+    }
+
+    /// Signs `value` with the current time, returning the signed value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::compat::django::{DjangoSigner, DEFAULT_SALT};
+    ///
+    /// let signer = DjangoSigner::new("django-secret-key", DEFAULT_SALT);
+    /// let signed = signer.sign("value");
+    /// assert_eq!(signed.matches(':').count(), 2);
+    /// ```
+    pub fn sign(&self, value: &str) -> String {
+        self.sign_at(value, OffsetDateTime::now_utc().unix_timestamp())
+    }
+
+    /// Verifies `signed`, a value produced by [`DjangoSigner::sign()`] or
+    /// Django's `TimestampSigner.sign()`, returning the original value. If
+    /// `max_age` is `Some`, values signed longer than `max_age` ago are
+    /// rejected with [`VerificationError::Expired`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::VerificationError;
+    /// use cookie::compat::django::{DjangoSigner, DEFAULT_SALT};
+    ///
+    /// let signer = DjangoSigner::new("django-secret-key", DEFAULT_SALT);
+    /// let signed = signer.sign("value");
+    /// assert_eq!(signer.unsign(&signed, None).unwrap(), "value");
+    ///
+    /// let tampered = signed.replacen("value", "other", 1);
+    /// assert_eq!(signer.unsign(&tampered, None), Err(VerificationError::BadSignature));
+    /// ```
+    pub fn unsign(&self, signed: &str, max_age: Option<Duration>) -> Result<String, VerificationError> {
+        let (value, signature) = signed.rsplit_once(SEPARATOR)
+            .ok_or(VerificationError::MissingDigest)?;
+
+        let signature = base64::decode_config(signature, URL_SAFE_NO_PAD)
+            .map_err(|_| VerificationError::BadBase64)?;
+
+        if self.mac(value).verify_slice(&signature).is_err() {
+            return Err(VerificationError::BadSignature);
+        }
+
+        let (value, timestamp) = value.rsplit_once(SEPARATOR)
+            .ok_or(VerificationError::MissingTimestamp)?;
+
+        let timestamp = base62_decode(timestamp).ok_or(VerificationError::MissingTimestamp)?;
+        if let Some(max_age) = max_age {
+            let age = OffsetDateTime::now_utc().unix_timestamp() - timestamp;
+            if age > max_age.whole_seconds() {
+                return Err(VerificationError::Expired);
+            }
+        }
+
+        Ok(value.to_string())
+    }
+
+    /// Signs `json`, a JSON document, as `django.core.signing.dumps()` signs
+    /// the serialization of an object, returning the signed value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::compat::django::{DjangoSigner, DEFAULT_SALT};
+    ///
+    /// let signer = DjangoSigner::new("django-secret-key", DEFAULT_SALT);
+    /// let signed = signer.dumps(r#"{"message":"hello"}"#);
+    /// assert!(signed.starts_with("eyJtZXNzYWdlIjoiaGVsbG8ifQ:"));
+    /// ```
+    pub fn dumps(&self, json: &str) -> String {
+        self.sign(&base64::encode_config(json, URL_SAFE_NO_PAD))
+    }
+
+    /// Verifies and decodes `signed`, a value produced by
+    /// [`DjangoSigner::dumps()`] or `django.core.signing.dumps()`, returning
+    /// the signed JSON document. `max_age` is as in
+    /// [`DjangoSigner::unsign()`]. Compressed values are rejected with
+    /// [`VerificationError::UnknownFormat`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::compat::django::{DjangoSigner, DEFAULT_SALT};
+    ///
+    /// let signer = DjangoSigner::new("django-secret-key", DEFAULT_SALT);
+    /// let signed = signer.dumps(r#"{"message":"hello"}"#);
+    /// assert_eq!(signer.loads(&signed, None).unwrap(), r#"{"message":"hello"}"#);
+    /// ```
+    pub fn loads(&self, signed: &str, max_age: Option<Duration>) -> Result<String, VerificationError> {
+        let payload = self.unsign(signed, max_age)?;
+        if payload.starts_with(COMPRESSED_PREFIX) {
+            return Err(VerificationError::UnknownFormat);
+        }
+
+        let json = base64::decode_config(&payload, URL_SAFE_NO_PAD)
+            .map_err(|_| VerificationError::BadBase64)?;
+
+        String::from_utf8(json).map_err(|_| VerificationError::BadBase64)
+    }
+
+    /// Signs `value` as if at the UNIX time `timestamp`.
+    fn sign_at(&self, value: &str, timestamp: i64) -> String {
+        let value = format!("{}{}{}", value, SEPARATOR, base62_encode(timestamp));
+        let signature = self.mac(&value).finalize().into_bytes();
+        let signature = base64::encode_config(signature, URL_SAFE_NO_PAD);
+        format!("{}{}{}", value, SEPARATOR, signature)
+    }
+
+    fn mac(&self, value: &str) -> Hmac<Sha256> {
+        let mut mac = <Hmac<Sha256> as KeyInit>::new_from_slice(&self.key).expect("good key");
+        mac.update(value.as_bytes());
+        mac
+    }
+}
+
+impl Drop for DjangoSigner {
+    fn drop(&mut self) {
+        wipe(&mut self.key);
+    }
+}
+
+/// Encodes `n` in Django's Base62.
+fn base62_encode(n: i64) -> String {
+    let (sign, mut n) = match n < 0 {
+        true => ("-", n.unsigned_abs()),
+        false => ("", n as u64),
+    };
+
+    let mut digits = vec![];
+    loop {
+        digits.push(BASE62[(n % 62) as usize]);
+        n /= 62;
+        if n == 0 {
+            break;
+        }
+    }
+
+    digits.reverse();
+    format!("{}{}", sign, String::from_utf8(digits).expect("ascii"))
+}
+
+/// Decodes `s`, Django's Base62 encoding of an integer.
+fn base62_decode(s: &str) -> Option<i64> {
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, s),
+    };
+
+    if digits.is_empty() {
+        return None;
+    }
+
+    let mut n: i64 = 0;
+    for c in digits.bytes() {
+        let digit = BASE62.iter().position(|&d| d == c)? as i64;
+        n = n.checked_mul(62)?.checked_add(digit)?;
+    }
+
+    Some(if negative { -n } else { n })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{base62_decode, base62_encode, DjangoSigner, DEFAULT_SALT};
+    use crate::secure::VerificationError;
+    use time::Duration;
+
+    #[test]
+    fn base62() {
+        assert_eq!(base62_encode(0), "0");
+        assert_eq!(base62_encode(61), "z");
+        assert_eq!(base62_encode(62), "10");
+        assert_eq!(base62_encode(1700000000), "1r31eq");
+        assert_eq!(base62_encode(-62), "-10");
+        for n in &[0, 1, 61, 62, 1700000000, -1, i64::MAX, i64::MIN + 1] {
+            assert_eq!(base62_decode(&base62_encode(*n)), Some(*n));
+        }
+
+        assert_eq!(base62_decode(""), None);
+        assert_eq!(base62_decode("-"), None);
+        assert_eq!(base62_decode("a+"), None);
+        assert_eq!(base62_decode("zzzzzzzzzzzzzzzzzzzz"), None);
+    }
+
+    #[test]
+    fn django_vectors() {
+        // Generated with Django's `TimestampSigner` at the time 1700000000.
+        let signer = DjangoSigner::new("secret", DEFAULT_SALT);
+        let signed = "eyJtZXNzYWdlIjoiaGVsbG8ifQ:1r31eq:GRycYxxnSnENBnPQL9uD9N57M3_zuEvI3JzAbZVRxjE";
+        let payload = "eyJtZXNzYWdlIjoiaGVsbG8ifQ";
+        assert_eq!(signer.sign_at(payload, 1700000000), signed);
+        assert_eq!(signer.loads(signed, None).unwrap(), r#"{"message":"hello"}"#);
+        assert_eq!(signer.loads(signed, Some(Duration::hours(1))), Err(VerificationError::Expired));
+
+        let signer = DjangoSigner::for_cookie("secret", "name", "");
+        let signed = "value:1r31eq:ZK8ledbiC_FZZBDVUH3E_SPTeq4siFs49HC-47IwvmU";
+        assert_eq!(signer.sign_at("value", 1700000000), signed);
+        assert_eq!(signer.unsign(signed, None).unwrap(), "value");
+
+        let other = DjangoSigner::for_cookie("secret", "other", "");
+        assert_eq!(other.unsign(signed, None), Err(VerificationError::BadSignature));
+    }
+
+    #[test]
+    fn django_errors() {
+        let signer = DjangoSigner::new("secret", DEFAULT_SALT);
+        assert_eq!(signer.unsign("value", None), Err(VerificationError::MissingDigest));
+        assert_eq!(signer.unsign("value:!!", None), Err(VerificationError::BadBase64));
+
+        let compressed = signer.sign(".eJyrVg");
+        assert_eq!(signer.loads(&compressed, None), Err(VerificationError::UnknownFormat));
+
+        let value_with_colons = signer.sign("a:b:c");
+        assert_eq!(signer.unsign(&value_with_colons, None).unwrap(), "a:b:c");
+    }
+}
@@ -0,0 +1,141 @@
+//! Compatibility with Express signed cookies.
+//!
+//! Express' `cookie-parser` middleware, via the `cookie-signature` package,
+//! signs cookies as `s:value.signature`, where `signature` is the standard,
+//! unpadded Base64 HMAC-SHA256 of `value` keyed with the application's cookie
+//! secret. A [`SignedJar`](crate::SignedJar) created with
+//! [`SignedJar::express_compatible()`](crate::SignedJar::express_compatible())
+//! reads and writes values in this format.
+//!
+//! Express percent-encodes cookie values when setting them, so the `:` in the
+//! prefix is sent as `%3A`. To read such cookies, parse them with
+//! `Cookie::parse_encoded()` (with the `percent-encode` feature) and send
+//! cookies produced by the jar with `Cookie::encoded()`.
+
+use hmac::{Hmac, Mac, digest::KeyInit};
+use sha2::Sha256;
+
+use crate::secure::{CookieSigner, VerificationError};
+use crate::secure::base64::{self, STANDARD_NO_PAD};
+use crate::secure::key::wipe;
+
+/// The prefix `cookie-parser` adds to signed values.
+const PREFIX: &str = "s:";
+
+/// The separator between the value and the signature in a signed value.
+const SEPARATOR: char = '.';
+
+/// A [`CookieSigner`] computing `cookie-signature` HMAC-SHA256 signatures.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::CookieSigner;
+/// use cookie::compat::express::ExpressSigner;
+///
+/// let signer = ExpressSigner::new(b"keyboard cat");
+/// let tag = signer.sign(b"value");
+/// assert!(signer.verify(b"value", &tag));
+/// ```
+#[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+pub struct ExpressSigner {
+    secret: Vec<u8>,
+}
+
+impl ExpressSigner {
+    /// Creates a new `ExpressSigner` with the cookie secret `secret`, as passed
+    /// to `cookieParser(secret)`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::compat::express::ExpressSigner;
+    ///
+    /// let signer = ExpressSigner::new(b"keyboard cat");
+    /// ```
+    pub fn new(secret: &[u8]) -> ExpressSigner {
+        ExpressSigner { secret: secret.to_vec() }
+    }
+
+    fn mac(&self, message: &[u8]) -> Hmac<Sha256> {
+        let mut mac = <Hmac<Sha256> as KeyInit>::new_from_slice(&self.secret)
+            .expect("any key length");
+
+        mac.update(message);
+        mac
+    }
+}
+
+impl CookieSigner for ExpressSigner {
+    fn tag_len(&self) -> usize {
+        32
+    }
+
+    fn sign(&self, message: &[u8]) -> Vec<u8> {
+        self.mac(message).finalize().into_bytes().to_vec()
+    }
+
+    fn verify(&self, message: &[u8], tag: &[u8]) -> bool {
+        self.mac(message).verify_slice(tag).is_ok()
+    }
+}
+
+impl Drop for ExpressSigner {
+    fn drop(&mut self) {
+        wipe(&mut self.secret);
+    }
+}
+
+/// Returns `value` signed with `signer` in the `cookie-parser` format.
+pub(crate) fn sign<S: CookieSigner>(signer: &S, value: &str) -> String {
+    let signature = base64::encode_config(signer.sign(value.as_bytes()), STANDARD_NO_PAD);
+    format!("{}{}{}{}", PREFIX, value, SEPARATOR, signature)
+}
+
+/// Verifies `signed`, a value in the `cookie-parser` format, with `signer`,
+/// returning the original value if it verifies.
+pub(crate) fn verify<S: CookieSigner>(
+    signer: &S,
+    signed: &str
+) -> Result<String, VerificationError> {
+    let signed = signed.strip_prefix(PREFIX).ok_or(VerificationError::UnknownFormat)?;
+    let (value, signature) = signed.rsplit_once(SEPARATOR)
+        .ok_or(VerificationError::MissingDigest)?;
+
+    let signature = base64::decode_config(signature, STANDARD_NO_PAD)
+        .map_err(|_| VerificationError::BadBase64)?;
+
+    match signer.verify(value.as_bytes(), &signature) {
+        true => Ok(value.to_string()),
+        false => Err(VerificationError::BadSignature),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{sign, verify, ExpressSigner};
+    use crate::secure::VerificationError;
+
+    #[test]
+    fn cookie_signature_vectors() {
+        // From the `cookie-signature` README.
+        let signer = ExpressSigner::new(b"tobiiscool");
+        let signed = "s:hello.DGDUkGlIkCzPz+C0B064FNgHdEjox7ch8tOBGslZ5QI";
+        assert_eq!(sign(&signer, "hello"), signed);
+        assert_eq!(verify(&signer, signed), Ok("hello".into()));
+
+        let other = ExpressSigner::new(b"luna");
+        assert_eq!(verify(&other, signed), Err(VerificationError::BadSignature));
+    }
+
+    #[test]
+    fn cookie_signature_errors() {
+        let signer = ExpressSigner::new(b"secret");
+        assert_eq!(verify(&signer, "hello"), Err(VerificationError::UnknownFormat));
+        assert_eq!(verify(&signer, "s:hello"), Err(VerificationError::MissingDigest));
+        assert_eq!(verify(&signer, "s:hello.!!"), Err(VerificationError::BadBase64));
+
+        let signed = sign(&signer, "a.b.c");
+        assert_eq!(verify(&signer, &signed), Ok("a.b.c".into()));
+    }
+}
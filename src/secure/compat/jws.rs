@@ -0,0 +1,269 @@
+//! JSON Web Signature (JWS) compact serialization.
+//!
+//! A value in the JWS compact serialization, as specified in RFC 7515, is of
+//! the form `header.payload.signature`, each part encoded as unpadded,
+//! URL-safe Base64. The header is a JSON object naming the MAC algorithm, the
+//! payload is the cookie's value, and the signature is the MAC of the encoded
+//! header and payload. A [`SignedJar`](crate::SignedJar) with
+//! [`SignedJar::with_jws_format()`](crate::SignedJar::with_jws_format())
+//! enabled reads and writes values in this format, allowing cookies to be
+//! verified by off-the-shelf JWS and JWT libraries, and vice-versa.
+//!
+//! Values are signed with HMAC-SHA256 (`HS256`), or HMAC-SHA512 (`HS512`) if
+//! the jar's algorithm is [`SigningAlgorithm::HmacSha512`], keyed with the
+//! key's [signing key](crate::Key::signing()): this is the secret to configure
+//! other libraries with. The ID of the signing key, if any, is the header's
+//! `kid`. When the cookie's value is a JSON object of claims, the value is a
+//! JSON Web Token (JWT).
+//!
+//! Inbound values are accepted with any header whose `alg` is the jar's
+//! algorithm, so values with `alg` `none` or an asymmetric algorithm are
+//! rejected, as are headers with critical extensions (`crit`).
+//!
+//! [`SigningAlgorithm::HmacSha512`]: crate::SigningAlgorithm::HmacSha512
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+use crate::secure::{CookieSigner, VerificationError};
+use crate::secure::base64::{self, URL_SAFE_NO_PAD};
+
+/// The separator between the parts of a value in the compact serialization.
+const SEPARATOR: char = '.';
+
+/// Returns the JWS `alg` of the HMAC with tags of `tag_len` bytes, if any.
+fn algorithm(tag_len: usize) -> Option<&'static str> {
+    match tag_len {
+        32 => Some("HS256"),
+        64 => Some("HS512"),
+        _ => None,
+    }
+}
+
+/// Returns `value` signed with `signer` in the JWS compact serialization.
+///
+/// # Panics
+///
+/// Panics if `signer`'s tags aren't those of HMAC-SHA256 or HMAC-SHA512.
+pub(crate) fn sign<S: CookieSigner>(signer: &S, value: &str) -> String {
+    let alg = algorithm(signer.tag_len()).expect("JWS requires an HMAC-SHA2 signer");
+    let header = match signer.key_id() {
+        Some(kid) => format!(r#"{{"alg":"{}","kid":"{}"}}"#, alg, kid),
+        None => format!(r#"{{"alg":"{}"}}"#, alg),
+    };
+
+    let mut signed = base64::encode_config(header, URL_SAFE_NO_PAD);
+    signed.push(SEPARATOR);
+    base64::encode_config_buf(value, URL_SAFE_NO_PAD, &mut signed);
+
+    let signature = signer.sign(signed.as_bytes());
+    signed.push(SEPARATOR);
+    base64::encode_config_buf(signature, URL_SAFE_NO_PAD, &mut signed);
+    signed
+}
+
+/// Verifies `signed`, a value in the JWS compact serialization, with `signer`,
+/// returning the payload if it verifies.
+pub(crate) fn verify<S: CookieSigner>(
+    signer: &S,
+    signed: &str
+) -> Result<String, VerificationError> {
+    let decode = |part| base64::decode_config(part, URL_SAFE_NO_PAD)
+        .map_err(|_| VerificationError::BadBase64);
+
+    let (input, signature) = signed.rsplit_once(SEPARATOR)
+        .ok_or(VerificationError::MissingDigest)?;
+
+    let (header, payload) = input.split_once(SEPARATOR).ok_or(VerificationError::MissingDigest)?;
+    let header = String::from_utf8(decode(header)?).map_err(|_| VerificationError::BadBase64)?;
+    let header = parse_header(&header).ok_or(VerificationError::UnknownFormat)?;
+    let member = |name| header.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str());
+
+    if member("alg") != algorithm(signer.tag_len()) || member("crit").is_some() {
+        return Err(VerificationError::UnknownFormat);
+    }
+
+    let signature = decode(signature)?;
+    let verified = match member("kid") {
+        Some(kid) => signer.verify_with_key_id(kid, input.as_bytes(), &signature),
+        None => signer.verify(input.as_bytes(), &signature),
+    };
+
+    if !verified {
+        return Err(VerificationError::BadSignature);
+    }
+
+    String::from_utf8(decode(payload)?).map_err(|_| VerificationError::BadBase64)
+}
+
+/// Parses `json`, a JOSE header: a JSON object whose members are all strings.
+/// Returns `None` if `json` is not such an object or has duplicate members.
+fn parse_header(json: &str) -> Option<Vec<(String, String)>> {
+    let mut chars = json.trim().chars().peekable();
+    let mut members: Vec<(String, String)> = vec![];
+    if chars.next()? != '{' {
+        return None;
+    }
+
+    skip_whitespace(&mut chars);
+    if chars.next_if_eq(&'}').is_some() {
+        return chars.next().map_or(Some(members), |_| None);
+    }
+
+    loop {
+        skip_whitespace(&mut chars);
+        let name = parse_string(&mut chars)?;
+        skip_whitespace(&mut chars);
+        chars.next_if_eq(&':')?;
+        skip_whitespace(&mut chars);
+        let value = parse_string(&mut chars)?;
+        if members.iter().any(|(n, _)| *n == name) {
+            return None;
+        }
+
+        members.push((name, value));
+        skip_whitespace(&mut chars);
+        match chars.next()? {
+            ',' => continue,
+            '}' if chars.next().is_none() => return Some(members),
+            _ => return None,
+        }
+    }
+}
+
+/// Skips JSON whitespace at the front of `chars`.
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| matches!(c, ' ' | '\t' | '\r' | '\n')).is_some() {}
+}
+
+/// Parses the four hexadecimal digits of a JSON `\u` escape from the front of
+/// `chars`.
+fn hex4(chars: &mut Peekable<Chars>) -> Option<u16> {
+    let digits: String = chars.take(4).collect();
+    match digits.len() {
+        4 => u16::from_str_radix(&digits, 16).ok(),
+        _ => None,
+    }
+}
+
+/// Parses a JSON string from the front of `chars`.
+fn parse_string(chars: &mut Peekable<Chars>) -> Option<String> {
+    let mut string = String::new();
+    chars.next_if_eq(&'"')?;
+    loop {
+        match chars.next()? {
+            '"' => return Some(string),
+            '\\' => match chars.next()? {
+                c @ ('"' | '\\' | '/') => string.push(c),
+                'b' => string.push('\u{8}'),
+                'f' => string.push('\u{c}'),
+                'n' => string.push('\n'),
+                'r' => string.push('\r'),
+                't' => string.push('\t'),
+                'u' => {
+                    let mut units = vec![hex4(chars)?];
+                    if (0xd800..0xdc00).contains(&units[0]) {
+                        chars.next_if_eq(&'\\')?;
+                        chars.next_if_eq(&'u')?;
+                        units.push(hex4(chars)?);
+                    }
+
+                    string.push(std::char::decode_utf16(units).next()?.ok()?);
+                }
+                _ => return None,
+            },
+            c if c < ' ' => return None,
+            c => string.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_header, sign, verify};
+    use crate::compat::express::ExpressSigner;
+    use crate::secure::base64::{self, URL_SAFE_NO_PAD};
+    use crate::secure::VerificationError;
+
+    #[test]
+    fn rfc7515_vector() {
+        // From RFC 7515, Appendix A.1.
+        let k = "AyM1SysPpbyDfgZld3umj1qzKObwVMkoqQ-EstJQLr_T-1qS0gZH75aKtMN3Yj0iPS4hcgUu\
+            TwjAzZr1Z9CAow";
+        let signer = ExpressSigner::new(&base64::decode_config(k, URL_SAFE_NO_PAD).unwrap());
+        let signed = "eyJ0eXAiOiJKV1QiLA0KICJhbGciOiJIUzI1NiJ9.\
+            eyJpc3MiOiJqb2UiLA0KICJleHAiOjEzMDA4MTkzODAsDQogImh0dHA6Ly9leGFtcGxlLmNvbS9pc19yb290Ij\
+            p0cnVlfQ.dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+
+        let payload = "{\"iss\":\"joe\",\r\n \"exp\":1300819380,\r\n \
+            \"http://example.com/is_root\":true}";
+        assert_eq!(verify(&signer, signed), Ok(payload.into()));
+
+        let other = ExpressSigner::new(b"other");
+        assert_eq!(verify(&other, signed), Err(VerificationError::BadSignature));
+    }
+
+    #[test]
+    fn roundtrip() {
+        let signer = ExpressSigner::new(b"secret");
+        let signed = sign(&signer, "{\"sub\":\"1234\"}");
+        assert!(signed.starts_with("eyJhbGciOiJIUzI1NiJ9."));
+        assert_eq!(verify(&signer, &signed), Ok("{\"sub\":\"1234\"}".into()));
+        assert_eq!(verify(&signer, &sign(&signer, "")), Ok("".into()));
+    }
+
+    #[test]
+    fn verification_errors() {
+        use VerificationError::*;
+
+        let signer = ExpressSigner::new(b"secret");
+        let jws = |header: &str, payload: &str| {
+            let input = format!("{}.{}", base64::encode_config(header, URL_SAFE_NO_PAD),
+                base64::encode_config(payload, URL_SAFE_NO_PAD));
+
+            let signature = crate::CookieSigner::sign(&signer, input.as_bytes());
+            format!("{}.{}", input, base64::encode_config(signature, URL_SAFE_NO_PAD))
+        };
+
+        let valid = jws(r#" { "typ" : "JWT", "alg":"HS256" } "#, "payload");
+        assert_eq!(verify(&signer, &valid), Ok("payload".into()));
+        assert_eq!(verify(&signer, &jws(r#"{"alg":"HS256","kid":"k1"}"#, "a")), Ok("a".into()));
+
+        assert_eq!(verify(&signer, &jws(r#"{"alg":"none"}"#, "a")), Err(UnknownFormat));
+        assert_eq!(verify(&signer, &jws(r#"{"alg":"HS512"}"#, "a")), Err(UnknownFormat));
+        assert_eq!(verify(&signer, &jws(r#"{"typ":"JWT"}"#, "a")), Err(UnknownFormat));
+        let crit = jws(r#"{"alg":"HS256","crit":"exp"}"#, "a");
+        assert_eq!(verify(&signer, &crit), Err(UnknownFormat));
+
+        assert_eq!(verify(&signer, "payload"), Err(MissingDigest));
+        assert_eq!(verify(&signer, "a.b"), Err(MissingDigest));
+        assert_eq!(verify(&signer, "!.a.b"), Err(BadBase64));
+        assert_eq!(verify(&signer, &format!("{}!", valid)), Err(BadBase64));
+
+        let (input, _) = valid.rsplit_once('.').unwrap();
+        assert_eq!(verify(&signer, &format!("{}.AAAA", input)), Err(BadSignature));
+        let (header, rest) = valid.split_once('.').unwrap();
+        let tampered = format!("{}.{}", header, rest.replacen('c', "d", 1));
+        assert_eq!(verify(&signer, &tampered), Err(BadSignature));
+    }
+
+    #[test]
+    fn header_parsing() {
+        let header = |json| parse_header(json).map(|members| {
+            members.into_iter().map(|(n, v)| format!("{}={}", n, v)).collect::<Vec<_>>()
+        });
+
+        assert_eq!(header("{}"), Some(vec![]));
+        assert_eq!(header(r#"{"alg":"HS256"}"#), Some(vec!["alg=HS256".into()]));
+        assert_eq!(header(r#" {"a" :"1" , "b": "2"} "#), Some(vec!["a=1".into(), "b=2".into()]));
+        let escaped = header(r#"{"a":"\"\\\/\n\u00e9\ud83c\udf6a"}"#);
+        assert_eq!(escaped, Some(vec!["a=\"\\/\n\u{e9}\u{1f36a}".into()]));
+
+        for bad in &["", "{", "}", "[]", "{}}", r#"{"a":1}"#, r#"{"a":"1",}"#,
+            r#"{"a":"1""b":"2"}"#, r#"{"a":"1","a":"2"}"#, r#"{"a":"\x"}"#, r#"{"a":"\ud83c"}"#,
+            r#"{"a":"\u12"}"#, "{\"a\":\"\n\"}", r#"{a:"1"}"#]
+        {
+            assert_eq!(header(bad), None, "{:?}", bad);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Interoperability with the signed cookie formats of other frameworks.
+//!
+//! The modules in `compat` read and write cookies in formats produced by
+//! frameworks in other languages, allowing Rust services to share cookies
+//! with existing applications, for instance during a migration.
+
+use hmac::{Hmac, Mac, digest::KeyInit};
+use sha2::Sha256;
+
+pub mod django;
+pub mod express;
+pub mod jws;
+pub mod rails;
+
+/// Fills `out` with PBKDF2-HMAC-SHA256 key material derived from `password`
+/// and `salt` using `rounds` iterations, as specified in RFC 8018.
+pub(crate) fn pbkdf2_hmac_sha256(password: &[u8], salt: &[u8], rounds: u32, out: &mut [u8]) {
+    let prf = <Hmac<Sha256> as KeyInit>::new_from_slice(password).expect("any key length");
+    for (i, block) in out.chunks_mut(32).enumerate() {
+        let mut mac = prf.clone();
+        mac.update(salt);
+        mac.update(&(i as u32 + 1).to_be_bytes());
+        let mut u = mac.finalize().into_bytes();
+        let mut t = u;
+
+        for _ in 1..rounds {
+            let mut mac = prf.clone();
+            mac.update(&u);
+            u = mac.finalize().into_bytes();
+            t.iter_mut().zip(u.iter()).for_each(|(t, u)| *t ^= u);
+        }
+
+        block.copy_from_slice(&t[..block.len()]);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::pbkdf2_hmac_sha256;
+    use crate::secure::hex;
+
+    #[test]
+    fn pbkdf2_vectors() {
+        let mut out = [0; 32];
+        pbkdf2_hmac_sha256(b"password", b"salt", 1, &mut out);
+        assert_eq!(hex::encode(&out),
+            "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b");
+
+        pbkdf2_hmac_sha256(b"password", b"salt", 2, &mut out);
+        assert_eq!(hex::encode(&out),
+            "ae4d0c95af6b46d32d0adff928f06dd02a303f8ef3c251dfd6e2d85a95474c43");
+
+        let mut out = [0; 40];
+        pbkdf2_hmac_sha256(b"passwordPASSWORDpassword",
+            b"saltSALTsaltSALTsaltSALTsaltSALTsalt", 4096, &mut out);
+        assert_eq!(hex::encode(&out), "348c89dbcbd32b2f32d814b8116e84cf2b17347e\
+            bc1800181c4e2a1fb8dd53e1c635518c7dac47e9");
+    }
+}
@@ -0,0 +1,229 @@
+//! Compatibility with Ruby on Rails signed cookies.
+//!
+//! Rails signs cookies with `ActiveSupport::MessageVerifier`, producing values
+//! of the form `base64(data)--hex(hmac(base64(data)))`, keyed with a key
+//! derived from the application's `secret_key_base` and a salt via PBKDF2.
+//! A [`SignedJar`](crate::SignedJar) created with
+//! [`SignedJar::rails_compatible()`](crate::SignedJar::rails_compatible())
+//! reads and writes values in this format.
+//!
+//! Only SHA-256 digests are supported, which requires the Rails application
+//! to be configured with:
+//!
+//! ```ruby
+//! config.active_support.key_generator_hash_digest_class = OpenSSL::Digest::SHA256
+//! config.action_dispatch.signed_cookie_digest = "SHA256"
+//! ```
+//!
+//! Like Rails, which writes cookie values form-encoded, the `+`, `/`, and `=`
+//! of the Base64 data are percent-escaped in cookies and unescaped when read.
+//!
+//! The verified value is the raw signed data. Depending on the application's
+//! cookie serializer and metadata settings, this is typically a JSON document
+//! which the application must decode itself.
+
+use hmac::{Hmac, Mac, digest::KeyInit};
+use sha2::Sha256;
+
+use crate::secure::{CookieSigner, VerificationError};
+use crate::secure::key::wipe;
+
+/// The number of PBKDF2 iterations used by `ActiveSupport::KeyGenerator`.
+const ITERATIONS: u32 = 1000;
+
+/// The length of the keys generated by `ActiveSupport::KeyGenerator`.
+const KEY_LEN: usize = 64;
+
+/// The salt Rails uses for signed cookies by default.
+pub const SIGNED_COOKIE_SALT: &str = "signed cookie";
+
+/// A [`CookieSigner`] computing Rails `MessageVerifier` HMAC-SHA256 digests.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::CookieSigner;
+/// use cookie::compat::rails::{RailsSigner, SIGNED_COOKIE_SALT};
+///
+/// let signer = RailsSigner::new(b"secret_key_base", SIGNED_COOKIE_SALT);
+/// let tag = signer.sign(b"data");
+/// assert!(signer.verify(b"data", &tag));
+/// ```
+#[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+pub struct RailsSigner {
+    key: [u8; KEY_LEN],
+}
+
+impl RailsSigner {
+    /// Creates a new `RailsSigner` with the key Rails derives from
+    /// `secret_key_base` and `salt`, as in
+    /// `ActiveSupport::KeyGenerator#generate_key(salt)`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::compat::rails::{RailsSigner, SIGNED_COOKIE_SALT};
+    ///
+    /// let signer = RailsSigner::new(b"secret_key_base", SIGNED_COOKIE_SALT);
+    /// ```
+    pub fn new(secret_key_base: &[u8], salt: &str) -> RailsSigner {
+        let mut key = [0; KEY_LEN];
+        super::pbkdf2_hmac_sha256(secret_key_base, salt.as_bytes(), ITERATIONS, &mut key);
+        RailsSigner { key }
+    }
+
+    fn mac(&self, message: &[u8]) -> Hmac<Sha256> {
+        let mut mac = <Hmac<Sha256> as KeyInit>::new_from_slice(&self.key).expect("good key");
+        mac.update(message);
+        mac
+    }
+}
+
+impl CookieSigner for RailsSigner {
+    fn tag_len(&self) -> usize {
+        32
+    }
+
+    fn sign(&self, message: &[u8]) -> Vec<u8> {
+        self.mac(message).finalize().into_bytes().to_vec()
+    }
+
+    fn verify(&self, message: &[u8], tag: &[u8]) -> bool {
+        self.mac(message).verify_slice(tag).is_ok()
+    }
+}
+
+impl Drop for RailsSigner {
+    fn drop(&mut self) {
+        wipe(&mut self.key);
+    }
+}
+
+/// The separator between the data and the digest in a signed value.
+const SEPARATOR: &str = "--";
+
+/// The Base64 characters Rails percent-escapes in cookie values and their
+/// escapes.
+const ESCAPES: &[(&str, &str)] = &[("+", "%2B"), ("/", "%2F"), ("=", "%3D")];
+
+/// Returns `value` signed with `signer` in the `MessageVerifier` format, with
+/// the data escaped as Rails escapes it in cookies.
+pub(crate) fn sign<S: CookieSigner>(signer: &S, value: &str) -> String {
+    let data = crate::secure::base64::encode(value);
+    let digest = crate::secure::hex::encode(&signer.sign(data.as_bytes()));
+    let data = ESCAPES.iter().fold(data, |data, (c, escape)| data.replace(c, escape));
+    format!("{}{}{}", data, SEPARATOR, digest)
+}
+
+/// Verifies `signed`, a value in the `MessageVerifier` format, with `signer`,
+/// returning the signed data if it verifies.
+pub(crate) fn verify<S: CookieSigner>(
+    signer: &S,
+    signed: &str
+) -> Result<String, VerificationError> {
+    let signed = ESCAPES.iter().fold(signed.to_string(), |signed, (c, escape)| {
+        signed.replace(escape, c).replace(&escape.to_lowercase(), c)
+    });
+
+    let split = signed.rfind(SEPARATOR).ok_or(VerificationError::MissingDigest)?;
+    let (data, digest) = (&signed[..split], &signed[(split + SEPARATOR.len())..]);
+    let digest = crate::secure::hex::decode(digest).ok_or(VerificationError::BadBase64)?;
+    if !signer.verify(data.as_bytes(), &digest) {
+        return Err(VerificationError::BadSignature);
+    }
+
+    let data = crate::secure::base64::decode(data).map_err(|_| VerificationError::BadBase64)?;
+    String::from_utf8(data).map_err(|_| VerificationError::BadBase64)
+}
+
+#[cfg(test)]
+mod test {
+    use hmac::{Hmac, Mac, digest::KeyInit};
+
+    use super::{sign, verify, RailsSigner, SIGNED_COOKIE_SALT};
+    use crate::{CookieJar, Cookie, CookieSigner, SignedJar};
+
+    /// The `secret_key_base` the SHA-256 vectors below are computed with.
+    const SECRET_KEY_BASE: &[u8] = b"0123456789abcdef0123456789abcdef0123456789abcdef\
+        0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+
+    /// A Rails 6.1 signed cookie named `name` holding the JSON string "hello",
+    /// as Rails writes it: the key is derived by `ActiveSupport::KeyGenerator`
+    /// with the SHA-256 settings in the module docs and the default salt, the
+    /// digest is HMAC-SHA256, and the data is escaped. Computed independently
+    /// of this crate, with Python's `hashlib` and `hmac`.
+    const RAILS_COOKIE: &str = "eyJfcmFpbHMiOnsibWVzc2FnZSI6IkltaGxiR3h2SWc9PSIsImV4cCI6bnV\
+        sbCwicHVyIjoiY29va2llLm5hbWUifX0%3D--\
+        540a26586572517860b6a10572adb569f4709b3fcd87d1fd788925377d94099c";
+
+    /// The data signed in `RAILS_COOKIE`.
+    const RAILS_DATA: &str =
+        r#"{"_rails":{"message":"ImhlbGxvIg==","exp":null,"pur":"cookie.name"}}"#;
+
+    /// A signer computing `MessageVerifier`'s default HMAC-SHA1 digests with a
+    /// raw secret.
+    struct Sha1Signer(&'static [u8]);
+
+    impl CookieSigner for Sha1Signer {
+        fn tag_len(&self) -> usize {
+            20
+        }
+
+        fn sign(&self, message: &[u8]) -> Vec<u8> {
+            let mut mac = <Hmac<sha1::Sha1> as KeyInit>::new_from_slice(self.0).unwrap();
+            mac.update(message);
+            mac.finalize().into_bytes().to_vec()
+        }
+
+        fn verify(&self, message: &[u8], tag: &[u8]) -> bool {
+            self.sign(message) == tag
+        }
+    }
+
+    #[test]
+    fn message_verifier_format() {
+        let signer = RailsSigner::new(b"secret", SIGNED_COOKIE_SALT);
+        let signed = sign(&signer, "hello");
+        assert!(signed.starts_with("aGVsbG8%3D--"));
+        assert_eq!(signed.len(), "aGVsbG8%3D--".len() + 64);
+        assert_eq!(verify(&signer, &signed), Ok("hello".into()));
+        assert_eq!(verify(&signer, &signed.replace("%3D", "=")), Ok("hello".into()));
+        assert_eq!(verify(&signer, &signed.replace("%3D", "%3d")), Ok("hello".into()));
+        assert!(sign(&signer, "\u{fb}\u{ff}").starts_with("w7vDvw%3D%3D--"));
+
+        let other = RailsSigner::new(b"secret", "other salt");
+        assert!(verify(&other, &signed).is_err());
+        assert!(verify(&signer, "aGVsbG8=").is_err());
+        assert!(verify(&signer, &signed.replace("aGVsbG8", "aGVsbG9")).is_err());
+        let upper = signed.to_uppercase().replace("AGVSBG8", "aGVsbG8");
+        assert!(verify(&signer, &upper).is_ok());
+    }
+
+    #[test]
+    fn known_answers() {
+        // Generated by `ActiveSupport::MessageVerifier.new("helloworld",
+        // serializer: JSON).generate({ key: "value" })`, with the default
+        // SHA1 digest and no key derivation.
+        let signed = "eyJrZXkiOiJ2YWx1ZSJ9--fa115453dbb4a28277b1ba07ef4c7437621f5d72";
+        let signer = Sha1Signer(b"helloworld");
+        assert_eq!(sign(&signer, r#"{"key":"value"}"#), signed);
+        assert_eq!(verify(&signer, signed), Ok(r#"{"key":"value"}"#.into()));
+
+        let signer = RailsSigner::new(SECRET_KEY_BASE, SIGNED_COOKIE_SALT);
+        assert_eq!(sign(&signer, RAILS_DATA), RAILS_COOKIE);
+        assert_eq!(verify(&signer, RAILS_COOKIE), Ok(RAILS_DATA.into()));
+    }
+
+    #[test]
+    fn rails_cookie_header() {
+        let header = format!("name={}", RAILS_COOKIE);
+        let mut jar = CookieJar::new();
+        jar.add_original(Cookie::parse(header).unwrap());
+
+        let signed = SignedJar::rails_compatible(&jar, SECRET_KEY_BASE, SIGNED_COOKIE_SALT);
+        assert_eq!(signed.get("name").unwrap().value(), RAILS_DATA);
+
+        let signed = SignedJar::rails_compatible(&jar, SECRET_KEY_BASE, "encrypted cookie");
+        assert!(signed.get("name").is_none());
+    }
+}
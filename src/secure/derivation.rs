@@ -0,0 +1,255 @@
+use hkdf::Hkdf;
+use sha2::{Sha256, Sha512};
+
+use crate::secure::{Key, KeyError};
+use crate::secure::key::wipe;
+
+/// The minimum length, in bytes, of the master key material to derive from.
+const MIN_MASTER_KEY_LEN: usize = 32;
+
+/// The length, in bytes, of the key material derived for a [`Key`].
+const DERIVED_LEN: usize = 64;
+
+/// The HKDF `info` used by [`Key::derive_from()`].
+const KEYS_INFO: &[u8] = b"COOKIE;SIGNED:HMAC-SHA256;PRIVATE:AEAD-AES-256-GCM";
+
+/// The hash function used by a [`KeyDerivation`].
+///
+/// The default hash is [`KdfHash::Sha256`].
+#[cfg_attr(all(nightly, doc), doc(cfg(feature = "key-expansion")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum KdfHash {
+    /// HKDF-SHA256.
+    Sha256,
+    /// HKDF-SHA512.
+    Sha512,
+}
+
+impl KdfHash {
+    /// The length, in bytes, of the output of the hash function.
+    fn output_len(self) -> usize {
+        match self {
+            KdfHash::Sha256 => 32,
+            KdfHash::Sha512 => 64,
+        }
+    }
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for KdfHash {
+    fn default() -> Self {
+        KdfHash::Sha256
+    }
+}
+
+/// Configurable HKDF derivation of a [`Key`] from master key material.
+///
+/// A `KeyDerivation` with the default settings derives the same keys as
+/// [`Key::derive_from()`]: HKDF-SHA256 without an extract step, the master key
+/// material being used as the pseudorandom key, and a fixed `info` string.
+/// Each setting can be changed to match an existing key derivation convention:
+///
+///   * [`KeyDerivation::with_salt()`] performs the HKDF extract step with the
+///     salt before expanding,
+///   * [`KeyDerivation::with_info()`] sets the `info` string, and
+///   * [`KeyDerivation::with_hash()`] sets the hash function.
+///
+/// The 64 bytes of output form the master key of the derived `Key`.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{Key, KeyDerivation, KdfHash};
+///
+/// # /*
+/// let master_key = { /* a cryptographically random key >= 32 bytes */ };
+/// # */
+/// # let master_key: &Vec<u8> = &(0..32).collect();
+///
+/// let key = KeyDerivation::new().derive(master_key).unwrap();
+/// assert!(key == Key::derive_from(master_key));
+///
+/// let key = KeyDerivation::new()
+///     .with_salt(b"my app")
+///     .with_info(b"cookie keys")
+///     .with_hash(KdfHash::Sha512)
+///     .derive(master_key)
+///     .unwrap();
+/// ```
+#[cfg_attr(all(nightly, doc), doc(cfg(feature = "key-expansion")))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyDerivation {
+    salt: Option<Vec<u8>>,
+    info: Vec<u8>,
+    hash: KdfHash,
+}
+
+impl KeyDerivation {
+    /// Creates a new `KeyDerivation` with the settings of
+    /// [`Key::derive_from()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::KeyDerivation;
+    ///
+    /// let derivation = KeyDerivation::new();
+    /// ```
+    pub fn new() -> KeyDerivation {
+        KeyDerivation { salt: None, info: KEYS_INFO.to_vec(), hash: KdfHash::default() }
+    }
+
+    /// Sets the salt for the HKDF extract step. By default, there is no
+    /// extract step: the master key material is used as the pseudorandom key
+    /// directly.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::KeyDerivation;
+    ///
+    /// # let master_key: &Vec<u8> = &(0..32).collect();
+    /// let a = KeyDerivation::new().with_salt(b"a").derive(master_key).unwrap();
+    /// let b = KeyDerivation::new().with_salt(b"b").derive(master_key).unwrap();
+    /// assert!(a != b);
+    /// ```
+    pub fn with_salt(mut self, salt: &[u8]) -> Self {
+        self.salt = Some(salt.to_vec());
+        self
+    }
+
+    /// Sets the HKDF `info` string.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::KeyDerivation;
+    ///
+    /// # let master_key: &Vec<u8> = &(0..32).collect();
+    /// let a = KeyDerivation::new().with_info(b"a").derive(master_key).unwrap();
+    /// let b = KeyDerivation::new().with_info(b"b").derive(master_key).unwrap();
+    /// assert!(a != b);
+    /// ```
+    pub fn with_info(mut self, info: &[u8]) -> Self {
+        self.info = info.to_vec();
+        self
+    }
+
+    /// Sets the hash function. The default is [`KdfHash::Sha256`].
+    ///
+    /// Without a salt, the master key material must be at least as long as the
+    /// output of the hash function: 64 bytes for [`KdfHash::Sha512`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{KdfHash, KeyDerivation};
+    ///
+    /// # let master_key: &Vec<u8> = &(0..64).collect();
+    /// let derivation = KeyDerivation::new().with_hash(KdfHash::Sha512);
+    /// assert!(derivation.derive(master_key).is_ok());
+    /// ```
+    pub fn with_hash(mut self, hash: KdfHash) -> Self {
+        self.hash = hash;
+        self
+    }
+
+    /// Derives a `Key` from `master_key` with the configured settings.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KeyError::TooShort`] if `master_key` is less than 32 bytes in
+    /// length or, without a salt, shorter than the output of the hash
+    /// function.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{KdfHash, KeyDerivation, KeyError};
+    ///
+    /// # let master_key: &Vec<u8> = &(0..32).collect();
+    /// assert!(KeyDerivation::new().derive(master_key).is_ok());
+    ///
+    /// let sha512 = KeyDerivation::new().with_hash(KdfHash::Sha512);
+    /// let error = sha512.derive(master_key).err();
+    /// assert_eq!(error, Some(KeyError::TooShort { expected: 64, found: 32 }));
+    /// assert!(sha512.with_salt(b"salt").derive(master_key).is_ok());
+    /// ```
+    pub fn derive(&self, master_key: &[u8]) -> Result<Key, KeyError> {
+        let expected = match self.salt {
+            Some(_) => MIN_MASTER_KEY_LEN,
+            None => MIN_MASTER_KEY_LEN.max(self.hash.output_len()),
+        };
+
+        if master_key.len() < expected {
+            return Err(KeyError::TooShort { expected, found: master_key.len() });
+        }
+
+        // Extract, if there's a salt, and expand with the hash `$H`.
+        macro_rules! hkdf {
+            ($H:ty, $output:expr) => {{
+                let hkdf = match self.salt {
+                    Some(ref salt) => Hkdf::<$H>::new(Some(salt), master_key),
+                    None => Hkdf::<$H>::from_prk(master_key).expect("key length prechecked"),
+                };
+
+                hkdf.expand(&self.info, $output).expect("expand into keys");
+            }};
+        }
+
+        let mut derived = [0; DERIVED_LEN];
+        match self.hash {
+            KdfHash::Sha256 => hkdf!(Sha256, &mut derived),
+            KdfHash::Sha512 => hkdf!(Sha512, &mut derived),
+        }
+
+        let key = Key::from(&derived);
+        wipe(&mut derived);
+        Ok(key)
+    }
+}
+
+impl Default for KeyDerivation {
+    fn default() -> Self {
+        KeyDerivation::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{KdfHash, KeyDerivation};
+    use crate::{Key, KeyError};
+
+    #[test]
+    fn default_matches_derive_from() {
+        let master_key: Vec<u8> = (0..32).collect();
+        let key = KeyDerivation::default().derive(&master_key).unwrap();
+        assert!(key == Key::derive_from(&master_key));
+    }
+
+    #[test]
+    fn known_vectors() {
+        let master_key: Vec<u8> = (0..32).collect();
+        let derivation = KeyDerivation::new().with_salt(b"salt").with_info(b"app;keys");
+
+        let key = derivation.derive(&master_key).unwrap();
+        assert_eq!(&key.to_hex()[..32], "027b4213107f5eab26d1a7dbbfd71744");
+
+        let key = derivation.with_hash(KdfHash::Sha512).derive(&master_key).unwrap();
+        assert_eq!(&key.to_hex()[..32], "7d0188e3a9eefa46c7b9c95a3377c298");
+    }
+
+    #[test]
+    fn too_short() {
+        let short = [0; 31];
+        let error = KeyError::TooShort { expected: 32, found: 31 };
+        assert_eq!(KeyDerivation::new().derive(&short).err(), Some(error));
+        assert_eq!(KeyDerivation::new().with_salt(b"s").derive(&short).err(), Some(error));
+
+        let sha512 = KeyDerivation::new().with_hash(KdfHash::Sha512);
+        let error = KeyError::TooShort { expected: 64, found: 63 };
+        assert_eq!(sha512.derive(&[0; 63]).err(), Some(error));
+        assert!(sha512.derive(&[0; 64]).is_ok());
+    }
+}
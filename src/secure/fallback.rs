@@ -0,0 +1,92 @@
+use crate::Cookie;
+
+/// A cookie retrieved with a plaintext fallback: either authenticated by a
+/// child jar or, if it failed to verify, returned as is.
+///
+/// Returned by [`SignedJar::get_or_plaintext()`] and
+/// [`PrivateJar::get_or_plaintext()`], which exist for staged rollouts in which
+/// cookies set before signing or encryption was introduced must keep working
+/// for a transition window. The contents of an `Unverified` cookie are entirely
+/// under the client's control and must be treated as such.
+///
+/// [`SignedJar::get_or_plaintext()`]: crate::SignedJar::get_or_plaintext()
+/// [`PrivateJar::get_or_plaintext()`]: crate::PrivateJar::get_or_plaintext()
+///
+/// # Example
+///
+/// ```rust
+/// # #[cfg(feature = "signed")] {
+/// use cookie::{CookieJar, Cookie, Key, MaybeVerified};
+///
+/// let key = Key::generate();
+/// let mut jar = CookieJar::new();
+/// jar.add(Cookie::new("session", "legacy"));
+///
+/// match jar.signed(&key).get_or_plaintext("session") {
+///     Some(MaybeVerified::Verified(cookie)) => assert_eq!(cookie.value(), "new"),
+///     Some(MaybeVerified::Unverified(cookie)) => assert_eq!(cookie.value(), "legacy"),
+///     None => panic!("the cookie exists"),
+/// }
+/// # }
+/// ```
+#[cfg_attr(all(nightly, doc), doc(cfg(any(feature = "private", feature = "signed"))))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum MaybeVerified {
+    /// The cookie verified: its value is authenticated.
+    Verified(Cookie<'static>),
+    /// The cookie failed to verify: its value is the raw, unauthenticated
+    /// value sent by the client.
+    Unverified(Cookie<'static>),
+}
+
+impl MaybeVerified {
+    /// Returns `true` if the cookie verified.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, MaybeVerified};
+    ///
+    /// assert!(MaybeVerified::Verified(Cookie::new("name", "value")).is_verified());
+    /// assert!(!MaybeVerified::Unverified(Cookie::new("name", "value")).is_verified());
+    /// ```
+    pub fn is_verified(&self) -> bool {
+        matches!(self, MaybeVerified::Verified(_))
+    }
+
+    /// Returns the cookie if it verified and `None` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, MaybeVerified};
+    ///
+    /// let verified = MaybeVerified::Verified(Cookie::new("name", "value"));
+    /// assert_eq!(verified.verified().unwrap().value(), "value");
+    ///
+    /// let unverified = MaybeVerified::Unverified(Cookie::new("name", "value"));
+    /// assert!(unverified.verified().is_none());
+    /// ```
+    pub fn verified(self) -> Option<Cookie<'static>> {
+        match self {
+            MaybeVerified::Verified(cookie) => Some(cookie),
+            MaybeVerified::Unverified(_) => None,
+        }
+    }
+
+    /// Returns the cookie, whether or not it verified.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Cookie, MaybeVerified};
+    ///
+    /// let unverified = MaybeVerified::Unverified(Cookie::new("name", "value"));
+    /// assert_eq!(unverified.into_cookie().value(), "value");
+    /// ```
+    pub fn into_cookie(self) -> Cookie<'static> {
+        match self {
+            MaybeVerified::Verified(cookie) | MaybeVerified::Unverified(cookie) => cookie,
+        }
+    }
+}
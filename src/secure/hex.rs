@@ -0,0 +1,48 @@
+/// Encodes `bytes` as lowercase hexadecimal.
+pub(crate) fn encode(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        hex.push(DIGITS[(byte >> 4) as usize] as char);
+        hex.push(DIGITS[(byte & 0xf) as usize] as char);
+    }
+
+    hex
+}
+
+/// Decodes `hex`, hexadecimal in either case. Returns `None` if `hex` has an
+/// odd length or contains a non-hexadecimal character.
+pub(crate) fn decode(hex: &str) -> Option<Vec<u8>> {
+    fn nibble(c: u8) -> Option<u8> {
+        match c {
+            b'0'..=b'9' => Some(c - b'0'),
+            b'a'..=b'f' => Some(c - b'a' + 10),
+            b'A'..=b'F' => Some(c - b'A' + 10),
+            _ => None,
+        }
+    }
+
+    let pairs = hex.as_bytes().chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return None;
+    }
+
+    pairs.map(|pair| Some(nibble(pair[0])? << 4 | nibble(pair[1])?)).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decode, encode};
+
+    #[test]
+    fn hex_roundtrip() {
+        assert_eq!(encode(&[0x00, 0x7f, 0xab, 0xff]), "007fabff");
+        assert_eq!(decode("007fabff"), Some(vec![0x00, 0x7f, 0xab, 0xff]));
+        assert_eq!(decode("007FABFF"), Some(vec![0x00, 0x7f, 0xab, 0xff]));
+        assert_eq!(decode(""), Some(vec![]));
+        assert_eq!(decode("abc"), None);
+        assert_eq!(decode("0g"), None);
+        assert_eq!(decode("£0"), None);
+    }
+}
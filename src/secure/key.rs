@@ -1,23 +1,89 @@
+use std::error::Error;
+use std::fmt;
+
 const SIGNING_KEY_LEN: usize = 32;
 const ENCRYPTION_KEY_LEN: usize = 32;
 const COMBINED_KEY_LENGTH: usize = SIGNING_KEY_LEN + ENCRYPTION_KEY_LEN;
 
+/// The length, in bytes, of the truncated hash forming a key's fingerprint.
+const FINGERPRINT_LEN: usize = 8;
+
+/// The prefix of the message hashed to compute a key's fingerprint.
+const FINGERPRINT_INFO: &[u8] = b"COOKIE;FINGERPRINT;";
+
 // Statically ensure the numbers above are in-sync.
 #[cfg(feature = "signed")]
 const_assert!(crate::secure::signed::KEY_LEN == SIGNING_KEY_LEN);
 #[cfg(feature = "private")]
 const_assert!(crate::secure::private::KEY_LEN == ENCRYPTION_KEY_LEN);
 
+/// Error returned when constructing a [`Key`] from invalid input.
+#[cfg_attr(all(nightly, doc), doc(cfg(any(feature = "private", feature = "signed"))))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum KeyError {
+    /// The key material is shorter than required.
+    TooShort {
+        /// The minimum required length, in bytes.
+        expected: usize,
+        /// The length, in bytes, of the supplied key material.
+        found: usize,
+    },
+    /// The key's textual encoding is invalid.
+    BadEncoding,
+}
+
+impl KeyError {
+    /// Returns a description of this error as a string
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            KeyError::TooShort { .. } => "the key material is too short",
+            KeyError::BadEncoding => "the key's encoding is invalid",
+        }
+    }
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            KeyError::TooShort { expected, found } => {
+                write!(f, "bad key length: expected >= {} bytes, found {}", expected, found)
+            }
+            KeyError::BadEncoding => write!(f, "{}", self.as_str()),
+        }
+    }
+}
+
+impl Error for KeyError {
+    fn description(&self) -> &str {
+        self.as_str()
+    }
+}
+
 /// A cryptographic master key for use with `Signed` and/or `Private` jars.
 ///
 /// This structure encapsulates secure, cryptographic keys for use with both
 /// [`PrivateJar`](crate::PrivateJar) and [`SignedJar`](crate::SignedJar). A
 /// single instance of a `Key` can be used for both a `PrivateJar` and a
 /// `SignedJar` simultaneously with no notable security implications.
+///
+/// With the `zeroize` feature, a `Key`'s bytes, and the raw key bytes copied
+/// into child jars and signers, are overwritten with zeros via [`zeroize`]
+/// when dropped, as is AES-GCM's derived GHASH key. The expanded key state
+/// held inside cipher and HMAC instances, which the underlying crates don't
+/// wipe, is not covered.
+///
+/// [`zeroize`]: https://docs.rs/zeroize
 #[cfg_attr(all(nightly, doc), doc(cfg(any(feature = "private", feature = "signed"))))]
 #[derive(Clone)]
 pub struct Key([u8; COMBINED_KEY_LENGTH /* SIGNING | ENCRYPTION */]);
 
+impl Drop for Key {
+    fn drop(&mut self) {
+        wipe(&mut self.0);
+    }
+}
+
 impl PartialEq for Key {
     fn eq(&self, other: &Self) -> bool {
         use subtle::ConstantTimeEq;
@@ -39,7 +105,8 @@ impl Key {
     ///
     /// # Panics
     ///
-    /// Panics if `key` is less than 64 bytes in length.
+    /// Panics if `key` is less than 64 bytes in length. See
+    /// [`Key::try_from_slice()`] for a non-panicking version.
     ///
     /// # Example
     ///
@@ -63,6 +130,66 @@ impl Key {
         output
     }
 
+    /// Attempts to create a new `Key` from a 512-bit cryptographically random
+    /// string, as in [`Key::from()`]. If `key` is less than 64 bytes in length,
+    /// returns [`KeyError::TooShort`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Key, KeyError};
+    ///
+    /// # /*
+    /// let key = { /* a cryptographically random key >= 64 bytes */ };
+    /// # */
+    /// # let key: &Vec<u8> = &(0..64).collect();
+    ///
+    /// assert!(Key::try_from_slice(key).is_ok());
+    ///
+    /// let error = Key::try_from_slice(&key[..32]).err();
+    /// assert_eq!(error, Some(KeyError::TooShort { expected: 64, found: 32 }));
+    /// ```
+    pub fn try_from_slice(key: &[u8]) -> Result<Key, KeyError> {
+        if key.len() < COMBINED_KEY_LENGTH {
+            return Err(KeyError::TooShort { expected: COMBINED_KEY_LENGTH, found: key.len() });
+        }
+
+        Ok(Key::from(key))
+    }
+
+    /// Creates a new `Key` from distinct signing and encryption keys, each a
+    /// 256-bit cryptographically random string.
+    ///
+    /// This is useful when signing and encryption secrets are already managed
+    /// separately. [`Key::signing()`] and [`Key::encryption()`] return `signing`
+    /// and `encryption`, respectively. For security, the two keys _must_ be
+    /// independent and cryptographically random.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Key;
+    ///
+    /// # /*
+    /// let signing = { /* a cryptographically random 32 byte key */ };
+    /// let encryption = { /* a different cryptographically random 32 byte key */ };
+    /// # */
+    /// # let (signing, encryption) = (&[1; 32], &[2; 32]);
+    ///
+    /// let key = Key::from_parts(signing, encryption);
+    /// assert_eq!(key.signing(), signing);
+    /// assert_eq!(key.encryption(), encryption);
+    /// ```
+    pub fn from_parts(
+        signing: &[u8; SIGNING_KEY_LEN],
+        encryption: &[u8; ENCRYPTION_KEY_LEN]
+    ) -> Key {
+        let mut output = Key::zero();
+        output.0[..SIGNING_KEY_LEN].copy_from_slice(signing);
+        output.0[SIGNING_KEY_LEN..].copy_from_slice(encryption);
+        output
+    }
+
     /// Derives new signing/encryption keys from a master key.
     ///
     /// The master key must be at least 256-bits (32 bytes). For security, the
@@ -71,7 +198,8 @@ impl Key {
     ///
     /// # Panics
     ///
-    /// Panics if `key` is less than 32 bytes in length.
+    /// Panics if `key` is less than 32 bytes in length. See
+    /// [`Key::try_derive_from()`] for a non-panicking version.
     ///
     /// # Example
     ///
@@ -92,12 +220,37 @@ impl Key {
             panic!("bad master key length: expected >= 32 bytes, found {}", master_key.len());
         }
 
+        Key::try_derive_from(master_key).expect("key length prechecked")
+    }
+
+    /// Attempts to derive new signing/encryption keys from a master key, as in
+    /// [`Key::derive_from()`]. If `master_key` is less than 32 bytes in length,
+    /// returns [`KeyError::TooShort`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Key, KeyError};
+    ///
+    /// # /*
+    /// let master_key = { /* a cryptographically random key >= 32 bytes */ };
+    /// # */
+    /// # let master_key: &Vec<u8> = &(0..32).collect();
+    ///
+    /// assert!(Key::try_derive_from(master_key).is_ok());
+    ///
+    /// let error = Key::try_derive_from(&master_key[..16]).err();
+    /// assert_eq!(error, Some(KeyError::TooShort { expected: 32, found: 16 }));
+    /// ```
+    #[cfg(feature = "key-expansion")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "key-expansion")))]
+    pub fn try_derive_from(master_key: &[u8]) -> Result<Key, KeyError> {
+        if master_key.len() < 32 {
+            return Err(KeyError::TooShort { expected: 32, found: master_key.len() });
+        }
+
         // Expand the master key into two HKDF generated keys.
-        const KEYS_INFO: &[u8] = b"COOKIE;SIGNED:HMAC-SHA256;PRIVATE:AEAD-AES-256-GCM";
-        let mut both_keys = [0; COMBINED_KEY_LENGTH];
-        let hk = hkdf::Hkdf::<sha2::Sha256>::from_prk(master_key).expect("key length prechecked");
-        hk.expand(KEYS_INFO, &mut both_keys).expect("expand into keys");
-        Key::from(&both_keys)
+        crate::secure::KeyDerivation::new().derive(master_key)
     }
 
     /// Generates signing/encryption keys from a secure, random source. Keys are
@@ -139,6 +292,36 @@ impl Key {
         Some(key)
     }
 
+    /// Generates signing/encryption keys from the cryptographically secure
+    /// random number generator `rng`. This allows the source of randomness to
+    /// be controlled, for instance on platforms without an operating system
+    /// provided source, or to generate keys deterministically in tests.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rng` fails to produce random bytes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Key;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let key = Key::generate_with(&mut StdRng::from_entropy());
+    ///
+    /// // A seeded generator generates the same key each time.
+    /// let a = Key::generate_with(&mut StdRng::seed_from_u64(42));
+    /// let b = Key::generate_with(&mut StdRng::seed_from_u64(42));
+    /// assert!(a == b);
+    /// ```
+    pub fn generate_with<R>(rng: &mut R) -> Key
+        where R: crate::secure::rand::CryptoRng + crate::secure::rand::RngCore
+    {
+        let mut key = Key::zero();
+        rng.fill_bytes(&mut key.0);
+        key
+    }
+
     /// Returns the raw bytes of a key suitable for signing cookies. Guaranteed
     /// to be at least 32 bytes.
     ///
@@ -183,11 +366,159 @@ impl Key {
     pub fn master(&self) -> &[u8] {
         &self.0
     }
+
+    /// Returns the fingerprint of `self`: a short, stable identifier for the key
+    /// that reveals nothing about the key itself. The fingerprint is the first
+    /// 64 bits of a domain-separated SHA-256 hash of the master key, encoded as
+    /// 16 lowercase hexadecimal characters, and is thus also a valid key ID.
+    ///
+    /// Fingerprints are useful to identify which key signed or encrypted a
+    /// cookie, both in logs and, when enabled via
+    /// [`SignedJar::with_key_fingerprints()`](crate::SignedJar::with_key_fingerprints())
+    /// or
+    /// [`PrivateJar::with_key_fingerprints()`](crate::PrivateJar::with_key_fingerprints()),
+    /// in the cookie's value itself.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Key;
+    ///
+    /// let key = Key::generate();
+    /// assert_eq!(key.fingerprint().len(), 16);
+    /// assert_eq!(key.fingerprint(), key.clone().fingerprint());
+    /// assert_ne!(key.fingerprint(), Key::generate().fingerprint());
+    /// ```
+    pub fn fingerprint(&self) -> String {
+        use sha2::Digest;
+
+        let hash = sha2::Sha256::new()
+            .chain_update(FINGERPRINT_INFO)
+            .chain_update(self.master())
+            .finalize();
+
+        crate::secure::hex::encode(&hash[..FINGERPRINT_LEN])
+    }
+
+    /// Returns the master key encoded as standard, padded Base64. The encoding
+    /// can be decoded with [`Key::from_base64()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Key;
+    ///
+    /// let key = Key::generate();
+    /// let encoded = key.to_base64();
+    /// assert!(Key::from_base64(&encoded).unwrap() == key);
+    /// ```
+    pub fn to_base64(&self) -> String {
+        crate::secure::base64::encode(self.master())
+    }
+
+    /// Decodes a `Key` from `encoded`, a master key encoded as standard Base64.
+    /// Leading and trailing whitespace is ignored. The decoded master key must
+    /// be at least 512-bits (64 bytes), as in [`Key::from()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KeyError::BadEncoding`] if `encoded` is not valid Base64 and
+    /// [`KeyError::TooShort`] if the decoded key is less than 64 bytes in
+    /// length.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Key, KeyError};
+    ///
+    /// let encoded = Key::generate().to_base64();
+    /// assert!(Key::from_base64(&encoded).is_ok());
+    ///
+    /// assert_eq!(Key::from_base64("!!").err(), Some(KeyError::BadEncoding));
+    /// assert!(Key::from_base64("AAAA").is_err());
+    /// ```
+    pub fn from_base64(encoded: &str) -> Result<Key, KeyError> {
+        let mut bytes = crate::secure::base64::decode(encoded.trim())
+            .map_err(|_| KeyError::BadEncoding)?;
+
+        let key = Key::try_from_slice(&bytes);
+        wipe(&mut bytes);
+        key
+    }
+
+    /// Returns the master key encoded as lowercase hexadecimal. The encoding
+    /// can be decoded with [`Key::from_hex()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Key;
+    ///
+    /// let key = Key::from(&[0xab; 64]);
+    /// assert_eq!(key.to_hex(), "ab".repeat(64));
+    /// ```
+    pub fn to_hex(&self) -> String {
+        crate::secure::hex::encode(self.master())
+    }
+
+    /// Decodes a `Key` from `encoded`, a master key encoded as hexadecimal in
+    /// either case. Leading and trailing whitespace is ignored. The decoded
+    /// master key must be at least 512-bits (64 bytes), as in [`Key::from()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KeyError::BadEncoding`] if `encoded` is not valid hexadecimal
+    /// and [`KeyError::TooShort`] if the decoded key is less than 64 bytes in
+    /// length.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Key, KeyError};
+    ///
+    /// let key = Key::generate();
+    /// assert!(Key::from_hex(&key.to_hex()).unwrap() == key);
+    /// assert!(Key::from_hex(&key.to_hex().to_uppercase()).unwrap() == key);
+    ///
+    /// assert_eq!(Key::from_hex("xy").err(), Some(KeyError::BadEncoding));
+    /// assert_eq!(Key::from_hex("abc").err(), Some(KeyError::BadEncoding));
+    /// assert!(Key::from_hex("abcd").is_err());
+    /// ```
+    pub fn from_hex(encoded: &str) -> Result<Key, KeyError> {
+        let mut bytes = crate::secure::hex::decode(encoded.trim())
+            .ok_or(KeyError::BadEncoding)?;
+
+        let key = Key::try_from_slice(&bytes);
+        wipe(&mut bytes);
+        key
+    }
+}
+
+/// Overwrites `bytes` with zeros, in a way the compiler won't elide, if the
+/// `zeroize` feature is enabled. Does nothing otherwise.
+pub(crate) fn wipe(bytes: &mut [u8]) {
+    #[cfg(feature = "zeroize")]
+    zeroize::Zeroize::zeroize(bytes);
+
+    #[cfg(not(feature = "zeroize"))]
+    let _ = bytes;
+}
+
+/// Returns `n_bytes` bytes from a cryptographically secure random number
+/// generator encoded as URL-safe, unpadded Base64.
+pub(crate) fn random_value(n_bytes: usize) -> String {
+    use crate::secure::rand::RngCore;
+    use crate::secure::base64::{self, URL_SAFE_NO_PAD};
+
+    let mut bytes = vec![0; n_bytes];
+    crate::secure::rand::thread_rng().fill_bytes(&mut bytes);
+    base64::encode_config(&bytes, URL_SAFE_NO_PAD)
 }
 
 #[cfg(test)]
 mod test {
-    use super::Key;
+    use super::{Key, KeyError};
+    use crate::secure::base64;
 
     #[test]
     fn from_works() {
@@ -200,6 +531,35 @@ mod test {
         assert_eq!(key.encryption(), &*encryption);
     }
 
+    #[test]
+    fn from_parts() {
+        let key = Key::from_parts(&[1; 32], &[2; 32]);
+        assert_eq!(key.signing(), &[1; 32]);
+        assert_eq!(key.encryption(), &[2; 32]);
+        assert!(key == Key::from(&[[1; 32], [2; 32]].concat()));
+    }
+
+    #[test]
+    fn try_from_slice() {
+        let bytes: Vec<u8> = (0..64).collect();
+        assert!(Key::try_from_slice(&bytes).unwrap() == Key::from(&bytes));
+
+        let short = KeyError::TooShort { expected: 64, found: 63 };
+        assert_eq!(Key::try_from_slice(&bytes[..63]).err(), Some(short));
+        let empty = KeyError::TooShort { expected: 64, found: 0 };
+        assert_eq!(Key::try_from_slice(&[]).err(), Some(empty));
+    }
+
+    #[test]
+    #[cfg(feature = "key-expansion")]
+    fn try_derive_from() {
+        let master_key: Vec<u8> = (0..32).collect();
+        assert!(Key::try_derive_from(&master_key).unwrap() == Key::derive_from(&master_key));
+
+        let short = KeyError::TooShort { expected: 32, found: 31 };
+        assert_eq!(Key::try_derive_from(&master_key[..31]).err(), Some(short));
+    }
+
     #[test]
     #[cfg(feature = "key-expansion")]
     fn deterministic_derive() {
@@ -219,6 +579,52 @@ mod test {
         assert_ne!(key_2.encryption(), key_a.encryption());
     }
 
+    #[test]
+    fn encodings() {
+        let key = Key::generate();
+        assert!(Key::from_base64(&key.to_base64()).unwrap() == key);
+        assert!(Key::from_base64(&format!(" {}\n", key.to_base64())).unwrap() == key);
+        assert!(Key::from_hex(&key.to_hex()).unwrap() == key);
+        assert!(Key::from_hex(&format!("{}\n", key.to_hex())).unwrap() == key);
+
+        let short = KeyError::TooShort { expected: 64, found: 63 };
+        assert_eq!(Key::from_hex(&"00".repeat(63)).err(), Some(short));
+        assert_eq!(Key::from_base64(&base64::encode([0; 63])).err(), Some(short));
+        assert_eq!(Key::from_hex("0g").err(), Some(KeyError::BadEncoding));
+        assert_eq!(Key::from_hex("£0").err(), Some(KeyError::BadEncoding));
+        assert_eq!(Key::from_base64("a$b").err(), Some(KeyError::BadEncoding));
+        assert_eq!(short.to_string(), "bad key length: expected >= 64 bytes, found 63");
+    }
+
+    #[test]
+    fn fingerprint() {
+        let key = Key::from(&(0..64).collect::<Vec<_>>());
+        assert_eq!(key.fingerprint(), "e6930829a872171b");
+        assert_ne!(key.fingerprint(), Key::from(&[0; 64]).fingerprint());
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    fn wipe_zeroes() {
+        use super::wipe;
+
+        let mut bytes = [0xffu8; 64];
+        wipe(&mut bytes);
+        assert_eq!(bytes, [0; 64]);
+    }
+
+    #[test]
+    fn generate_with() {
+        use crate::secure::rand::{rngs::StdRng, SeedableRng};
+
+        let key_a = Key::generate_with(&mut StdRng::seed_from_u64(1));
+        let key_b = Key::generate_with(&mut StdRng::seed_from_u64(1));
+        let key_c = Key::generate_with(&mut StdRng::seed_from_u64(2));
+        assert!(key_a == key_b);
+        assert_ne!(key_a.signing(), key_c.signing());
+        assert_ne!(key_a.signing(), key_a.encryption());
+    }
+
     #[test]
     fn non_deterministic_generate() {
         let key_a = Key::generate();
@@ -0,0 +1,291 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use aes_gcm::Aes256Gcm;
+use aes_gcm::aead::{Aead, NewAead, generic_array::GenericArray, Payload};
+
+use crate::secure::{rand::RngCore, Key};
+use crate::secure::base64::{self, URL_SAFE_NO_PAD};
+use crate::secure::compat::pbkdf2_hmac_sha256;
+use crate::secure::key::wipe;
+use crate::secure::private::{KEY_LEN, NONCE_LEN};
+
+/// The header of an encrypted key file: the file format and the passphrase
+/// key derivation function.
+const HEADER: &str = "cookie-key:v1:pbkdf2-sha256";
+
+/// The separator between the fields of an encrypted key file.
+const FIELD_SEPARATOR: char = ':';
+
+/// The number of PBKDF2 iterations used when saving a key file. Loading uses
+/// the number recorded in the file.
+const ROUNDS: u32 = 600_000;
+
+/// The largest number of PBKDF2 iterations accepted when loading a key file,
+/// bounding the work an untrusted file can demand.
+const MAX_ROUNDS: u32 = 10 * ROUNDS;
+
+/// The length, in bytes, of the random salt for the passphrase derivation.
+const SALT_LEN: usize = 16;
+
+/// Error returned when loading an encrypted key file with
+/// [`Key::load_encrypted()`].
+#[cfg_attr(all(nightly, doc), doc(cfg(all(feature = "private", feature = "signed"))))]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum KeyFileError {
+    /// The file could not be read.
+    Io(io::Error),
+    /// The file is not an encrypted key file, or its iteration count is too
+    /// large.
+    Malformed,
+    /// The file failed to decrypt: the passphrase is incorrect or the file
+    /// has been modified.
+    BadPassphrase,
+}
+
+impl KeyFileError {
+    /// Returns a description of this error as a string
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            KeyFileError::Io(_) => "the key file could not be read",
+            KeyFileError::Malformed => "the key file is malformed",
+            KeyFileError::BadPassphrase => "the key file passphrase is incorrect",
+        }
+    }
+}
+
+impl fmt::Display for KeyFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            KeyFileError::Io(ref e) => write!(f, "{}: {}", self.as_str(), e),
+            _ => write!(f, "{}", self.as_str()),
+        }
+    }
+}
+
+impl Error for KeyFileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            KeyFileError::Io(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for KeyFileError {
+    fn from(error: io::Error) -> Self {
+        KeyFileError::Io(error)
+    }
+}
+
+impl Key {
+    /// Writes this key to the file at `path`, encrypted with a key derived
+    /// from `passphrase`, creating the file or replacing its contents. The
+    /// file can be read with [`Key::load_encrypted()`].
+    ///
+    /// The encryption key is derived with PBKDF2-HMAC-SHA256, 600,000
+    /// iterations, and a random salt; the master key is then encrypted with
+    /// AES-256-GCM. The file is a single line of text recording the format,
+    /// the iteration count, the salt, and the Base64 encrypted key, all of
+    /// which are authenticated. On Unix, a newly created file is readable and
+    /// writable only by its owner.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Key;
+    ///
+    /// fn provision(passphrase: &str) -> std::io::Result<()> {
+    ///     Key::generate().save_encrypted("/etc/my-app/cookie.key", passphrase.as_bytes())
+    /// }
+    /// ```
+    pub fn save_encrypted<P: AsRef<Path>>(&self, path: P, passphrase: &[u8]) -> io::Result<()> {
+        save(self, path.as_ref(), passphrase, ROUNDS)
+    }
+
+    /// Reads a key from the file at `path`, as written by
+    /// [`Key::save_encrypted()`], decrypting it with a key derived from
+    /// `passphrase`. Leading and trailing whitespace in the file is ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`KeyFileError::Io`] if the file cannot be read,
+    /// [`KeyFileError::Malformed`] if it isn't an encrypted key file or
+    /// records more than 6,000,000 iterations, and
+    /// [`KeyFileError::BadPassphrase`] if it fails to decrypt with
+    /// `passphrase`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Key, KeyFileError};
+    ///
+    /// fn cookie_key() -> Result<Key, KeyFileError> {
+    ///     let passphrase = std::env::var("COOKIE_KEY_PASSPHRASE").unwrap_or_default();
+    ///     Key::load_encrypted("/etc/my-app/cookie.key", passphrase.as_bytes())
+    /// }
+    /// ```
+    pub fn load_encrypted<P: AsRef<Path>>(path: P, passphrase: &[u8]) -> Result<Key, KeyFileError> {
+        open(&fs::read_to_string(path)?, passphrase)
+    }
+}
+
+/// Writes `key`, encrypted with a key derived from `passphrase` with `rounds`
+/// PBKDF2 iterations, to the file at `path`.
+fn save(key: &Key, path: &Path, passphrase: &[u8], rounds: u32) -> io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let mut file = options.open(path)?;
+    file.write_all(seal(key, passphrase, rounds).as_bytes())?;
+    file.sync_all()
+}
+
+/// Returns the contents of a key file holding `key` encrypted with a key
+/// derived from `passphrase` with `rounds` PBKDF2 iterations.
+fn seal(key: &Key, passphrase: &[u8], rounds: u32) -> String {
+    let mut salt = [0; SALT_LEN];
+    let mut nonce = [0; NONCE_LEN];
+    let mut rng = crate::secure::rand::thread_rng();
+    rng.try_fill_bytes(&mut salt).expect("couldn't random fill salt");
+    rng.try_fill_bytes(&mut nonce).expect("couldn't random fill nonce");
+
+    // The header and parameters are authenticated as associated data.
+    let aad = format!("{}{sep}{}{sep}{}{sep}", HEADER, rounds,
+        base64::encode_config(salt, URL_SAFE_NO_PAD), sep = FIELD_SEPARATOR);
+
+    let aead = cipher(passphrase, &salt, rounds);
+    let payload = Payload { msg: key.master(), aad: aad.as_bytes() };
+    let mut data = nonce.to_vec();
+    data.extend(aead.encrypt(GenericArray::from_slice(&nonce), payload)
+        .expect("encryption failure!"));
+
+    format!("{}{}\n", aad, base64::encode_config(&data, URL_SAFE_NO_PAD))
+}
+
+/// Decrypts `contents`, the contents of a key file, with `passphrase`.
+fn open(contents: &str, passphrase: &[u8]) -> Result<Key, KeyFileError> {
+    let contents = contents.trim();
+    let (aad, sealed) = contents.rsplit_once(FIELD_SEPARATOR).ok_or(KeyFileError::Malformed)?;
+    let (rounds, salt) = aad.strip_prefix(HEADER)
+        .and_then(|params| params.strip_prefix(FIELD_SEPARATOR))
+        .and_then(|params| params.split_once(FIELD_SEPARATOR))
+        .ok_or(KeyFileError::Malformed)?;
+
+    let rounds = match rounds.parse::<u32>() {
+        Ok(n) if n > 0 && n <= MAX_ROUNDS && !rounds.starts_with('+') => n,
+        _ => return Err(KeyFileError::Malformed),
+    };
+
+    let decode = |s| base64::decode_config(s, URL_SAFE_NO_PAD).map_err(|_| KeyFileError::Malformed);
+    let (salt, data) = (decode(salt)?, decode(sealed)?);
+    if data.len() <= NONCE_LEN {
+        return Err(KeyFileError::Malformed);
+    }
+
+    let (nonce, cipher_text) = data.split_at(NONCE_LEN);
+    let aad = &contents.as_bytes()[..(aad.len() + 1)];
+    let payload = Payload { msg: cipher_text, aad };
+    let mut master = cipher(passphrase, &salt, rounds)
+        .decrypt(GenericArray::from_slice(nonce), payload)
+        .map_err(|_| KeyFileError::BadPassphrase)?;
+
+    let key = Key::try_from_slice(&master).map_err(|_| KeyFileError::Malformed);
+    wipe(&mut master);
+    key
+}
+
+/// Returns the AES-256-GCM cipher keyed with the key derived from `passphrase`
+/// and `salt` with `rounds` PBKDF2 iterations.
+fn cipher(passphrase: &[u8], salt: &[u8], rounds: u32) -> Aes256Gcm {
+    let mut key = [0; KEY_LEN];
+    pbkdf2_hmac_sha256(passphrase, salt, rounds, &mut key);
+    let aead = Aes256Gcm::new(GenericArray::from_slice(&key));
+    wipe(&mut key);
+    aead
+}
+
+#[cfg(test)]
+mod test {
+    use super::{open, save, seal, KeyFileError, HEADER, MAX_ROUNDS};
+    use crate::Key;
+
+    #[test]
+    fn roundtrip() {
+        let key = Key::generate();
+        let sealed = seal(&key, b"passphrase", 2);
+        assert!(sealed.starts_with(&format!("{}:2:", HEADER)));
+        assert!(sealed.ends_with('\n'));
+        assert_ne!(sealed, seal(&key, b"passphrase", 2));
+        assert!(open(&sealed, b"passphrase").unwrap() == key);
+
+        let sealed = seal(&key, b"", 1);
+        assert!(open(&sealed, b"").unwrap() == key);
+    }
+
+    #[test]
+    fn errors() {
+        use KeyFileError::*;
+
+        let sealed = seal(&Key::generate(), b"passphrase", 2);
+        assert!(matches!(open(&sealed, b"passphras"), Err(BadPassphrase)));
+
+        // The parameters are authenticated.
+        let tampered = sealed.replacen(":2:", ":3:", 1);
+        assert!(matches!(open(&tampered, b"passphrase"), Err(BadPassphrase)));
+        let tampered = sealed.replacen("v1", "v2", 1);
+        assert!(matches!(open(&tampered, b"passphrase"), Err(Malformed)));
+
+        for malformed in &["", "key", ":::", "cookie-key:v1:pbkdf2-sha256:2:AAAA"] {
+            assert!(matches!(open(malformed, b"passphrase"), Err(Malformed)));
+        }
+
+        let zero = sealed.replacen(":2:", ":0:", 1);
+        assert!(matches!(open(&zero, b"passphrase"), Err(Malformed)));
+        let plus = sealed.replacen(":2:", ":+2:", 1);
+        assert!(matches!(open(&plus, b"passphrase"), Err(Malformed)));
+
+        // Oversized iteration counts are rejected before any derivation.
+        let huge = sealed.replacen(":2:", &format!(":{}:", MAX_ROUNDS + 1), 1);
+        assert!(matches!(open(&huge, b"passphrase"), Err(Malformed)));
+        let huge = sealed.replacen(":2:", &format!(":{}:", u32::MAX), 1);
+        assert!(matches!(open(&huge, b"passphrase"), Err(Malformed)));
+    }
+
+    #[test]
+    fn files() {
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        let name = format!("cookie-key-{}-{}", std::process::id(), nanos);
+        let path = std::env::temp_dir().join(name);
+        let key = Key::generate();
+
+        save(&key, &path, b"passphrase", 2).unwrap();
+        assert!(Key::load_encrypted(&path, b"passphrase").unwrap() == key);
+        #[cfg(unix)] {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // Saving replaces the file's contents.
+        let key = Key::generate();
+        save(&key, &path, b"other", 1).unwrap();
+        assert!(Key::load_encrypted(&path, b"other").unwrap() == key);
+        assert!(matches!(Key::load_encrypted(&path, b""), Err(KeyFileError::BadPassphrase)));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(Key::load_encrypted(&path, b""), Err(KeyFileError::Io(_))));
+    }
+}
@@ -0,0 +1,403 @@
+use std::collections::HashMap;
+use std::convert::TryInto;
+
+use crate::secure::Key;
+use crate::secure::key::wipe;
+
+/// The maximum length, in bytes, of a key ID.
+const MAX_KEY_ID_LEN: usize = 16;
+
+/// The HKDF `info` prefix, followed by a cookie's name, used to derive keys for
+/// the cookie.
+const DERIVED_KEY_INFO: &[u8] = b"COOKIE;DERIVED;";
+
+/// The separator between a key ID and the rest of a cookie value. This is not a
+/// valid key ID nor Base64 character, so a key ID prefix is unambiguous.
+const KEY_ID_SEPARATOR: char = '$';
+
+/// A collection of [`Key`]s, each identified by a short key ID.
+///
+/// A `Keyring` always contains a _primary_ key, the key used to sign and/or
+/// encrypt new cookies, and any number of additional keys used only to verify
+/// and/or decrypt existing cookies. Jars created from a keyring via
+/// [`CookieJar::signed_with_keyring()`](crate::CookieJar::signed_with_keyring())
+/// or
+/// [`CookieJar::private_with_keyring()`](crate::CookieJar::private_with_keyring())
+/// prefix the values they produce with the ID of the primary key. When reading
+/// a cookie, the key with the embedded ID is selected directly instead of
+/// attempting verification with every key.
+///
+/// Key IDs are between 1 and 16 ASCII alphanumeric characters. Because key IDs
+/// are sent to clients, they should not contain sensitive information.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{Key, Keyring};
+///
+/// let mut keyring = Keyring::new("k1", Key::generate());
+///
+/// // Rotate: new cookies are signed with `k2`, old cookies verify with `k1`.
+/// keyring.add("k2", Key::generate());
+/// assert_eq!(keyring.primary_id(), "k2");
+///
+/// // Once `k1` is retired, cookies signed with it no longer verify.
+/// assert!(keyring.retire("k1").is_some());
+/// assert!(keyring.get("k1").is_none());
+/// ```
+#[cfg_attr(all(nightly, doc), doc(cfg(any(feature = "private", feature = "signed"))))]
+#[derive(Clone)]
+pub struct Keyring {
+    primary: String,
+    keys: HashMap<String, Key>,
+}
+
+impl Keyring {
+    /// Creates a new `Keyring` with the primary key `key` identified by `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not a valid key ID: between 1 and 16 ASCII
+    /// alphanumeric characters.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Key, Keyring};
+    ///
+    /// let keyring = Keyring::new("k1", Key::generate());
+    /// assert_eq!(keyring.primary_id(), "k1");
+    /// ```
+    pub fn new(id: &str, key: Key) -> Keyring {
+        check_key_id(id);
+
+        let mut keys = HashMap::new();
+        keys.insert(id.to_string(), key);
+        Keyring { primary: id.to_string(), keys }
+    }
+
+    /// Adds `key`, identified by `id`, to `self` and makes it the primary key.
+    /// The previous primary key remains in the keyring, and can be used to
+    /// verify and decrypt cookies, until it is retired. If a key with the same
+    /// ID already exists, it is replaced.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not a valid key ID: between 1 and 16 ASCII
+    /// alphanumeric characters.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Key, Keyring};
+    ///
+    /// let mut keyring = Keyring::new("k1", Key::generate());
+    /// keyring.add("k2", Key::generate());
+    /// assert_eq!(keyring.primary_id(), "k2");
+    /// assert!(keyring.get("k1").is_some());
+    /// ```
+    pub fn add(&mut self, id: &str, key: Key) -> &mut Self {
+        check_key_id(id);
+        self.keys.insert(id.to_string(), key);
+        self.primary = id.to_string();
+        self
+    }
+
+    /// Removes the key identified by `id` from `self` and returns it. Cookies
+    /// signed or encrypted with a retired key are no longer valid. The primary
+    /// key cannot be retired: if `id` identifies the primary key, or there is
+    /// no key with the ID `id`, returns `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Key, Keyring};
+    ///
+    /// let mut keyring = Keyring::new("k1", Key::generate());
+    /// assert!(keyring.retire("k1").is_none());
+    ///
+    /// keyring.add("k2", Key::generate());
+    /// assert!(keyring.retire("k1").is_some());
+    /// assert!(keyring.get("k1").is_none());
+    /// ```
+    pub fn retire(&mut self, id: &str) -> Option<Key> {
+        if id == self.primary {
+            return None;
+        }
+
+        self.keys.remove(id)
+    }
+
+    /// Returns the key identified by `id`, if there is one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Key, Keyring};
+    ///
+    /// let key = Key::generate();
+    /// let keyring = Keyring::new("k1", key.clone());
+    /// assert!(keyring.get("k1") == Some(&key));
+    /// assert!(keyring.get("k2").is_none());
+    /// ```
+    pub fn get(&self, id: &str) -> Option<&Key> {
+        self.keys.get(id)
+    }
+
+    /// Returns the ID of the primary key.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Key, Keyring};
+    ///
+    /// let keyring = Keyring::new("k1", Key::generate());
+    /// assert_eq!(keyring.primary_id(), "k1");
+    /// ```
+    pub fn primary_id(&self) -> &str {
+        &self.primary
+    }
+
+    /// Returns the primary key.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Key, Keyring};
+    ///
+    /// let key = Key::generate();
+    /// let mut keyring = Keyring::new("k1", Key::generate());
+    /// keyring.add("k2", key.clone());
+    /// assert!(keyring.primary() == &key);
+    /// ```
+    pub fn primary(&self) -> &Key {
+        &self.keys[&self.primary]
+    }
+
+    /// Returns an iterator over the IDs of all of the keys in `self`, in no
+    /// particular order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{Key, Keyring};
+    ///
+    /// let mut keyring = Keyring::new("k1", Key::generate());
+    /// keyring.add("k2", Key::generate());
+    ///
+    /// let mut ids: Vec<_> = keyring.ids().collect();
+    /// ids.sort();
+    /// assert_eq!(ids, ["k1", "k2"]);
+    /// ```
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        self.keys.keys().map(|id| id.as_str())
+    }
+}
+
+/// Panics if `id` is not a valid key ID.
+pub(crate) fn check_key_id(id: &str) {
+    let valid = (1..=MAX_KEY_ID_LEN).contains(&id.len())
+        && id.bytes().all(|b| b.is_ascii_alphanumeric());
+
+    if !valid {
+        panic!("bad key ID {:?}: expected 1 to {} ASCII alphanumerics", id, MAX_KEY_ID_LEN);
+    }
+}
+
+/// Splits a key ID prefix, if there is one, from `value`.
+pub(crate) fn split_key_id(value: &str) -> (Option<&str>, &str) {
+    let prefix = value.char_indices()
+        .take(MAX_KEY_ID_LEN + 1)
+        .find(|&(_, c)| c == KEY_ID_SEPARATOR || !c.is_ascii_alphanumeric());
+
+    match prefix {
+        Some((i, KEY_ID_SEPARATOR)) if i > 0 => (Some(&value[..i]), &value[(i + 1)..]),
+        _ => (None, value),
+    }
+}
+
+/// The set of raw keys used by a child jar: a primary key, used to sign or
+/// encrypt, and fallback keys, additionally used to verify or decrypt. Keys
+/// from a `Keyring` carry their IDs. Every key carries the fingerprint of the
+/// [`Key`] it was selected from.
+pub(crate) struct KeySet<const N: usize> {
+    primary: Entry<N>,
+    fallbacks: Vec<Entry<N>>,
+    fingerprint_ids: bool,
+}
+
+/// A raw key in a [`KeySet`].
+struct Entry<const N: usize> {
+    id: Option<String>,
+    fingerprint: String,
+    key: [u8; N],
+}
+
+impl<const N: usize> Entry<N> {
+    fn new(id: Option<String>, key: &Key, raw: fn(&Key) -> &[u8]) -> Self {
+        let fingerprint = key.fingerprint();
+        Entry { id, fingerprint, key: raw(key).try_into().expect("raw key len") }
+    }
+
+    /// Returns `true` if `self` is identified by `id`, as a key ID or a
+    /// fingerprint.
+    fn is(&self, id: &str) -> bool {
+        self.id.as_deref() == Some(id) || self.fingerprint == id
+    }
+}
+
+impl<const N: usize> KeySet<N> {
+    /// Creates a set from `keys`, the first of which is the primary key,
+    /// applying `raw` to each key to select its raw key bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keys` is empty.
+    pub fn from_keys(keys: &[Key], raw: fn(&Key) -> &[u8]) -> Self {
+        let (primary, fallbacks) = keys.split_first().expect("at least one key");
+        let entry = |k: &Key| Entry::new(None, k, raw);
+        let fallbacks = fallbacks.iter().map(entry).collect();
+        KeySet { primary: entry(primary), fallbacks, fingerprint_ids: false }
+    }
+
+    /// Creates a set from the keys in `keyring` applying `raw` to each key to
+    /// select its raw key bytes.
+    pub fn from_keyring(keyring: &Keyring, raw: fn(&Key) -> &[u8]) -> Self {
+        let entry = |(id, k): (&String, &Key)| Entry::new(Some(id.clone()), k, raw);
+        let mut fallbacks = keyring.keys.iter().map(entry).collect::<Vec<_>>();
+        let i = fallbacks.iter().position(|e| e.id.as_ref() == Some(&keyring.primary));
+        let primary = fallbacks.swap_remove(i.expect("primary key in keyring"));
+        KeySet { primary, fallbacks, fingerprint_ids: false }
+    }
+
+    /// Sets whether keys without an ID are identified by their fingerprint.
+    pub fn set_fingerprint_ids(&mut self, fingerprint_ids: bool) {
+        self.fingerprint_ids = fingerprint_ids;
+    }
+
+    /// Returns the primary key and its ID, if it has one. If fingerprint IDs
+    /// are enabled, a key without an ID is identified by its fingerprint.
+    pub fn primary(&self) -> (Option<&str>, &[u8; N]) {
+        let id = match (&self.primary.id, self.fingerprint_ids) {
+            (Some(id), _) => Some(id.as_str()),
+            (None, true) => Some(self.primary.fingerprint.as_str()),
+            (None, false) => None,
+        };
+
+        (id, &self.primary.key)
+    }
+
+    /// Returns the keys that should be tried, in order, to verify or decrypt a
+    /// value carrying the key ID `id`, which may be a key's ID or fingerprint.
+    /// If there is no ID, or the keys in the set have no IDs, fingerprint IDs
+    /// are disabled, and no key's fingerprint is `id`, all keys are returned,
+    /// beginning with the primary key.
+    pub fn candidates<'a>(&'a self, id: Option<&'a str>) -> impl Iterator<Item = &'a [u8; N]> {
+        let entries = move || std::iter::once(&self.primary).chain(&self.fallbacks);
+        let by_id = id.filter(|id| {
+            self.primary.id.is_some() || self.fingerprint_ids || entries().any(|e| e.is(id))
+        });
+
+        entries()
+            .filter(move |entry| match by_id {
+                Some(id) => entry.is(id),
+                None => true,
+            })
+            .map(|entry| &entry.key)
+    }
+
+    /// Returns a set of the keys derived from each key in `self` for the cookie
+    /// named `name`, with the same IDs. Keys are derived with HKDF-SHA256 using
+    /// the cookie's name as part of the `info`, so the keys for one cookie
+    /// reveal nothing about the keys for any other.
+    pub fn derive(&self, name: &str) -> KeySet<N> {
+        let mut info = DERIVED_KEY_INFO.to_vec();
+        info.extend_from_slice(name.as_bytes());
+
+        let derive = |entry: &Entry<N>| {
+            let mut derived = [0; N];
+            hkdf::Hkdf::<sha2::Sha256>::new(None, &entry.key)
+                .expand(&info, &mut derived)
+                .expect("derived key len");
+
+            Entry { id: entry.id.clone(), fingerprint: entry.fingerprint.clone(), key: derived }
+        };
+
+        let fallbacks = self.fallbacks.iter().map(derive).collect();
+        KeySet { primary: derive(&self.primary), fallbacks, fingerprint_ids: self.fingerprint_ids }
+    }
+}
+
+impl<const N: usize> Drop for Entry<N> {
+    fn drop(&mut self) {
+        wipe(&mut self.key);
+    }
+}
+
+/// Prefixes `value` with the key ID `id` if there is one.
+pub(crate) fn with_key_id(id: Option<&str>, value: String) -> String {
+    match id {
+        Some(id) => format!("{}{}{}", id, KEY_ID_SEPARATOR, value),
+        None => value,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{split_key_id, KeySet, Keyring};
+    use crate::Key;
+
+    #[test]
+    fn key_id_prefix() {
+        assert_eq!(split_key_id("k1$abc"), (Some("k1"), "abc"));
+        assert_eq!(split_key_id("k1$"), (Some("k1"), ""));
+        assert_eq!(split_key_id("$abc"), (None, "$abc"));
+        assert_eq!(split_key_id("k-1$abc"), (None, "k-1$abc"));
+        assert_eq!(split_key_id("abcdefghijklmnopq$abc"), (None, "abcdefghijklmnopq$abc"));
+        assert_eq!(split_key_id("abcdefghijklmnop$abc"), (Some("abcdefghijklmnop"), "abc"));
+        assert_eq!(split_key_id("3tdHXEQ2kf6fxC7dWzBGmpSL$"), (None, "3tdHXEQ2kf6fxC7dWzBGmpSL$"));
+        assert_eq!(split_key_id("£$"), (None, "£$"));
+    }
+
+    #[test]
+    fn derived_keys() {
+        let keyring = Keyring::new("k1", Key::generate());
+        let keys = KeySet::<32>::from_keyring(&keyring, Key::signing);
+        let (a, b) = (keys.derive("a"), keys.derive("b"));
+        assert_eq!(a.primary().0, Some("k1"));
+        assert_eq!(a.primary().1, keys.derive("a").primary().1);
+        assert_ne!(a.primary().1, b.primary().1);
+        assert_ne!(a.primary().1, keys.primary().1);
+    }
+
+    #[test]
+    fn fingerprint_ids() {
+        let (k1, k2) = (Key::generate(), Key::generate());
+        let mut keys = KeySet::<32>::from_keys(&[k1.clone(), k2.clone()], Key::signing);
+        assert_eq!(keys.primary().0, None);
+        assert_eq!(keys.candidates(Some(&k2.fingerprint())).count(), 1);
+        assert_eq!(keys.candidates(Some("unknown")).count(), 2);
+
+        keys.set_fingerprint_ids(true);
+        assert_eq!(keys.primary().0, Some(&*k1.fingerprint()));
+        assert_eq!(keys.derive("a").primary().0, Some(&*k1.fingerprint()));
+        assert_eq!(keys.candidates(Some("unknown")).count(), 0);
+        assert_eq!(keys.candidates(None).count(), 2);
+
+        // Keys with IDs are identified by either.
+        let mut keyring = Keyring::new("k1", k1.clone());
+        keyring.add("k2", k2);
+        let mut keys = KeySet::<32>::from_keyring(&keyring, Key::signing);
+        keys.set_fingerprint_ids(true);
+        assert_eq!(keys.primary().0, Some("k2"));
+        let fingerprint = k1.fingerprint();
+        let by_fingerprint = keys.candidates(Some(&fingerprint)).next();
+        assert_eq!(by_fingerprint, keys.candidates(Some("k1")).next());
+    }
+
+    #[test]
+    #[should_panic]
+    fn bad_key_id() {
+        Keyring::new("bad id", Key::generate());
+    }
+}
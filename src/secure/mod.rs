@@ -4,11 +4,33 @@ extern crate base64;
 #[macro_use]
 mod macros;
 mod key;
+mod keyring;
+mod hex;
+mod version;
+mod fallback;
 
 pub use self::key::*;
+pub use self::keyring::Keyring;
+pub use self::fallback::MaybeVerified;
+
+#[cfg(feature = "key-expansion")] mod derivation;
+#[cfg(feature = "key-expansion")] pub use self::derivation::*;
+
+#[cfg(feature = "async")] mod provider;
+#[cfg(feature = "async")] pub use self::provider::*;
 
 #[cfg(feature = "private")] mod private;
 #[cfg(feature = "private")] pub use self::private::*;
 
+#[cfg(feature = "signed")] pub mod compat;
 #[cfg(feature = "signed")] mod signed;
 #[cfg(feature = "signed")] pub use self::signed::*;
+#[cfg(feature = "signed")] mod remote;
+#[cfg(feature = "signed")] pub use self::remote::*;
+#[cfg(feature = "signed")] mod claims;
+#[cfg(feature = "signed")] pub use self::claims::*;
+
+#[cfg(all(feature = "private", feature = "signed"))] mod secure_jar;
+#[cfg(all(feature = "private", feature = "signed"))] pub use self::secure_jar::*;
+#[cfg(all(feature = "private", feature = "signed"))] mod key_file;
+#[cfg(all(feature = "private", feature = "signed"))] pub use self::key_file::*;
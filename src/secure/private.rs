@@ -1,9 +1,13 @@
 extern crate aes_gcm;
 
-use std::convert::TryInto;
 use std::borrow::{Borrow, BorrowMut};
+use std::error::Error;
+use std::fmt;
 
-use crate::secure::{base64, rand, Key};
+use crate::secure::{rand, Key, Keyring, MaybeVerified};
+#[cfg(feature = "async")] use crate::secure::{KeyProvider, KeyProviderError};
+use crate::secure::keyring::{KeySet, split_key_id, with_key_id};
+use crate::secure::version::{Flags, Format};
 use crate::{Cookie, CookieJar};
 
 use self::aes_gcm::Aes256Gcm;
@@ -16,6 +20,46 @@ pub(crate) const NONCE_LEN: usize = 12;
 pub(crate) const TAG_LEN: usize = 16;
 pub(crate) const KEY_LEN: usize = 32;
 
+/// The reason a cookie failed to decrypt in a [`PrivateJar`].
+#[cfg_attr(all(nightly, doc), doc(cfg(feature = "private")))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum DecryptionError {
+    /// There is no cookie with the requested name.
+    NotFound,
+    /// The value is tagged with an unknown format.
+    UnknownFormat,
+    /// The value is not valid Base64, is too short to contain a nonce and
+    /// ciphertext, or decrypts to a value that is not valid UTF-8.
+    Malformed,
+    /// The ciphertext failed to authenticate under any of the jar's keys.
+    AuthenticationFailed,
+}
+
+impl DecryptionError {
+    /// Returns a description of this error as a string
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            DecryptionError::NotFound => "the cookie was not found",
+            DecryptionError::UnknownFormat => "the cookie's value has an unknown format",
+            DecryptionError::Malformed => "the cookie's value is malformed",
+            DecryptionError::AuthenticationFailed => "the cookie's value failed to authenticate",
+        }
+    }
+}
+
+impl fmt::Display for DecryptionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Error for DecryptionError {
+    fn description(&self) -> &str {
+        self.as_str()
+    }
+}
+
 /// A child cookie jar that provides authenticated encryption for its cookies.
 ///
 /// A _private_ child jar signs and encrypts all the cookies added to it and
@@ -26,22 +70,258 @@ pub(crate) const KEY_LEN: usize = 32;
 #[cfg_attr(all(nightly, doc), doc(cfg(feature = "private")))]
 pub struct PrivateJar<J> {
     parent: J,
-    key: [u8; KEY_LEN]
+    keys: KeySet<KEY_LEN>,
+    derive_keys: bool,
+    context: Option<Vec<u8>>,
+    url_safe: bool,
+    on_failure: Option<fn(&str, DecryptionError)>,
 }
 
 impl<J> PrivateJar<J> {
     /// Creates a new child `PrivateJar` with parent `parent` and key `key`.
-    /// This method is typically called indirectly via the `signed` method of
-    /// `CookieJar`.
+    /// This method is typically called indirectly via the `private{_mut}`
+    /// methods of `CookieJar`.
     pub(crate) fn new(parent: J, key: &Key) -> PrivateJar<J> {
-        PrivateJar { parent, key: key.encryption().try_into().expect("enc key len") }
+        PrivateJar::with_keys(parent, std::slice::from_ref(key))
+    }
+
+    /// Creates a new child `PrivateJar` with parent `parent` that encrypts with
+    /// `keys[0]` and decrypts with each key in `keys`, in order. This method is
+    /// typically called indirectly via the `private_with_keys{_mut}` methods of
+    /// `CookieJar`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keys` is empty.
+    pub(crate) fn with_keys(parent: J, keys: &[Key]) -> PrivateJar<J> {
+        let keys = KeySet::from_keys(keys, Key::encryption);
+        PrivateJar {
+            parent,
+            keys,
+            derive_keys: false,
+            context: None,
+            url_safe: false,
+            on_failure: None,
+        }
+    }
+
+    /// Creates a new child `PrivateJar` with parent `parent` that encrypts with
+    /// the primary key in `keyring` and decrypts with the key identified in the
+    /// cookie's value. This method is typically called indirectly via the
+    /// `private_with_keyring{_mut}` methods of `CookieJar`.
+    pub(crate) fn with_keyring(parent: J, keyring: &Keyring) -> PrivateJar<J> {
+        let keys = KeySet::from_keyring(keyring, Key::encryption);
+        PrivateJar {
+            parent,
+            keys,
+            derive_keys: false,
+            context: None,
+            url_safe: false,
+            on_failure: None,
+        }
+    }
+
+    /// Fetches the current keys from `provider` and creates a new child
+    /// `PrivateJar` with parent `parent` that encrypts with the primary key and
+    /// decrypts with the key identified in the cookie's value, as if by
+    /// [`CookieJar::private_with_keyring()`]. If the keys cannot be fetched,
+    /// returns the provider's error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, KeyProvider, KeyProviderError, PrivateJar};
+    ///
+    /// async fn session(
+    ///     jar: &CookieJar,
+    ///     secrets: &dyn KeyProvider,
+    /// ) -> Result<Option<String>, KeyProviderError> {
+    ///     let private = PrivateJar::with_provider(jar, secrets).await?;
+    ///     Ok(private.get("session").map(|c| c.value().to_string()))
+    /// }
+    /// ```
+    #[cfg(feature = "async")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "async")))]
+    pub async fn with_provider<P>(parent: J, provider: &P) -> Result<PrivateJar<J>, KeyProviderError>
+        where P: KeyProvider + ?Sized
+    {
+        let keyring = provider.fetch().await?;
+        Ok(PrivateJar::with_keyring(parent, &keyring))
+    }
+
+    /// Sets whether cookies are encrypted with per-cookie keys. Disabled by
+    /// default.
+    ///
+    /// When enabled, each cookie is encrypted with keys derived, via HKDF, from
+    /// the jar's keys and the cookie's name, so that the keys for one cookie
+    /// reveal nothing about the keys for another. Cookies encrypted with
+    /// per-cookie keys are marked as such and decrypt regardless of this
+    /// setting, so the setting may be enabled once all servers understand the
+    /// marked values. When enabled, cookies not encrypted with per-cookie keys
+    /// fail to decrypt.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.private_mut(&key).with_derived_keys(true).add(Cookie::new("name", "value"));
+    ///
+    /// let private = jar.private(&key).with_derived_keys(true);
+    /// assert_eq!(private.get("name").unwrap().value(), "value");
+    /// ```
+    pub fn with_derived_keys(mut self, derive_keys: bool) -> Self {
+        self.derive_keys = derive_keys;
+        self
+    }
+
+    /// Sets whether encrypted values are prefixed with the fingerprint of the
+    /// key that encrypted them, as returned by [`Key::fingerprint()`], unless
+    /// the key has a key ID. Disabled by default.
+    ///
+    /// Values carrying a fingerprint are decrypted only with the identified key
+    /// and always decrypt, with or without this setting, if one of the jar's
+    /// keys matches. When enabled, values carrying an unknown key ID or
+    /// fingerprint fail to decrypt without any decryption being attempted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.private_mut(&key).with_key_fingerprints(true).add(Cookie::new("name", "value"));
+    ///
+    /// let prefix = format!("v2:{}$", key.fingerprint());
+    /// assert!(jar.get("name").unwrap().value().starts_with(&prefix));
+    /// assert_eq!(jar.private(&key).get("name").unwrap().value(), "value");
+    /// ```
+    pub fn with_key_fingerprints(mut self, fingerprints: bool) -> Self {
+        self.keys.set_fingerprint_ids(fingerprints);
+        self
+    }
+
+    /// Sets whether encrypted values are encoded as URL-safe, unpadded Base64
+    /// instead of standard, padded Base64. Disabled by default.
+    ///
+    /// Values encrypted with this setting enabled contain no `=`, `+`, or `/`
+    /// characters, which some proxies and log pipelines mangle. Such values are
+    /// marked as such and decrypt regardless of this setting, as do values in
+    /// the standard encoding.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.private_mut(&key).with_url_safe_encoding(true).add(Cookie::new("name", "value"));
+    ///
+    /// assert!(jar.get("name").unwrap().value().starts_with("v2u:"));
+    /// assert_eq!(jar.private(&key).get("name").unwrap().value(), "value");
+    /// ```
+    pub fn with_url_safe_encoding(mut self, url_safe: bool) -> Self {
+        self.url_safe = url_safe;
+        self
+    }
+
+    /// Sets the context cookies are bound to. Cookies encrypted with a context
+    /// only decrypt with the same context, and cookies encrypted without a
+    /// context fail to decrypt when one is set.
+    ///
+    /// The context is authenticated as associated data but isn't stored in the
+    /// cookie. It can be any data the application can reproduce when
+    /// decrypting the cookie: a hash of the client's user agent, a TLS channel
+    /// binding, or the application's version, for instance. Cookies replayed
+    /// outside of their original context then fail to decrypt.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.private_mut(&key).with_context(b"app v1").add(Cookie::new("name", "value"));
+    ///
+    /// assert!(jar.private(&key).with_context(b"app v1").get("name").is_some());
+    /// assert!(jar.private(&key).with_context(b"app v2").get("name").is_none());
+    /// assert!(jar.private(&key).get("name").is_none());
+    /// ```
+    pub fn with_context(mut self, context: &[u8]) -> Self {
+        self.context = Some(context.to_vec());
+        self
+    }
+
+    /// Sets `hook` to be called with the name of every cookie that fails to
+    /// authenticate or decrypt in this jar and the reason why, for instance to
+    /// count or alert on tampering attempts. The hook is called by every method
+    /// that decrypts a cookie, including [`PrivateJar::get()`] and
+    /// [`PrivateJar::decrypt()`], but not for cookies that are missing
+    /// altogether. No hook is set by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use cookie::{CookieJar, Cookie, Key, DecryptionError};
+    ///
+    /// static FAILURES: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// fn on_failure(name: &str, error: DecryptionError) {
+    ///     assert_eq!((name, error), ("name", DecryptionError::AuthenticationFailed));
+    ///     FAILURES.fetch_add(1, Ordering::Relaxed);
+    /// }
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.private_mut(&key).add(Cookie::new("name", "value"));
+    ///
+    /// let private = jar.private(&Key::generate()).on_failure(on_failure);
+    /// assert!(private.get("name").is_none());
+    /// assert!(private.get("missing").is_none());
+    /// assert_eq!(FAILURES.load(Ordering::Relaxed), 1);
+    /// ```
+    pub fn on_failure(mut self, hook: fn(&str, DecryptionError)) -> Self {
+        self.on_failure = Some(hook);
+        self
+    }
+
+    /// Calls the failure hook, if any, if `result`, the result of decrypting
+    /// the cookie named `name`, is an error. Returns `result`.
+    fn report<T>(
+        &self,
+        name: &str,
+        result: Result<T, DecryptionError>
+    ) -> Result<T, DecryptionError> {
+        if let (Err(error), Some(hook)) = (&result, self.on_failure) {
+            hook(name, *error);
+        }
+
+        result
     }
 
     /// Encrypts the cookie's value with authenticated encryption providing
     /// confidentiality, integrity, and authenticity.
     fn encrypt_cookie(&self, cookie: &mut Cookie) {
+        let value = self.seal(cookie.name(), cookie.value());
+        cookie.set_value(value);
+    }
+
+    /// Returns `value`, the value of the cookie named `name`, encrypted with
+    /// authenticated encryption.
+    pub(crate) fn seal(&self, name: &str, value: &str) -> String {
+        self.seal_bytes(name, value.as_bytes())
+    }
+
+    /// Returns `cookie_val`, the binary value of the cookie named `name`,
+    /// encrypted with authenticated encryption.
+    fn seal_bytes(&self, name: &str, cookie_val: &[u8]) -> String {
         // Create a vec to hold the [nonce | cookie value | tag].
-        let cookie_val = cookie.value().as_bytes();
         let mut data = vec![0; NONCE_LEN + cookie_val.len() + TAG_LEN];
 
         // Split data into three: nonce, input/output, tag. Copy input.
@@ -54,43 +334,119 @@ impl<J> PrivateJar<J> {
         rng.try_fill_bytes(nonce).expect("couldn't random fill nonce");
         let nonce = GenericArray::clone_from_slice(nonce);
 
-        // Perform the actual sealing operation, using the cookie's name as
-        // associated data to prevent value swapping.
-        let aad = cookie.name().as_bytes();
-        let aead = Aes256Gcm::new(GenericArray::from_slice(&self.key));
-        let aad_tag = aead.encrypt_in_place_detached(&nonce, aad, in_out)
+        // Perform the actual sealing operation, using the version tag and the
+        // cookie's name as associated data to prevent value swapping.
+        let format = match self.context {
+            Some(_) => Format::CURRENT.with(Flags::CONTEXT),
+            None => Format::CURRENT,
+        };
+
+        let format = match self.url_safe {
+            true => format.with(Flags::URL_SAFE),
+            false => format,
+        };
+
+        let derived;
+        let (format, keys) = match self.derive_keys {
+            true => {
+                derived = self.keys.derive(name);
+                (format.with(Flags::DERIVED), &derived)
+            }
+            false => (format, &self.keys),
+        };
+
+        let aad = self.associated_data(format, name);
+        let (key_id, key) = keys.primary();
+        let aead = Aes256Gcm::new(GenericArray::from_slice(key));
+        let aad_tag = aead.encrypt_in_place_detached(&nonce, &aad, in_out)
             .expect("encryption failure!");
 
         // Copy the tag into the tag piece.
         tag.copy_from_slice(&aad_tag);
 
-        // Base64 encode [nonce | encrypted value | tag].
-        cookie.set_value(base64::encode(&data));
+        // Base64 encode [nonce | encrypted value | tag], prefixed by the
+        // version tag and key ID.
+        let value = with_key_id(key_id, format.encode(&data));
+        format.tagged(value)
     }
 
     /// Given a sealed value `str` and a key name `name`, where the nonce is
     /// prepended to the original value and then both are Base64 encoded,
     /// verifies and decrypts the sealed value and returns it. If there's a
-    /// problem, returns an `Err` with a string describing the issue.
-    fn unseal(&self, name: &str, value: &str) -> Result<String, &'static str> {
-        let data = base64::decode(value).map_err(|_| "bad base64 value")?;
+    /// problem, returns an `Err` describing the issue.
+    pub(crate) fn unseal(&self, name: &str, value: &str) -> Result<String, DecryptionError> {
+        let plaintext = self.unseal_bytes(name, value)?;
+        String::from_utf8(plaintext).map_err(|_| DecryptionError::Malformed)
+    }
+
+    /// Like [`PrivateJar::unseal()`], but returns the decrypted value as bytes,
+    /// which need not be valid UTF-8.
+    fn unseal_bytes(&self, name: &str, value: &str) -> Result<Vec<u8>, DecryptionError> {
+        let (format, value) = Format::split(value).map_err(|_| DecryptionError::UnknownFormat)?;
+        // A value encrypted with a context only decrypts with one, and vice-versa.
+        if format.flags.contains(Flags::CONTEXT) != self.context.is_some() {
+            return Err(DecryptionError::AuthenticationFailed);
+        }
+
+        let (key_id, value) = split_key_id(value);
+        let data = format.decode(value).map_err(|_| DecryptionError::Malformed)?;
         if data.len() <= NONCE_LEN {
-            return Err("length of decoded data is <= NONCE_LEN");
+            return Err(DecryptionError::Malformed);
         }
 
+        // Use the cookie's own keys if the value was encrypted with them. If
+        // the jar requires them, reject values that weren't.
+        let derived;
+        let keys = match (format.flags.contains(Flags::DERIVED), self.derive_keys) {
+            (true, _) => {
+                derived = self.keys.derive(name);
+                &derived
+            }
+            (false, true) => return Err(DecryptionError::AuthenticationFailed),
+            (false, false) => &self.keys,
+        };
+
         let (nonce, cipher) = data.split_at(NONCE_LEN);
-        let payload = Payload { msg: cipher, aad: name.as_bytes() };
+        // Try the identified key or the primary key first, then fallbacks.
+        let aad = self.associated_data(format, name);
+        let plaintext = keys.candidates(key_id)
+            .find_map(|key| {
+                let payload = Payload { msg: cipher, aad: &aad };
+                let aead = Aes256Gcm::new(GenericArray::from_slice(key));
+                aead.decrypt(GenericArray::from_slice(nonce), payload).ok()
+            })
+            .ok_or(DecryptionError::AuthenticationFailed)?;
+
+        Ok(plaintext)
+    }
 
-        let aead = Aes256Gcm::new(GenericArray::from_slice(&self.key));
-        aead.decrypt(GenericArray::from_slice(nonce), payload)
-            .map_err(|_| "invalid key/nonce/value: bad seal")
-            .and_then(|s| String::from_utf8(s).map_err(|_| "bad unsealed utf8"))
+    /// Returns the associated data authenticated with the value of the cookie
+    /// named `name` in the format `format`. Untagged values authenticate just
+    /// the name. Tagged values authenticate the format's fixed-width encoding,
+    /// the length-prefixed name and, for values bound to a context, the
+    /// length-prefixed context, so that distinct inputs never share their
+    /// associated data.
+    fn associated_data(&self, format: Format, name: &str) -> Vec<u8> {
+        if format == Format::V1 {
+            return name.as_bytes().to_vec();
+        }
+
+        let mut aad = format.authenticated();
+        aad.extend_from_slice(&(name.len() as u64).to_be_bytes());
+        aad.extend_from_slice(name.as_bytes());
+        if format.flags.contains(Flags::CONTEXT) {
+            let context = self.context.as_deref().unwrap_or(&[]);
+            aad.extend_from_slice(&(context.len() as u64).to_be_bytes());
+            aad.extend_from_slice(context);
+        }
+
+        aad
     }
 
     /// Authenticates and decrypts `cookie`, returning the plaintext version if
     /// decryption succeeds or `None` otherwise. Authenticatation and decryption
     /// _always_ succeeds if `cookie` was generated by a `PrivateJar` with the
-    /// same key as `self`.
+    /// same key as `self` or with any of its fallback keys.
     ///
     /// # Example
     ///
@@ -112,13 +468,41 @@ impl<J> PrivateJar<J> {
     /// let plain = Cookie::new("plaintext", "hello");
     /// assert!(jar.private(&key).decrypt(plain).is_none());
     /// ```
-    pub fn decrypt(&self, mut cookie: Cookie<'static>) -> Option<Cookie<'static>> {
-        if let Ok(value) = self.unseal(cookie.name(), cookie.value()) {
-            cookie.set_value(value);
-            return Some(cookie);
-        }
+    pub fn decrypt(&self, cookie: Cookie<'static>) -> Option<Cookie<'static>> {
+        self.decrypt_result(cookie).ok()
+    }
 
-        None
+    /// Authenticates and decrypts `cookie`, returning the plaintext version if
+    /// decryption succeeds or a [`DecryptionError`] describing why decryption
+    /// failed otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key, DecryptionError};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.private_mut(&key).add(Cookie::new("name", "value"));
+    ///
+    /// let sealed = jar.get("name").cloned().unwrap();
+    /// let decrypted = jar.private(&key).decrypt_result(sealed.clone()).unwrap();
+    /// assert_eq!(decrypted.value(), "value");
+    ///
+    /// let error = jar.private(&Key::generate()).decrypt_result(sealed).unwrap_err();
+    /// assert_eq!(error, DecryptionError::AuthenticationFailed);
+    ///
+    /// let plain = Cookie::new("plaintext", "hello");
+    /// let error = jar.private(&key).decrypt_result(plain).unwrap_err();
+    /// assert_eq!(error, DecryptionError::Malformed);
+    /// ```
+    pub fn decrypt_result(
+        &self,
+        mut cookie: Cookie<'static>
+    ) -> Result<Cookie<'static>, DecryptionError> {
+        let value = self.report(cookie.name(), self.unseal(cookie.name(), cookie.value()))?;
+        cookie.set_value(value);
+        Ok(cookie)
     }
 }
 
@@ -143,7 +527,89 @@ impl<J: Borrow<CookieJar>> PrivateJar<J> {
     /// assert_eq!(private_jar.get("name").unwrap().value(), "value");
     /// ```
     pub fn get(&self, name: &str) -> Option<Cookie<'static>> {
-        self.parent.borrow().get(name).and_then(|c| self.decrypt(c.clone()))
+        self.get_result(name).ok()
+    }
+
+    /// Returns a reference to the `Cookie` inside this jar with the name `name`
+    /// and authenticates and decrypts the cookie's value, returning a `Cookie`
+    /// with the decrypted value. If the cookie cannot be found, returns
+    /// [`DecryptionError::NotFound`]. If the cookie fails to authenticate or
+    /// decrypt, returns a [`DecryptionError`] describing why.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key, DecryptionError};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// assert_eq!(jar.private(&key).get_result("name"), Err(DecryptionError::NotFound));
+    ///
+    /// jar.private_mut(&key).add(Cookie::new("name", "value"));
+    /// assert_eq!(jar.private(&key).get_result("name").unwrap().value(), "value");
+    /// ```
+    pub fn get_result(&self, name: &str) -> Result<Cookie<'static>, DecryptionError> {
+        let cookie = self.parent.borrow().get(name).ok_or(DecryptionError::NotFound)?;
+        self.decrypt_result(cookie.clone())
+    }
+
+    /// Returns the binary value of the `Cookie` inside this jar with the name
+    /// `name`, as added by [`PrivateJar::add_bytes()`], after authenticating
+    /// and decrypting it. If the cookie cannot be found, or the cookie fails to
+    /// authenticate or decrypt, `None` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.private_mut(&key).add_bytes(Cookie::named("blob"), &[0, 159, 146, 150]);
+    ///
+    /// assert_eq!(jar.private(&key).get_bytes("blob").unwrap(), [0, 159, 146, 150]);
+    /// assert!(jar.private(&key).get("blob").is_none());
+    /// ```
+    pub fn get_bytes(&self, name: &str) -> Option<Vec<u8>> {
+        let cookie = self.parent.borrow().get(name)?;
+        self.report(name, self.unseal_bytes(name, cookie.value())).ok()
+    }
+
+    /// Returns the `Cookie` inside this jar with the name `name`,
+    /// authenticating and decrypting its value, or, if it fails to decrypt,
+    /// the cookie as is. If the cookie cannot be found, `None` is returned.
+    ///
+    /// This method is intended for staged rollouts of encryption, where
+    /// plaintext cookies set before the rollout must keep working for a while:
+    /// the returned [`MaybeVerified`] distinguishes decrypted cookies from
+    /// unverified ones, whose values are unauthenticated and may have been set
+    /// or modified by the client. Failures are reported to the [failure
+    /// hook](PrivateJar::on_failure()), if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key, MaybeVerified};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.add(Cookie::new("legacy", "value"));
+    /// jar.private_mut(&key).add(Cookie::new("private", "value"));
+    ///
+    /// let private = jar.private(&key);
+    /// let legacy = private.get_or_plaintext("legacy").unwrap();
+    /// assert_eq!(legacy, MaybeVerified::Unverified(Cookie::new("legacy", "value")));
+    ///
+    /// let decrypted = private.get_or_plaintext("private").unwrap();
+    /// assert_eq!(decrypted.verified().unwrap().value(), "value");
+    /// assert!(private.get_or_plaintext("missing").is_none());
+    /// ```
+    pub fn get_or_plaintext(&self, name: &str) -> Option<MaybeVerified> {
+        let cookie = self.parent.borrow().get(name)?;
+        match self.decrypt_result(cookie.clone()) {
+            Ok(cookie) => Some(MaybeVerified::Verified(cookie)),
+            Err(_) => Some(MaybeVerified::Unverified(cookie.clone())),
+        }
     }
 }
 
@@ -196,6 +662,30 @@ impl<J: BorrowMut<CookieJar>> PrivateJar<J> {
         self.parent.borrow_mut().add_original(cookie);
     }
 
+    /// Adds `cookie` to the parent jar with the binary value `bytes`, which
+    /// need not be valid UTF-8, in place of the cookie's value. The bytes are
+    /// encrypted directly, without first being encoded, with authenticated
+    /// encryption assuring confidentiality, integrity, and authenticity. The
+    /// value can be retrieved with [`PrivateJar::get_bytes()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// let session: Vec<u8> = (0..=255).collect();
+    /// jar.private_mut(&key).add_bytes(Cookie::named("session"), &session);
+    ///
+    /// assert_eq!(jar.private(&key).get_bytes("session").unwrap(), session);
+    /// ```
+    pub fn add_bytes(&mut self, mut cookie: Cookie<'static>, bytes: &[u8]) {
+        let value = self.seal_bytes(cookie.name(), bytes);
+        cookie.set_value(value);
+        self.parent.borrow_mut().add(cookie);
+    }
+
     /// Removes `cookie` from the parent jar.
     ///
     /// For correct removal, the passed in `cookie` must contain the same `path`
@@ -224,6 +714,53 @@ impl<J: BorrowMut<CookieJar>> PrivateJar<J> {
     }
 }
 
+/// Encrypts `value` with `key` using authenticated encryption, providing
+/// confidentiality, integrity, and authenticity, and returns the encrypted
+/// value.
+///
+/// This is the encryption performed by a [`PrivateJar`] with the default
+/// settings, without a cookie or a jar, for values stored elsewhere, such as in
+/// query parameters or headers. The encrypted value can only be decrypted by
+/// [`decrypt_value()`]: a `PrivateJar` authenticates a cookie's name along
+/// with its value.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{Key, encrypt_value, decrypt_value};
+///
+/// let key = Key::generate();
+/// let encrypted = encrypt_value(&key, "value");
+/// assert_ne!(encrypted, "value");
+/// assert_eq!(decrypt_value(&key, &encrypted).unwrap(), "value");
+/// ```
+#[cfg_attr(all(nightly, doc), doc(cfg(feature = "private")))]
+pub fn encrypt_value(key: &Key, value: &str) -> String {
+    PrivateJar::new((), key).seal("", value)
+}
+
+/// Authenticates and decrypts `value`, a value encrypted by
+/// [`encrypt_value()`], with `key`. Returns the original value if decryption
+/// succeeds or a [`DecryptionError`] describing why decryption failed
+/// otherwise.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{Key, DecryptionError, encrypt_value, decrypt_value};
+///
+/// let key = Key::generate();
+/// let encrypted = encrypt_value(&key, "value");
+/// assert_eq!(decrypt_value(&key, &encrypted).unwrap(), "value");
+///
+/// let error = decrypt_value(&Key::generate(), &encrypted).unwrap_err();
+/// assert_eq!(error, DecryptionError::AuthenticationFailed);
+/// ```
+#[cfg_attr(all(nightly, doc), doc(cfg(feature = "private")))]
+pub fn decrypt_value(key: &Key, value: &str) -> Result<String, DecryptionError> {
+    PrivateJar::new((), key).unseal("", value)
+}
+
 #[cfg(test)]
 mod test {
     use crate::{CookieJar, Cookie, Key};
@@ -242,6 +779,352 @@ mod test {
         assert_secure_behaviour!(jar, jar.private_mut(&key));
     }
 
+    #[test]
+    fn rotation() {
+        let (old, new) = (Key::generate(), Key::generate());
+        let mut jar = CookieJar::new();
+        jar.private_mut(&old).add(Cookie::new("old", "value"));
+
+        // The old cookie decrypts with the fallback key; new cookies are
+        // encrypted with the primary key.
+        let mut private = jar.private_with_keys_mut(&[new.clone(), old.clone()]);
+        assert_eq!(private.get("old").unwrap().value(), "value");
+        private.add(Cookie::new("new", "value"));
+        assert_eq!(jar.private(&new).get("new").unwrap().value(), "value");
+        assert!(jar.private(&old).get("new").is_none());
+
+        // Once the old key is dropped, the old cookie no longer decrypts.
+        assert!(jar.private(&new).get("old").is_none());
+        assert!(jar.private_with_keys(&[new, Key::generate()]).get("old").is_none());
+    }
+
+    #[test]
+    fn keyring() {
+        let mut keyring = crate::Keyring::new("k1", Key::generate());
+        let mut jar = CookieJar::new();
+        jar.private_with_keyring_mut(&keyring).add(Cookie::new("one", "value"));
+        assert!(jar.get("one").unwrap().value().starts_with("v2:k1$"));
+
+        keyring.add("k2", Key::generate());
+        jar.private_with_keyring_mut(&keyring).add(Cookie::new("two", "value"));
+        assert!(jar.get("two").unwrap().value().starts_with("v2:k2$"));
+
+        let private = jar.private_with_keyring(&keyring);
+        assert_eq!(private.get("one").unwrap().value(), "value");
+        assert_eq!(private.get("two").unwrap().value(), "value");
+
+        // Only the identified key is used for decryption.
+        let value = jar.get("two").unwrap().value().replacen("v2:k2$", "v2:k1$", 1);
+        jar.add(Cookie::new("two", value));
+        assert!(jar.private_with_keyring(&keyring).get("two").is_none());
+
+        // Values without a key ID are decrypted with all keys.
+        jar.private_mut(keyring.get("k1").unwrap()).add(Cookie::new("three", "value"));
+        assert_eq!(jar.private_with_keyring(&keyring).get("three").unwrap().value(), "value");
+
+        // Plain jars ignore the key ID.
+        let key = keyring.get("k1").unwrap();
+        assert_eq!(jar.private(key).get("one").unwrap().value(), "value");
+
+        keyring.retire("k1");
+        assert!(jar.private_with_keyring(&keyring).get("one").is_none());
+    }
+
+    #[test]
+    fn versioned() {
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        jar.private_mut(&key).add(Cookie::new("name", "value"));
+
+        // New values are tagged with the current version.
+        let sealed = jar.get("name").unwrap().value().to_string();
+        assert!(sealed.starts_with("v2:"));
+
+        // The tag is authenticated: stripping it or changing it fails.
+        let mut untagged = Cookie::new("name", sealed["v2:".len()..].to_string());
+        jar.add(untagged.clone());
+        assert!(jar.private(&key).get("name").is_none());
+
+        untagged.set_value(sealed.replacen("v2:", "v3:", 1));
+        jar.add(untagged);
+        assert!(jar.private(&key).get("name").is_none());
+
+        // Nor does it decrypt untagged as a cookie named after the tag.
+        jar.add(Cookie::new("v2:name", sealed["v2:".len()..].to_string()));
+        assert!(jar.private(&key).get("v2:name").is_none());
+    }
+
+    #[test]
+    fn derived_keys() {
+        use super::DecryptionError;
+
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        jar.private_mut(&key).with_derived_keys(true).add(Cookie::new("name", "value"));
+        jar.private_mut(&key).add(Cookie::new("plain", "value"));
+        assert!(jar.get("name").unwrap().value().starts_with("v2d:"));
+
+        // Derived values decrypt with and without the setting.
+        assert_eq!(jar.private(&key).get("name").unwrap().value(), "value");
+        let derived = jar.private(&key).with_derived_keys(true);
+        assert_eq!(derived.get("name").unwrap().value(), "value");
+        assert_eq!(derived.get_result("plain"), Err(DecryptionError::AuthenticationFailed));
+
+        // Stripping the flag fails to decrypt.
+        let value = jar.get("name").unwrap().value().replacen("v2d:", "v2:", 1);
+        jar.add(Cookie::new("name", value));
+        assert!(jar.private(&key).get("name").is_none());
+    }
+
+    #[test]
+    fn fingerprints() {
+        let (old, new) = (Key::generate(), Key::generate());
+        let mut jar = CookieJar::new();
+        jar.private_mut(&old).with_key_fingerprints(true).add(Cookie::new("name", "value"));
+        let prefix = format!("v2:{}$", old.fingerprint());
+        assert!(jar.get("name").unwrap().value().starts_with(&prefix));
+
+        // The identified key is found regardless of its position or the setting.
+        let keys = [new.clone(), old.clone()];
+        assert_eq!(jar.private_with_keys(&keys).get("name").unwrap().value(), "value");
+        let private = jar.private_with_keys(&keys).with_key_fingerprints(true);
+        assert_eq!(private.get("name").unwrap().value(), "value");
+
+        // Unknown fingerprints are rejected outright when enabled.
+        assert!(jar.private(&new).with_key_fingerprints(true).get("name").is_none());
+        jar.private_mut(&new).add(Cookie::new("plain", "value"));
+        assert!(jar.private(&new).with_key_fingerprints(true).get("plain").is_some());
+    }
+
+    #[test]
+    fn url_safe_encoding() {
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        let value = "\u{fbff}".repeat(40);
+        for i in 0..8 {
+            let (name, value) = (format!("c{}", i), value[..(i * 3)].to_string());
+            let cookie = Cookie::new(name.clone(), value.clone());
+            jar.private_mut(&key).with_url_safe_encoding(true).add(cookie);
+
+            let sealed = jar.get(&name).unwrap().value().to_string();
+            assert!(sealed.starts_with("v2u:"));
+            assert!(!sealed["v2u:".len()..].contains(['=', '+', '/']));
+            assert_eq!(jar.private(&key).get(&name).unwrap().value(), value);
+
+            // Stripping the flag fails to decrypt.
+            jar.add(Cookie::new("stripped", sealed.replacen("v2u:", "v2:", 1)));
+            assert!(jar.private(&key).get("stripped").is_none());
+        }
+
+        // Values in the standard encoding are still accepted.
+        jar.private_mut(&key).add(Cookie::new("standard", "value"));
+        let private = jar.private(&key).with_url_safe_encoding(true);
+        assert_eq!(private.get("standard").unwrap().value(), "value");
+    }
+
+    #[test]
+    fn context() {
+        use super::DecryptionError;
+
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        jar.private_with_context_mut(&key, b"ctx").add(Cookie::new("name", "value"));
+        jar.private_mut(&key).add(Cookie::new("plain", "value"));
+        assert!(jar.get("name").unwrap().value().starts_with("v2c:"));
+
+        let bound = jar.private_with_context(&key, b"ctx");
+        assert_eq!(bound.get("name").unwrap().value(), "value");
+        assert_eq!(bound.get_result("plain"), Err(DecryptionError::AuthenticationFailed));
+
+        let f = |context: &[u8]| jar.private_with_context(&key, context).get_result("name");
+        assert_eq!(f(b"other"), Err(DecryptionError::AuthenticationFailed));
+        assert_eq!(f(b""), Err(DecryptionError::AuthenticationFailed));
+        let unbound = jar.private(&key);
+        assert_eq!(unbound.get_result("name"), Err(DecryptionError::AuthenticationFailed));
+
+        // The context composes with per-cookie keys.
+        jar.private_mut(&key).with_derived_keys(true).with_context(b"ctx")
+            .add(Cookie::new("derived", "value"));
+
+        assert!(jar.get("derived").unwrap().value().starts_with("v2dc:"));
+        assert_eq!(jar.private_with_context(&key, b"ctx").get("derived").unwrap().value(), "value");
+    }
+
+    #[test]
+    fn associated_data() {
+        use crate::secure::version::{Flags, Format};
+
+        let (key, jar) = (Key::generate(), CookieJar::new());
+        let v2c = Format::CURRENT.with(Flags::CONTEXT);
+        let inputs: &[(Format, &str, Option<&[u8]>)] = &[
+            (Format::V1, "name", None),
+            (Format::V1, "v2:name", None),
+            (Format::CURRENT, "name", None),
+            (Format::CURRENT.with(Flags::DERIVED), "name", None),
+            (v2c, "name", Some(b"")),
+            (v2c, "name", Some(b"ctx")),
+            (v2c, "namec", Some(b"tx")),
+            (v2c, "", Some(b"namectx")),
+        ];
+
+        let aads: Vec<_> = inputs.iter()
+            .map(|&(format, name, context)| {
+                let private = match context {
+                    Some(context) => jar.private_with_context(&key, context),
+                    None => jar.private(&key),
+                };
+
+                private.associated_data(format, name)
+            })
+            .collect();
+
+        assert_eq!(aads[0], b"name");
+        for (i, aad) in aads.iter().enumerate() {
+            assert!(aads[..i].iter().all(|other| other != aad), "{:?}", inputs[i]);
+        }
+    }
+
+    #[test]
+    fn binary_values() {
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        let blob: Vec<u8> = (0..=255).rev().collect();
+        jar.private_mut(&key).add_bytes(Cookie::build("blob", "").path("/").finish(), &blob);
+        jar.private_mut(&key).add_bytes(Cookie::named("empty"), &[]);
+        jar.private_mut(&key).add(Cookie::new("text", "value"));
+
+        // The bytes are sealed as-is: the 12 byte nonce, 256 byte value, and 16
+        // byte tag are encoded once, into 380 bytes.
+        let sealed = jar.get("blob").unwrap();
+        assert_eq!(sealed.path(), Some("/"));
+        assert_eq!(sealed.value().len(), "v2:".len() + 380);
+
+        let private = jar.private(&key);
+        assert_eq!(private.get_bytes("blob").unwrap(), blob);
+        assert_eq!(private.get_bytes("empty").unwrap(), b"");
+        assert_eq!(private.get_bytes("text").unwrap(), b"value");
+        assert!(private.get_bytes("missing").is_none());
+        assert!(private.get("blob").is_none());
+        assert!(jar.private(&Key::generate()).get_bytes("blob").is_none());
+    }
+
+    #[test]
+    fn failure_hook() {
+        use std::cell::RefCell;
+        use crate::DecryptionError::{self, *};
+
+        thread_local! {
+            static FAILURES: RefCell<Vec<(String, DecryptionError)>> = RefCell::default();
+        }
+
+        fn record(name: &str, error: DecryptionError) {
+            FAILURES.with(|f| f.borrow_mut().push((name.into(), error)));
+        }
+
+        let failures = || FAILURES.with(|f| f.borrow_mut().split_off(0));
+        let failure = |name: &str, error| vec![(name.to_string(), error)];
+
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        jar.private_mut(&key).add(Cookie::new("private", "value"));
+        jar.add(Cookie::new("plain", "value"));
+
+        let private = jar.private(&key).on_failure(record);
+        assert!(private.get("private").is_some());
+        assert!(private.get("missing").is_none());
+        assert_eq!(failures(), []);
+
+        assert!(private.get("plain").is_none());
+        assert_eq!(failures(), failure("plain", Malformed));
+        assert!(private.get_bytes("private").is_some());
+        assert!(private.decrypt(Cookie::new("other", "value")).is_none());
+        assert_eq!(failures(), failure("other", Malformed));
+
+        let private = jar.private(&Key::generate()).on_failure(record);
+        assert!(private.get_bytes("private").is_none());
+        assert_eq!(failures(), failure("private", AuthenticationFailed));
+        assert!(jar.private(&Key::generate()).get("private").is_none());
+        assert_eq!(failures(), []);
+    }
+
+    #[test]
+    fn plaintext_fallback() {
+        use crate::MaybeVerified::*;
+
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        jar.add(Cookie::new("plain", "value"));
+        jar.private_mut(&key).add(Cookie::new("private", "value"));
+        let raw = jar.get("private").cloned().unwrap();
+
+        let private = jar.private(&key);
+        let cookie = Cookie::new("plain", "value");
+        assert_eq!(private.get_or_plaintext("plain"), Some(Unverified(cookie)));
+        let cookie = Cookie::new("private", "value");
+        assert_eq!(private.get_or_plaintext("private"), Some(Verified(cookie)));
+        assert_eq!(private.get_or_plaintext("missing"), None);
+
+        // Values that fail to decrypt are returned as is, never as verified.
+        let other = jar.private(&Key::generate());
+        assert_eq!(other.get_or_plaintext("private"), Some(Unverified(raw)));
+    }
+
+    #[test]
+    fn raw_values() {
+        use super::{decrypt_value, encrypt_value, DecryptionError};
+
+        let key = Key::generate();
+        let encrypted = encrypt_value(&key, "a value");
+        assert_ne!(encrypted, encrypt_value(&key, "a value"));
+        assert_eq!(decrypt_value(&key, &encrypted), Ok("a value".into()));
+        assert_eq!(decrypt_value(&key, "a value"), Err(DecryptionError::Malformed));
+
+        // Raw values are not valid cookie values.
+        let mut jar = CookieJar::new();
+        jar.add(Cookie::new("name", encrypted));
+        assert!(jar.private(&key).get("name").is_none());
+    }
+
+    #[test]
+    fn decryption_errors() {
+        use super::DecryptionError::*;
+
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        jar.private_mut(&key).add(Cookie::new("sealed", "value"));
+        jar.add(Cookie::new("plain", "value!"));
+        jar.add(Cookie::new("short", "AAAA"));
+        jar.add(Cookie::new("future", "v9:AAAA"));
+
+        let private = jar.private(&key);
+        assert_eq!(private.get_result("sealed").unwrap().value(), "value");
+        assert_eq!(private.get_result("missing"), Err(NotFound));
+        assert_eq!(private.get_result("plain"), Err(Malformed));
+        assert_eq!(private.get_result("short"), Err(Malformed));
+        assert_eq!(private.get_result("future"), Err(UnknownFormat));
+        assert_eq!(jar.private(&Key::generate()).get_result("sealed"), Err(AuthenticationFailed));
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn provider() {
+        use crate::secure::provider::block_on;
+        use crate::{Keyring, PrivateJar};
+
+        let mut keyring = Keyring::new("k1", Key::generate());
+        let mut jar = CookieJar::new();
+        block_on(PrivateJar::with_provider(&mut jar, &keyring)).unwrap()
+            .add(Cookie::new("name", "value"));
+
+        keyring.add("k2", Key::generate());
+        let private = block_on(PrivateJar::with_provider(&jar, &keyring)).unwrap();
+        assert_eq!(private.get("name").unwrap().value(), "value");
+
+        keyring.retire("k1");
+        let private = block_on(PrivateJar::with_provider(&jar, &keyring)).unwrap();
+        assert!(private.get("name").is_none());
+    }
+
     #[test]
     fn roundtrip() {
         // Secret is SHA-256 hash of 'Super secret!' passed through HKDF-SHA256.
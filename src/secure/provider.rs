@@ -0,0 +1,79 @@
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::secure::Keyring;
+
+/// The error returned when a [`KeyProvider`] fails to fetch keys.
+#[cfg_attr(all(nightly, doc), doc(cfg(feature = "async")))]
+pub type KeyProviderError = Box<dyn Error + Send + Sync>;
+
+/// The future returned by [`KeyProvider::fetch()`].
+#[cfg_attr(all(nightly, doc), doc(cfg(feature = "async")))]
+pub type KeyFuture<'a> = Pin<Box<dyn Future<Output = Result<Keyring, KeyProviderError>> + Send + 'a>>;
+
+/// An asynchronous source of keys, such as a secrets manager.
+///
+/// A `KeyProvider` fetches the current [`Keyring`] on demand. Child jars are
+/// created from a provider via
+/// [`SignedJar::with_provider()`](crate::SignedJar::with_provider()) or
+/// [`PrivateJar::with_provider()`](crate::PrivateJar::with_provider()), which
+/// fetch the keys each time a jar is created. Rotated keys are thus picked up
+/// without restarting the application. Implementations that talk to a remote
+/// service will typically cache keys for some time.
+///
+/// `Keyring` itself implements `KeyProvider` by returning a clone of itself.
+///
+/// # Example
+///
+/// ```rust
+/// use std::sync::Mutex;
+/// use cookie::{Key, Keyring, KeyProvider, KeyFuture};
+///
+/// /// A provider whose keys can be replaced at runtime.
+/// struct Reloadable(Mutex<Keyring>);
+///
+/// impl KeyProvider for Reloadable {
+///     fn fetch(&self) -> KeyFuture<'_> {
+///         let keyring = self.0.lock().unwrap().clone();
+///         Box::pin(async move { Ok(keyring) })
+///     }
+/// }
+///
+/// let provider = Reloadable(Mutex::new(Keyring::new("k1", Key::generate())));
+/// provider.0.lock().unwrap().add("k2", Key::generate());
+/// ```
+#[cfg_attr(all(nightly, doc), doc(cfg(feature = "async")))]
+pub trait KeyProvider {
+    /// Fetches the current keys.
+    fn fetch(&self) -> KeyFuture<'_>;
+}
+
+impl KeyProvider for Keyring {
+    fn fetch(&self) -> KeyFuture<'_> {
+        Box::pin(async move { Ok(self.clone()) })
+    }
+}
+
+/// Polls `future` to completion on the current thread. Used in tests to drive
+/// futures that never wait.
+#[cfg(test)]
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake};
+
+    struct Noop;
+
+    impl Wake for Noop {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    let waker = Arc::new(Noop).into();
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
@@ -0,0 +1,383 @@
+#[cfg(feature = "async")] use std::future::Future;
+#[cfg(feature = "async")] use std::pin::Pin;
+
+use subtle::ConstantTimeEq;
+
+use crate::secure::CookieSigner;
+#[cfg(feature = "async")] use crate::secure::KeyProviderError;
+use crate::secure::keyring::check_key_id;
+
+/// The signing callback of a [`RemoteKey`].
+type SignFn = Box<dyn Fn(&[u8]) -> Vec<u8> + Send + Sync>;
+
+/// The verification callback of a [`RemoteKey`].
+type VerifyFn = Box<dyn Fn(&[u8], &[u8]) -> bool + Send + Sync>;
+
+/// A [`CookieSigner`] that delegates signing to a callback.
+///
+/// A `RemoteKey` is a handle to a key held outside of the process, such as in
+/// an HSM accessed via PKCS#11 or in a cloud key management service. The key
+/// never enters process memory: tags are computed by the callback passed to
+/// [`RemoteKey::new()`], which typically asks the remote service to compute a
+/// MAC. By default, tags are verified by computing the expected tag with the
+/// callback and comparing it, in constant time, to the presented tag. A
+/// dedicated verification callback can be set with
+/// [`RemoteKey::with_verifier()`].
+///
+/// Use a `RemoteKey` with [`CookieJar::signed_with_signer()`] and friends. See
+/// [`AsyncRemoteKey`] for a variant with an asynchronous callback.
+///
+/// [`CookieJar::signed_with_signer()`]: crate::CookieJar::signed_with_signer()
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{CookieJar, Cookie, CookieSigner, HmacSigner, Key, RemoteKey};
+///
+/// // Stands in for a client of a key management service.
+/// let service = HmacSigner::new(&Key::generate());
+/// let key = RemoteKey::new(32, move |message| service.sign(message));
+///
+/// let mut jar = CookieJar::new();
+/// jar.signed_with_signer_mut(&key).add(Cookie::new("name", "value"));
+/// assert_eq!(jar.signed_with_signer(&key).get("name").unwrap().value(), "value");
+/// ```
+#[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+pub struct RemoteKey {
+    tag_len: usize,
+    key_id: Option<String>,
+    sign: SignFn,
+    verify: Option<VerifyFn>,
+}
+
+impl RemoteKey {
+    /// Creates a new `RemoteKey` whose tags, each `tag_len` bytes long, are
+    /// computed by `sign`. The callback is passed the message to authenticate
+    /// and must return its tag, which must be exactly `tag_len` bytes long.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieSigner, HmacSigner, Key, RemoteKey};
+    ///
+    /// let service = HmacSigner::new(&Key::generate());
+    /// let key = RemoteKey::new(32, move |message| service.sign(message));
+    /// assert_eq!(key.tag_len(), 32);
+    /// ```
+    pub fn new<F>(tag_len: usize, sign: F) -> RemoteKey
+        where F: Fn(&[u8]) -> Vec<u8> + Send + Sync + 'static
+    {
+        RemoteKey { tag_len, key_id: None, sign: Box::new(sign), verify: None }
+    }
+
+    /// Sets the callback used to verify tags to `verify`, which is passed a
+    /// message and a presented tag and returns `true` if the tag is valid. This
+    /// is useful with services that verify MACs themselves.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use cookie::{CookieSigner, HmacSigner, Key, RemoteKey};
+    ///
+    /// let service = Arc::new(HmacSigner::new(&Key::generate()));
+    /// let (signer, verifier) = (service.clone(), service);
+    /// let key = RemoteKey::new(32, move |message| signer.sign(message))
+    ///     .with_verifier(move |message, tag| verifier.verify(message, tag));
+    ///
+    /// let tag = key.sign(b"message");
+    /// assert!(key.verify(b"message", &tag));
+    /// ```
+    pub fn with_verifier<F>(mut self, verify: F) -> Self
+        where F: Fn(&[u8], &[u8]) -> bool + Send + Sync + 'static
+    {
+        self.verify = Some(Box::new(verify));
+        self
+    }
+
+    /// Sets the ID of the remote key to `id`. The ID is embedded in signed
+    /// values, and values carrying a different ID fail to verify.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not a valid key ID: between 1 and 16 ASCII
+    /// alphanumeric characters.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieSigner, HmacSigner, Key, RemoteKey};
+    ///
+    /// let service = HmacSigner::new(&Key::generate());
+    /// let key = RemoteKey::new(32, move |message| service.sign(message))
+    ///     .with_key_id("kms1");
+    ///
+    /// assert_eq!(key.key_id(), Some("kms1"));
+    /// ```
+    pub fn with_key_id(mut self, id: &str) -> Self {
+        check_key_id(id);
+        self.key_id = Some(id.to_string());
+        self
+    }
+}
+
+impl CookieSigner for RemoteKey {
+    fn tag_len(&self) -> usize {
+        self.tag_len
+    }
+
+    fn sign(&self, message: &[u8]) -> Vec<u8> {
+        (self.sign)(message)
+    }
+
+    fn verify(&self, message: &[u8], tag: &[u8]) -> bool {
+        match self.verify {
+            Some(ref verify) => verify(message, tag),
+            None => self.sign(message).ct_eq(tag).into(),
+        }
+    }
+
+    fn key_id(&self) -> Option<&str> {
+        self.key_id.as_deref()
+    }
+
+    fn verify_with_key_id(&self, key_id: &str, message: &[u8], tag: &[u8]) -> bool {
+        match self.key_id {
+            Some(ref id) if id != key_id => false,
+            _ => self.verify(message, tag),
+        }
+    }
+}
+
+/// The future returned by the signing callback of an [`AsyncRemoteKey`].
+#[cfg(feature = "async")]
+#[cfg_attr(all(nightly, doc), doc(cfg(all(feature = "signed", feature = "async"))))]
+pub type TagFuture = Pin<Box<dyn Future<Output = Result<Vec<u8>, KeyProviderError>> + Send>>;
+
+/// A handle to a remote key whose signing callback is asynchronous.
+///
+/// An `AsyncRemoteKey` is the asynchronous variant of [`RemoteKey`]: tags are
+/// computed by a callback returning a [`TagFuture`], for instance one that
+/// sends a request to a key management service, and verified by comparing the
+/// expected tag, in constant time, to the presented tag. Because computing a
+/// tag may fail, for instance when the service is unreachable, the callback
+/// returns a `Result`.
+///
+/// Jars using an `AsyncRemoteKey` are created with
+/// [`SignedJar::with_remote_key()`](crate::SignedJar::with_remote_key()) and
+/// sign and verify cookies via their `_async` methods.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{AsyncRemoteKey, CookieSigner, HmacSigner, Key};
+///
+/// let service = HmacSigner::new(&Key::generate());
+/// let key = AsyncRemoteKey::new(32, move |message| {
+///     let tag = service.sign(&message);
+///     Box::pin(async move { Ok(tag) })
+/// });
+/// ```
+#[cfg(feature = "async")]
+#[cfg_attr(all(nightly, doc), doc(cfg(all(feature = "signed", feature = "async"))))]
+pub struct AsyncRemoteKey {
+    tag_len: usize,
+    key_id: Option<String>,
+    sign: Box<dyn Fn(Vec<u8>) -> TagFuture + Send + Sync>,
+}
+
+#[cfg(feature = "async")]
+impl AsyncRemoteKey {
+    /// Creates a new `AsyncRemoteKey` whose tags, each `tag_len` bytes long,
+    /// are computed by `sign`. The callback is passed the message to
+    /// authenticate and returns a future resolving to its tag, which must be
+    /// exactly `tag_len` bytes long.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{AsyncRemoteKey, CookieSigner, HmacSigner, Key};
+    ///
+    /// let service = HmacSigner::new(&Key::generate());
+    /// let key = AsyncRemoteKey::new(32, move |message| {
+    ///     let tag = service.sign(&message);
+    ///     Box::pin(async move { Ok(tag) })
+    /// });
+    ///
+    /// assert_eq!(key.tag_len(), 32);
+    /// ```
+    pub fn new<F>(tag_len: usize, sign: F) -> AsyncRemoteKey
+        where F: Fn(Vec<u8>) -> TagFuture + Send + Sync + 'static
+    {
+        AsyncRemoteKey { tag_len, key_id: None, sign: Box::new(sign) }
+    }
+
+    /// Sets the ID of the remote key to `id`. The ID is embedded in signed
+    /// values, and values carrying a different ID fail to verify.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not a valid key ID: between 1 and 16 ASCII
+    /// alphanumeric characters.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{AsyncRemoteKey, KeyProviderError};
+    ///
+    /// let key = AsyncRemoteKey::new(32, |_| Box::pin(async {
+    ///     Err(KeyProviderError::from("unreachable"))
+    /// }));
+    ///
+    /// let key = key.with_key_id("kms1");
+    /// assert_eq!(key.key_id(), Some("kms1"));
+    /// ```
+    pub fn with_key_id(mut self, id: &str) -> Self {
+        check_key_id(id);
+        self.key_id = Some(id.to_string());
+        self
+    }
+
+    /// The length, in bytes, of every tag computed by this key.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{AsyncRemoteKey, KeyProviderError};
+    ///
+    /// let key = AsyncRemoteKey::new(64, |_| Box::pin(async {
+    ///     Err(KeyProviderError::from("unreachable"))
+    /// }));
+    ///
+    /// assert_eq!(key.tag_len(), 64);
+    /// ```
+    pub fn tag_len(&self) -> usize {
+        self.tag_len
+    }
+
+    /// Returns the ID of this key, if one was set.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{AsyncRemoteKey, KeyProviderError};
+    ///
+    /// let key = AsyncRemoteKey::new(32, |_| Box::pin(async {
+    ///     Err(KeyProviderError::from("unreachable"))
+    /// }));
+    ///
+    /// assert_eq!(key.key_id(), None);
+    /// ```
+    pub fn key_id(&self) -> Option<&str> {
+        self.key_id.as_deref()
+    }
+
+    /// Computes the tag of `message`.
+    pub(crate) fn sign(&self, message: Vec<u8>) -> TagFuture {
+        (self.sign)(message)
+    }
+
+    /// Returns `Ok(true)` if `tag` is the tag of `message`, or a prefix of it,
+    /// under the key identified by `key_id`, if any.
+    pub(crate) async fn verify(
+        &self,
+        key_id: Option<&str>,
+        message: Vec<u8>,
+        tag: &[u8]
+    ) -> Result<bool, KeyProviderError> {
+        match (self.key_id.as_deref(), key_id) {
+            (Some(id), Some(key_id)) if id != key_id => Ok(false),
+            _ => {
+                let full = self.sign(message).await?;
+                Ok(full.len() >= tag.len() && full[..tag.len()].ct_eq(tag).into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{CookieJar, Cookie, CookieSigner, HmacSigner, Key, RemoteKey};
+
+    fn remote(service: HmacSigner) -> RemoteKey {
+        RemoteKey::new(32, move |message| service.sign(message))
+    }
+
+    #[test]
+    fn simple() {
+        let key = remote(HmacSigner::new(&Key::generate()));
+        let mut jar = CookieJar::new();
+        assert_simple_behaviour!(jar, jar.signed_with_signer_mut(&key));
+    }
+
+    #[test]
+    fn secure() {
+        let key = remote(HmacSigner::new(&Key::generate()));
+        let mut jar = CookieJar::new();
+        assert_secure_behaviour!(jar, jar.signed_with_signer_mut(&key));
+    }
+
+    #[test]
+    fn interop() {
+        // A remote key computing the same MAC as a local key is compatible.
+        let local = Key::generate();
+        let key = remote(HmacSigner::new(&local)).with_key_id("r1");
+        let mut jar = CookieJar::new();
+        jar.signed_with_signer_mut(&key).add(Cookie::new("name", "value"));
+        assert!(jar.get("name").unwrap().value().starts_with("v2:r1$"));
+        assert_eq!(jar.signed(&local).get("name").unwrap().value(), "value");
+
+        // Values carrying another key ID don't verify.
+        let value = jar.get("name").unwrap().value().replacen("r1$", "r2$", 1);
+        jar.add(Cookie::new("other", value));
+        assert!(jar.signed_with_signer(&key).get("other").is_none());
+
+        // A dedicated verifier is used when set.
+        let key = remote(HmacSigner::new(&local)).with_verifier(|_, _| false);
+        assert!(jar.signed_with_signer(key).get("name").is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn async_remote_key() {
+        use crate::secure::provider::block_on;
+        use crate::{AsyncRemoteKey, KeyProviderError, SignedJar, VerificationError};
+
+        let local = Key::generate();
+        let service = HmacSigner::new(&local);
+        let service = std::sync::Arc::new(service);
+        let key = || {
+            let service = service.clone();
+            AsyncRemoteKey::new(32, move |message| {
+                let tag = service.sign(&message);
+                Box::pin(async move { Ok(tag) })
+            })
+        };
+
+        let mut jar = CookieJar::new();
+        let mut signed = SignedJar::with_remote_key(&mut jar, key().with_key_id("r1"));
+        block_on(signed.add_async(Cookie::new("name", "value"))).unwrap();
+        assert_eq!(block_on(signed.get_async("name")).unwrap().value(), "value");
+        assert!(block_on(signed.get_async("missing")).is_none());
+        assert_eq!(jar.signed(&local).get("name").unwrap().value(), "value");
+
+        // Truncated tags are produced and verified.
+        let mut truncated = SignedJar::with_remote_key(&mut jar, key()).with_truncated_tags(true);
+        block_on(truncated.add_async(Cookie::new("short", "value"))).unwrap();
+        assert_eq!(block_on(truncated.get_async("short")).unwrap().value(), "value");
+        assert!(jar.get("short").unwrap().value().starts_with("v2s:"));
+        assert_eq!(jar.signed(&local).get("short").unwrap().value(), "value");
+
+        // Failures of the remote service are reported.
+        let down = || AsyncRemoteKey::new(32, |_| Box::pin(async {
+            Err(KeyProviderError::from("unreachable"))
+        }));
+
+        let mut signed = SignedJar::with_remote_key(&mut jar, down());
+        assert!(block_on(signed.add_async(Cookie::new("other", "value"))).is_err());
+
+        let cookie = jar.get("name").unwrap().clone();
+        let result = block_on(SignedJar::with_remote_key(&jar, down()).verify_async(cookie));
+        assert_eq!(result.unwrap_err(), VerificationError::Unavailable);
+    }
+}
@@ -0,0 +1,290 @@
+use std::borrow::{Borrow, BorrowMut};
+
+use crate::secure::{Key, Keyring, PrivateJar, SignedJar};
+use crate::{Cookie, CookieJar};
+
+/// A child cookie jar that encrypts and then signs its cookies.
+///
+/// A _secure_ child jar encrypts all the cookies added to it, as if by a
+/// [`PrivateJar`], and then signs the encrypted value, as if by a
+/// [`SignedJar`]. Cookies retrieved from it are verified and then decrypted.
+/// Any cookies stored in a `SecureJar` are thus assured confidentiality,
+/// integrity, and authenticity, and, in addition, their integrity can be
+/// checked independently of decryption, by anyone with the signing key, with
+/// a `SignedJar` using the same key: such a jar returns the encrypted value.
+///
+/// The signing and encryption layers use distinct keys derived from the same
+/// [`Key`].
+///
+/// The format of values produced by a `SecureJar` is stable: the value is the
+/// cookie's value encrypted as by a [`PrivateJar`], which is then signed as by
+/// a [`SignedJar`], both with default settings. Values produced by one release
+/// remain readable by later releases.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{CookieJar, Cookie, Key};
+///
+/// let key = Key::generate();
+/// let mut jar = CookieJar::new();
+/// jar.secure_mut(&key).add(Cookie::new("name", "value"));
+/// assert_eq!(jar.secure(&key).get("name").unwrap().value(), "value");
+///
+/// // The integrity layer can be checked without decrypting.
+/// let encrypted = jar.signed(&key).get("name").unwrap();
+/// assert_ne!(encrypted.value(), "value");
+/// assert_eq!(jar.private(&key).decrypt(encrypted).unwrap().value(), "value");
+/// ```
+#[cfg_attr(all(nightly, doc), doc(cfg(all(feature = "private", feature = "signed"))))]
+pub struct SecureJar<J> {
+    parent: J,
+    private: PrivateJar<()>,
+    signed: SignedJar<()>,
+}
+
+impl<J> SecureJar<J> {
+    /// Creates a new child `SecureJar` with parent `parent` and key `key`. This
+    /// method is typically called indirectly via the `secure{_mut}` methods of
+    /// `CookieJar`.
+    pub(crate) fn new(parent: J, key: &Key) -> SecureJar<J> {
+        SecureJar::with_keys(parent, std::slice::from_ref(key))
+    }
+
+    /// Creates a new child `SecureJar` with parent `parent` that encrypts and
+    /// signs with `keys[0]` and verifies and decrypts with each key in `keys`,
+    /// in order. This method is typically called indirectly via the
+    /// `secure_with_keys{_mut}` methods of `CookieJar`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keys` is empty.
+    pub(crate) fn with_keys(parent: J, keys: &[Key]) -> SecureJar<J> {
+        let private = PrivateJar::with_keys((), keys);
+        let signed = SignedJar::with_keys((), keys);
+        SecureJar { parent, private, signed }
+    }
+
+    /// Creates a new child `SecureJar` with parent `parent` that encrypts and
+    /// signs with the primary key in `keyring` and verifies and decrypts with
+    /// the key identified in the cookie's value. This method is typically
+    /// called indirectly via the `secure_with_keyring{_mut}` methods of
+    /// `CookieJar`.
+    pub(crate) fn with_keyring(parent: J, keyring: &Keyring) -> SecureJar<J> {
+        let private = PrivateJar::with_keyring((), keyring);
+        let signed = SignedJar::with_keyring((), keyring);
+        SecureJar { parent, private, signed }
+    }
+
+    /// Encrypts and then signs the cookie's value.
+    fn seal_cookie(&self, cookie: &mut Cookie) {
+        let encrypted = self.private.seal(cookie.name(), cookie.value());
+        let signed = self.signed.sign(cookie.name(), &encrypted, None, None);
+        cookie.set_value(signed);
+    }
+
+    /// Verifies and then decrypts `cookie`, returning the plaintext version if
+    /// both verification and decryption succeed or `None` otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.secure_mut(&key).add(Cookie::new("name", "value"));
+    ///
+    /// let sealed = jar.get("name").cloned().unwrap();
+    /// assert_eq!(jar.secure(&key).decrypt(sealed).unwrap().value(), "value");
+    ///
+    /// let plain = Cookie::new("plaintext", "hello");
+    /// assert!(jar.secure(&key).decrypt(plain).is_none());
+    /// ```
+    pub fn decrypt(&self, mut cookie: Cookie<'static>) -> Option<Cookie<'static>> {
+        let encrypted = self.signed._verify(cookie.name(), cookie.value()).ok()?;
+        let value = self.private.unseal(cookie.name(), &encrypted).ok()?;
+        cookie.set_value(value);
+        Some(cookie)
+    }
+}
+
+impl<J: Borrow<CookieJar>> SecureJar<J> {
+    /// Returns a reference to the `Cookie` inside this jar with the name `name`
+    /// and verifies and decrypts the cookie's value, returning a `Cookie` with
+    /// the decrypted value. If the cookie cannot be found, or the cookie fails
+    /// to verify or decrypt, `None` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// assert!(jar.secure(&key).get("name").is_none());
+    ///
+    /// jar.secure_mut(&key).add(Cookie::new("name", "value"));
+    /// assert_eq!(jar.secure(&key).get("name").unwrap().value(), "value");
+    /// ```
+    pub fn get(&self, name: &str) -> Option<Cookie<'static>> {
+        self.parent.borrow().get(name).and_then(|c| self.decrypt(c.clone()))
+    }
+}
+
+impl<J: BorrowMut<CookieJar>> SecureJar<J> {
+    /// Adds `cookie` to the parent jar. The cookie's value is encrypted and
+    /// then signed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.secure_mut(&key).add(Cookie::new("name", "value"));
+    ///
+    /// assert!(!jar.get("name").unwrap().value().contains("value"));
+    /// assert_eq!(jar.secure(&key).get("name").unwrap().value(), "value");
+    /// ```
+    pub fn add(&mut self, mut cookie: Cookie<'static>) {
+        self.seal_cookie(&mut cookie);
+        self.parent.borrow_mut().add(cookie);
+    }
+
+    /// Adds an "original" `cookie` to parent jar. The cookie's value is
+    /// encrypted and then signed. Adding an original cookie does not affect the
+    /// [`CookieJar::delta()`] computation. This method is intended to be used to
+    /// seed the cookie jar with cookies received from a client's HTTP message.
+    ///
+    /// For accurate `delta` computations, this method should not be called
+    /// after calling `remove`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.secure_mut(&key).add_original(Cookie::new("name", "value"));
+    ///
+    /// assert_eq!(jar.iter().count(), 1);
+    /// assert_eq!(jar.delta().count(), 0);
+    /// ```
+    pub fn add_original(&mut self, mut cookie: Cookie<'static>) {
+        self.seal_cookie(&mut cookie);
+        self.parent.borrow_mut().add_original(cookie);
+    }
+
+    /// Removes `cookie` from the parent jar.
+    ///
+    /// For correct removal, the passed in `cookie` must contain the same `path`
+    /// and `domain` as the cookie that was initially set.
+    ///
+    /// This is identical to [`CookieJar::remove()`]. See the method's
+    /// documentation for more details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// let mut secure_jar = jar.secure_mut(&key);
+    ///
+    /// secure_jar.add(Cookie::new("name", "value"));
+    /// assert!(secure_jar.get("name").is_some());
+    ///
+    /// secure_jar.remove(Cookie::named("name"));
+    /// assert!(secure_jar.get("name").is_none());
+    /// ```
+    pub fn remove(&mut self, cookie: Cookie<'static>) {
+        self.parent.borrow_mut().remove(cookie);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{CookieJar, Cookie, Key, Keyring};
+
+    #[test]
+    fn simple() {
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        assert_simple_behaviour!(jar, jar.secure_mut(&key));
+    }
+
+    #[test]
+    fn secure() {
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        assert_secure_behaviour!(jar, jar.secure_mut(&key));
+    }
+
+    #[test]
+    fn layers() {
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        jar.secure_mut(&key).add(Cookie::new("name", "value"));
+
+        // The signed layer verifies without decrypting...
+        let encrypted = jar.signed(&key).get("name").unwrap();
+        assert_eq!(jar.private(&key).decrypt(encrypted.clone()).unwrap().value(), "value");
+
+        // ...and is checked before decryption.
+        let mut tampered = jar.get("name").unwrap().clone();
+        tampered.set_value(tampered.value().replacen("v2:", "v2t:", 1));
+        assert!(jar.secure(&key).decrypt(tampered).is_none());
+
+        // Either layer alone is not accepted.
+        jar.private_mut(&key).add(Cookie::new("private", "value"));
+        jar.signed_mut(&key).add(Cookie::new("signed", "value"));
+        jar.add(Cookie::new("inner", encrypted.value().to_string()));
+        assert!(jar.secure(&key).get("private").is_none());
+        assert!(jar.secure(&key).get("signed").is_none());
+        assert!(jar.secure(&key).get("inner").is_none());
+    }
+
+    #[test]
+    fn rotation() {
+        let (old, new) = (Key::generate(), Key::generate());
+        let mut jar = CookieJar::new();
+        jar.secure_mut(&old).add(Cookie::new("old", "value"));
+
+        let mut secure = jar.secure_with_keys_mut(&[new.clone(), old.clone()]);
+        assert_eq!(secure.get("old").unwrap().value(), "value");
+        secure.add(Cookie::new("new", "value"));
+        assert_eq!(jar.secure(&new).get("new").unwrap().value(), "value");
+        assert!(jar.secure(&old).get("new").is_none());
+
+        let mut keyring = Keyring::new("k1", old);
+        jar.secure_with_keyring_mut(&keyring).add(Cookie::new("ring", "value"));
+        keyring.add("k2", new);
+        assert_eq!(jar.secure_with_keyring(&keyring).get("ring").unwrap().value(), "value");
+    }
+
+    #[test]
+    fn stable_format() {
+        // A value produced by an earlier version must remain readable.
+        let key = Key::from(&(0..64).collect::<Vec<u8>>());
+        let value = "v2:6gpO3XeSN8wB0Ej5xf1JtW4XyQZXuwpQH0wL7njYFbk=\
+            v2:4I1BKth5n7yEkUYCKRO1yFOGOolOklBMwQRSe1NoMPox";
+
+        let mut jar = CookieJar::new();
+        jar.add(Cookie::new("name", value));
+        assert_eq!(jar.secure(&key).get("name").unwrap().value(), "value");
+        assert!(jar.secure(&Key::generate()).get("name").is_none());
+
+        // The outer, signed value wraps an encrypted value.
+        let encrypted = jar.signed(&key).get("name").unwrap();
+        assert!(encrypted.value().starts_with("v2:"));
+        assert_eq!(jar.private(&key).decrypt(encrypted).unwrap().value(), "value");
+
+        // The value is bound to the cookie's name by the encryption layer.
+        jar.add(Cookie::new("other", value));
+        assert!(jar.secure(&key).get("other").is_none());
+    }
+}
@@ -1,17 +1,448 @@
-use std::convert::TryInto;
 use std::borrow::{Borrow, BorrowMut};
+use std::error::Error;
+use std::fmt;
 
-use sha2::Sha256;
-use hmac::{Hmac, Mac};
+use sha2::{Sha256, Sha512};
+use hmac::{Hmac, Mac, digest::KeyInit};
+use time::{Duration, OffsetDateTime};
 
-use crate::secure::{base64, Key};
+use crate::secure::{base64, Key, Keyring, MaybeVerified};
+use crate::secure::base64::URL_SAFE_NO_PAD;
+#[cfg(feature = "async")] use crate::secure::{AsyncRemoteKey, KeyProvider, KeyProviderError};
+use crate::secure::keyring::{KeySet, split_key_id, with_key_id};
+use crate::secure::version::{Flags, Format};
+use crate::secure::compat::express::{self, ExpressSigner};
+use crate::secure::compat::jws;
+use crate::secure::compat::rails::{self, RailsSigner};
 use crate::{Cookie, CookieJar};
 
-// Keep these in sync, and keep the key len synced with the `signed` docs as
-// well as the `KEYS_INFO` const in secure::Key.
-pub(crate) const BASE64_DIGEST_LEN: usize = 44;
+// Keep the key len synced with the `signed` docs as well as the `KEYS_INFO`
+// const in secure::Key.
 pub(crate) const KEY_LEN: usize = 32;
 
+/// The separator between the issue and expiration timestamps and a
+/// timestamped value.
+const TIMESTAMP_SEPARATOR: char = '.';
+
+/// The separator between the fields of encoded bound attributes.
+const ATTRIBUTE_SEPARATOR: char = '\0';
+
+/// The length, in bytes, of a truncated tag.
+const TRUNCATED_TAG_LEN: usize = 16;
+
+/// The suffix of the name of the cookie holding a detached signature.
+const SIGNATURE_SUFFIX: &str = ".sig";
+
+/// The MAC algorithm used by a [`SignedJar`] to sign and verify cookies.
+///
+/// The default algorithm is [`SigningAlgorithm::HmacSha256`]. A different
+/// algorithm can be selected via [`SignedJar::with_algorithm()`] or
+/// [`HmacSigner::with_algorithm()`]. Cookies must be verified with the same
+/// algorithm they were signed with.
+#[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SigningAlgorithm {
+    /// HMAC-SHA256, which produces a 32 byte tag.
+    HmacSha256,
+    /// HMAC-SHA512, which produces a 64 byte tag.
+    HmacSha512,
+}
+
+impl SigningAlgorithm {
+    /// The length, in bytes, of a tag produced by `self`.
+    fn tag_len(self) -> usize {
+        match self {
+            SigningAlgorithm::HmacSha256 => 32,
+            SigningAlgorithm::HmacSha512 => 64,
+        }
+    }
+
+    /// Computes the tag of the concatenation of `parts` with `key`.
+    fn sign(self, key: &[u8], parts: &[&[u8]]) -> Vec<u8> {
+        match self {
+            SigningAlgorithm::HmacSha256 => keyed_mac::<Hmac<Sha256>>(key, parts)
+                .finalize().into_bytes().to_vec(),
+            SigningAlgorithm::HmacSha512 => keyed_mac::<Hmac<Sha512>>(key, parts)
+                .finalize().into_bytes().to_vec(),
+        }
+    }
+
+    /// Verifies, in constant time, that `tag` is the tag of the concatenation
+    /// of `parts` with `key`.
+    fn verify(self, key: &[u8], parts: &[&[u8]], tag: &[u8]) -> bool {
+        match self {
+            SigningAlgorithm::HmacSha256 => keyed_mac::<Hmac<Sha256>>(key, parts)
+                .verify_slice(tag).is_ok(),
+            SigningAlgorithm::HmacSha512 => keyed_mac::<Hmac<Sha512>>(key, parts)
+                .verify_slice(tag).is_ok(),
+        }
+    }
+
+    /// Verifies, in constant time, that `tag` is a prefix of the tag of the
+    /// concatenation of `parts` with `key`.
+    fn verify_truncated(self, key: &[u8], parts: &[&[u8]], tag: &[u8]) -> bool {
+        match self {
+            SigningAlgorithm::HmacSha256 => keyed_mac::<Hmac<Sha256>>(key, parts)
+                .verify_truncated_left(tag).is_ok(),
+            SigningAlgorithm::HmacSha512 => keyed_mac::<Hmac<Sha512>>(key, parts)
+                .verify_truncated_left(tag).is_ok(),
+        }
+    }
+}
+
+#[allow(clippy::derivable_impls)]
+impl Default for SigningAlgorithm {
+    fn default() -> Self {
+        SigningAlgorithm::HmacSha256
+    }
+}
+
+/// The reason a cookie failed to verify in a [`SignedJar`].
+#[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum VerificationError {
+    /// There is no cookie with the requested name.
+    NotFound,
+    /// The value is tagged with an unknown format.
+    UnknownFormat,
+    /// The value is too short to contain a signature, or the signature is cut
+    /// off.
+    MissingDigest,
+    /// The signature, or the signed data, is not validly encoded.
+    BadBase64,
+    /// The signature does not match the value under any of the jar's keys.
+    BadSignature,
+    /// The value has no timestamps but the jar requires them, or the
+    /// timestamps are malformed.
+    MissingTimestamp,
+    /// The value's expiration timestamp has passed, or it was issued longer
+    /// ago than the jar's maximum age.
+    Expired,
+    /// The jar's signer failed to compute the tag needed to check the
+    /// signature, for instance because a remote key was unreachable.
+    Unavailable,
+}
+
+impl VerificationError {
+    /// Returns a description of this error as a string
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            VerificationError::NotFound => "the cookie was not found",
+            VerificationError::UnknownFormat => "the cookie's value has an unknown format",
+            VerificationError::MissingDigest => "the cookie's value is missing a signature",
+            VerificationError::BadBase64 => "the cookie's signature is not valid base64",
+            VerificationError::BadSignature => "the cookie's signature did not verify",
+            VerificationError::MissingTimestamp => {
+                "the cookie's value is missing its timestamps"
+            }
+            VerificationError::Expired => "the cookie's value has expired",
+            VerificationError::Unavailable => "the cookie's signature could not be checked",
+        }
+    }
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Error for VerificationError {
+    fn description(&self) -> &str {
+        self.as_str()
+    }
+}
+
+fn keyed_mac<M: Mac + KeyInit>(key: &[u8], parts: &[&[u8]]) -> M {
+    let mut mac = <M as KeyInit>::new_from_slice(key).expect("good key");
+    parts.iter().for_each(|part| mac.update(part));
+    mac
+}
+
+/// Computes and checks the tags that authenticate the values in a
+/// [`SignedJar`].
+///
+/// A `SignedJar` encodes and decodes signed cookie values and determines the
+/// message that is authenticated for each cookie; a `CookieSigner` computes and
+/// verifies tags of those messages. The default signer, [`HmacSigner`], signs
+/// with the keys supplied to [`CookieJar::signed()`] and friends. Implement
+/// this trait to sign with keys held elsewhere, such as in an HSM or a key
+/// management service, or to wrap another signer, and use the implementation
+/// via [`CookieJar::signed_with_signer()`].
+///
+/// # Example
+///
+/// ```rust
+/// use std::cell::Cell;
+/// use cookie::{CookieJar, Cookie, CookieSigner, HmacSigner, Key};
+///
+/// /// A signer that counts failed verifications.
+/// struct Audited {
+///     inner: HmacSigner,
+///     failures: Cell<usize>,
+/// }
+///
+/// impl CookieSigner for Audited {
+///     fn tag_len(&self) -> usize {
+///         self.inner.tag_len()
+///     }
+///
+///     fn sign(&self, message: &[u8]) -> Vec<u8> {
+///         self.inner.sign(message)
+///     }
+///
+///     fn verify(&self, message: &[u8], tag: &[u8]) -> bool {
+///         let valid = self.inner.verify(message, tag);
+///         if !valid {
+///             self.failures.set(self.failures.get() + 1);
+///         }
+///
+///         valid
+///     }
+/// }
+///
+/// let key = Key::generate();
+/// let mut jar = CookieJar::new();
+/// jar.signed_mut(&key).add(Cookie::new("name", "value"));
+///
+/// let signer = Audited { inner: HmacSigner::new(&Key::generate()), failures: Cell::new(0) };
+/// let signed = jar.signed_with_signer(signer);
+/// assert!(signed.get("name").is_none());
+/// assert_eq!(signed.signer().failures.get(), 1);
+/// ```
+#[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+pub trait CookieSigner {
+    /// The length, in bytes, of every tag returned by
+    /// [`CookieSigner::sign()`].
+    fn tag_len(&self) -> usize;
+
+    /// Computes the tag of `message`.
+    fn sign(&self, message: &[u8]) -> Vec<u8>;
+
+    /// Returns `true` if `tag` is a valid tag of `message`. Comparisons of
+    /// secret-dependent data should be performed in constant time.
+    fn verify(&self, message: &[u8], tag: &[u8]) -> bool;
+
+    /// Returns the ID of the key used by [`CookieSigner::sign()`], if there
+    /// is one. The ID is embedded in signed values and passed back to
+    /// [`CookieSigner::verify_with_key_id()`] on verification. It must be a
+    /// valid [`Keyring`] key ID. By default, returns `None`.
+    fn key_id(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns `true` if `tag` is a valid tag of `message` under the key
+    /// identified by `key_id`, the key ID embedded in the signed value. By
+    /// default, ignores `key_id` and calls [`CookieSigner::verify()`].
+    fn verify_with_key_id(&self, key_id: &str, message: &[u8], tag: &[u8]) -> bool {
+        let _ = key_id;
+        self.verify(message, tag)
+    }
+
+    /// Returns `true` if `tag` is a valid tag of `message`, under the key
+    /// identified by `key_id` if there is one, truncated to its first
+    /// `tag.len()` bytes, as used by jars with [truncated
+    /// tags](SignedJar::with_truncated_tags()) enabled.
+    ///
+    /// By default, computes the tag of `message` with [`CookieSigner::sign()`],
+    /// if `key_id` is `None` or [`CookieSigner::key_id()`], and compares its
+    /// prefix to `tag` in constant time. The default is thus only suitable for
+    /// deterministic signers, such as MACs.
+    fn verify_truncated(&self, key_id: Option<&str>, message: &[u8], tag: &[u8]) -> bool {
+        use subtle::ConstantTimeEq;
+
+        if key_id.is_some() && self.key_id().is_some() && key_id != self.key_id() {
+            return false;
+        }
+
+        let full = self.sign(message);
+        !tag.is_empty() && matches!(full.get(..tag.len()), Some(t) if bool::from(t.ct_eq(tag)))
+    }
+
+    /// Returns a signer for the cookie named `name` whose keys are derived from
+    /// this signer's keys, as used by jars with [per-cookie
+    /// keys](SignedJar::with_derived_keys()) enabled. Returns `None`, the
+    /// default, if the signer does not support deriving keys.
+    fn derive(&self, name: &str) -> Option<Self> where Self: Sized {
+        let _ = name;
+        None
+    }
+}
+
+impl<S: CookieSigner + ?Sized> CookieSigner for &S {
+    fn tag_len(&self) -> usize {
+        (**self).tag_len()
+    }
+
+    fn sign(&self, message: &[u8]) -> Vec<u8> {
+        (**self).sign(message)
+    }
+
+    fn verify(&self, message: &[u8], tag: &[u8]) -> bool {
+        (**self).verify(message, tag)
+    }
+
+    fn key_id(&self) -> Option<&str> {
+        (**self).key_id()
+    }
+
+    fn verify_with_key_id(&self, key_id: &str, message: &[u8], tag: &[u8]) -> bool {
+        (**self).verify_with_key_id(key_id, message, tag)
+    }
+
+    fn verify_truncated(&self, key_id: Option<&str>, message: &[u8], tag: &[u8]) -> bool {
+        (**self).verify_truncated(key_id, message, tag)
+    }
+}
+
+/// The default [`CookieSigner`]: HMAC with one or more [`Key`]s.
+///
+/// An `HmacSigner` signs with its primary key and verifies with each of its
+/// keys. It is created implicitly by [`CookieJar::signed()`] and friends, and
+/// can be created directly for use with wrapping signers.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{CookieSigner, HmacSigner, Key, SigningAlgorithm};
+///
+/// let signer = HmacSigner::new(&Key::generate())
+///     .with_algorithm(SigningAlgorithm::HmacSha512);
+///
+/// let tag = signer.sign(b"message");
+/// assert_eq!(tag.len(), signer.tag_len());
+/// assert!(signer.verify(b"message", &tag));
+/// ```
+#[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+pub struct HmacSigner {
+    keys: KeySet<KEY_LEN>,
+    algorithm: SigningAlgorithm,
+}
+
+impl HmacSigner {
+    /// Creates a new `HmacSigner` that signs and verifies with `key`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{HmacSigner, Key};
+    ///
+    /// let signer = HmacSigner::new(&Key::generate());
+    /// ```
+    pub fn new(key: &Key) -> HmacSigner {
+        HmacSigner::with_keys(std::slice::from_ref(key))
+    }
+
+    /// Creates a new `HmacSigner` that signs with `keys[0]` and verifies with
+    /// each key in `keys`, in order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keys` is empty.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieSigner, HmacSigner, Key};
+    ///
+    /// let (old, new) = (Key::generate(), Key::generate());
+    /// let tag = HmacSigner::new(&old).sign(b"message");
+    ///
+    /// let signer = HmacSigner::with_keys(&[new, old]);
+    /// assert!(signer.verify(b"message", &tag));
+    /// ```
+    pub fn with_keys(keys: &[Key]) -> HmacSigner {
+        let keys = KeySet::from_keys(keys, Key::signing);
+        HmacSigner { keys, algorithm: SigningAlgorithm::default() }
+    }
+
+    /// Creates a new `HmacSigner` that signs with the primary key in `keyring`
+    /// and verifies with the key identified in the signed value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieSigner, HmacSigner, Key, Keyring};
+    ///
+    /// let keyring = Keyring::new("k1", Key::generate());
+    /// let signer = HmacSigner::with_keyring(&keyring);
+    /// assert_eq!(signer.key_id(), Some("k1"));
+    /// ```
+    pub fn with_keyring(keyring: &Keyring) -> HmacSigner {
+        let keys = KeySet::from_keyring(keyring, Key::signing);
+        HmacSigner { keys, algorithm: SigningAlgorithm::default() }
+    }
+
+    /// Sets the MAC algorithm used to sign and verify to `algorithm`. The
+    /// default is [`SigningAlgorithm::HmacSha256`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieSigner, HmacSigner, Key, SigningAlgorithm};
+    ///
+    /// let signer = HmacSigner::new(&Key::generate())
+    ///     .with_algorithm(SigningAlgorithm::HmacSha512);
+    ///
+    /// assert_eq!(signer.tag_len(), 64);
+    /// ```
+    pub fn with_algorithm(mut self, algorithm: SigningAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Sets whether keys without a key ID are identified by their fingerprint.
+    /// See [`SignedJar::with_key_fingerprints()`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieSigner, HmacSigner, Key};
+    ///
+    /// let key = Key::generate();
+    /// let signer = HmacSigner::new(&key).with_key_fingerprints(true);
+    /// assert_eq!(signer.key_id(), Some(&*key.fingerprint()));
+    /// ```
+    pub fn with_key_fingerprints(mut self, fingerprints: bool) -> Self {
+        self.keys.set_fingerprint_ids(fingerprints);
+        self
+    }
+}
+
+impl CookieSigner for HmacSigner {
+    fn tag_len(&self) -> usize {
+        self.algorithm.tag_len()
+    }
+
+    fn sign(&self, message: &[u8]) -> Vec<u8> {
+        self.algorithm.sign(self.keys.primary().1, &[message])
+    }
+
+    fn verify(&self, message: &[u8], tag: &[u8]) -> bool {
+        self.keys.candidates(None).any(|key| self.algorithm.verify(key, &[message], tag))
+    }
+
+    fn key_id(&self) -> Option<&str> {
+        self.keys.primary().0
+    }
+
+    fn verify_with_key_id(&self, key_id: &str, message: &[u8], tag: &[u8]) -> bool {
+        self.keys.candidates(Some(key_id))
+            .any(|key| self.algorithm.verify(key, &[message], tag))
+    }
+
+    fn verify_truncated(&self, key_id: Option<&str>, message: &[u8], tag: &[u8]) -> bool {
+        self.keys.candidates(key_id)
+            .any(|key| self.algorithm.verify_truncated(key, &[message], tag))
+    }
+
+    fn derive(&self, name: &str) -> Option<Self> {
+        Some(HmacSigner { keys: self.keys.derive(name), algorithm: self.algorithm })
+    }
+}
+
 /// A child cookie jar that authenticates its cookies.
 ///
 /// A _signed_ child jar signs all the cookies added to it and verifies cookies
@@ -19,10 +450,48 @@ pub(crate) const KEY_LEN: usize = 32;
 /// integrity and authenticity. In other words, clients cannot tamper with the
 /// contents of a cookie nor can they fabricate cookie values, but the data is
 /// visible in plaintext.
+///
+/// Tags are computed and verified by a [`CookieSigner`], `S`, which defaults to
+/// [`HmacSigner`].
 #[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
-pub struct SignedJar<J> {
+pub struct SignedJar<J, S = HmacSigner> {
     parent: J,
-    key: [u8; KEY_LEN],
+    signer: S,
+    bind_name: bool,
+    max_age: Option<Duration>,
+    codec: Codec,
+    detached: bool,
+    derive_keys: bool,
+    bind_attributes: bool,
+    context: Option<Vec<u8>>,
+    url_safe: bool,
+    truncate: bool,
+    on_failure: Option<fn(&str, VerificationError)>,
+}
+
+/// The format of the values in a `SignedJar`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Codec {
+    /// This crate's own, versioned format.
+    Native,
+    /// The Rails `MessageVerifier` format.
+    Rails,
+    /// The Express `cookie-parser` format.
+    Express,
+    /// The JWS compact serialization.
+    Jws,
+}
+
+impl Codec {
+    /// The name of the format, for messages.
+    fn name(self) -> &'static str {
+        match self {
+            Codec::Native => "native",
+            Codec::Rails => "Rails",
+            Codec::Express => "Express",
+            Codec::Jws => "JWS",
+        }
+    }
 }
 
 impl<J> SignedJar<J> {
@@ -30,81 +499,88 @@ impl<J> SignedJar<J> {
     /// method is typically called indirectly via the `signed{_mut}` methods of
     /// `CookieJar`.
     pub(crate) fn new(parent: J, key: &Key) -> SignedJar<J> {
-        SignedJar { parent, key: key.signing().try_into().expect("sign key len") }
+        SignedJar::with_keys(parent, std::slice::from_ref(key))
     }
 
-    /// Signs the cookie's value providing integrity and authenticity.
-    fn sign_cookie(&self, cookie: &mut Cookie) {
-        // Compute HMAC-SHA256 of the cookie's value.
-        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key).expect("good key");
-        mac.update(cookie.value().as_bytes());
-
-        // Cookie's new value is [MAC | original-value].
-        let mut new_value = base64::encode(&mac.finalize().into_bytes());
-        new_value.push_str(cookie.value());
-        cookie.set_value(new_value);
+    /// Creates a new child `SignedJar` with parent `parent` that signs with
+    /// `keys[0]` and verifies with each key in `keys`, in order. This method is
+    /// typically called indirectly via the `signed_with_keys{_mut}` methods of
+    /// `CookieJar`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keys` is empty.
+    pub(crate) fn with_keys(parent: J, keys: &[Key]) -> SignedJar<J> {
+        SignedJar::with_signer(parent, HmacSigner::with_keys(keys))
     }
 
-    /// Given a signed value `str` where the signature is prepended to `value`,
-    /// verifies the signed value and returns it. If there's a problem, returns
-    /// an `Err` with a string describing the issue.
-    fn _verify(&self, cookie_value: &str) -> Result<String, &'static str> {
-        if !cookie_value.is_char_boundary(BASE64_DIGEST_LEN) {
-            return Err("missing or invalid digest");
-        }
-
-        // Split [MAC | original-value] into its two parts.
-        let (digest_str, value) = cookie_value.split_at(BASE64_DIGEST_LEN);
-        let digest = base64::decode(digest_str).map_err(|_| "bad base64 digest")?;
+    /// Creates a new child `SignedJar` with parent `parent` that signs with the
+    /// primary key in `keyring` and verifies with the key identified in the
+    /// cookie's value. This method is typically called indirectly via the
+    /// `signed_with_keyring{_mut}` methods of `CookieJar`.
+    pub(crate) fn with_keyring(parent: J, keyring: &Keyring) -> SignedJar<J> {
+        SignedJar::with_signer(parent, HmacSigner::with_keyring(keyring))
+    }
 
-        // Perform the verification.
-        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key).expect("good key");
-        mac.update(value.as_bytes());
-        mac.verify_slice(&digest)
-            .map(|_| value.to_string())
-            .map_err(|_| "value did not verify")
+    /// Fetches the current keys from `provider` and creates a new child
+    /// `SignedJar` with parent `parent` that signs with the primary key and
+    /// verifies with the key identified in the cookie's value, as if by
+    /// [`CookieJar::signed_with_keyring()`]. If the keys cannot be fetched,
+    /// returns the provider's error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, KeyProvider, KeyProviderError, SignedJar};
+    ///
+    /// async fn session(
+    ///     jar: &CookieJar,
+    ///     secrets: &dyn KeyProvider,
+    /// ) -> Result<Option<String>, KeyProviderError> {
+    ///     let signed = SignedJar::with_provider(jar, secrets).await?;
+    ///     Ok(signed.get("session").map(|c| c.value().to_string()))
+    /// }
+    /// ```
+    #[cfg(feature = "async")]
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "async")))]
+    pub async fn with_provider<P>(parent: J, provider: &P) -> Result<SignedJar<J>, KeyProviderError>
+        where P: KeyProvider + ?Sized
+    {
+        let keyring = provider.fetch().await?;
+        Ok(SignedJar::with_keyring(parent, &keyring))
     }
 
-    /// Verifies the authenticity and integrity of `cookie`, returning the
-    /// plaintext version if verification succeeds or `None` otherwise.
-    /// Verification _always_ succeeds if `cookie` was generated by a
-    /// `SignedJar` with the same key as `self`.
+    /// Sets the MAC algorithm used to sign and verify cookies to `algorithm`.
+    /// The default is [`SigningAlgorithm::HmacSha256`].
     ///
     /// # Example
     ///
     /// ```rust
-    /// use cookie::{CookieJar, Cookie, Key};
+    /// use cookie::{CookieJar, Cookie, Key, SigningAlgorithm};
     ///
     /// let key = Key::generate();
     /// let mut jar = CookieJar::new();
-    /// assert!(jar.signed(&key).get("name").is_none());
-    ///
-    /// jar.signed_mut(&key).add(Cookie::new("name", "value"));
-    /// assert_eq!(jar.signed(&key).get("name").unwrap().value(), "value");
-    ///
-    /// let plain = jar.get("name").cloned().unwrap();
-    /// assert_ne!(plain.value(), "value");
-    /// let verified = jar.signed(&key).verify(plain).unwrap();
-    /// assert_eq!(verified.value(), "value");
+    /// jar.signed_mut(&key)
+    ///     .with_algorithm(SigningAlgorithm::HmacSha512)
+    ///     .add(Cookie::new("name", "value"));
     ///
-    /// let plain = Cookie::new("plaintext", "hello");
-    /// assert!(jar.signed(&key).verify(plain).is_none());
+    /// let signed = jar.signed(&key).with_algorithm(SigningAlgorithm::HmacSha512);
+    /// assert_eq!(signed.get("name").unwrap().value(), "value");
+    /// assert!(jar.signed(&key).get("name").is_none());
     /// ```
-    pub fn verify(&self, mut cookie: Cookie<'static>) -> Option<Cookie<'static>> {
-        if let Ok(value) = self._verify(cookie.value()) {
-            cookie.set_value(value);
-            return Some(cookie);
-        }
-
-        None
+    pub fn with_algorithm(mut self, algorithm: SigningAlgorithm) -> Self {
+        self.signer.algorithm = algorithm;
+        self
     }
-}
 
-impl<J: Borrow<CookieJar>> SignedJar<J> {
-    /// Returns a reference to the `Cookie` inside this jar with the name `name`
-    /// and verifies the authenticity and integrity of the cookie's value,
-    /// returning a `Cookie` with the authenticated value. If the cookie cannot
-    /// be found, or the cookie fails to verify, `None` is returned.
+    /// Sets whether signed values are prefixed with the fingerprint of the key
+    /// that signed them, as returned by [`Key::fingerprint()`], unless the key
+    /// has a key ID. Disabled by default.
+    ///
+    /// Values carrying a fingerprint are verified only with the identified key
+    /// and always verify, with or without this setting, if one of the jar's
+    /// keys matches. When enabled, values carrying an unknown key ID or
+    /// fingerprint fail to verify without any verification being attempted.
     ///
     /// # Example
     ///
@@ -112,22 +588,36 @@ impl<J: Borrow<CookieJar>> SignedJar<J> {
     /// use cookie::{CookieJar, Cookie, Key};
     ///
     /// let key = Key::generate();
-    /// let jar = CookieJar::new();
-    /// assert!(jar.signed(&key).get("name").is_none());
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_mut(&key).with_key_fingerprints(true).add(Cookie::new("name", "value"));
     ///
-    /// let mut jar = jar;
-    /// let mut signed_jar = jar.signed_mut(&key);
-    /// signed_jar.add(Cookie::new("name", "value"));
-    /// assert_eq!(signed_jar.get("name").unwrap().value(), "value");
+    /// let prefix = format!("v2:{}$", key.fingerprint());
+    /// assert!(jar.get("name").unwrap().value().starts_with(&prefix));
+    /// assert_eq!(jar.signed(&key).get("name").unwrap().value(), "value");
     /// ```
-    pub fn get(&self, name: &str) -> Option<Cookie<'static>> {
-        self.parent.borrow().get(name).and_then(|c| self.verify(c.clone()))
+    pub fn with_key_fingerprints(mut self, fingerprints: bool) -> Self {
+        self.signer = self.signer.with_key_fingerprints(fingerprints);
+        self
     }
-}
 
-impl<J: BorrowMut<CookieJar>> SignedJar<J> {
-    /// Adds `cookie` to the parent jar. The cookie's value is signed assuring
-    /// integrity and authenticity.
+    /// Sets whether cookies are signed and verified in the JWS compact
+    /// serialization, `header.payload.signature`, with `HS256` or, with
+    /// [`SigningAlgorithm::HmacSha512`], `HS512`, so that they can be verified
+    /// by standard JWS and JWT libraries with the key's signing key. See the
+    /// [`compat::jws`](crate::compat::jws) module for details. Disabled by
+    /// default.
+    ///
+    /// Of the jar's settings, only the algorithm, key fingerprints, which are
+    /// written as the `kid` header, and the failure hook apply to the JWS
+    /// format. The settings of the native format, name binding, maximum ages
+    /// and expiration timestamps, detached signatures, per-cookie keys,
+    /// attribute binding, contexts, URL-safe encoding, and truncated tags, are
+    /// not supported.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `jws` is `true` and a setting of the native format has been
+    /// enabled.
     ///
     /// # Example
     ///
@@ -136,107 +626,2609 @@ impl<J: BorrowMut<CookieJar>> SignedJar<J> {
     ///
     /// let key = Key::generate();
     /// let mut jar = CookieJar::new();
-    /// jar.signed_mut(&key).add(Cookie::new("name", "value"));
+    /// jar.signed_mut(&key).with_jws_format(true)
+    ///     .add(Cookie::new("token", r#"{"sub":"1234"}"#));
     ///
-    /// assert_ne!(jar.get("name").unwrap().value(), "value");
-    /// assert!(jar.get("name").unwrap().value().contains("value"));
-    /// assert_eq!(jar.signed(&key).get("name").unwrap().value(), "value");
+    /// // `{"alg":"HS256"}`, the payload, and the signature.
+    /// let value = jar.get("token").unwrap().value();
+    /// assert!(value.starts_with("eyJhbGciOiJIUzI1NiJ9.eyJzdWIiOiIxMjM0In0."));
+    ///
+    /// let signed = jar.signed(&key).with_jws_format(true);
+    /// assert_eq!(signed.get("token").unwrap().value(), r#"{"sub":"1234"}"#);
+    /// assert!(jar.signed(&key).get("token").is_none());
     /// ```
-    pub fn add(&mut self, mut cookie: Cookie<'static>) {
-        self.sign_cookie(&mut cookie);
-        self.parent.borrow_mut().add(cookie);
+    pub fn with_jws_format(mut self, jws: bool) -> Self {
+        self.codec = match jws {
+            true => Codec::Jws,
+            false => Codec::Native,
+        };
+
+        self.require_native("with_name_binding", self.bind_name);
+        self.require_native("with_max_age", self.max_age.is_some());
+        self.require_native("with_detached_signatures", self.detached);
+        self.require_native("with_derived_keys", self.derive_keys);
+        self.require_native("with_attribute_binding", self.bind_attributes);
+        self.require_native("with_context", self.context.is_some());
+        self.require_native("with_url_safe_encoding", self.url_safe);
+        self.require_native("with_truncated_tags", self.truncate);
+        self
     }
+}
 
-    /// Adds an "original" `cookie` to this jar. The cookie's value is signed
-    /// assuring integrity and authenticity. Adding an original cookie does not
-    /// affect the [`CookieJar::delta()`] computation. This method is intended
-    /// to be used to seed the cookie jar with cookies received from a client's
-    /// HTTP message.
+impl<J> SignedJar<J, RailsSigner> {
+    /// Creates a new child `SignedJar` with parent `parent` that signs and
+    /// verifies cookies in the format of Ruby on Rails' signed cookies, with
+    /// the key Rails derives from `secret_key_base` and `salt`. Rails uses the
+    /// salt [`rails::SIGNED_COOKIE_SALT`] unless configured otherwise. See
+    /// the [`compat::rails`](crate::compat::rails) module for the supported
+    /// Rails configurations.
     ///
-    /// For accurate `delta` computations, this method should not be called
-    /// after calling `remove`.
+    /// Of the jar's settings, only the failure hook applies to the Rails
+    /// format. Enabling a setting of the native format, such as name binding,
+    /// a maximum age, or a context, or adding a cookie with an expiration
+    /// timestamp, panics.
     ///
     /// # Example
     ///
     /// ```rust
-    /// use cookie::{CookieJar, Cookie, Key};
+    /// use cookie::{CookieJar, Cookie, SignedJar};
+    /// use cookie::compat::rails::SIGNED_COOKIE_SALT;
     ///
-    /// let key = Key::generate();
+    /// let secret_key_base = b"a very long secret key base";
     /// let mut jar = CookieJar::new();
-    /// jar.signed_mut(&key).add_original(Cookie::new("name", "value"));
+    /// SignedJar::rails_compatible(&mut jar, secret_key_base, SIGNED_COOKIE_SALT)
+    ///     .add(Cookie::new("name", "\"value\""));
     ///
-    /// assert_eq!(jar.iter().count(), 1);
-    /// assert_eq!(jar.delta().count(), 0);
+    /// assert!(jar.get("name").unwrap().value().starts_with("InZhbHVlIg%3D%3D--"));
+    ///
+    /// let signed = SignedJar::rails_compatible(&jar, secret_key_base, SIGNED_COOKIE_SALT);
+    /// assert_eq!(signed.get("name").unwrap().value(), "\"value\"");
     /// ```
-    pub fn add_original(&mut self, mut cookie: Cookie<'static>) {
-        self.sign_cookie(&mut cookie);
-        self.parent.borrow_mut().add_original(cookie);
+    pub fn rails_compatible(parent: J, secret_key_base: &[u8], salt: &str) -> Self {
+        let signer = RailsSigner::new(secret_key_base, salt);
+        SignedJar { codec: Codec::Rails, ..SignedJar::with_signer(parent, signer) }
     }
+}
 
-    /// Removes `cookie` from the parent jar.
+impl<J> SignedJar<J, ExpressSigner> {
+    /// Creates a new child `SignedJar` with parent `parent` that signs and
+    /// verifies cookies in the format of Express' signed cookies, as read and
+    /// written by `cookie-parser` with the cookie secret `secret`. See the
+    /// [`compat::express`](crate::compat::express) module for details.
     ///
-    /// For correct removal, the passed in `cookie` must contain the same `path`
-    /// and `domain` as the cookie that was initially set.
-    ///
-    /// This is identical to [`CookieJar::remove()`]. See the method's
-    /// documentation for more details.
+    /// Of the jar's settings, only the failure hook applies to the Express
+    /// format. Enabling a setting of the native format, such as name binding,
+    /// a maximum age, or a context, or adding a cookie with an expiration
+    /// timestamp, panics.
     ///
     /// # Example
     ///
     /// ```rust
-    /// use cookie::{CookieJar, Cookie, Key};
+    /// use cookie::{CookieJar, Cookie, SignedJar};
     ///
-    /// let key = Key::generate();
     /// let mut jar = CookieJar::new();
-    /// let mut signed_jar = jar.signed_mut(&key);
+    /// SignedJar::express_compatible(&mut jar, b"tobiiscool")
+    ///     .add(Cookie::new("name", "hello"));
     ///
-    /// signed_jar.add(Cookie::new("name", "value"));
-    /// assert!(signed_jar.get("name").is_some());
+    /// let value = jar.get("name").unwrap().value();
+    /// assert_eq!(value, "s:hello.DGDUkGlIkCzPz+C0B064FNgHdEjox7ch8tOBGslZ5QI");
     ///
-    /// signed_jar.remove(Cookie::named("name"));
-    /// assert!(signed_jar.get("name").is_none());
+    /// let signed = SignedJar::express_compatible(&jar, b"tobiiscool");
+    /// assert_eq!(signed.get("name").unwrap().value(), "hello");
     /// ```
-    pub fn remove(&mut self, cookie: Cookie<'static>) {
-        self.parent.borrow_mut().remove(cookie);
+    pub fn express_compatible(parent: J, secret: &[u8]) -> Self {
+        let signer = ExpressSigner::new(secret);
+        SignedJar { codec: Codec::Express, ..SignedJar::with_signer(parent, signer) }
     }
 }
 
-#[cfg(test)]
-mod test {
-    use crate::{CookieJar, Cookie, Key};
-
-    #[test]
-    fn simple() {
+#[cfg(feature = "async")]
+impl<J> SignedJar<J, AsyncRemoteKey> {
+    /// Creates a new child `SignedJar` with parent `parent` that signs and
+    /// verifies with the remote key `key`. Cookies are signed and verified with
+    /// [`SignedJar::add_async()`], [`SignedJar::get_async()`], and
+    /// [`SignedJar::verify_async()`].
+    ///
+    /// Per-cookie keys and detached signatures are not supported with remote
+    /// keys: the corresponding settings are ignored and cookies signed with
+    /// per-cookie keys fail to verify.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{AsyncRemoteKey, CookieJar, Cookie, KeyProviderError, SignedJar};
+    ///
+    /// async fn login(jar: &mut CookieJar, key: AsyncRemoteKey) -> Result<(), KeyProviderError> {
+    ///     let mut signed = SignedJar::with_remote_key(jar, key);
+    ///     signed.add_async(Cookie::new("session", "user")).await
+    /// }
+    /// ```
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "async")))]
+    pub fn with_remote_key(parent: J, key: AsyncRemoteKey) -> Self {
+        SignedJar::with_signer(parent, key)
+    }
+
+    /// Signs the cookie's value with the remote key. If `max_age` is set, the
+    /// value is timestamped to expire after `max_age`.
+    async fn sign_cookie_async(
+        &self,
+        cookie: &mut Cookie<'_>,
+        max_age: Option<Duration>
+    ) -> Result<(), KeyProviderError> {
+        let (format, value) = self.unsigned(cookie.value(), max_age, Some(cookie));
+        let format = self.truncation(format, self.signer.tag_len());
+        let mut tag = self.signer.sign(self.message(format, cookie.name(), &value)).await?;
+        if format.flags.contains(Flags::TRUNCATED) {
+            tag.truncate(TRUNCATED_TAG_LEN);
+        }
+
+        cookie.set_value(signed_value(format, self.signer.key_id(), &tag, &value));
+        Ok(())
+    }
+
+    /// Verifies the authenticity and integrity of `cookie` with the remote key,
+    /// returning the plaintext version if verification succeeds or a
+    /// [`VerificationError`] describing why verification failed otherwise. If
+    /// the remote key fails to compute a tag, returns
+    /// [`VerificationError::Unavailable`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{AsyncRemoteKey, Cookie, SignedJar, VerificationError};
+    ///
+    /// async fn check(
+    ///     signed: &SignedJar<(), AsyncRemoteKey>,
+    ///     cookie: Cookie<'static>,
+    /// ) -> Result<String, VerificationError> {
+    ///     let verified = signed.verify_async(cookie).await?;
+    ///     Ok(verified.value().to_string())
+    /// }
+    /// ```
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "async")))]
+    pub async fn verify_async(
+        &self,
+        cookie: Cookie<'static>
+    ) -> Result<Cookie<'static>, VerificationError> {
+        let name = cookie.name().to_string();
+        let result = self.verify_remote(cookie).await;
+        self.report(&name, result)
+    }
+
+    /// Verifies `cookie` with the remote key without reporting failures.
+    async fn verify_remote(
+        &self,
+        mut cookie: Cookie<'static>
+    ) -> Result<Cookie<'static>, VerificationError> {
+        let tag_len = self.signer.tag_len();
+        let parts = self.split_signed(cookie.value(), tag_len)?;
+        if parts.format.flags.contains(Flags::DERIVED) {
+            return Err(VerificationError::UnknownFormat);
+        }
+
+        let expected_len = match parts.format.flags.contains(Flags::TRUNCATED) {
+            true => TRUNCATED_TAG_LEN,
+            false => tag_len,
+        };
+
+        if parts.tag.len() != expected_len {
+            return Err(VerificationError::BadSignature);
+        }
+
+        let message = self.message(parts.format, cookie.name(), parts.value);
+        match self.signer.verify(parts.key_id, message, &parts.tag).await {
+            Ok(true) => {},
+            Ok(false) => return Err(VerificationError::BadSignature),
+            Err(_) => return Err(VerificationError::Unavailable),
+        }
+
+        let (value, attributes) = self.open(parts.format, parts.value)?;
+        cookie.set_value(value);
+        attributes.into_iter().for_each(|a| a.apply(&mut cookie));
+        Ok(cookie)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<J: Borrow<CookieJar>> SignedJar<J, AsyncRemoteKey> {
+    /// Returns a reference to the `Cookie` inside this jar with the name `name`
+    /// and verifies, with the remote key, the authenticity and integrity of the
+    /// cookie's value, returning a `Cookie` with the authenticated value. If
+    /// the cookie cannot be found, or the cookie fails to verify, `None` is
+    /// returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{AsyncRemoteKey, CookieJar, SignedJar};
+    ///
+    /// async fn session(jar: &CookieJar, key: AsyncRemoteKey) -> Option<String> {
+    ///     let signed = SignedJar::with_remote_key(jar, key);
+    ///     signed.get_async("session").await.map(|c| c.value().to_string())
+    /// }
+    /// ```
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "async")))]
+    pub async fn get_async(&self, name: &str) -> Option<Cookie<'static>> {
+        let cookie = self.parent.borrow().get(name)?.clone();
+        self.verify_async(cookie).await.ok()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<J: BorrowMut<CookieJar>> SignedJar<J, AsyncRemoteKey> {
+    /// Adds `cookie` to the parent jar. The cookie's value is signed with the
+    /// remote key assuring integrity and authenticity. If the remote key fails
+    /// to compute a tag, returns its error and the cookie is not added.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{AsyncRemoteKey, CookieJar, Cookie, KeyProviderError, SignedJar};
+    ///
+    /// async fn login(jar: &mut CookieJar, key: AsyncRemoteKey) -> Result<(), KeyProviderError> {
+    ///     let mut signed = SignedJar::with_remote_key(jar, key);
+    ///     signed.add_async(Cookie::new("session", "user")).await
+    /// }
+    /// ```
+    #[cfg_attr(all(nightly, doc), doc(cfg(feature = "async")))]
+    pub async fn add_async(&mut self, mut cookie: Cookie<'static>) -> Result<(), KeyProviderError> {
+        self.sign_cookie_async(&mut cookie, self.max_age).await?;
+        self.parent.borrow_mut().add(cookie);
+        Ok(())
+    }
+}
+
+impl<J, S> SignedJar<J, S> {
+    /// Creates a new child `SignedJar` with parent `parent` that signs and
+    /// verifies with `signer`. This method is typically called indirectly via
+    /// the `signed_with_signer{_mut}` methods of `CookieJar`.
+    pub(crate) fn with_signer(parent: J, signer: S) -> SignedJar<J, S> {
+        SignedJar {
+            parent,
+            signer,
+            bind_name: false,
+            max_age: None,
+            codec: Codec::Native,
+            detached: false,
+            derive_keys: false,
+            bind_attributes: false,
+            context: None,
+            url_safe: false,
+            truncate: false,
+            on_failure: None,
+        }
+    }
+
+    /// Returns a reference to the [`CookieSigner`] used by this jar.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, CookieSigner, Key};
+    ///
+    /// let key = Key::generate();
+    /// let jar = CookieJar::new();
+    /// assert_eq!(jar.signed(&key).signer().tag_len(), 32);
+    /// ```
+    pub fn signer(&self) -> &S {
+        &self.signer
+    }
+
+    /// Sets whether the cookie's name is authenticated along with its value.
+    /// When enabled, a signed value cannot be transplanted from one cookie to
+    /// another cookie with a different name. Disabled by default.
+    ///
+    /// Cookies signed with name binding enabled only verify when name binding
+    /// is enabled, and vice-versa.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bind_name` is `true` and the jar doesn't use the native format.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_mut(&key).with_name_binding(true).add(Cookie::new("name", "value"));
+    ///
+    /// // The signed value verifies for its own name...
+    /// let signed = jar.signed(&key).with_name_binding(true);
+    /// assert_eq!(signed.get("name").unwrap().value(), "value");
+    ///
+    /// // ...but not when moved to a different cookie.
+    /// let value = jar.get("name").unwrap().value().to_string();
+    /// jar.add(Cookie::new("other", value));
+    /// let signed = jar.signed(&key).with_name_binding(true);
+    /// assert!(signed.get("other").is_none());
+    /// ```
+    pub fn with_name_binding(mut self, bind_name: bool) -> Self {
+        self.require_native("with_name_binding", bind_name);
+        self.bind_name = bind_name;
+        self
+    }
+
+    /// Sets the maximum age of cookies signed and verified by this jar to
+    /// `max_age`. Unset by default.
+    ///
+    /// When set, cookies added to the jar are signed along with the time they
+    /// were issued and an expiration timestamp `max_age` from then, as if by
+    /// [`SignedJar::add_with_expiry()`]. Cookies issued more than `max_age`
+    /// ago fail to verify, whatever expiration they were signed with, as do
+    /// cookies without timestamps, including all cookies signed before a max
+    /// age was set. Independently of this setting, cookies with an expiration
+    /// timestamp that has passed always fail to verify.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the jar doesn't use the native format.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    /// use cookie::time::Duration;
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_mut(&key).add(Cookie::new("untimed", "value"));
+    /// jar.signed_mut(&key)
+    ///     .with_max_age(Duration::hours(1))
+    ///     .add(Cookie::new("timed", "value"));
+    ///
+    /// let signed = jar.signed(&key).with_max_age(Duration::hours(1));
+    /// assert_eq!(signed.get("timed").unwrap().value(), "value");
+    /// assert!(signed.get("untimed").is_none());
+    ///
+    /// // A long expiration chosen when signing doesn't extend the max age.
+    /// jar.signed_mut(&key).add_with_expiry(Cookie::new("long", "value"), Duration::days(3650));
+    /// assert!(jar.signed(&key).get("long").is_some());
+    /// assert!(jar.signed(&key).with_max_age(Duration::seconds(-1)).get("long").is_none());
+    /// ```
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.require_native("with_max_age", true);
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Sets whether signatures are stored in a sibling cookie instead of in
+    /// the cookie's value. Disabled by default.
+    ///
+    /// When enabled, the cookie's value is stored unmodified and its signature
+    /// is stored in a cookie with the same attributes named `{name}.sig`.
+    /// [`SignedJar::get()`] and [`SignedJar::get_result()`] read both
+    /// cookies, and [`SignedJar::remove()`] removes both. Because the cookies
+    /// are separate, enabling [name binding](SignedJar::with_name_binding())
+    /// is recommended.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `detached` is `true` and the jar doesn't use the native format.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_mut(&key)
+    ///     .with_detached_signatures(true)
+    ///     .add(Cookie::new("name", "value"));
+    ///
+    /// assert_eq!(jar.get("name").unwrap().value(), "value");
+    /// assert!(jar.get("name.sig").is_some());
+    ///
+    /// let signed = jar.signed(&key).with_detached_signatures(true);
+    /// assert_eq!(signed.get("name").unwrap().value(), "value");
+    /// ```
+    pub fn with_detached_signatures(mut self, detached: bool) -> Self {
+        self.require_native("with_detached_signatures", detached);
+        self.detached = detached;
+        self
+    }
+
+    /// Sets whether cookies are signed with per-cookie keys. Disabled by
+    /// default.
+    ///
+    /// When enabled, each cookie is signed with keys derived, via HKDF, from
+    /// the jar's keys and the cookie's name. A signature for one cookie thus
+    /// never verifies for another, even with name binding disabled, and an
+    /// oracle that signs values for one cookie cannot be used to forge values
+    /// for another. Cookies signed with per-cookie keys are marked as such and
+    /// verify regardless of this setting, so the setting may be enabled once
+    /// all servers understand the marked values. When enabled, cookies not
+    /// signed with per-cookie keys fail to verify.
+    ///
+    /// The setting only takes effect if the jar's [`CookieSigner`] supports
+    /// [deriving keys](CookieSigner::derive()), as [`HmacSigner`] does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `derive_keys` is `true` and the jar doesn't use the native format.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_mut(&key).with_derived_keys(true).add(Cookie::new("name", "value"));
+    ///
+    /// let signed = jar.signed(&key).with_derived_keys(true);
+    /// assert_eq!(signed.get("name").unwrap().value(), "value");
+    ///
+    /// // The value doesn't verify when moved to a different cookie.
+    /// let value = jar.get("name").unwrap().value().to_string();
+    /// jar.add(Cookie::new("other", value));
+    /// assert!(jar.signed(&key).get("other").is_none());
+    /// ```
+    pub fn with_derived_keys(mut self, derive_keys: bool) -> Self {
+        self.require_native("with_derived_keys", derive_keys);
+        self.derive_keys = derive_keys;
+        self
+    }
+
+    /// Sets whether the cookie's `Path`, `Domain`, and `Expires` attributes are
+    /// authenticated along with its value. Disabled by default.
+    ///
+    /// Browsers only send a cookie's name and value back to the server, so a
+    /// client, or an attacker able to set cookies for the site, can replay a
+    /// signed cookie with attributes other than those it was issued with, for
+    /// instance taking a cookie scoped to `/admin` and setting it for `/`. When
+    /// enabled, the attributes are embedded in the signed value, and cookies
+    /// returned by [`SignedJar::get()`] and friends carry the authenticated
+    /// attributes instead of those presented, and fail to verify with
+    /// [`VerificationError::Expired`] once the authenticated `Expires` date has
+    /// passed. Applications should compare the authenticated path and domain
+    /// against the request. When enabled, cookies signed without bound
+    /// attributes fail to verify. Cookies signed with bound attributes have
+    /// them restored regardless of this setting.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bind_attributes` is `true` and the jar doesn't use the native format.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_mut(&key)
+    ///     .with_attribute_binding(true)
+    ///     .add(Cookie::build("name", "value").path("/admin").finish());
+    ///
+    /// // The cookie is presented without its attributes, or with others...
+    /// let presented = Cookie::new("name", jar.get("name").unwrap().value().to_string());
+    ///
+    /// // ...but verifies with the attributes it was signed with.
+    /// let signed = jar.signed(&key).with_attribute_binding(true);
+    /// let verified = signed.verify(presented).unwrap();
+    /// assert_eq!(verified.value(), "value");
+    /// assert_eq!(verified.path(), Some("/admin"));
+    /// ```
+    pub fn with_attribute_binding(mut self, bind_attributes: bool) -> Self {
+        self.require_native("with_attribute_binding", bind_attributes);
+        self.bind_attributes = bind_attributes;
+        self
+    }
+
+    /// Sets the context cookies are bound to. Cookies signed with a context
+    /// only verify with the same context, and cookies signed without a context
+    /// fail to verify when one is set.
+    ///
+    /// The context is authenticated along with the cookie's value but isn't
+    /// stored in the cookie. It can be any data the application can reproduce
+    /// when verifying the cookie: a hash of the client's user agent, a TLS
+    /// channel binding, or the application's version, for instance. Cookies
+    /// replayed outside of their original context then fail to verify.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the jar doesn't use the native format.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_mut(&key).with_context(b"app v1").add(Cookie::new("name", "value"));
+    ///
+    /// assert!(jar.signed(&key).with_context(b"app v1").get("name").is_some());
+    /// assert!(jar.signed(&key).with_context(b"app v2").get("name").is_none());
+    /// assert!(jar.signed(&key).get("name").is_none());
+    /// ```
+    pub fn with_context(mut self, context: &[u8]) -> Self {
+        self.require_native("with_context", true);
+        self.context = Some(context.to_vec());
+        self
+    }
+
+    /// Sets whether signatures are encoded as URL-safe, unpadded Base64 instead
+    /// of standard, padded Base64. Disabled by default.
+    ///
+    /// Values signed with this setting enabled contain no `=`, `+`, or `/`
+    /// characters outside of the original value, which some proxies and log
+    /// pipelines mangle. Such values are marked as such and verify regardless
+    /// of this setting, as do values in the standard encoding.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `url_safe` is `true` and the jar doesn't use the native format.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_mut(&key).with_url_safe_encoding(true).add(Cookie::new("name", "value"));
+    ///
+    /// assert!(jar.get("name").unwrap().value().starts_with("v2u:"));
+    /// assert_eq!(jar.signed(&key).get("name").unwrap().value(), "value");
+    /// ```
+    pub fn with_url_safe_encoding(mut self, url_safe: bool) -> Self {
+        self.require_native("with_url_safe_encoding", url_safe);
+        self.url_safe = url_safe;
+        self
+    }
+
+    /// Sets whether signatures are truncated to 16 bytes (128 bits). Disabled
+    /// by default.
+    ///
+    /// A full HMAC-SHA256 signature takes 44 characters of every cookie value;
+    /// a truncated one takes 24. A 128-bit tag remains far beyond the reach of
+    /// forgery by guessing, but it is shorter than the tag of the configured
+    /// algorithm, so this setting should only be enabled when cookie size
+    /// matters. Values with truncated signatures are marked as such and verify
+    /// regardless of this setting, as do values with full signatures. The
+    /// setting has no effect if the signer's tags are no longer than 16 bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `truncate` is `true` and the jar doesn't use the native format.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_mut(&key).with_truncated_tags(true).add(Cookie::new("name", "value"));
+    ///
+    /// let signed = jar.get("name").unwrap().value();
+    /// assert_eq!(signed.len(), "v2s:".len() + 24 + "value".len());
+    /// assert_eq!(jar.signed(&key).get("name").unwrap().value(), "value");
+    /// ```
+    pub fn with_truncated_tags(mut self, truncate: bool) -> Self {
+        self.require_native("with_truncated_tags", truncate);
+        self.truncate = truncate;
+        self
+    }
+
+    /// Sets `hook` to be called with the name of every cookie that fails to
+    /// verify in this jar and the reason why, for instance to count or alert
+    /// on tampering attempts. The hook is called by every method that verifies
+    /// a cookie, including [`SignedJar::get()`], [`SignedJar::verify()`], and
+    /// [`SignedJar::get_all_failures()`], but not for cookies that are missing
+    /// altogether. No hook is set by default.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use cookie::{CookieJar, Cookie, Key, VerificationError};
+    ///
+    /// static TAMPERED: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// fn on_failure(_name: &str, error: VerificationError) {
+    ///     if error == VerificationError::BadSignature {
+    ///         TAMPERED.fetch_add(1, Ordering::Relaxed);
+    ///     }
+    /// }
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_mut(&key).add(Cookie::new("name", "value"));
+    ///
+    /// let signed = jar.signed(&Key::generate()).on_failure(on_failure);
+    /// assert!(signed.get("name").is_none());
+    /// assert!(signed.get("missing").is_none());
+    /// assert_eq!(TAMPERED.load(Ordering::Relaxed), 1);
+    /// ```
+    pub fn on_failure(mut self, hook: fn(&str, VerificationError)) -> Self {
+        self.on_failure = Some(hook);
+        self
+    }
+
+    /// Calls the failure hook, if any, if `result`, the result of verifying
+    /// the cookie named `name`, is an error. Returns `result`.
+    fn report<T>(
+        &self,
+        name: &str,
+        result: Result<T, VerificationError>
+    ) -> Result<T, VerificationError> {
+        if let (Err(error), Some(hook)) = (&result, self.on_failure) {
+            hook(name, *error);
+        }
+
+        result
+    }
+
+    /// Returns `format` with the truncation flag set if tags, `tag_len` bytes
+    /// long, are to be truncated.
+    fn truncation(&self, format: Format, tag_len: usize) -> Format {
+        match self.truncate && tag_len > TRUNCATED_TAG_LEN {
+            true => format.with(Flags::TRUNCATED),
+            false => format,
+        }
+    }
+
+    /// Returns `true` if signatures are stored in sibling cookies.
+    fn is_detached(&self) -> bool {
+        self.detached && self.codec == Codec::Native
+    }
+
+    /// Panics, naming the setting `method`, if `enabled` and the jar doesn't
+    /// use the native format, which is the only one to support the setting.
+    fn require_native(&self, method: &str, enabled: bool) {
+        if enabled && self.codec != Codec::Native {
+            let format = self.codec.name();
+            panic!("`SignedJar::{}()` is not supported by the {} format", method, format);
+        }
+    }
+
+    /// Returns the message that is authenticated for a cookie named `name`
+    /// with value `value` in the format `format`.
+    fn message(&self, format: Format, name: &str, value: &str) -> Vec<u8> {
+        let mut message = format.authenticated();
+        if self.bind_name {
+            // Length-prefix the name so that name/value boundaries are unique.
+            message.extend_from_slice(&(name.len() as u64).to_be_bytes());
+            message.extend_from_slice(name.as_bytes());
+        }
+
+        if format.flags.contains(Flags::CONTEXT) {
+            let context = self.context.as_deref().unwrap_or(&[]);
+            message.extend_from_slice(&(context.len() as u64).to_be_bytes());
+            message.extend_from_slice(context);
+        }
+
+        message.extend_from_slice(value.as_bytes());
+        message
+    }
+
+    /// Returns the format of, and the value to sign for, `value` in the native
+    /// format: `value` prefixed with the attributes of `attributes` if
+    /// attributes are bound, and then with issue and expiration timestamps if
+    /// `max_age` is set.
+    fn unsigned(
+        &self,
+        value: &str,
+        max_age: Option<Duration>,
+        attributes: Option<&Cookie>
+    ) -> (Format, String) {
+        let format = match self.context {
+            Some(_) => Format::CURRENT.with(Flags::CONTEXT),
+            None => Format::CURRENT,
+        };
+
+        let format = match self.url_safe {
+            true => format.with(Flags::URL_SAFE),
+            false => format,
+        };
+
+        let (format, value) = match attributes.filter(|_| self.bind_attributes) {
+            Some(cookie) => {
+                let value = format!("{}{}{}", BoundAttributes::of(cookie).encode(),
+                    TIMESTAMP_SEPARATOR, value);
+
+                (format.with(Flags::ATTRIBUTES), value)
+            }
+            None => (format, value.to_string()),
+        };
+
+        match max_age {
+            Some(max_age) => {
+                let issued = OffsetDateTime::now_utc().unix_timestamp();
+                let expires = issued.saturating_add(max_age.whole_seconds());
+                let value = format!("{}{sep}{}{sep}{}", issued, expires, value,
+                    sep = TIMESTAMP_SEPARATOR);
+
+                (format.with(Flags::TIMESTAMPED), value)
+            }
+            None => (format, value),
+        }
+    }
+
+    /// Splits `cookie_value`, a value in the native format signed with tags of
+    /// length `tag_len`, into its parts.
+    fn split_signed<'v>(
+        &self,
+        cookie_value: &'v str,
+        tag_len: usize
+    ) -> Result<SignedParts<'v>, VerificationError> {
+        let (format, cookie_value) = Format::split(cookie_value)
+            .map_err(|_| VerificationError::UnknownFormat)?;
+
+        // A value signed with a context only verifies with one, and vice-versa.
+        if format.flags.contains(Flags::CONTEXT) != self.context.is_some() {
+            return Err(VerificationError::BadSignature);
+        }
+
+        let (key_id, cookie_value) = split_key_id(cookie_value);
+        // The length of the Base64 encoding of the (truncated) tag.
+        let tag_len = match format.flags.contains(Flags::TRUNCATED) {
+            true if tag_len <= TRUNCATED_TAG_LEN => return Err(VerificationError::UnknownFormat),
+            true => TRUNCATED_TAG_LEN,
+            false => tag_len,
+        };
+
+        let digest_len = format.encoded_len(tag_len);
+        if !cookie_value.is_char_boundary(digest_len) {
+            return Err(VerificationError::MissingDigest);
+        }
+
+        // Split [MAC | original-value] into its two parts.
+        let (digest_str, value) = cookie_value.split_at(digest_len);
+        let tag = format.decode(digest_str).map_err(|_| VerificationError::BadBase64)?;
+        Ok(SignedParts { format, key_id, tag, value })
+    }
+
+    /// Checks and strips the issue and expiration timestamps and bound
+    /// attributes, if any, from `value`, the verified value of a value in the
+    /// format `format`, returning the original value and the bound attributes.
+    fn open(
+        &self,
+        format: Format,
+        value: &str
+    ) -> Result<(String, Option<BoundAttributes>), VerificationError> {
+        let value = match format.flags.contains(Flags::TIMESTAMPED) {
+            true => {
+                let mut parts = value.splitn(3, TIMESTAMP_SEPARATOR);
+                let timestamp = |part: Option<&str>| part.and_then(|t| t.parse::<i64>().ok());
+                let (issued, expires) = timestamp(parts.next()).zip(timestamp(parts.next()))
+                    .ok_or(VerificationError::MissingTimestamp)?;
+
+                let value = parts.next().ok_or(VerificationError::MissingTimestamp)?;
+                let now = OffsetDateTime::now_utc().unix_timestamp();
+                if now > expires {
+                    return Err(VerificationError::Expired);
+                }
+
+                // The verifier's max age applies regardless of the expiration.
+                let age = now.saturating_sub(issued);
+                if matches!(self.max_age, Some(max_age) if age > max_age.whole_seconds()) {
+                    return Err(VerificationError::Expired);
+                }
+
+                value
+            }
+            false if self.max_age.is_some() => return Err(VerificationError::MissingTimestamp),
+            false => value,
+        };
+
+        if !format.flags.contains(Flags::ATTRIBUTES) {
+            return match self.bind_attributes {
+                true => Err(VerificationError::BadSignature),
+                false => Ok((value.to_string(), None)),
+            };
+        }
+
+        let (attributes, value) = value.split_once(TIMESTAMP_SEPARATOR)
+            .and_then(|(attributes, value)| Some((BoundAttributes::decode(attributes)?, value)))
+            .ok_or(VerificationError::BadBase64)?;
+
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        if matches!(attributes.expires, Some(expires) if now > expires) {
+            return Err(VerificationError::Expired);
+        }
+
+        Ok((value.to_string(), Some(attributes)))
+    }
+}
+
+impl<J, S: CookieSigner> SignedJar<J, S> {
+    /// Signs the cookie's value providing integrity and authenticity. If
+    /// `max_age` is set, the value is timestamped to expire after `max_age`.
+    fn sign_cookie(&self, cookie: &mut Cookie, max_age: Option<Duration>) {
+        let value = self.sign(cookie.name(), cookie.value(), max_age, Some(cookie));
+        cookie.set_value(value);
+    }
+
+    /// Returns `value`, the value of the cookie named `name`, signed. If
+    /// `max_age` is set, the value is timestamped to expire after `max_age`. If
+    /// attributes are bound, the attributes of `attributes` are signed too.
+    pub(crate) fn sign(
+        &self,
+        name: &str,
+        value: &str,
+        max_age: Option<Duration>,
+        attributes: Option<&Cookie>
+    ) -> String {
+        self.require_native("add_with_expiry", max_age.is_some());
+        match self.codec {
+            Codec::Native => {},
+            Codec::Rails => return rails::sign(&self.signer, value),
+            Codec::Express => return express::sign(&self.signer, value),
+            Codec::Jws => return jws::sign(&self.signer, value),
+        }
+
+        // Prefix the value with the expiration timestamp, if any.
+        let (format, value) = self.unsigned(value, max_age, attributes);
+
+        // Sign with the cookie's own keys, if enabled and supported.
+        let derived = match self.derive_keys {
+            true => self.signer.derive(name),
+            false => None,
+        };
+
+        let (format, signer) = match derived {
+            Some(ref signer) => (format.with(Flags::DERIVED), signer),
+            None => (format, &self.signer),
+        };
+
+        // Compute the MAC of the (timestamped) value, truncated if enabled.
+        let format = self.truncation(format, signer.tag_len());
+        let mut tag = signer.sign(&self.message(format, name, &value));
+        if format.flags.contains(Flags::TRUNCATED) {
+            tag.truncate(TRUNCATED_TAG_LEN);
+        }
+
+        signed_value(format, signer.key_id(), &tag, &value)
+    }
+
+    /// Given a signed value `str` where the signature is prepended to `value`,
+    /// verifies the signed value of the cookie named `name` and returns it. If
+    /// there's a problem, returns an `Err` describing the issue.
+    pub(crate) fn _verify(
+        &self,
+        name: &str,
+        cookie_value: &str
+    ) -> Result<String, VerificationError> {
+        self.verify_bound(name, cookie_value).map(|(value, _)| value)
+    }
+
+    /// Like [`SignedJar::_verify()`], but additionally returns the bound
+    /// attributes, if any.
+    fn verify_bound(
+        &self,
+        name: &str,
+        cookie_value: &str
+    ) -> Result<(String, Option<BoundAttributes>), VerificationError> {
+        match self.codec {
+            Codec::Native => {},
+            Codec::Rails => return rails::verify(&self.signer, cookie_value).map(|v| (v, None)),
+            Codec::Express => return express::verify(&self.signer, cookie_value).map(|v| (v, None)),
+            Codec::Jws => return jws::verify(&self.signer, cookie_value).map(|v| (v, None)),
+        }
+
+        let tag_len = self.signer.tag_len();
+        let SignedParts { format, key_id, tag, value } = self.split_signed(cookie_value, tag_len)?;
+
+        // Use the cookie's own keys if the value was signed with them. If the
+        // jar requires them, reject values that weren't.
+        let derived = match format.flags.contains(Flags::DERIVED) || self.derive_keys {
+            true => self.signer.derive(name),
+            false => None,
+        };
+
+        let signer = match (format.flags.contains(Flags::DERIVED), derived.as_ref()) {
+            (true, Some(signer)) => signer,
+            (true, None) => return Err(VerificationError::UnknownFormat),
+            (false, Some(_)) => return Err(VerificationError::BadSignature),
+            (false, None) => &self.signer,
+        };
+
+        // Perform the verification with the identified key, if any.
+        let message = self.message(format, name, value);
+        let verified = match (format.flags.contains(Flags::TRUNCATED), key_id) {
+            (true, _) if tag.len() != TRUNCATED_TAG_LEN => false,
+            (true, key_id) => signer.verify_truncated(key_id, &message, &tag),
+            (false, Some(key_id)) => signer.verify_with_key_id(key_id, &message, &tag),
+            (false, None) => signer.verify(&message, &tag),
+        };
+
+        if !verified {
+            return Err(VerificationError::BadSignature);
+        }
+
+        // Check and strip the timestamp and attributes, now authenticated.
+        self.open(format, value)
+    }
+
+    /// Returns `cookie_value`, a verified value in the native format of the
+    /// cookie named `name`, signed with the signer's current key, or `None` if
+    /// it already is or isn't in the native format. The format, including any
+    /// timestamp and bound attributes, is preserved.
+    fn resigned(&self, name: &str, cookie_value: &str) -> Option<String> {
+        use subtle::ConstantTimeEq;
+
+        if self.codec != Codec::Native {
+            return None;
+        }
+
+        let parts = self.split_signed(cookie_value, self.signer.tag_len()).ok()?;
+        let derived = match parts.format.flags.contains(Flags::DERIVED) {
+            true => Some(self.signer.derive(name)?),
+            false => None,
+        };
+
+        let signer = derived.as_ref().unwrap_or(&self.signer);
+        let mut tag = signer.sign(&self.message(parts.format, name, parts.value));
+        if parts.format.flags.contains(Flags::TRUNCATED) {
+            tag.truncate(TRUNCATED_TAG_LEN);
+        }
+
+        let resigned = signed_value(parts.format, signer.key_id(), &tag, parts.value);
+        match bool::from(resigned.as_bytes().ct_eq(cookie_value.as_bytes())) {
+            true => None,
+            false => Some(resigned),
+        }
+    }
+
+    /// Verifies the authenticity and integrity of `cookie`, returning the
+    /// plaintext version if verification succeeds or `None` otherwise.
+    /// Verification _always_ succeeds if `cookie` was generated by a
+    /// `SignedJar` with the same key as `self` or with any of its fallback
+    /// keys.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// assert!(jar.signed(&key).get("name").is_none());
+    ///
+    /// jar.signed_mut(&key).add(Cookie::new("name", "value"));
+    /// assert_eq!(jar.signed(&key).get("name").unwrap().value(), "value");
+    ///
+    /// let plain = jar.get("name").cloned().unwrap();
+    /// assert_ne!(plain.value(), "value");
+    /// let verified = jar.signed(&key).verify(plain).unwrap();
+    /// assert_eq!(verified.value(), "value");
+    ///
+    /// let plain = Cookie::new("plaintext", "hello");
+    /// assert!(jar.signed(&key).verify(plain).is_none());
+    /// ```
+    pub fn verify(&self, cookie: Cookie<'static>) -> Option<Cookie<'static>> {
+        self.verify_result(cookie).ok()
+    }
+
+    /// Verifies the authenticity and integrity of `cookie`, returning the
+    /// plaintext version if verification succeeds or a [`VerificationError`]
+    /// describing why verification failed otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key, VerificationError};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_mut(&key).add(Cookie::new("name", "value"));
+    ///
+    /// let signed = jar.get("name").cloned().unwrap();
+    /// let verified = jar.signed(&key).verify_result(signed).unwrap();
+    /// assert_eq!(verified.value(), "value");
+    ///
+    /// let plain = Cookie::new("plaintext", "hello");
+    /// let error = jar.signed(&key).verify_result(plain).unwrap_err();
+    /// assert_eq!(error, VerificationError::MissingDigest);
+    /// ```
+    pub fn verify_result(
+        &self,
+        mut cookie: Cookie<'static>
+    ) -> Result<Cookie<'static>, VerificationError> {
+        let verified = self.verify_bound(cookie.name(), cookie.value());
+        let (value, attributes) = self.report(cookie.name(), verified)?;
+        cookie.set_value(value);
+        attributes.into_iter().for_each(|a| a.apply(&mut cookie));
+        Ok(cookie)
+    }
+}
+
+impl<J: Borrow<CookieJar>, S: CookieSigner> SignedJar<J, S> {
+    /// Returns a reference to the `Cookie` inside this jar with the name `name`
+    /// and verifies the authenticity and integrity of the cookie's value,
+    /// returning a `Cookie` with the authenticated value. If the cookie cannot
+    /// be found, or the cookie fails to verify, `None` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let jar = CookieJar::new();
+    /// assert!(jar.signed(&key).get("name").is_none());
+    ///
+    /// let mut jar = jar;
+    /// let mut signed_jar = jar.signed_mut(&key);
+    /// signed_jar.add(Cookie::new("name", "value"));
+    /// assert_eq!(signed_jar.get("name").unwrap().value(), "value");
+    /// ```
+    pub fn get(&self, name: &str) -> Option<Cookie<'static>> {
+        self.get_result(name).ok()
+    }
+
+    /// Returns a reference to the `Cookie` inside this jar with the name `name`
+    /// and verifies the authenticity and integrity of the cookie's value,
+    /// returning a `Cookie` with the authenticated value. If the cookie cannot
+    /// be found, returns [`VerificationError::NotFound`]. If the cookie fails
+    /// to verify, returns a [`VerificationError`] describing why.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key, VerificationError};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// assert_eq!(jar.signed(&key).get_result("name"), Err(VerificationError::NotFound));
+    ///
+    /// jar.signed_mut(&key).add(Cookie::new("name", "value"));
+    /// assert_eq!(jar.signed(&key).get_result("name").unwrap().value(), "value");
+    ///
+    /// let error = jar.signed(&Key::generate()).get_result("name").unwrap_err();
+    /// assert_eq!(error, VerificationError::BadSignature);
+    /// ```
+    pub fn get_result(&self, name: &str) -> Result<Cookie<'static>, VerificationError> {
+        let parent = self.parent.borrow();
+        let cookie = parent.get(name).ok_or(VerificationError::NotFound)?;
+        self.verify_in(parent, cookie)
+    }
+
+    /// Returns the binary value of the `Cookie` inside this jar with the name
+    /// `name`, as added by [`SignedJar::add_bytes()`], after verifying its
+    /// authenticity and integrity. If the cookie cannot be found, fails to
+    /// verify, or isn't valid unpadded, URL-safe Base64, `None` is returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_mut(&key).add_bytes(Cookie::named("blob"), &[0, 159, 146, 150]);
+    ///
+    /// assert_eq!(jar.signed(&key).get_bytes("blob").unwrap(), [0, 159, 146, 150]);
+    /// assert_eq!(jar.signed(&key).get("blob").unwrap().value(), "AJ-Slg");
+    /// ```
+    pub fn get_bytes(&self, name: &str) -> Option<Vec<u8>> {
+        let cookie = self.get(name)?;
+        base64::decode_config(cookie.value(), URL_SAFE_NO_PAD).ok()
+    }
+
+    /// Returns the `Cookie` inside this jar with the name `name`, verifying
+    /// the authenticity and integrity of its value, or, if it fails to verify,
+    /// the cookie as is. If the cookie cannot be found, `None` is returned.
+    ///
+    /// This method is intended for staged rollouts of signing, where cookies
+    /// set before the rollout must keep working for a while: the returned
+    /// [`MaybeVerified`] distinguishes verified cookies from unverified ones,
+    /// whose values are unauthenticated and may have been set or modified by
+    /// the client. Failures are reported to the [failure
+    /// hook](SignedJar::on_failure()), if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key, MaybeVerified};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.add(Cookie::new("legacy", "value"));
+    /// jar.signed_mut(&key).add(Cookie::new("signed", "value"));
+    ///
+    /// let signed = jar.signed(&key);
+    /// let legacy = signed.get_or_plaintext("legacy").unwrap();
+    /// assert_eq!(legacy, MaybeVerified::Unverified(Cookie::new("legacy", "value")));
+    ///
+    /// let verified = signed.get_or_plaintext("signed").unwrap();
+    /// assert_eq!(verified.verified().unwrap().value(), "value");
+    /// assert!(signed.get_or_plaintext("missing").is_none());
+    /// ```
+    pub fn get_or_plaintext(&self, name: &str) -> Option<MaybeVerified> {
+        match self.get_result(name) {
+            Ok(cookie) => Some(MaybeVerified::Verified(cookie)),
+            Err(VerificationError::NotFound) => None,
+            Err(_) => {
+                let cookie = self.parent.borrow().get(name)?.clone();
+                Some(MaybeVerified::Unverified(cookie))
+            }
+        }
+    }
+
+    /// Verifies the seal named `name`, as added by [`SignedJar::add_seal()`],
+    /// against the cookies in the parent jar named in `names`, in any order.
+    /// Returns `Ok` if the seal verifies and every cookie in the set has the
+    /// value it was sealed with, or is still absent. If the seal cannot be
+    /// found, returns [`VerificationError::NotFound`]; if the cookies differ,
+    /// returns [`VerificationError::BadSignature`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key, VerificationError};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_mut(&key).add(Cookie::new("a", "1"));
+    /// jar.signed_mut(&key).add(Cookie::new("b", "2"));
+    /// jar.signed_mut(&key).add_seal(Cookie::named("seal"), &["a", "b"]);
+    ///
+    /// // Replace `b` with another validly signed value.
+    /// let old = jar.get("b").cloned().unwrap();
+    /// jar.signed_mut(&key).add(Cookie::new("b", "3"));
+    /// let error = jar.signed(&key).verify_seal("seal", &["a", "b"]).unwrap_err();
+    /// assert_eq!(error, VerificationError::BadSignature);
+    ///
+    /// jar.add(old);
+    /// assert_eq!(jar.signed(&key).verify_seal("seal", &["a", "b"]), Ok(()));
+    /// assert_eq!(jar.signed(&key).verify_seal("none", &["a"]), Err(VerificationError::NotFound));
+    /// ```
+    pub fn verify_seal(&self, name: &str, names: &[&str]) -> Result<(), VerificationError> {
+        use subtle::ConstantTimeEq;
+
+        let parent = self.parent.borrow();
+        let seal = parent.get(name).ok_or(VerificationError::NotFound)?;
+        let digest = self.report(name, self._verify(name, seal.value()))?;
+        let expected = seal_digest(parent, name, names);
+        match bool::from(digest.as_bytes().ct_eq(expected.as_bytes())) {
+            true => Ok(()),
+            false => self.report(name, Err(VerificationError::BadSignature)),
+        }
+    }
+
+    /// Returns an iterator over every cookie in the parent jar that verifies,
+    /// with its authenticated value, in no particular order. Cookies that fail
+    /// to verify are skipped; see [`SignedJar::get_all_failures()`]. When
+    /// signatures are detached, the cookies holding signatures are not
+    /// themselves returned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_mut(&key).add(Cookie::new("a", "1"));
+    /// jar.signed_mut(&key).add(Cookie::new("b", "2"));
+    /// jar.add(Cookie::new("plain", "3"));
+    ///
+    /// let mut verified: Vec<_> = jar.signed(&key).get_all_verified()
+    ///     .map(|c| (c.name().to_string(), c.value().to_string()))
+    ///     .collect();
+    ///
+    /// verified.sort();
+    /// assert_eq!(verified, [("a".into(), "1".into()), ("b".into(), "2".into())]);
+    /// ```
+    pub fn get_all_verified(&self) -> impl Iterator<Item = Cookie<'static>> + '_ {
+        self.verify_all().filter_map(|(_, result)| result.ok())
+    }
+
+    /// Returns an iterator over the name of every cookie in the parent jar
+    /// that fails to verify along with the reason why, in no particular order.
+    /// This is the complement of [`SignedJar::get_all_verified()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key, VerificationError};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_mut(&key).add(Cookie::new("a", "1"));
+    /// jar.add(Cookie::new("plain", "3"));
+    ///
+    /// let signed = jar.signed(&key);
+    /// let failures: Vec<_> = signed.get_all_failures().collect();
+    /// assert_eq!(failures, [("plain", VerificationError::MissingDigest)]);
+    /// ```
+    pub fn get_all_failures(&self) -> impl Iterator<Item = (&str, VerificationError)> + '_ {
+        self.verify_all().filter_map(|(cookie, result)| Some((cookie.name(), result.err()?)))
+    }
+
+    /// Verifies every cookie in the parent jar, skipping, when signatures are
+    /// detached, the signature cookies of other cookies in the jar.
+    fn verify_all(&self) -> impl Iterator<Item = (&Cookie<'static>, Verified)> + '_ {
+        let parent = self.parent.borrow();
+        let is_signature = move |c: &Cookie<'_>| match c.name().strip_suffix(SIGNATURE_SUFFIX) {
+            Some(name) => self.is_detached() && parent.get(name).is_some(),
+            None => false,
+        };
+
+        parent.iter()
+            .filter(move |c| !is_signature(c))
+            .map(move |cookie| (cookie, self.verify_in(parent, cookie)))
+    }
+
+    /// Verifies `cookie`, a cookie in `parent`, reattaching its signature from
+    /// `parent` if signatures are detached.
+    fn verify_in(&self, parent: &CookieJar, cookie: &Cookie<'static>) -> Verified {
+        if !self.is_detached() {
+            return self.verify_result(cookie.clone());
+        }
+
+        let name = cookie.name();
+
+        // Reattach the signature, a prefix of a signed value, to the value.
+        let signature = parent.get(&format!("{}{}", name, SIGNATURE_SUFFIX))
+            .ok_or(VerificationError::MissingDigest);
+
+        let signature = self.report(name, signature)?;
+        let mut signed = cookie.clone();
+        signed.set_value(format!("{}{}", signature.value(), cookie.value()));
+        self.verify_result(signed)
+    }
+}
+
+impl<J: BorrowMut<CookieJar>, S: CookieSigner> SignedJar<J, S> {
+    /// Adds `cookie` to the parent jar. The cookie's value is signed assuring
+    /// integrity and authenticity.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_mut(&key).add(Cookie::new("name", "value"));
+    ///
+    /// assert_ne!(jar.get("name").unwrap().value(), "value");
+    /// assert!(jar.get("name").unwrap().value().contains("value"));
+    /// assert_eq!(jar.signed(&key).get("name").unwrap().value(), "value");
+    /// ```
+    pub fn add(&mut self, cookie: Cookie<'static>) {
+        self.add_signed(cookie, self.max_age, false);
+    }
+
+    /// Returns the cookie inside this jar with the name `name`, verified as if
+    /// by [`SignedJar::get()`]. If the cookie verified with a key other than
+    /// the one the jar signs with, such as a fallback key or a key retired from
+    /// the primary position of a [`Keyring`], it is additionally re-signed with
+    /// the current key and added to the parent jar, so that it appears in the
+    /// [`CookieJar::delta()`]. Cookies signed with old keys thus converge to
+    /// the current key as clients present them, and the old key can be dropped
+    /// once they have.
+    ///
+    /// The re-signed value keeps the original's expiration timestamp, bound
+    /// attributes, and other format options; the cookie keeps the attributes
+    /// it has in the parent jar. Only the native format is re-signed. Stale
+    /// values are detected by re-signing them, so a signer whose tags aren't
+    /// deterministic re-signs every cookie.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let (old, new) = (Key::generate(), Key::generate());
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_mut(&old).add_original(Cookie::new("name", "value"));
+    ///
+    /// let mut signed = jar.signed_with_keys_mut(&[new.clone(), old]);
+    /// assert_eq!(signed.get_and_resign("name").unwrap().value(), "value");
+    ///
+    /// assert_eq!(jar.delta().count(), 1);
+    /// assert_eq!(jar.signed(&new).get("name").unwrap().value(), "value");
+    /// ```
+    pub fn get_and_resign(&mut self, name: &str) -> Option<Cookie<'static>> {
+        let parent = self.parent.borrow();
+        let cookie = parent.get(name)?;
+        let verified = self.verify_in(parent, cookie).ok()?;
+
+        // The cookie holding the signature and the full signed value.
+        let (mut target, signed) = match self.is_detached() {
+            true => {
+                let signature = parent.get(&format!("{}{}", name, SIGNATURE_SUFFIX))?;
+                (signature.clone(), format!("{}{}", signature.value(), cookie.value()))
+            }
+            false => (cookie.clone(), cookie.value().to_string()),
+        };
+
+        if let Some(resigned) = self.resigned(name, &signed) {
+            let detached_len = signed.len() - target.value().len();
+            target.set_value(resigned[..(resigned.len() - detached_len)].to_string());
+            self.parent.borrow_mut().add(target);
+        }
+
+        Some(verified)
+    }
+
+    /// Signs `cookie`, timestamping it to expire after `max_age` if it is set,
+    /// and adds it to the parent jar, as an "original" cookie if `original`.
+    /// If signatures are detached, also adds the signature cookie.
+    fn add_signed(
+        &mut self,
+        mut cookie: Cookie<'static>,
+        max_age: Option<Duration>,
+        original: bool
+    ) {
+        let value = cookie.value().to_string();
+        self.sign_cookie(&mut cookie, max_age);
+
+        let mut cookies = vec![];
+        if self.is_detached() {
+            // The native format ends with the original value: split it off.
+            let signed = cookie.value();
+            let signature = signed[..(signed.len() - value.len())].to_string();
+            let mut signature_cookie = cookie.clone();
+            signature_cookie.set_name(format!("{}{}", cookie.name(), SIGNATURE_SUFFIX));
+            signature_cookie.set_value(signature);
+            cookie.set_value(value);
+            cookies.push(signature_cookie);
+        }
+
+        cookies.push(cookie);
+        let parent = self.parent.borrow_mut();
+        for cookie in cookies {
+            match original {
+                true => parent.add_original(cookie),
+                false => parent.add(cookie),
+            }
+        }
+    }
+
+    /// Adds `cookie` to the parent jar with the binary value `bytes`, which
+    /// need not be valid UTF-8, in place of the cookie's value. The bytes are
+    /// encoded as unpadded, URL-safe Base64, the encoding being the value that
+    /// is signed, assuring integrity and authenticity. The value can be
+    /// retrieved, decoded, with [`SignedJar::get_bytes()`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// let session: Vec<u8> = (0..=255).collect();
+    /// jar.signed_mut(&key).add_bytes(Cookie::named("session"), &session);
+    ///
+    /// assert_eq!(jar.signed(&key).get_bytes("session").unwrap(), session);
+    /// ```
+    pub fn add_bytes(&mut self, mut cookie: Cookie<'static>, bytes: &[u8]) {
+        cookie.set_value(base64::encode_config(bytes, URL_SAFE_NO_PAD));
+        self.add(cookie);
+    }
+
+    /// Adds `seal` to the parent jar, its value replaced by a signature over
+    /// the names and values of the cookies in the parent jar named in `names`,
+    /// in any order, including the absence of any of them. The set can then
+    /// be verified as a whole with [`SignedJar::verify_seal()`], detecting the
+    /// deletion or replacement of individual cookies, which per-cookie
+    /// signatures cannot. The seal must be added again whenever a cookie in
+    /// the set changes.
+    ///
+    /// The values covered are those in the parent jar, as they are sent by the
+    /// client: the cookies need not be in this jar. The seal itself is never
+    /// covered. If a maximum age is set with [`SignedJar::with_max_age()`],
+    /// the seal expires after it, limiting the replay of entire sets.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_mut(&key).add(Cookie::new("user", "alice"));
+    /// jar.add(Cookie::new("theme", "dark"));
+    /// jar.signed_mut(&key).add_seal(Cookie::named("seal"), &["user", "theme"]);
+    /// assert!(jar.signed(&key).verify_seal("seal", &["theme", "user"]).is_ok());
+    ///
+    /// jar.remove(Cookie::named("theme"));
+    /// assert!(jar.signed(&key).verify_seal("seal", &["theme", "user"]).is_err());
+    /// ```
+    pub fn add_seal(&mut self, mut seal: Cookie<'static>, names: &[&str]) {
+        let digest = seal_digest(self.parent.borrow(), seal.name(), names);
+        let value = self.sign(seal.name(), &digest, self.max_age, None);
+        seal.set_value(value);
+        self.parent.borrow_mut().add(seal);
+    }
+
+    /// Adds `cookie` to the parent jar. The cookie's value is signed, assuring
+    /// integrity and authenticity, along with an expiration timestamp
+    /// `duration` from now. Once the timestamp has passed, the cookie no longer
+    /// verifies, regardless of any expiration the client enforces. The
+    /// cookie's `Expires` and `Max-Age` attributes are not modified.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    /// use cookie::time::Duration;
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// let mut signed = jar.signed_mut(&key);
+    /// signed.add_with_expiry(Cookie::new("fresh", "value"), Duration::minutes(5));
+    /// signed.add_with_expiry(Cookie::new("stale", "value"), Duration::minutes(-5));
+    ///
+    /// assert_eq!(jar.signed(&key).get("fresh").unwrap().value(), "value");
+    /// assert!(jar.signed(&key).get("stale").is_none());
+    /// ```
+    pub fn add_with_expiry(&mut self, cookie: Cookie<'static>, duration: Duration) {
+        self.add_signed(cookie, Some(duration), false);
+    }
+
+    /// Adds an "original" `cookie` to this jar. The cookie's value is signed
+    /// assuring integrity and authenticity. Adding an original cookie does not
+    /// affect the [`CookieJar::delta()`] computation. This method is intended
+    /// to be used to seed the cookie jar with cookies received from a client's
+    /// HTTP message.
+    ///
+    /// For accurate `delta` computations, this method should not be called
+    /// after calling `remove`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// jar.signed_mut(&key).add_original(Cookie::new("name", "value"));
+    ///
+    /// assert_eq!(jar.iter().count(), 1);
+    /// assert_eq!(jar.delta().count(), 0);
+    /// ```
+    pub fn add_original(&mut self, cookie: Cookie<'static>) {
+        self.add_signed(cookie, self.max_age, true);
+    }
+
+    /// Removes `cookie` from the parent jar.
+    ///
+    /// For correct removal, the passed in `cookie` must contain the same `path`
+    /// and `domain` as the cookie that was initially set.
+    ///
+    /// This is identical to [`CookieJar::remove()`]. See the method's
+    /// documentation for more details.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{CookieJar, Cookie, Key};
+    ///
+    /// let key = Key::generate();
+    /// let mut jar = CookieJar::new();
+    /// let mut signed_jar = jar.signed_mut(&key);
+    ///
+    /// signed_jar.add(Cookie::new("name", "value"));
+    /// assert!(signed_jar.get("name").is_some());
+    ///
+    /// signed_jar.remove(Cookie::named("name"));
+    /// assert!(signed_jar.get("name").is_none());
+    /// ```
+    pub fn remove(&mut self, cookie: Cookie<'static>) {
+        if self.is_detached() {
+            let mut signature_cookie = cookie.clone();
+            signature_cookie.set_name(format!("{}{}", cookie.name(), SIGNATURE_SUFFIX));
+            self.parent.borrow_mut().remove(signature_cookie);
+        }
+
+        self.parent.borrow_mut().remove(cookie);
+    }
+}
+
+/// The parts of a value in the native format.
+struct SignedParts<'v> {
+    format: Format,
+    key_id: Option<&'v str>,
+    /// The decoded tag.
+    tag: Vec<u8>,
+    /// The signed, possibly timestamped, value.
+    value: &'v str,
+}
+
+/// The result of verifying a cookie.
+type Verified = Result<Cookie<'static>, VerificationError>;
+
+/// The attributes of a cookie authenticated along with its value.
+struct BoundAttributes {
+    path: Option<String>,
+    domain: Option<String>,
+    /// The `Expires` date as a Unix timestamp.
+    expires: Option<i64>,
+}
+
+impl BoundAttributes {
+    /// Returns the bindable attributes of `cookie`.
+    fn of(cookie: &Cookie) -> BoundAttributes {
+        BoundAttributes {
+            path: cookie.path().map(|p| p.to_string()),
+            domain: cookie.domain().map(|d| d.to_string()),
+            expires: cookie.expires_datetime().map(|e| e.unix_timestamp()),
+        }
+    }
+
+    /// Encodes the attributes, separated by `ATTRIBUTE_SEPARATOR`, as URL-safe
+    /// Base64. An absent attribute is encoded as an empty field.
+    fn encode(&self) -> String {
+        let expires = self.expires.map(|e| e.to_string());
+        let fields = [self.path.as_deref(), self.domain.as_deref(), expires.as_deref()];
+        let fields: Vec<_> = fields.iter().map(|f| f.unwrap_or("")).collect();
+        let separator = ATTRIBUTE_SEPARATOR.to_string();
+        base64::encode_config(fields.join(&separator), URL_SAFE_NO_PAD)
+    }
+
+    /// Decodes attributes encoded with [`BoundAttributes::encode()`].
+    fn decode(encoded: &str) -> Option<BoundAttributes> {
+        let decoded = base64::decode_config(encoded, URL_SAFE_NO_PAD).ok()?;
+        let decoded = String::from_utf8(decoded).ok()?;
+        let mut fields = decoded.split(ATTRIBUTE_SEPARATOR)
+            .map(|f| Some(f.to_string()).filter(|f| !f.is_empty()));
+
+        let (path, domain, expires) = (fields.next()?, fields.next()?, fields.next()?);
+        if fields.next().is_some() {
+            return None;
+        }
+
+        let expires = match expires {
+            Some(expires) => Some(expires.parse().ok()?),
+            None => None,
+        };
+
+        Some(BoundAttributes { path, domain, expires })
+    }
+
+    /// Replaces the bindable attributes of `cookie` with `self`.
+    fn apply(self, cookie: &mut Cookie) {
+        match self.path {
+            Some(path) => cookie.set_path(path),
+            None => cookie.unset_path(),
+        }
+
+        match self.domain {
+            Some(domain) => cookie.set_domain(domain),
+            None => cookie.unset_domain(),
+        }
+
+        let expires = self.expires.and_then(|e| OffsetDateTime::from_unix_timestamp(e).ok());
+        match expires {
+            Some(expires) => cookie.set_expires(expires),
+            None => cookie.unset_expires(),
+        }
+    }
+}
+
+/// Returns the signed value [format: | key-id$ | MAC | issued.expires. | value] in
+/// the native format for `value`, the (timestamped) value signed with `tag`.
+fn signed_value(format: Format, key_id: Option<&str>, tag: &[u8], value: &str) -> String {
+    let mut new_value = format.encode(tag);
+    new_value.push_str(value);
+    format.tagged(with_key_id(key_id, new_value))
+}
+
+/// Returns the digest signed by the seal named `seal` over the cookies in `jar`
+/// named in `names`: the URL-safe Base64 SHA-256 hash of each distinct name
+/// other than `seal`, in sorted order, each with the value of the cookie of
+/// that name in `jar`, if any. Every field is length-prefixed.
+fn seal_digest(jar: &CookieJar, seal: &str, names: &[&str]) -> String {
+    use sha2::Digest;
+
+    let mut names: Vec<&str> = names.iter().copied().filter(|name| *name != seal).collect();
+    names.sort_unstable();
+    names.dedup();
+
+    let mut hash = Sha256::new();
+    for name in names {
+        hash.update((name.len() as u64).to_be_bytes());
+        hash.update(name);
+        match jar.get(name) {
+            Some(cookie) => {
+                hash.update([1]);
+                hash.update((cookie.value().len() as u64).to_be_bytes());
+                hash.update(cookie.value());
+            }
+            None => hash.update([0]),
+        }
+    }
+
+    base64::encode_config(hash.finalize(), URL_SAFE_NO_PAD)
+}
+
+/// Signs `value` with `key`, providing integrity and authenticity, and returns
+/// the signed value.
+///
+/// This is the signing performed by a [`SignedJar`] with the default settings,
+/// without a cookie or a jar, for values stored elsewhere, such as in query
+/// parameters or headers. The signed value can be verified by
+/// [`verify_value()`] as well as by a `SignedJar` as the value of a cookie
+/// with any name.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{Key, sign_value, verify_value};
+///
+/// let key = Key::generate();
+/// let signed = sign_value(&key, "value");
+/// assert_ne!(signed, "value");
+/// assert_eq!(verify_value(&key, &signed).unwrap(), "value");
+/// ```
+#[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+pub fn sign_value(key: &Key, value: &str) -> String {
+    SignedJar::new((), key).sign("", value, None, None)
+}
+
+/// Verifies `value`, a value signed by [`sign_value()`] or by a [`SignedJar`]
+/// with the default settings, with `key`. Returns the original value if
+/// verification succeeds or a [`VerificationError`] describing why
+/// verification failed otherwise.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{Key, VerificationError, sign_value, verify_value};
+///
+/// let key = Key::generate();
+/// let signed = sign_value(&key, "value");
+/// assert_eq!(verify_value(&key, &signed).unwrap(), "value");
+///
+/// let error = verify_value(&Key::generate(), &signed).unwrap_err();
+/// assert_eq!(error, VerificationError::BadSignature);
+/// ```
+#[cfg_attr(all(nightly, doc), doc(cfg(feature = "signed")))]
+pub fn verify_value(key: &Key, value: &str) -> Result<String, VerificationError> {
+    SignedJar::new((), key)._verify("", value)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{CookieJar, Cookie, Key};
+
+    #[test]
+    fn simple() {
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        assert_simple_behaviour!(jar, jar.signed_mut(&key));
+    }
+
+    #[test]
+    fn private() {
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        assert_secure_behaviour!(jar, jar.signed_mut(&key));
+    }
+
+    #[test]
+    fn roundtrip() {
+        // Secret is SHA-256 hash of 'Super secret!' passed through HKDF-SHA256.
+        let key = Key::from(&[89, 202, 200, 125, 230, 90, 197, 245, 166, 249,
+            34, 169, 135, 31, 20, 197, 94, 154, 254, 79, 60, 26, 8, 143, 254,
+            24, 116, 138, 92, 225, 159, 60, 157, 41, 135, 129, 31, 226, 196, 16,
+            198, 168, 134, 4, 42, 1, 196, 24, 57, 103, 241, 147, 201, 185, 233,
+            10, 180, 170, 187, 89, 252, 137, 110, 107]);
+
+        let mut jar = CookieJar::new();
+        jar.add(Cookie::new("signed_with_ring014",
+                "3tdHXEQ2kf6fxC7dWzBGmpSLMtJenXLKrZ9cHkSsl1w=Tamper-proof"));
+        jar.add(Cookie::new("signed_with_ring016",
+                "3tdHXEQ2kf6fxC7dWzBGmpSLMtJenXLKrZ9cHkSsl1w=Tamper-proof"));
+
+        let signed = jar.signed(&key);
+        assert_eq!(signed.get("signed_with_ring014").unwrap().value(), "Tamper-proof");
+        assert_eq!(signed.get("signed_with_ring016").unwrap().value(), "Tamper-proof");
+    }
+
+    #[test]
+    fn rotation() {
+        let (old, new) = (Key::generate(), Key::generate());
+        let mut jar = CookieJar::new();
+        jar.signed_mut(&old).add(Cookie::new("old", "value"));
+
+        // The old cookie verifies with the fallback key; new cookies are signed
+        // with the primary key.
+        let mut signed = jar.signed_with_keys_mut(&[new.clone(), old.clone()]);
+        assert_eq!(signed.get("old").unwrap().value(), "value");
+        signed.add(Cookie::new("new", "value"));
+        assert_eq!(jar.signed(&new).get("new").unwrap().value(), "value");
+        assert!(jar.signed(&old).get("new").is_none());
+
+        // Once the old key is dropped, the old cookie no longer verifies.
+        assert!(jar.signed(&new).get("old").is_none());
+        assert!(jar.signed_with_keys(&[new, Key::generate()]).get("old").is_none());
+    }
+
+    #[test]
+    fn foreign_format_settings() {
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use time::Duration;
+        use crate::SignedJar;
+
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        let panics = |f: &dyn Fn()| catch_unwind(AssertUnwindSafe(f)).is_err();
+
+        // Settings the other formats don't support are rejected, in any order.
+        assert!(panics(&|| drop(jar.signed(&key).with_jws_format(true).with_context(b"c"))));
+        assert!(panics(&|| drop(jar.signed(&key).with_context(b"c").with_jws_format(true))));
+        assert!(panics(&|| drop(jar.signed(&key).with_name_binding(true).with_jws_format(true))));
+        assert!(panics(&|| {
+            SignedJar::rails_compatible(&jar, b"secret", "salt").with_attribute_binding(true);
+        }));
+        assert!(panics(&|| {
+            SignedJar::express_compatible(&jar, b"secret").with_max_age(Duration::hours(1));
+        }));
+
+        let mut express = SignedJar::express_compatible(&mut jar, b"secret");
+        let expiring = AssertUnwindSafe(|| {
+            express.add_with_expiry(Cookie::new("name", "value"), Duration::hours(1));
+        });
+        assert!(catch_unwind(expiring).is_err());
+
+        // Disabled settings and the settings the formats support are accepted.
+        let signed = jar.signed(&key).with_jws_format(true).with_key_fingerprints(true)
+            .with_name_binding(false).with_truncated_tags(false);
+        assert!(signed.get("name").is_none());
+        SignedJar::rails_compatible(&jar, b"secret", "salt").with_detached_signatures(false);
+    }
+
+    #[test]
+    fn jws_format() {
+        use crate::{Keyring, SigningAlgorithm};
+
         let key = Key::generate();
         let mut jar = CookieJar::new();
-        assert_simple_behaviour!(jar, jar.signed_mut(&key));
+        jar.signed_mut(&key).with_jws_format(true).add(Cookie::new("token", "value"));
+
+        let signed = jar.signed(&key).with_jws_format(true);
+        assert_eq!(signed.get("token").unwrap().value(), "value");
+        assert!(jar.signed(&Key::generate()).with_jws_format(true).get("token").is_none());
+
+        // The signature is HMAC-SHA256 of the header and payload with the
+        // signing key.
+        let value = jar.get("token").unwrap().value().to_string();
+        let (input, signature) = value.rsplit_once('.').unwrap();
+        let tag = SigningAlgorithm::HmacSha256.sign(key.signing(), &[input.as_bytes()]);
+        assert_eq!(signature, base64::encode_config(tag, base64::URL_SAFE_NO_PAD));
+
+        // HS512 is used with HMAC-SHA512, and values are not interchangeable.
+        jar.signed_mut(&key).with_jws_format(true).with_algorithm(SigningAlgorithm::HmacSha512)
+            .add(Cookie::new("long", "value"));
+
+        assert!(jar.get("long").unwrap().value().starts_with("eyJhbGciOiJIUzUxMiJ9."));
+        let sha512 = jar.signed(&key).with_jws_format(true)
+            .with_algorithm(SigningAlgorithm::HmacSha512);
+        assert_eq!(sha512.get("long").unwrap().value(), "value");
+        assert!(sha512.get("token").is_none());
+        assert!(jar.signed(&key).with_jws_format(true).get("long").is_none());
+
+        // Key IDs are carried in the header.
+        let mut keyring = Keyring::new("k1", Key::generate());
+        jar.signed_with_keyring_mut(&keyring).with_jws_format(true)
+            .add(Cookie::new("ring", "value"));
+
+        let header = jar.get("ring").unwrap().value().split('.').next().unwrap().to_string();
+        let header = base64::decode_config(header, base64::URL_SAFE_NO_PAD).unwrap();
+        assert_eq!(header, br#"{"alg":"HS256","kid":"k1"}"#);
+
+        keyring.add("k2", Key::generate());
+        let ring = jar.signed_with_keyring(&keyring).with_jws_format(true);
+        assert_eq!(ring.get("ring").unwrap().value(), "value");
     }
 
     #[test]
-    fn private() {
+    fn failure_hook() {
+        use std::cell::RefCell;
+        use crate::VerificationError::{self, *};
+
+        thread_local! {
+            static FAILURES: RefCell<Vec<(String, VerificationError)>> = RefCell::default();
+        }
+
+        fn record(name: &str, error: VerificationError) {
+            FAILURES.with(|f| f.borrow_mut().push((name.into(), error)));
+        }
+
+        let failures = || FAILURES.with(|f| f.borrow_mut().split_off(0));
+        let failure = |name: &str, error| vec![(name.to_string(), error)];
+
         let key = Key::generate();
         let mut jar = CookieJar::new();
-        assert_secure_behaviour!(jar, jar.signed_mut(&key));
+        jar.signed_mut(&key).add(Cookie::new("signed", "value"));
+        jar.add(Cookie::new("plain", "value"));
+
+        let signed = jar.signed(&key).on_failure(record);
+        assert!(signed.get("signed").is_some());
+        assert!(signed.get("missing").is_none());
+        assert_eq!(failures(), []);
+
+        assert!(signed.get("plain").is_none());
+        assert_eq!(failures(), failure("plain", MissingDigest));
+        assert!(signed.verify(Cookie::new("other", "value")).is_none());
+        assert_eq!(failures(), failure("other", MissingDigest));
+        assert_eq!(signed.get_all_failures().count(), 1);
+        assert_eq!(failures(), failure("plain", MissingDigest));
+
+        let signed = jar.signed(&Key::generate()).on_failure(record);
+        assert!(signed.get_bytes("signed").is_none());
+        assert_eq!(failures(), failure("signed", BadSignature));
+        assert!(jar.signed(&Key::generate()).get("signed").is_none());
+        assert_eq!(failures(), []);
+
+        // Missing detached signatures are reported once.
+        let mut jar = CookieJar::new();
+        jar.add(Cookie::new("name", "value"));
+        let signed = jar.signed(&key).with_detached_signatures(true).on_failure(record);
+        assert!(signed.get("name").is_none());
+        assert_eq!(failures(), failure("name", MissingDigest));
     }
 
     #[test]
-    fn roundtrip() {
-        // Secret is SHA-256 hash of 'Super secret!' passed through HKDF-SHA256.
-        let key = Key::from(&[89, 202, 200, 125, 230, 90, 197, 245, 166, 249,
-            34, 169, 135, 31, 20, 197, 94, 154, 254, 79, 60, 26, 8, 143, 254,
-            24, 116, 138, 92, 225, 159, 60, 157, 41, 135, 129, 31, 226, 196, 16,
-            198, 168, 134, 4, 42, 1, 196, 24, 57, 103, 241, 147, 201, 185, 233,
-            10, 180, 170, 187, 89, 252, 137, 110, 107]);
+    fn plaintext_fallback() {
+        use crate::MaybeVerified::*;
+
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        jar.add(Cookie::new("plain", "value"));
+        jar.signed_mut(&key).add(Cookie::new("signed", "value"));
+        let raw = jar.get("signed").cloned().unwrap();
+
+        let signed = jar.signed(&key);
+        let cookie = Cookie::new("plain", "value");
+        assert_eq!(signed.get_or_plaintext("plain"), Some(Unverified(cookie)));
+        let cookie = Cookie::new("signed", "value");
+        assert_eq!(signed.get_or_plaintext("signed"), Some(Verified(cookie)));
+        assert_eq!(signed.get_or_plaintext("missing"), None);
+
+        // Values that fail to verify are returned as is, never as verified.
+        let other = jar.signed(&Key::generate());
+        assert_eq!(other.get_or_plaintext("signed"), Some(Unverified(raw)));
 
         let mut jar = CookieJar::new();
-        jar.add(Cookie::new("signed_with_ring014",
-                "3tdHXEQ2kf6fxC7dWzBGmpSLMtJenXLKrZ9cHkSsl1w=Tamper-proof"));
-        jar.add(Cookie::new("signed_with_ring016",
-                "3tdHXEQ2kf6fxC7dWzBGmpSLMtJenXLKrZ9cHkSsl1w=Tamper-proof"));
+        jar.signed_mut(&key).with_detached_signatures(true).add(Cookie::new("name", "value"));
+        let detached = jar.signed(&key).with_detached_signatures(true);
+        assert!(detached.get_or_plaintext("name").unwrap().is_verified());
+        jar.remove(Cookie::named("name.sig"));
+        let detached = jar.signed(&key).with_detached_signatures(true);
+        let cookie = Cookie::new("name", "value");
+        assert_eq!(detached.get_or_plaintext("name"), Some(Unverified(cookie)));
+    }
+
+    #[test]
+    fn seals() {
+        use time::Duration;
+        use crate::VerificationError::*;
+
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        jar.signed_mut(&key).add(Cookie::new("a", "1"));
+        jar.add(Cookie::new("b", "2"));
+
+        // Absent cookies are sealed too; the seal itself and repeats are ignored.
+        let names = ["a", "b", "c"];
+        jar.signed_mut(&key).add_seal(Cookie::named("seal"), &["c", "seal", "b", "a", "a"]);
+        assert_eq!(jar.signed(&key).verify_seal("seal", &names), Ok(()));
+        assert_eq!(jar.signed(&key).verify_seal("seal", &["a", "b"]), Err(BadSignature));
+        assert_eq!(jar.signed(&key).verify_seal("seal", &["b", "c"]), Err(BadSignature));
+        assert_eq!(jar.signed(&Key::generate()).verify_seal("seal", &names), Err(BadSignature));
+
+        jar.add(Cookie::new("c", "3"));
+        assert_eq!(jar.signed(&key).verify_seal("seal", &names), Err(BadSignature));
+        jar.remove(Cookie::named("c"));
+        assert_eq!(jar.signed(&key).verify_seal("seal", &names), Ok(()));
+        jar.remove(Cookie::named("a"));
+        assert_eq!(jar.signed(&key).verify_seal("seal", &names), Err(BadSignature));
+
+        // Fields are length-prefixed: values can't shift between cookies.
+        let mut jar = CookieJar::new();
+        jar.add(Cookie::new("a", "1b"));
+        jar.add(Cookie::new("b", ""));
+        jar.signed_mut(&key).add_seal(Cookie::named("seal"), &["a", "b"]);
+        jar.add(Cookie::new("a", "1"));
+        jar.add(Cookie::new("b", "b"));
+        assert_eq!(jar.signed(&key).verify_seal("seal", &["a", "b"]), Err(BadSignature));
+
+        // Seals are signed as values of the seal cookie, honoring the jar's settings.
+        let mut jar = CookieJar::new();
+        jar.signed_mut(&key).with_max_age(Duration::seconds(-1))
+            .add_seal(Cookie::named("seal"), &["a"]);
+        assert_eq!(jar.signed(&key).verify_seal("seal", &["a"]), Err(Expired));
+
+        jar.signed_mut(&key).with_name_binding(true).add_seal(Cookie::named("seal"), &["a"]);
+        let bound = jar.signed(&key).with_name_binding(true);
+        assert_eq!(bound.verify_seal("seal", &["a"]), Ok(()));
+        jar.add(Cookie::new("other", jar.get("seal").unwrap().value().to_string()));
+        let bound = jar.signed(&key).with_name_binding(true);
+        assert_eq!(bound.verify_seal("other", &["a"]), Err(BadSignature));
+        assert_eq!(bound.verify_seal("missing", &["a"]), Err(NotFound));
+    }
+
+    #[test]
+    fn binary_values() {
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        let blob: Vec<u8> = (0..=255).rev().collect();
+        jar.signed_mut(&key).add_bytes(Cookie::build("blob", "").path("/").finish(), &blob);
+        jar.signed_mut(&key).with_detached_signatures(true)
+            .add_bytes(Cookie::named("detached"), &blob);
+        jar.signed_mut(&key).add_bytes(Cookie::named("empty"), &[]);
+        jar.signed_mut(&key).add(Cookie::new("text", "not base64!"));
+
+        let signed = jar.get("blob").unwrap();
+        assert_eq!(signed.path(), Some("/"));
+        // The 256 bytes are encoded into 342, following the 44 byte digest.
+        assert_eq!(signed.value().len(), "v2:".len() + 44 + 342);
 
         let signed = jar.signed(&key);
-        assert_eq!(signed.get("signed_with_ring014").unwrap().value(), "Tamper-proof");
-        assert_eq!(signed.get("signed_with_ring016").unwrap().value(), "Tamper-proof");
+        assert_eq!(signed.get_bytes("blob").unwrap(), blob);
+        assert_eq!(signed.get_bytes("empty").unwrap(), b"");
+        assert!(signed.get_bytes("text").is_none());
+        assert!(signed.get_bytes("missing").is_none());
+        assert!(jar.signed(&Key::generate()).get_bytes("blob").is_none());
+
+        let detached = jar.signed(&key).with_detached_signatures(true);
+        assert_eq!(detached.get_bytes("detached").unwrap(), blob);
+    }
+
+    #[test]
+    fn resigning() {
+        use time::Duration;
+
+        let (old, new) = (Key::generate(), Key::generate());
+        let keys = [new.clone(), old.clone()];
+        let mut jar = CookieJar::new();
+
+        jar.signed_mut(&old).with_name_binding(true).add_original(Cookie::new("old", "value"));
+        jar.signed_mut(&old).with_name_binding(true).with_max_age(Duration::minutes(5))
+            .add_original(Cookie::new("timed", "value"));
+
+        jar.signed_mut(&new).with_name_binding(true).add_original(Cookie::new("new", "value"));
+        jar.add_original(Cookie::new("plain", "value"));
+
+        // The expiration timestamp follows the 4 byte tag and 44 byte digest.
+        let expires = |jar: &CookieJar| jar.get("timed").unwrap().value()[48..58].to_string();
+        let timestamp = expires(&jar);
+
+        // Cookies signed with the current key, or that don't verify, are left.
+        let mut signed = jar.signed_with_keys_mut(&keys).with_name_binding(true);
+        assert_eq!(signed.get_and_resign("new").unwrap().value(), "value");
+        assert!(signed.get_and_resign("plain").is_none());
+        assert!(signed.get_and_resign("missing").is_none());
+        assert_eq!(jar.delta().count(), 0);
+
+        // Cookies signed with old keys are re-signed, keeping their timestamp.
+        let mut signed = jar.signed_with_keys_mut(&keys).with_name_binding(true);
+        assert_eq!(signed.get_and_resign("old").unwrap().value(), "value");
+        assert_eq!(signed.get_and_resign("timed").unwrap().value(), "value");
+        assert_eq!(jar.delta().count(), 2);
+
+        let signed = jar.signed(&new).with_name_binding(true);
+        assert_eq!(signed.get("old").unwrap().value(), "value");
+        assert_eq!(signed.get("timed").unwrap().value(), "value");
+        assert_eq!(expires(&jar), timestamp);
+
+        let mut signed = jar.signed_with_keys_mut(&keys).with_name_binding(true);
+        assert!(signed.get_and_resign("old").is_some());
+        assert_eq!(jar.delta().count(), 2);
+
+        // Keyring key IDs and detached signatures are updated.
+        let mut keyring = crate::Keyring::new("k1", old);
+        jar.signed_with_keyring_mut(&keyring).with_detached_signatures(true)
+            .add_original(Cookie::new("ring", "value"));
+
+        keyring.add("k2", new);
+        let mut signed = jar.signed_with_keyring_mut(&keyring).with_detached_signatures(true);
+        assert_eq!(signed.get_and_resign("ring").unwrap().value(), "value");
+        assert!(jar.get("ring.sig").unwrap().value().starts_with("v2:k2$"));
+        assert_eq!(jar.get("ring").unwrap().value(), "value");
+
+        keyring.retire("k1");
+        let signed = jar.signed_with_keyring(&keyring).with_detached_signatures(true);
+        assert_eq!(signed.get("ring").unwrap().value(), "value");
+    }
+
+    #[test]
+    fn keyring() {
+        let mut keyring = crate::Keyring::new("k1", Key::generate());
+        let mut jar = CookieJar::new();
+        jar.signed_with_keyring_mut(&keyring).add(Cookie::new("one", "value"));
+        assert!(jar.get("one").unwrap().value().starts_with("v2:k1$"));
+
+        keyring.add("k2", Key::generate());
+        jar.signed_with_keyring_mut(&keyring).add(Cookie::new("two", "value"));
+        assert!(jar.get("two").unwrap().value().starts_with("v2:k2$"));
+
+        let signed = jar.signed_with_keyring(&keyring);
+        assert_eq!(signed.get("one").unwrap().value(), "value");
+        assert_eq!(signed.get("two").unwrap().value(), "value");
+
+        // Only the identified key is used for verification.
+        let value = jar.get("two").unwrap().value().replacen("v2:k2$", "v2:k1$", 1);
+        jar.add(Cookie::new("two", value));
+        assert!(jar.signed_with_keyring(&keyring).get("two").is_none());
+
+        // Values without a key ID are verified with all keys.
+        jar.signed_mut(keyring.get("k1").unwrap()).add(Cookie::new("three", "value"));
+        assert_eq!(jar.signed_with_keyring(&keyring).get("three").unwrap().value(), "value");
+
+        // Plain jars ignore the key ID.
+        let key = keyring.get("k1").unwrap();
+        assert_eq!(jar.signed(key).get("one").unwrap().value(), "value");
+
+        keyring.retire("k1");
+        assert!(jar.signed_with_keyring(&keyring).get("one").is_none());
+    }
+
+    #[test]
+    fn algorithms() {
+        use crate::SigningAlgorithm::*;
+
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        jar.signed_mut(&key).with_algorithm(HmacSha512).add(Cookie::new("512", "value"));
+        jar.signed_mut(&key).with_algorithm(HmacSha256).add(Cookie::new("256", "value"));
+        assert_eq!(jar.get("512").unwrap().value().len(), "v2:".len() + 88 + "value".len());
+        assert_eq!(jar.get("256").unwrap().value().len(), "v2:".len() + 44 + "value".len());
+
+        let signed = jar.signed(&key).with_algorithm(HmacSha512);
+        assert_eq!(signed.get("512").unwrap().value(), "value");
+        assert!(signed.get("256").is_none());
+
+        let signed = jar.signed(&key);
+        assert_eq!(signed.get("256").unwrap().value(), "value");
+        assert!(signed.get("512").is_none());
+    }
+
+    #[test]
+    fn name_binding() {
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        jar.signed_mut(&key).with_name_binding(true).add(Cookie::new("bound", "value"));
+        jar.signed_mut(&key).add(Cookie::new("unbound", "value"));
+
+        let bound = jar.signed(&key).with_name_binding(true);
+        assert_eq!(bound.get("bound").unwrap().value(), "value");
+        assert!(bound.get("unbound").is_none());
+        assert!(jar.signed(&key).get("bound").is_none());
+
+        // Swapping values between cookies is detected only for bound values.
+        let bound_value = jar.get("bound").unwrap().value().to_string();
+        let unbound_value = jar.get("unbound").unwrap().value().to_string();
+        jar.add(Cookie::new("bound_copy", bound_value));
+        jar.add(Cookie::new("unbound_copy", unbound_value));
+        assert!(jar.signed(&key).with_name_binding(true).get("bound_copy").is_none());
+        assert_eq!(jar.signed(&key).get("unbound_copy").unwrap().value(), "value");
+    }
+
+    #[test]
+    fn versioned() {
+        use super::{base64, keyed_mac, Hmac, Mac, Sha256};
+
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        jar.signed_mut(&key).add(Cookie::new("name", "value"));
+
+        // New values are tagged with the current version.
+        let signed_value = jar.get("name").unwrap().value().to_string();
+        assert!(signed_value.starts_with("v2:"));
+
+        // The tag is authenticated: stripping it or changing it fails.
+        jar.add(Cookie::new("untagged", signed_value["v2:".len()..].to_string()));
+        jar.add(Cookie::new("v3", signed_value.replacen("v2:", "v3:", 1)));
+        assert!(jar.signed(&key).get("untagged").is_none());
+        assert!(jar.signed(&key).get("v3").is_none());
+
+        // Moving the tag into the value doesn't turn it into a legacy value
+        // whose message is the tag and the rest of the value.
+        let timed = jar.signed(&key).sign("timed", "value", Some(time::Duration::days(-1)), None);
+        let (digest, rest) = timed["v2t:".len()..].split_at(44);
+        jar.add(Cookie::new("retagged", format!("{}v2t:{}", digest, rest)));
+        assert!(jar.signed(&key).get("retagged").is_none());
+
+        // Legacy, untagged values still verify.
+        let tag = keyed_mac::<Hmac<Sha256>>(key.signing(), &[b"value"]).finalize();
+        let legacy_value = format!("{}value", base64::encode(tag.into_bytes()));
+        jar.add(Cookie::new("legacy", legacy_value));
+        assert_eq!(jar.signed(&key).get("legacy").unwrap().value(), "value");
+    }
+
+    #[test]
+    fn expiry() {
+        use time::{Duration, OffsetDateTime};
+        use crate::CookieSigner;
+        use crate::secure::version::{Flags, Format};
+
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        let mut signed = jar.signed_mut(&key);
+        signed.add_with_expiry(Cookie::new("fresh", "a.b"), Duration::minutes(5));
+        signed.add_with_expiry(Cookie::new("stale", "a.b"), Duration::minutes(-5));
+        signed.add(Cookie::new("untimed", "a.b"));
+        assert!(jar.get("fresh").unwrap().value().starts_with("v2t:"));
+
+        let signed = jar.signed(&key);
+        assert_eq!(signed.get("fresh").unwrap().value(), "a.b");
+        assert_eq!(signed.get("untimed").unwrap().value(), "a.b");
+        assert!(signed.get("stale").is_none());
+
+        // With a max age, untimed values are rejected.
+        let signed = jar.signed(&key).with_max_age(Duration::hours(1));
+        assert_eq!(signed.get("fresh").unwrap().value(), "a.b");
+        assert!(signed.get("untimed").is_none());
+        assert!(signed.get("stale").is_none());
+
+        // Tampering with the timestamp is detected.
+        let value = jar.get("stale").unwrap().value().to_string();
+        let (prefix, rest) = value.split_at("v2t:".len() + 44);
+        let (_, rest) = rest.split_once('.').unwrap();
+        jar.add(Cookie::new("stale", format!("{}{}.{}", prefix, i64::MAX, rest)));
+        assert!(jar.signed(&key).get("stale").is_none());
+
+        // A max age applies to added cookies.
+        jar.signed_mut(&key).with_max_age(Duration::minutes(-1)).add(Cookie::new("new", "v"));
+        assert!(jar.get("new").unwrap().value().starts_with("v2t:"));
+        assert!(jar.signed(&key).get("new").is_none());
+
+        // The verifier's max age limits how long ago a value was issued,
+        // whatever expiration it was signed with.
+        let signed = jar.signed(&key);
+        let format = Format::CURRENT.with(Flags::TIMESTAMPED);
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let value = format!("{}.{}.v", now - 7200, i64::MAX);
+        let tag = signed.signer.sign(&signed.message(format, "old", &value));
+        jar.add(Cookie::new("old", super::signed_value(format, None, &tag, &value)));
+        assert_eq!(jar.signed(&key).get("old").unwrap().value(), "v");
+        assert!(jar.signed(&key).with_max_age(Duration::hours(1)).get("old").is_none());
+        assert!(jar.signed(&key).with_max_age(Duration::hours(3)).get("old").is_some());
+
+        // Durations too large to add to the current time don't panic.
+        jar.signed_mut(&key).add_with_expiry(Cookie::new("max", "v"), Duration::MAX);
+        jar.signed_mut(&key).add_with_expiry(Cookie::new("min", "v"), Duration::MIN);
+        assert_eq!(jar.signed(&key).with_max_age(Duration::MAX).get("max").unwrap().value(), "v");
+        assert!(jar.signed(&key).with_max_age(Duration::MIN).get("max").is_none());
+        assert!(jar.signed(&key).get("min").is_none());
+    }
+
+    #[test]
+    fn verification_errors() {
+        use super::VerificationError::*;
+        use time::Duration;
+
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        jar.signed_mut(&key).add(Cookie::new("signed", "value"));
+        jar.signed_mut(&key).add_with_expiry(Cookie::new("stale", "v"), Duration::minutes(-1));
+        jar.add(Cookie::new("plain", "value"));
+        jar.add(Cookie::new("future", "v9:value"));
+        jar.add(Cookie::new("bad_base64", format!("{}value", "!".repeat(44))));
+
+        let signed = jar.signed(&key);
+        assert_eq!(signed.get_result("signed").unwrap().value(), "value");
+        assert_eq!(signed.get_result("missing"), Err(NotFound));
+        assert_eq!(signed.get_result("plain"), Err(MissingDigest));
+        assert_eq!(signed.get_result("future"), Err(UnknownFormat));
+        assert_eq!(signed.get_result("bad_base64"), Err(BadBase64));
+        assert_eq!(signed.get_result("stale"), Err(Expired));
+        assert_eq!(jar.signed(&Key::generate()).get_result("signed"), Err(BadSignature));
+
+        let signed = jar.signed(&key).with_max_age(Duration::hours(1));
+        assert_eq!(signed.get_result("signed"), Err(MissingTimestamp));
+    }
+
+    #[test]
+    fn custom_signer() {
+        use std::cell::Cell;
+        use crate::{CookieSigner, HmacSigner};
+
+        // A signer with a fixed key ID that counts its operations.
+        struct Counting(HmacSigner, Cell<usize>);
+
+        impl CookieSigner for Counting {
+            fn tag_len(&self) -> usize { self.0.tag_len() }
+            fn sign(&self, message: &[u8]) -> Vec<u8> {
+                self.1.set(self.1.get() + 1);
+                self.0.sign(message)
+            }
+            fn verify(&self, _: &[u8], _: &[u8]) -> bool { false }
+            fn key_id(&self) -> Option<&str> { Some("hsm") }
+            fn verify_with_key_id(&self, id: &str, message: &[u8], tag: &[u8]) -> bool {
+                self.1.set(self.1.get() + 1);
+                id == "hsm" && self.0.verify(message, tag)
+            }
+        }
+
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        let signer = Counting(HmacSigner::new(&key), Cell::new(0));
+        let mut signed = jar.signed_with_signer_mut(signer);
+        signed.add(Cookie::new("name", "value"));
+        assert_eq!(signed.get("name").unwrap().value(), "value");
+        assert_eq!(signed.signer().1.get(), 2);
+        assert!(jar.get("name").unwrap().value().starts_with("v2:hsm$"));
+
+        // Without the key ID, `verify` is used and fails.
+        let value = jar.get("name").unwrap().value().replacen("hsm$", "", 1);
+        jar.add(Cookie::new("name", value));
+        let signer = Counting(HmacSigner::new(&key), Cell::new(0));
+        assert!(jar.signed_with_signer(signer).get("name").is_none());
+        assert_eq!(jar.signed(&key).get("name").unwrap().value(), "value");
+    }
+
+    #[test]
+    #[cfg(feature = "async")]
+    fn provider() {
+        use crate::secure::provider::block_on;
+        use crate::{CookieSigner, Keyring, SignedJar};
+
+        let mut keyring = Keyring::new("k1", Key::generate());
+        let mut jar = CookieJar::new();
+        block_on(SignedJar::with_provider(&mut jar, &keyring)).unwrap()
+            .add(Cookie::new("name", "value"));
+
+        keyring.add("k2", Key::generate());
+        let signed = block_on(SignedJar::with_provider(&jar, &keyring)).unwrap();
+        assert_eq!(signed.get("name").unwrap().value(), "value");
+        assert!(signed.signer().key_id() == Some("k2"));
+    }
+
+    #[test]
+    fn derived_keys() {
+        use super::VerificationError;
+
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        jar.signed_mut(&key).with_derived_keys(true).add(Cookie::new("name", "value"));
+        jar.signed_mut(&key).add(Cookie::new("plain", "value"));
+        assert!(jar.get("name").unwrap().value().starts_with("v2d:"));
+
+        // Derived values verify with and without the setting.
+        assert_eq!(jar.signed(&key).get("name").unwrap().value(), "value");
+        let derived = jar.signed(&key).with_derived_keys(true);
+        assert_eq!(derived.get("name").unwrap().value(), "value");
+        assert_eq!(derived.get_result("plain"), Err(VerificationError::BadSignature));
+
+        // A derived value never verifies for another cookie.
+        let value = jar.get("name").unwrap().value().to_string();
+        jar.add(Cookie::new("other", value));
+        assert_eq!(jar.signed(&key).get_result("other"), Err(VerificationError::BadSignature));
+
+        // Derived values compose with key IDs and timestamps.
+        let keyring = crate::Keyring::new("k1", key.clone());
+        let mut signed = jar.signed_with_keyring_mut(&keyring)
+            .with_derived_keys(true)
+            .with_max_age(time::Duration::hours(1));
+
+        signed.add(Cookie::new("timed", "value"));
+        assert!(jar.get("timed").unwrap().value().starts_with("v2td:k1$"));
+        assert_eq!(jar.signed_with_keyring(&keyring).get("timed").unwrap().value(), "value");
+
+        // Signers that can't derive keys sign and verify as usual.
+        let signer = crate::compat::express::ExpressSigner::new(b"secret");
+        jar.signed_with_signer_mut(signer).with_derived_keys(true).add(Cookie::new("e", "v"));
+        assert!(jar.get("e").unwrap().value().starts_with("v2:"));
+    }
+
+    #[test]
+    fn raw_values() {
+        use super::{sign_value, verify_value, VerificationError};
+
+        let key = Key::generate();
+        let signed = sign_value(&key, "a value");
+        assert_eq!(verify_value(&key, &signed), Ok("a value".into()));
+        assert_eq!(verify_value(&key, "a value"), Err(VerificationError::MissingDigest));
+
+        // Raw values and cookie values are interchangeable.
+        let mut jar = CookieJar::new();
+        jar.add(Cookie::new("name", signed));
+        assert_eq!(jar.signed(&key).get("name").unwrap().value(), "a value");
+
+        jar.signed_mut(&key).add(Cookie::new("other", "value"));
+        assert_eq!(verify_value(&key, jar.get("other").unwrap().value()), Ok("value".into()));
+    }
+
+    #[test]
+    fn detached() {
+        use time::Duration;
+
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        let mut signed = jar.signed_mut(&key)
+            .with_detached_signatures(true)
+            .with_name_binding(true);
+        signed.add(Cookie::build("name", "value").path("/app").finish());
+        signed.add_with_expiry(Cookie::new("timed", "a.b"), Duration::hours(1));
+        assert_eq!(signed.get("name").unwrap().value(), "value");
+        assert_eq!(signed.get("timed").unwrap().value(), "a.b");
+
+        assert_eq!(jar.get("name").unwrap().value(), "value");
+        assert_eq!(jar.get("name.sig").unwrap().path(), Some("/app"));
+        assert!(jar.get("name.sig").unwrap().value().starts_with("v2:"));
+        assert!(jar.signed(&key).get("name").is_none());
+
+        // Tampering with the value or losing the signature is detected.
+        let detached = |jar: &CookieJar| {
+            jar.signed(&key).with_detached_signatures(true).with_name_binding(true)
+                .get_result("name")
+        };
+
+        jar.add(Cookie::new("name", "other"));
+        assert_eq!(detached(&jar), Err(super::VerificationError::BadSignature));
+        jar.remove(Cookie::named("name.sig"));
+        assert_eq!(detached(&jar), Err(super::VerificationError::MissingDigest));
+
+        // Only the signatures of cookies in the jar are skipped.
+        let mut jar = CookieJar::new();
+        let mut signed = jar.signed_mut(&key).with_detached_signatures(true);
+        signed.add(Cookie::new("a", "1"));
+        signed.add(Cookie::new("b.sig", "2"));
+        jar.add(Cookie::new("c.sig", "3"));
+        let signed = jar.signed(&key).with_detached_signatures(true);
+        let mut verified: Vec<_> = signed.get_all_verified().map(|c| c.name().to_string())
+            .collect();
+        verified.sort();
+        assert_eq!(verified, ["a", "b.sig"]);
+        let failures: Vec<_> = signed.get_all_failures().collect();
+        assert_eq!(failures, [("c.sig", super::VerificationError::MissingDigest)]);
+
+        // Removal removes both cookies.
+        let mut signed = jar.signed_mut(&key).with_detached_signatures(true);
+        signed.add_original(Cookie::new("orig", "value"));
+        signed.remove(Cookie::named("orig"));
+        assert!(jar.get("orig").is_none());
+        assert!(jar.get("orig.sig").is_none());
+    }
+
+    #[test]
+    fn fingerprints() {
+        let (old, new) = (Key::generate(), Key::generate());
+        let mut jar = CookieJar::new();
+        jar.signed_mut(&old).with_key_fingerprints(true).add(Cookie::new("name", "value"));
+        let prefix = format!("v2:{}$", old.fingerprint());
+        assert!(jar.get("name").unwrap().value().starts_with(&prefix));
+
+        // The identified key is found regardless of its position or the setting.
+        let keys = [new.clone(), old.clone()];
+        assert_eq!(jar.signed_with_keys(&keys).get("name").unwrap().value(), "value");
+        let signed = jar.signed_with_keys(&keys).with_key_fingerprints(true);
+        assert_eq!(signed.get("name").unwrap().value(), "value");
+
+        // Unknown fingerprints are rejected outright when enabled.
+        assert!(jar.signed(&new).with_key_fingerprints(true).get("name").is_none());
+        jar.signed_mut(&new).add(Cookie::new("plain", "value"));
+        assert!(jar.signed(&new).with_key_fingerprints(true).get("plain").is_some());
+
+        // Key IDs take precedence over fingerprints.
+        let keyring = crate::Keyring::new("k1", old);
+        jar.signed_with_keyring_mut(&keyring).with_key_fingerprints(true)
+            .add(Cookie::new("id", "value"));
+        assert!(jar.get("id").unwrap().value().starts_with("v2:k1$"));
+    }
+
+    #[test]
+    fn url_safe_encoding() {
+        use crate::SigningAlgorithm;
+
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        for algorithm in &[SigningAlgorithm::HmacSha256, SigningAlgorithm::HmacSha512] {
+            jar.signed_mut(&key).with_algorithm(*algorithm).with_url_safe_encoding(true)
+                .add(Cookie::new("name", "a+b/c="));
+
+            let signed = jar.get("name").unwrap().value().to_string();
+            let digest = &signed["v2u:".len()..(signed.len() - "a+b/c=".len())];
+            assert!(signed.starts_with("v2u:"));
+            assert!(!digest.contains(['=', '+', '/']));
+
+            let verifier = jar.signed(&key).with_algorithm(*algorithm);
+            assert_eq!(verifier.get("name").unwrap().value(), "a+b/c=");
+
+            // Stripping the flag fails to verify.
+            jar.add(Cookie::new("stripped", signed.replacen("v2u:", "v2:", 1)));
+            assert!(jar.signed(&key).with_algorithm(*algorithm).get("stripped").is_none());
+        }
+
+        // Values in the standard encoding are still accepted.
+        jar.signed_mut(&key).add(Cookie::new("standard", "value"));
+        let signed = jar.signed(&key).with_url_safe_encoding(true);
+        assert_eq!(signed.get("standard").unwrap().value(), "value");
+    }
+
+    #[test]
+    fn context() {
+        use super::VerificationError;
+
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        jar.signed_with_context_mut(&key, b"ctx").add(Cookie::new("name", "value"));
+        jar.signed_mut(&key).add(Cookie::new("plain", "value"));
+        assert!(jar.get("name").unwrap().value().starts_with("v2c:"));
+
+        let bound = jar.signed_with_context(&key, b"ctx");
+        assert_eq!(bound.get("name").unwrap().value(), "value");
+        assert_eq!(bound.get_result("plain"), Err(VerificationError::BadSignature));
+
+        let f = |context: &[u8]| jar.signed_with_context(&key, context).get_result("name");
+        assert_eq!(f(b"other"), Err(VerificationError::BadSignature));
+        assert_eq!(f(b""), Err(VerificationError::BadSignature));
+        assert_eq!(jar.signed(&key).get_result("name"), Err(VerificationError::BadSignature));
+
+        // Stripping the flag fails to verify, even without a context.
+        let value = jar.get("name").unwrap().value().replacen("v2c:", "v2:", 1);
+        jar.add(Cookie::new("name", value));
+        assert!(jar.signed(&key).get("name").is_none());
+
+        // The context composes with name binding and timestamps.
+        jar.signed_mut(&key).with_context(b"ctx").with_name_binding(true)
+            .add_with_expiry(Cookie::new("timed", "value"), time::Duration::hours(1));
+
+        let bound = jar.signed_with_context(&key, b"ctx").with_name_binding(true);
+        assert!(jar.get("timed").unwrap().value().starts_with("v2tc:"));
+        assert_eq!(bound.get("timed").unwrap().value(), "value");
+    }
+
+    #[test]
+    fn truncated_tags() {
+        use crate::{CookieSigner, Keyring, RemoteKey, SigningAlgorithm};
+        use super::VerificationError;
+
+        let (key, old) = (Key::generate(), Key::generate());
+        let mut jar = CookieJar::new();
+        for algorithm in &[SigningAlgorithm::HmacSha256, SigningAlgorithm::HmacSha512] {
+            jar.signed_mut(&old).with_algorithm(*algorithm).with_truncated_tags(true)
+                .add(Cookie::new("name", "value"));
+
+            let signed = jar.get("name").unwrap().value().to_string();
+            assert_eq!(signed, format!("v2s:{}value", &signed[4..28]));
+
+            let keys = [key.clone(), old.clone()];
+            let verifier = jar.signed_with_keys(&keys).with_algorithm(*algorithm);
+            assert_eq!(verifier.get("name").unwrap().value(), "value");
+
+            // Tampering with the tag, value, or flag fails to verify.
+            let verify = |value: String| verifier.verify_result(Cookie::new("name", value));
+            let bad = VerificationError::BadSignature;
+            assert_eq!(verify(signed.replacen("v2s:", "v2:", 1)).unwrap_err(),
+                VerificationError::MissingDigest);
+            assert_eq!(verify(signed.replace("value", "other")).unwrap_err(), bad);
+            let mut flipped = signed.clone().into_bytes();
+            flipped[4] = if flipped[4] == b'A' { b'B' } else { b'A' };
+            assert_eq!(verify(String::from_utf8(flipped).unwrap()).unwrap_err(), bad);
+            let zeroed = signed.replacen(&signed[4..28], &"A".repeat(24), 1);
+            assert_eq!(verify(zeroed).unwrap_err(), bad);
+        }
+
+        // Truncation composes with key IDs, derived keys, and custom signers.
+        let keyring = Keyring::new("k1", key.clone());
+        jar.signed_with_keyring_mut(&keyring).with_truncated_tags(true).with_derived_keys(true)
+            .add(Cookie::new("ring", "value"));
+
+        assert!(jar.get("ring").unwrap().value().starts_with("v2ds:k1$"));
+        assert_eq!(jar.signed_with_keyring(&keyring).get("ring").unwrap().value(), "value");
+
+        let hmac = super::HmacSigner::new(&key);
+        let remote = RemoteKey::new(32, move |message| hmac.sign(message));
+        jar.signed_with_signer_mut(&remote).with_truncated_tags(true)
+            .add(Cookie::new("remote", "value"));
+
+        assert!(jar.get("remote").unwrap().value().starts_with("v2s:"));
+        assert_eq!(jar.signed(&key).get("remote").unwrap().value(), "value");
+        assert_eq!(jar.signed_with_signer(&remote).get("remote").unwrap().value(), "value");
+    }
+
+    #[test]
+    fn batch_verification() {
+        use super::VerificationError;
+
+        let key = Key::generate();
+        let mut jar = CookieJar::new();
+        jar.signed_mut(&key).add(Cookie::new("a", "1"));
+        jar.signed_mut(&key).add_original(Cookie::new("b", "2"));
+        jar.signed_mut(&Key::generate()).add(Cookie::new("forged", "3"));
+        jar.add(Cookie::new("plain", "4"));
+        jar.signed_mut(&key).add(Cookie::new("removed", "5"));
+        jar.remove(Cookie::named("removed"));
+
+        let sorted = |mut v: Vec<(String, String)>| { v.sort(); v };
+        let verified = jar.signed(&key).get_all_verified()
+            .map(|c| (c.name().to_string(), c.value().to_string()))
+            .collect();
+
+        assert_eq!(sorted(verified), [("a".into(), "1".into()), ("b".into(), "2".into())]);
+
+        let signed = jar.signed(&key);
+        let mut failures: Vec<_> = signed.get_all_failures().collect();
+        failures.sort_by_key(|(name, _)| *name);
+        assert_eq!(failures, [
+            ("forged", VerificationError::BadSignature),
+            ("plain", VerificationError::MissingDigest),
+        ]);
+
+        // Detached signature cookies are reattached, not reported.
+        let mut jar = CookieJar::new();
+        let mut detached = jar.signed_mut(&key).with_detached_signatures(true);
+        detached.add(Cookie::new("a", "1"));
+        detached.add(Cookie::new("b", "2"));
+        jar.add(Cookie::new("plain", "3"));
+
+        let detached = jar.signed(&key).with_detached_signatures(true);
+        let verified = detached.get_all_verified()
+            .map(|c| (c.name().to_string(), c.value().to_string()))
+            .collect();
+
+        assert_eq!(sorted(verified), [("a".into(), "1".into()), ("b".into(), "2".into())]);
+        let failures: Vec<_> = detached.get_all_failures().collect();
+        assert_eq!(failures, [("plain", VerificationError::MissingDigest)]);
+    }
+
+    #[test]
+    fn attribute_binding() {
+        use time::{Duration, OffsetDateTime};
+        use super::VerificationError;
+
+        let key = Key::generate();
+        let expires = OffsetDateTime::now_utc() + Duration::days(1);
+        let mut jar = CookieJar::new();
+        let mut signed = jar.signed_mut(&key).with_attribute_binding(true);
+        signed.add(Cookie::build("admin", "a.b").path("/admin").domain("example.com")
+            .expires(expires).finish());
+        signed.add(Cookie::new("plain", "value"));
+        signed.add_with_expiry(Cookie::build("timed", "v").path("/t").finish(), Duration::hours(1));
+
+        assert!(jar.get("admin").unwrap().value().starts_with("v2a:"));
+        assert!(jar.get("timed").unwrap().value().starts_with("v2ta:"));
+
+        // The authenticated attributes replace those presented.
+        let bound = jar.signed(&key).with_attribute_binding(true);
+        let replayed = Cookie::build("admin", jar.get("admin").unwrap().value().to_string())
+            .path("/").finish();
+
+        let admin = bound.verify(replayed.clone()).unwrap();
+        assert_eq!(admin.value(), "a.b");
+        assert_eq!(admin.path(), Some("/admin"));
+        assert_eq!(admin.domain(), Some("example.com"));
+        assert_eq!(admin.expires_datetime().unwrap().unix_timestamp(), expires.unix_timestamp());
+
+        let plain = bound.verify(Cookie::build("plain", jar.get("plain").unwrap().value()
+            .to_string()).path("/x").finish()).unwrap();
+        assert_eq!((plain.value(), plain.path()), ("value", None));
+        assert_eq!(bound.get("timed").unwrap().path(), Some("/t"));
+        assert_eq!(bound.get("timed").unwrap().value(), "v");
+
+        // Bound attributes are restored even if binding isn't required.
+        let unbound = jar.signed(&key);
+        assert_eq!(unbound.verify(replayed).unwrap().path(), Some("/admin"));
+
+        // Values without bound attributes are rejected when binding is enabled.
+        jar.signed_mut(&key).add(Cookie::new("unbound", "value"));
+        let bound = jar.signed(&key).with_attribute_binding(true);
+        assert_eq!(bound.get_result("unbound"), Err(VerificationError::BadSignature));
+
+        // Cookies past their bound expiration date are rejected.
+        let expired = OffsetDateTime::now_utc() - Duration::hours(1);
+        jar.signed_mut(&key).with_attribute_binding(true)
+            .add(Cookie::build("old", "value").expires(expired).finish());
+
+        let bound = jar.signed(&key).with_attribute_binding(true);
+        assert_eq!(bound.get_result("old"), Err(VerificationError::Expired));
+
+        // Attributes survive detached signatures.
+        let mut detached = jar.signed_mut(&key)
+            .with_attribute_binding(true)
+            .with_detached_signatures(true);
+
+        detached.add(Cookie::build("split", "value").path("/s").finish());
+        assert_eq!(jar.get("split").unwrap().value(), "value");
+        let detached = jar.signed(&key).with_attribute_binding(true).with_detached_signatures(true);
+        assert_eq!(detached.get("split").unwrap().path(), Some("/s"));
     }
 
     #[test]
@@ -0,0 +1,267 @@
+use crate::secure::base64::{self, URL_SAFE_NO_PAD};
+
+/// The maximum length, in bytes, of a format tag, including the separator.
+const MAX_TAG_LEN: usize = 16;
+
+/// The separator between a format tag and the rest of a cookie value. This is
+/// not a Base64 character and does not appear in a key ID, so a format tag
+/// prefix is unambiguous.
+const TAG_SEPARATOR: char = ':';
+
+/// The first byte of the authenticated encoding of every tagged format. No
+/// message authenticated in the untagged `V1` format begins with it: such a
+/// message is either a cookie value, which is UTF-8, where `0xFF` never
+/// appears, or a name prefixed with its big-endian 64-bit length, whose first
+/// byte is `0` for any name that fits in memory.
+const TAGGED_MARKER: u8 = 0xFF;
+
+/// The format version of a signed or encrypted cookie value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Version {
+    /// The original, untagged format.
+    V1,
+    /// The `v2` tagged format. The tag is authenticated along with the value.
+    V2,
+}
+
+/// Optional features of a tagged format, each identified in the format tag by
+/// a single lowercase letter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct Flags(u8);
+
+impl Flags {
+    /// The value is prefixed by an authenticated timestamp.
+    pub const TIMESTAMPED: Flags = Flags(1 << 0);
+
+    /// The value is signed or encrypted with a key derived for the cookie.
+    pub const DERIVED: Flags = Flags(1 << 1);
+
+    /// The value, after any timestamp, is prefixed by authenticated attributes.
+    pub const ATTRIBUTES: Flags = Flags(1 << 2);
+
+    /// The value is authenticated along with an application-provided context.
+    pub const CONTEXT: Flags = Flags(1 << 3);
+
+    /// Binary data in the value is encoded as URL-safe, unpadded Base64 instead
+    /// of standard, padded Base64.
+    pub const URL_SAFE: Flags = Flags(1 << 4);
+
+    /// The value's signature is truncated.
+    pub const TRUNCATED: Flags = Flags(1 << 5);
+
+    /// Every flag with its letter, in the order letters appear in a tag.
+    const LETTERS: &'static [(Flags, char)] = &[
+        (Flags::TIMESTAMPED, 't'),
+        (Flags::DERIVED, 'd'),
+        (Flags::ATTRIBUTES, 'a'),
+        (Flags::CONTEXT, 'c'),
+        (Flags::URL_SAFE, 'u'),
+        (Flags::TRUNCATED, 's'),
+    ];
+
+    /// Returns `true` if every flag in `other` is set in `self`.
+    pub fn contains(self, other: Flags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+/// The format of a signed or encrypted cookie value: its version and, for
+/// tagged versions, its flags.
+///
+/// Tagged values are prefixed with a tag of the form `vN<flags>:`, the rest of
+/// the value, including any key ID, following the tag. Values produced before
+/// versioning was introduced carry no tag and are treated as `V1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Format {
+    pub version: Version,
+    pub flags: Flags,
+}
+
+impl Format {
+    /// The original, untagged format.
+    pub const V1: Format = Format { version: Version::V1, flags: Flags(0) };
+
+    /// The format used for newly signed or encrypted values.
+    pub const CURRENT: Format = Format { version: Version::V2, flags: Flags(0) };
+
+    /// Returns this format with `flags` additionally set.
+    pub fn with(self, flags: Flags) -> Format {
+        Format { flags: Flags(self.flags.0 | flags.0), ..self }
+    }
+
+    /// The tag that prefixes values in this format, including the separator.
+    pub fn tag(self) -> String {
+        let version = match self.version {
+            Version::V1 => return String::new(),
+            Version::V2 => "v2",
+        };
+
+        let mut tag = version.to_string();
+        Flags::LETTERS.iter()
+            .filter(|(flag, _)| self.flags.contains(*flag))
+            .for_each(|(_, letter)| tag.push(*letter));
+
+        tag.push(TAG_SEPARATOR);
+        tag
+    }
+
+    /// The fixed-width encoding of this format that begins every message or
+    /// associated data authenticated in it: nothing for `V1` and, for tagged
+    /// versions, [`TAGGED_MARKER`], the version number, and the flags. The
+    /// marker keeps tagged values from verifying as `V1` values, and vice
+    /// versa, when their tag is moved or removed.
+    pub fn authenticated(self) -> Vec<u8> {
+        match self.version {
+            Version::V1 => vec![],
+            Version::V2 => vec![TAGGED_MARKER, 2, self.flags.0],
+        }
+    }
+
+    /// Prefixes `value` with the tag for this format.
+    pub fn tagged(self, value: String) -> String {
+        match self.version {
+            Version::V1 => value,
+            _ => self.tag() + &value,
+        }
+    }
+
+    /// Encodes `bytes` as Base64 in the alphabet of this format.
+    pub fn encode(self, bytes: &[u8]) -> String {
+        match self.flags.contains(Flags::URL_SAFE) {
+            true => base64::encode_config(bytes, URL_SAFE_NO_PAD),
+            false => base64::encode(bytes),
+        }
+    }
+
+    /// Decodes `encoded`, Base64 in the alphabet of this format.
+    pub fn decode(self, encoded: &str) -> Result<Vec<u8>, base64::DecodeError> {
+        match self.flags.contains(Flags::URL_SAFE) {
+            true => base64::decode_config(encoded, URL_SAFE_NO_PAD),
+            false => base64::decode(encoded),
+        }
+    }
+
+    /// The length of the Base64 encoding of `len` bytes in this format.
+    #[cfg_attr(not(feature = "signed"), allow(dead_code))]
+    pub fn encoded_len(self, len: usize) -> usize {
+        match (self.flags.contains(Flags::URL_SAFE), len % 3) {
+            (_, 0) => len / 3 * 4,
+            (true, rem) => len / 3 * 4 + rem + 1,
+            (false, _) => len / 3 * 4 + 4,
+        }
+    }
+
+    /// Splits the format tag, if there is one, from `value`, returning the
+    /// value's format and the remainder of the value. Returns an `Err` if the
+    /// value is tagged with an unknown version or flags.
+    pub fn split(value: &str) -> Result<(Format, &str), &'static str> {
+        let end = value.char_indices()
+            .take(MAX_TAG_LEN)
+            .find(|&(_, c)| c == TAG_SEPARATOR)
+            .map(|(i, _)| i);
+
+        let (tag, rest) = match end {
+            Some(i) => (&value[..i], &value[(i + 1)..]),
+            None => return Ok((Format::V1, value)),
+        };
+
+        let digits = tag.get(1..).unwrap_or("");
+        let version_len = digits.bytes().take_while(|b| b.is_ascii_digit()).count();
+        if !tag.starts_with('v') || version_len == 0 {
+            return Ok((Format::V1, value));
+        }
+
+        let (version, letters) = digits.split_at(version_len);
+        if version != "2" {
+            return Err("unknown value format version");
+        }
+
+        let mut flags = Flags(0);
+        for letter in letters.chars() {
+            let flag = Flags::LETTERS.iter().find(|(_, l)| *l == letter);
+            match flag {
+                Some((flag, _)) => flags = Flags(flags.0 | flag.0),
+                None => return Err("unknown value format flag"),
+            }
+        }
+
+        let format = Format { version: Version::V2, flags };
+        let canonical = format.tag();
+        if canonical[..(canonical.len() - 1)] != *tag {
+            return Err("non-canonical value format tag");
+        }
+
+        Ok((format, rest))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Flags, Format};
+
+    #[test]
+    fn format_tag() {
+        let v1 = Format::V1;
+        let v2 = Format::CURRENT;
+        let v2t = Format::CURRENT.with(Flags::TIMESTAMPED);
+        let v2d = Format::CURRENT.with(Flags::DERIVED);
+        let v2td = v2t.with(Flags::DERIVED);
+
+        assert_eq!(Format::split("v2:abc"), Ok((v2, "abc")));
+        assert_eq!(Format::split("v2:k1$abc"), Ok((v2, "k1$abc")));
+        assert_eq!(Format::split("v2:"), Ok((v2, "")));
+        assert_eq!(Format::split("v2t:abc"), Ok((v2t, "abc")));
+        assert_eq!(Format::split("v2d:abc"), Ok((v2d, "abc")));
+        assert_eq!(Format::split("v2td:abc"), Ok((v2td, "abc")));
+        let v2tda = v2td.with(Flags::ATTRIBUTES);
+        assert_eq!(Format::split("v2tda:abc"), Ok((v2tda, "abc")));
+        assert!(Format::split("v2ad:abc").is_err());
+        let v2tdac = v2tda.with(Flags::CONTEXT);
+        assert_eq!(Format::split("v2tdac:abc"), Ok((v2tdac, "abc")));
+        assert_eq!(Format::split("abc"), Ok((v1, "abc")));
+        assert_eq!(Format::split("k1$v2:abc"), Ok((v1, "k1$v2:abc")));
+        assert_eq!(Format::split("abcdefgh:v2"), Ok((v1, "abcdefgh:v2")));
+        assert_eq!(Format::split("v:abc"), Ok((v1, "v:abc")));
+        assert!(Format::split("v3:abc").is_err());
+        assert!(Format::split("v10:abc").is_err());
+        assert!(Format::split("v2x:abc").is_err());
+        assert!(Format::split("v2tt:abc").is_err());
+        assert!(Format::split("v2ttt:abc").is_err());
+        assert!(Format::split("v2dt:abc").is_err());
+        let v2tdacu = v2tdac.with(Flags::URL_SAFE);
+        assert_eq!(Format::split("v2tdacu:abc"), Ok((v2tdacu, "abc")));
+        let v2tdacus = v2tdacu.with(Flags::TRUNCATED);
+        assert_eq!(Format::split("v2tdacus:abc"), Ok((v2tdacus, "abc")));
+        assert_eq!(Format::split("v2tdacus01234567:"), Ok((v1, "v2tdacus01234567:")));
+
+        for format in &[v1, v2, v2t, v2d, v2td] {
+            let tagged = format.tagged("abc".into());
+            assert_eq!(Format::split(&tagged), Ok((*format, "abc")));
+        }
+    }
+
+    #[test]
+    fn authenticated() {
+        let v2t = Format::CURRENT.with(Flags::TIMESTAMPED);
+        assert_eq!(Format::V1.authenticated(), b"");
+        assert_eq!(Format::CURRENT.authenticated(), [0xFF, 2, 0]);
+        assert_eq!(v2t.authenticated(), [0xFF, 2, Flags::TIMESTAMPED.0]);
+        assert_ne!(v2t.authenticated(), v2t.with(Flags::DERIVED).authenticated());
+    }
+
+    #[test]
+    fn encoding() {
+        let url_safe = Format::CURRENT.with(Flags::URL_SAFE);
+        for len in 0..70 {
+            let bytes: Vec<u8> = (0..len).map(|i| (i * 37) as u8 | 0xf8).collect();
+            for format in &[Format::V1, Format::CURRENT, url_safe] {
+                let encoded = format.encode(&bytes);
+                assert_eq!(encoded.len(), format.encoded_len(bytes.len()));
+                assert_eq!(format.decode(&encoded).unwrap(), bytes);
+            }
+
+            let encoded = url_safe.encode(&bytes);
+            assert!(!encoded.contains(['=', '+', '/']));
+        }
+    }
+}
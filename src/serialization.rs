@@ -0,0 +1,989 @@
+//! `Serialize` and `Deserialize` implementations, enabled by the `serde`
+//! feature.
+//!
+//! A [`Cookie`] is serialized as a struct with the fields `name`, `value`,
+//! `expires`, `max_age`, `domain`, `path`, `secure`, `http_only`, `same_site`,
+//! `partitioned`, `priority`, `extensions`, and `quoted`, every field but
+//! `name` and `value` being optional. An [`Expiration`] is an enum whose
+//! `DateTime` variant holds an RFC 3339 date-time, keeping the time's
+//! fractional seconds and UTC offset, a `Max-Age` is a number of seconds, a
+//! [`SameSite`] is an enum whose `Other` variant holds the unrecognized value,
+//! a [`Priority`] is a unit-only enum, `extensions` is a sequence of `(name,
+//! value)` pairs as returned by [`Cookie::extensions()`], and `quoted` is
+//! [`Cookie::is_quoted()`], `false` if missing. The `domain` is serialized as
+//! it is written out, with a leading dot if [`Cookie::domain_leading_dot()`];
+//! as when parsing, a leading dot is stripped when deserializing. Every field
+//! is always serialized so that non-self-describing formats can be used.
+//!
+//! A [`CookieJar`] is serialized as a struct with the fields `original`,
+//! `added`, and `removed`, each a sequence of cookies: the jar's original
+//! cookies and the additions and removals in its delta. Missing fields are
+//! deserialized as empty. To serialize only the cookies in a jar, use
+//! [`cookies_only`].
+
+use std::borrow::Cow;
+use std::fmt;
+
+use serde::de::{self, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess};
+use serde::de::{Unexpected, VariantAccess, Visitor};
+use serde::ser::{self, SerializeStruct, Serializer};
+use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Rfc3339;
+use time::{Duration, OffsetDateTime};
+
+use crate::delta::DeltaCookie;
+#[cfg(feature = "signed")]
+use crate::Claims;
+use crate::{Cookie, CookieJar, CookieStr, Expiration, Priority, SameSite};
+
+/// The names of the fields of a serialized `Cookie`, in order.
+const COOKIE_FIELDS: &[&str] = &[
+    "name", "value", "expires", "max_age", "domain", "path", "secure", "http_only",
+    "same_site", "partitioned", "priority", "extensions", "quoted",
+];
+
+/// The names of the fields of serialized `Claims`, in order.
+#[cfg(feature = "signed")]
+const CLAIMS_FIELDS: &[&str] = &["iat", "exp", "claims"];
+
+/// The names of the fields of a serialized `CookieJar`, in order.
+const JAR_FIELDS: &[&str] = &["original", "added", "removed"];
+
+/// The names of the variants of a serialized `Expiration`, in order.
+const EXPIRATION_VARIANTS: &[&str] = &["DateTime", "Session"];
+
+/// The names of the variants of a serialized `SameSite`, in order.
+const SAME_SITE_VARIANTS: &[&str] = &["Strict", "Lax", "None", "Other"];
+
+/// The names of the variants of a serialized `Priority`, in order.
+const PRIORITY_VARIANTS: &[&str] = &["Low", "Medium", "High"];
+
+/// A seed deserializing the identifier of a struct field to its index in a list
+/// of names, or to `None` if it's not in the list.
+struct Field(&'static [&'static str]);
+
+/// A seed deserializing the identifier of an enum variant to its index in a
+/// list of names. Unknown variants are an error.
+struct Variant(&'static [&'static str]);
+
+impl<'de> DeserializeSeed<'de> for Field {
+    type Value = Option<usize>;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_identifier(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Field {
+    type Value = Option<usize>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a field identifier")
+    }
+
+    fn visit_u64<E: de::Error>(self, index: u64) -> Result<Self::Value, E> {
+        Ok(Some(index as usize).filter(|&i| i < self.0.len()))
+    }
+
+    fn visit_str<E: de::Error>(self, name: &str) -> Result<Self::Value, E> {
+        Ok(self.0.iter().position(|field| *field == name))
+    }
+
+    fn visit_bytes<E: de::Error>(self, name: &[u8]) -> Result<Self::Value, E> {
+        Ok(self.0.iter().position(|field| field.as_bytes() == name))
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for Variant {
+    type Value = usize;
+
+    fn deserialize<D: Deserializer<'de>>(self, de: D) -> Result<Self::Value, D::Error> {
+        de.deserialize_identifier(self)
+    }
+}
+
+impl<'de> Visitor<'de> for Variant {
+    type Value = usize;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "one of the variants {:?}", self.0)
+    }
+
+    fn visit_u64<E: de::Error>(self, index: u64) -> Result<Self::Value, E> {
+        match Some(index as usize).filter(|&i| i < self.0.len()) {
+            Some(i) => Ok(i),
+            None => Err(E::invalid_value(Unexpected::Unsigned(index), &self)),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, name: &str) -> Result<Self::Value, E> {
+        self.0.iter().position(|variant| *variant == name)
+            .ok_or_else(|| E::unknown_variant(name, self.0))
+    }
+
+    fn visit_bytes<E: de::Error>(self, name: &[u8]) -> Result<Self::Value, E> {
+        match std::str::from_utf8(name) {
+            Ok(name) => self.visit_str(name),
+            Err(_) => Err(E::invalid_value(Unexpected::Bytes(name), &self)),
+        }
+    }
+}
+
+impl Serialize for SameSite {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let index = match self {
+            SameSite::Strict => 0,
+            SameSite::Lax => 1,
+            SameSite::None => 2,
+            SameSite::Other(value) => {
+                return serializer.serialize_newtype_variant("SameSite", 3, "Other", value);
+            }
+        };
+
+        serializer.serialize_unit_variant("SameSite", index, SAME_SITE_VARIANTS[index as usize])
+    }
+}
+
+impl<'de> Deserialize<'de> for SameSite {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SameSiteVisitor;
+
+        impl<'de> Visitor<'de> for SameSiteVisitor {
+            type Value = SameSite;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a `SameSite` variant")
+            }
+
+            fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<SameSite, A::Error> {
+                let (index, variant) = data.variant_seed(Variant(SAME_SITE_VARIANTS))?;
+                if index == 3 {
+                    return variant.newtype_variant().map(SameSite::Other);
+                }
+
+                variant.unit_variant()?;
+                Ok(match index {
+                    0 => SameSite::Strict,
+                    1 => SameSite::Lax,
+                    _ => SameSite::None,
+                })
+            }
+        }
+
+        deserializer.deserialize_enum("SameSite", SAME_SITE_VARIANTS, SameSiteVisitor)
+    }
+}
+
+impl Serialize for Priority {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let index = match self {
+            Priority::Low => 0,
+            Priority::Medium => 1,
+            Priority::High => 2,
+        };
+
+        serializer.serialize_unit_variant("Priority", index, PRIORITY_VARIANTS[index as usize])
+    }
+}
+
+impl<'de> Deserialize<'de> for Priority {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct PriorityVisitor;
+
+        impl<'de> Visitor<'de> for PriorityVisitor {
+            type Value = Priority;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a `Priority` variant")
+            }
+
+            fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Priority, A::Error> {
+                let (index, variant) = data.variant_seed(Variant(PRIORITY_VARIANTS))?;
+                variant.unit_variant()?;
+                Ok(match index {
+                    0 => Priority::Low,
+                    1 => Priority::Medium,
+                    _ => Priority::High,
+                })
+            }
+        }
+
+        deserializer.deserialize_enum("Priority", PRIORITY_VARIANTS, PriorityVisitor)
+    }
+}
+
+impl Serialize for Expiration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Expiration::DateTime(time) => {
+                let time = time.format(&Rfc3339).map_err(ser::Error::custom)?;
+                serializer.serialize_newtype_variant("Expiration", 0, "DateTime", &time)
+            }
+            Expiration::Session => serializer.serialize_unit_variant("Expiration", 1, "Session"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Expiration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ExpirationVisitor;
+
+        impl<'de> Visitor<'de> for ExpirationVisitor {
+            type Value = Expiration;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an `Expiration` variant")
+            }
+
+            fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Expiration, A::Error> {
+                match data.variant_seed(Variant(EXPIRATION_VARIANTS))? {
+                    (0, variant) => {
+                        let time: String = variant.newtype_variant()?;
+                        OffsetDateTime::parse(&time, &Rfc3339)
+                            .map(Expiration::DateTime)
+                            .map_err(de::Error::custom)
+                    }
+                    (_, variant) => variant.unit_variant().map(|_| Expiration::Session),
+                }
+            }
+        }
+
+        deserializer.deserialize_enum("Expiration", EXPIRATION_VARIANTS, ExpirationVisitor)
+    }
+}
+
+impl Serialize for Cookie<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let extensions: Vec<_> = self.extensions().collect();
+        let mut state = serializer.serialize_struct("Cookie", COOKIE_FIELDS.len())?;
+        state.serialize_field("name", self.name())?;
+        state.serialize_field("value", self.value())?;
+        state.serialize_field("expires", &self.expires())?;
+        state.serialize_field("max_age", &self.max_age().map(|d| d.whole_seconds()))?;
+        let dot = match self.domain_leading_dot() {
+            true => self.domain().filter(|d| !d.starts_with('.')).map(|d| format!(".{}", d)),
+            false => None,
+        };
+
+        state.serialize_field("domain", &dot.as_deref().or_else(|| self.domain()))?;
+        state.serialize_field("path", &self.path())?;
+        state.serialize_field("secure", &self.secure())?;
+        state.serialize_field("http_only", &self.http_only())?;
+        state.serialize_field("same_site", &self.same_site())?;
+        state.serialize_field("partitioned", &self.partitioned())?;
+        state.serialize_field("priority", &self.priority())?;
+        state.serialize_field("extensions", &extensions)?;
+        state.serialize_field("quoted", &self.is_quoted())?;
+        state.end()
+    }
+}
+
+/// The values of the fields of a serialized struct, read from either a map or
+/// a sequence.
+trait FieldValues<'de> {
+    type Error: de::Error;
+
+    /// Reads the value of the next field.
+    fn next<T: Deserialize<'de>>(&mut self) -> Result<T, Self::Error>;
+}
+
+/// Field values read from a map, after reading the field's key.
+struct MapValues<A>(A);
+
+/// Field values read from a sequence, in order, with the number read so far
+/// and a description of the expected sequence.
+struct SeqValues<A>(A, usize, &'static str);
+
+impl<'de, A: MapAccess<'de>> FieldValues<'de> for MapValues<A> {
+    type Error = A::Error;
+
+    fn next<T: Deserialize<'de>>(&mut self) -> Result<T, A::Error> {
+        self.0.next_value()
+    }
+}
+
+impl<'de, A: SeqAccess<'de>> FieldValues<'de> for SeqValues<A> {
+    type Error = A::Error;
+
+    fn next<T: Deserialize<'de>>(&mut self) -> Result<T, A::Error> {
+        let value = self.0.next_element()?
+            .ok_or_else(|| de::Error::invalid_length(self.1, &self.2))?;
+
+        self.1 += 1;
+        Ok(value)
+    }
+}
+
+/// Reads the value of the field of `cookie` with index `field` in
+/// `COOKIE_FIELDS` from `values` and sets it in `cookie`.
+fn read_field<'de, F: FieldValues<'de>>(
+    cookie: &mut Cookie<'static>,
+    field: usize,
+    values: &mut F
+) -> Result<(), F::Error> {
+    let concrete = |string: String| CookieStr::Concrete(Cow::Owned(string));
+    match field {
+        0 => cookie.set_name(values.next::<String>()?),
+        1 => cookie.value = concrete(values.next()?),
+        2 => cookie.expires = values.next()?,
+        3 => cookie.max_age = values.next::<Option<i64>>()?.map(Duration::seconds),
+        4 => if let Some(domain) = values.next::<Option<String>>()? {
+            match domain.strip_prefix('.') {
+                Some(stripped) => {
+                    cookie.set_domain(stripped.to_string());
+                    cookie.set_domain_leading_dot(true);
+                }
+                None => cookie.set_domain(domain),
+            }
+        },
+        5 => cookie.path = values.next::<Option<String>>()?.map(concrete),
+        6 => cookie.secure = values.next()?,
+        7 => cookie.http_only = values.next()?,
+        8 => cookie.same_site = values.next()?,
+        9 => cookie.partitioned = values.next()?,
+        10 => cookie.priority = values.next()?,
+        11 => {
+            let extensions: Vec<(String, Option<String>)> = values.next()?;
+            cookie.extensions = extensions.into_iter()
+                .map(|(name, value)| (concrete(name), value.map(concrete)))
+                .collect();
+        }
+        _ => cookie.quoted = values.next()?,
+    }
+
+    Ok(())
+}
+
+impl<'de, 'c> Deserialize<'de> for Cookie<'c> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct CookieVisitor;
+
+        impl<'de> Visitor<'de> for CookieVisitor {
+            type Value = Cookie<'static>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a cookie")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+                let mut cookie = Cookie::new("", "");
+                let mut values = SeqValues(seq, 0, "a cookie with 13 fields");
+                for field in 0..COOKIE_FIELDS.len() {
+                    read_field(&mut cookie, field, &mut values)?;
+                }
+
+                Ok(cookie)
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                let mut cookie = Cookie::new("", "");
+                let mut values = MapValues(map);
+                let mut seen = [false; 2];
+                while let Some(field) = values.0.next_key_seed(Field(COOKIE_FIELDS))? {
+                    match field {
+                        Some(field) => read_field(&mut cookie, field, &mut values)?,
+                        None => drop(values.0.next_value::<de::IgnoredAny>()?),
+                    }
+
+                    if let Some(seen) = field.and_then(|i| seen.get_mut(i)) {
+                        *seen = true;
+                    }
+                }
+
+                match seen.iter().position(|seen| !seen) {
+                    Some(i) => Err(de::Error::missing_field(COOKIE_FIELDS[i])),
+                    None => Ok(cookie),
+                }
+            }
+        }
+
+        let cookie = deserializer.deserialize_struct("Cookie", COOKIE_FIELDS, CookieVisitor)?;
+        Ok(cookie)
+    }
+}
+
+impl Serialize for CookieJar {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let cookies = |removed: bool| self.delta_cookies.iter()
+            .filter(move |c| c.removed == removed)
+            .map(|c| &c.cookie)
+            .collect::<Vec<_>>();
+
+        let original: Vec<_> = self.original_cookies.iter().map(|c| &c.cookie).collect();
+        let mut state = serializer.serialize_struct("CookieJar", JAR_FIELDS.len())?;
+        state.serialize_field("original", &original)?;
+        state.serialize_field("added", &cookies(false))?;
+        state.serialize_field("removed", &cookies(true))?;
+        state.end()
+    }
+}
+
+/// Reads the value of the field of `jar` with index `field` in `JAR_FIELDS`
+/// from `values` and adds its cookies to `jar`.
+fn read_jar_field<'de, F: FieldValues<'de>>(
+    jar: &mut CookieJar,
+    field: usize,
+    values: &mut F
+) -> Result<(), F::Error> {
+    let cookies: Vec<Cookie<'static>> = values.next()?;
+    for cookie in cookies {
+        match field {
+            0 => jar.original_cookies.replace(DeltaCookie::added(cookie)),
+            1 => jar.delta_cookies.replace(DeltaCookie::added(cookie)),
+            _ => jar.delta_cookies.replace(DeltaCookie::removed(cookie)),
+        };
+    }
+
+    Ok(())
+}
+
+impl<'de> Deserialize<'de> for CookieJar {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct JarVisitor;
+
+        impl<'de> Visitor<'de> for JarVisitor {
+            type Value = CookieJar;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a cookie jar")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<CookieJar, A::Error> {
+                let mut jar = CookieJar::new();
+                let mut values = SeqValues(seq, 0, "a cookie jar with 3 fields");
+                for field in 0..JAR_FIELDS.len() {
+                    read_jar_field(&mut jar, field, &mut values)?;
+                }
+
+                Ok(jar)
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<CookieJar, A::Error> {
+                let mut jar = CookieJar::new();
+                let mut values = MapValues(map);
+                while let Some(field) = values.0.next_key_seed(Field(JAR_FIELDS))? {
+                    match field {
+                        Some(field) => read_jar_field(&mut jar, field, &mut values)?,
+                        None => drop(values.0.next_value::<de::IgnoredAny>()?),
+                    }
+                }
+
+                Ok(jar)
+            }
+        }
+
+        deserializer.deserialize_struct("CookieJar", JAR_FIELDS, JarVisitor)
+    }
+}
+
+#[cfg(feature = "signed")]
+impl Serialize for Claims {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let claims: Vec<_> = self.iter().collect();
+        let mut state = serializer.serialize_struct("Claims", CLAIMS_FIELDS.len())?;
+        state.serialize_field("iat", &self.issued_at().unix_timestamp())?;
+        state.serialize_field("exp", &self.expires().map(|e| e.unix_timestamp()))?;
+        state.serialize_field("claims", &claims)?;
+        state.end()
+    }
+}
+
+/// Reads the value of the field of `claims` with index `field` in
+/// `CLAIMS_FIELDS` from `values` and sets it in `claims`.
+#[cfg(feature = "signed")]
+fn read_claims_field<'de, F: FieldValues<'de>>(
+    claims: &mut Claims,
+    field: usize,
+    values: &mut F
+) -> Result<(), F::Error> {
+    let timestamp = |t| OffsetDateTime::from_unix_timestamp(t).map_err(de::Error::custom);
+    match field {
+        0 => claims.issued_at = timestamp(values.next()?)?,
+        1 => claims.expires = values.next::<Option<i64>>()?.map(timestamp).transpose()?,
+        _ => claims.claims = values.next::<Vec<(String, String)>>()?.into_iter().collect(),
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "signed")]
+impl<'de> Deserialize<'de> for Claims {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ClaimsVisitor;
+
+        impl<'de> Visitor<'de> for ClaimsVisitor {
+            type Value = Claims;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a set of claims")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Claims, A::Error> {
+                let mut claims = Claims::new();
+                let mut values = SeqValues(seq, 0, "claims with 3 fields");
+                for field in 0..CLAIMS_FIELDS.len() {
+                    read_claims_field(&mut claims, field, &mut values)?;
+                }
+
+                Ok(claims)
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Claims, A::Error> {
+                let mut claims = Claims::new();
+                let mut values = MapValues(map);
+                let mut seen_iat = false;
+                while let Some(field) = values.0.next_key_seed(Field(CLAIMS_FIELDS))? {
+                    match field {
+                        Some(field) => read_claims_field(&mut claims, field, &mut values)?,
+                        None => drop(values.0.next_value::<de::IgnoredAny>()?),
+                    }
+
+                    seen_iat |= field == Some(0);
+                }
+
+                match seen_iat {
+                    true => Ok(claims),
+                    false => Err(de::Error::missing_field(CLAIMS_FIELDS[0])),
+                }
+            }
+        }
+
+        deserializer.deserialize_struct("Claims", CLAIMS_FIELDS, ClaimsVisitor)
+    }
+}
+
+/// Serialization of a [`CookieJar`] as just its cookies, discarding its delta.
+///
+/// Unlike the `Serialize` implementation of `CookieJar`, which records which
+/// cookies are original and which were added or removed, [`serialize()`]
+/// writes only the cookies currently in the jar, as returned by
+/// [`CookieJar::iter()`], as a sequence of cookies. [`deserialize()`] reads
+/// such a sequence into a jar, adding each cookie as an original one. The
+/// functions can be used directly or, with serde's derive macros, via a
+/// `#[serde(with = "cookie::cookies_only")]` field attribute.
+#[cfg_attr(all(nightly, doc), doc(cfg(feature = "serde")))]
+pub mod cookies_only {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::{Cookie, CookieJar};
+
+    /// Serializes the cookies in `jar` as a sequence, discarding its delta.
+    pub fn serialize<S: Serializer>(jar: &CookieJar, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(jar.iter())
+    }
+
+    /// Deserializes a sequence of cookies into a new jar, each cookie added
+    /// as an original cookie.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<CookieJar, D::Error> {
+        let mut jar = CookieJar::new();
+        for cookie in Vec::<Cookie<'static>>::deserialize(deserializer)? {
+            jar.add_original(cookie);
+        }
+
+        Ok(jar)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::de::value::{Error, MapAccessDeserializer, MapDeserializer, SeqDeserializer};
+    use serde::de::{self, Deserialize, IntoDeserializer, Visitor};
+    use serde::ser::{self, Impossible, Serialize};
+
+    use crate::{Cookie, CookieJar, Expiration, Priority, SameSite};
+    use time::{Duration, OffsetDateTime};
+
+    /// A minimal self-describing data format: serialized values, kept in memory.
+    #[derive(Debug, Clone, PartialEq)]
+    enum Value {
+        Bool(bool),
+        I64(i64),
+        Str(String),
+        Option(Option<Box<Value>>),
+        Seq(Vec<Value>),
+        Struct(Vec<(String, Value)>),
+        Variant(String, Option<Box<Value>>),
+    }
+
+    /// Serializes `value` into a `Value`.
+    fn to_value<T: Serialize + ?Sized>(value: &T) -> Value {
+        value.serialize(ValueSerializer).expect("serializable value")
+    }
+
+    /// Deserializes a `T` from `value`.
+    fn from_value<T: for<'de> Deserialize<'de>>(value: Value) -> Result<T, Error> {
+        T::deserialize(value)
+    }
+
+    /// Serializes `value` into a `Value` and back.
+    fn roundtrip<T: Serialize + for<'de> Deserialize<'de>>(value: &T) -> T {
+        from_value(to_value(value)).expect("deserializable value")
+    }
+
+    struct ValueSerializer;
+
+    /// A sequence or struct being serialized.
+    struct Compound(Vec<(String, Value)>);
+
+    fn unsupported<T>() -> Result<T, Error> {
+        Err(ser::Error::custom("unsupported by the test format"))
+    }
+
+    impl ser::Serializer for ValueSerializer {
+        type Ok = Value;
+        type Error = Error;
+        type SerializeSeq = Compound;
+        type SerializeTuple = Compound;
+        type SerializeTupleStruct = Impossible<Value, Error>;
+        type SerializeTupleVariant = Impossible<Value, Error>;
+        type SerializeMap = Impossible<Value, Error>;
+        type SerializeStruct = Compound;
+        type SerializeStructVariant = Impossible<Value, Error>;
+
+        fn serialize_bool(self, v: bool) -> Result<Value, Error> { Ok(Value::Bool(v)) }
+        fn serialize_i8(self, _: i8) -> Result<Value, Error> { unsupported() }
+        fn serialize_i16(self, _: i16) -> Result<Value, Error> { unsupported() }
+        fn serialize_i32(self, _: i32) -> Result<Value, Error> { unsupported() }
+        fn serialize_i64(self, v: i64) -> Result<Value, Error> { Ok(Value::I64(v)) }
+        fn serialize_u8(self, _: u8) -> Result<Value, Error> { unsupported() }
+        fn serialize_u16(self, _: u16) -> Result<Value, Error> { unsupported() }
+        fn serialize_u32(self, _: u32) -> Result<Value, Error> { unsupported() }
+        fn serialize_u64(self, _: u64) -> Result<Value, Error> { unsupported() }
+        fn serialize_f32(self, _: f32) -> Result<Value, Error> { unsupported() }
+        fn serialize_f64(self, _: f64) -> Result<Value, Error> { unsupported() }
+        fn serialize_char(self, _: char) -> Result<Value, Error> { unsupported() }
+        fn serialize_str(self, v: &str) -> Result<Value, Error> { Ok(Value::Str(v.into())) }
+        fn serialize_bytes(self, _: &[u8]) -> Result<Value, Error> { unsupported() }
+        fn serialize_none(self) -> Result<Value, Error> { Ok(Value::Option(None)) }
+        fn serialize_unit(self) -> Result<Value, Error> { unsupported() }
+        fn serialize_unit_struct(self, _: &str) -> Result<Value, Error> { unsupported() }
+
+        fn serialize_some<T: Serialize + ?Sized>(self, v: &T) -> Result<Value, Error> {
+            Ok(Value::Option(Some(Box::new(v.serialize(self)?))))
+        }
+
+        fn serialize_unit_variant(self, _: &str, _: u32, v: &str) -> Result<Value, Error> {
+            Ok(Value::Variant(v.into(), None))
+        }
+
+        fn serialize_newtype_struct<T: Serialize + ?Sized>(self, _: &str, _: &T)
+            -> Result<Value, Error>
+        {
+            unsupported()
+        }
+
+        fn serialize_newtype_variant<T: Serialize + ?Sized>(self, _: &str, _: u32, v: &str, x: &T)
+            -> Result<Value, Error>
+        {
+            Ok(Value::Variant(v.into(), Some(Box::new(x.serialize(self)?))))
+        }
+
+        fn serialize_seq(self, _: Option<usize>) -> Result<Compound, Error> {
+            Ok(Compound(vec![]))
+        }
+
+        fn serialize_tuple(self, _: usize) -> Result<Compound, Error> {
+            Ok(Compound(vec![]))
+        }
+
+        fn serialize_tuple_struct(self, _: &str, _: usize)
+            -> Result<Self::SerializeTupleStruct, Error>
+        {
+            unsupported()
+        }
+
+        fn serialize_tuple_variant(self, _: &str, _: u32, _: &str, _: usize)
+            -> Result<Self::SerializeTupleVariant, Error>
+        {
+            unsupported()
+        }
+
+        fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, Error> {
+            unsupported()
+        }
+
+        fn serialize_struct(self, _: &str, _: usize) -> Result<Compound, Error> {
+            Ok(Compound(vec![]))
+        }
+
+        fn serialize_struct_variant(self, _: &str, _: u32, _: &str, _: usize)
+            -> Result<Self::SerializeStructVariant, Error>
+        {
+            unsupported()
+        }
+    }
+
+    impl ser::SerializeSeq for Compound {
+        type Ok = Value;
+        type Error = Error;
+
+        fn serialize_element<T: Serialize + ?Sized>(&mut self, v: &T) -> Result<(), Error> {
+            self.0.push((String::new(), v.serialize(ValueSerializer)?));
+            Ok(())
+        }
+
+        fn end(self) -> Result<Value, Error> {
+            Ok(Value::Seq(self.0.into_iter().map(|(_, v)| v).collect()))
+        }
+    }
+
+    impl ser::SerializeTuple for Compound {
+        type Ok = Value;
+        type Error = Error;
+
+        fn serialize_element<T: Serialize + ?Sized>(&mut self, v: &T) -> Result<(), Error> {
+            ser::SerializeSeq::serialize_element(self, v)
+        }
+
+        fn end(self) -> Result<Value, Error> {
+            ser::SerializeSeq::end(self)
+        }
+    }
+
+    impl ser::SerializeStruct for Compound {
+        type Ok = Value;
+        type Error = Error;
+
+        fn serialize_field<T: Serialize + ?Sized>(&mut self, k: &str, v: &T) -> Result<(), Error> {
+            self.0.push((k.into(), v.serialize(ValueSerializer)?));
+            Ok(())
+        }
+
+        fn end(self) -> Result<Value, Error> {
+            Ok(Value::Struct(self.0))
+        }
+    }
+
+    impl<'de> IntoDeserializer<'de, Error> for Value {
+        type Deserializer = Value;
+
+        fn into_deserializer(self) -> Value {
+            self
+        }
+    }
+
+    impl<'de> de::Deserializer<'de> for Value {
+        type Error = Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            match self {
+                Value::Bool(v) => visitor.visit_bool(v),
+                Value::I64(v) => visitor.visit_i64(v),
+                Value::Str(v) => visitor.visit_string(v),
+                Value::Option(None) => visitor.visit_none(),
+                Value::Option(Some(v)) => visitor.visit_some(*v),
+                Value::Seq(v) => visitor.visit_seq(SeqDeserializer::new(v.into_iter())),
+                Value::Struct(v) => visitor.visit_map(MapDeserializer::new(v.into_iter())),
+                Value::Variant(..) => self.deserialize_enum("", &[], visitor),
+            }
+        }
+
+        fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            match self {
+                Value::Option(_) => self.deserialize_any(visitor),
+                v => visitor.visit_some(v),
+            }
+        }
+
+        fn deserialize_enum<V: Visitor<'de>>(self, _: &str, _: &[&str], visitor: V)
+            -> Result<V::Value, Error>
+        {
+            match self {
+                Value::Variant(name, None) => visitor.visit_enum(name.into_deserializer()),
+                Value::Variant(name, Some(v)) => {
+                    let map = MapDeserializer::new(std::iter::once((name, *v)));
+                    visitor.visit_enum(MapAccessDeserializer::new(map))
+                }
+                _ => Err(de::Error::custom("expected a variant")),
+            }
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+            bytes byte_buf unit unit_struct newtype_struct seq tuple
+            tuple_struct map struct identifier ignored_any
+        }
+    }
+
+    /// Returns `value` without the struct field named `name`.
+    fn without(value: Value, name: &str) -> Value {
+        match value {
+            Value::Struct(fields) => Value::Struct(fields.into_iter()
+                .filter(|(n, _)| n != name)
+                .collect()),
+            value => value,
+        }
+    }
+
+    #[test]
+    fn cookie_roundtrip() {
+        let cookie = Cookie::parse("name=value; HttpOnly; Secure; SameSite=Lax; Partitioned; \
+            Path=/; Domain=.example.com; Max-Age=60; Expires=Wed, 21 Oct 2015 07:28:00 GMT; \
+            Priority=High; X-Flag; X-Flag").unwrap();
+
+        let decoded: Cookie = roundtrip(&cookie);
+        assert_eq!(decoded, cookie);
+        assert_eq!(decoded.to_string(), cookie.to_string());
+        assert_eq!(decoded.same_site(), Some(SameSite::Lax));
+        assert_eq!(decoded.priority(), Some(Priority::High));
+        assert_eq!(decoded.extensions().count(), 2);
+        assert_eq!(decoded.domain(), Some("example.com"));
+        assert!(decoded.domain_leading_dot());
+
+        let cookie = Cookie::parse("name=\"a value\"; Path=/").unwrap();
+        let decoded: Cookie = roundtrip(&cookie);
+        assert!(decoded.is_quoted());
+        assert_eq!(decoded.value(), "a value");
+        assert_eq!(decoded.to_string(), cookie.to_string());
+
+        let cookie = Cookie::new("name", "value");
+        let decoded: Cookie = roundtrip(&cookie);
+        assert_eq!(decoded, cookie);
+        assert_eq!(decoded.to_string(), "name=value");
+        assert_eq!(decoded.secure(), None);
+
+        let cookie = Cookie::build("a", "b").expires(Expiration::Session)
+            .same_site(SameSite::None).max_age(Duration::seconds(-1)).finish();
+        assert_eq!(roundtrip(&cookie), cookie);
+        assert_eq!(roundtrip(&cookie).expires(), Some(Expiration::Session));
+
+        // Expiration date-times keep their fractional seconds and offset.
+        let time = time::macros::datetime!(2015-10-21 07:28:00.123456789 +02:30);
+        let cookie = Cookie::build("a", "b").expires(time).finish();
+        let expires = roundtrip(&cookie).expires_datetime().unwrap();
+        assert_eq!((expires, expires.offset()), (time, time.offset()));
+        assert_eq!(roundtrip(&Expiration::from(time)), Expiration::from(time));
+    }
+
+    #[test]
+    fn cookie_encoding() {
+        let cookie = Cookie::build("a", "b").path("/").same_site(SameSite::Strict)
+            .expires(OffsetDateTime::UNIX_EPOCH).priority(Priority::Low)
+            .extension("X", None::<&str>).finish();
+
+        let none = || Value::Option(None);
+        let some = |v| Value::Option(Some(Box::new(v)));
+        let string = |s: &str| Value::Str(s.into());
+        let variant = |name: &str, v: Option<Value>| Value::Variant(name.into(), v.map(Box::new));
+        let expected = vec![
+            ("name", string("a")),
+            ("value", string("b")),
+            ("expires", some(variant("DateTime", Some(string("1970-01-01T00:00:00Z"))))),
+            ("max_age", none()),
+            ("domain", none()),
+            ("path", some(string("/"))),
+            ("secure", none()),
+            ("http_only", none()),
+            ("same_site", some(variant("Strict", None))),
+            ("partitioned", none()),
+            ("priority", some(variant("Low", None))),
+            ("extensions", Value::Seq(vec![Value::Seq(vec![string("X"), none()])])),
+            ("quoted", Value::Bool(false)),
+        ];
+
+        let value = to_value(&cookie);
+        let expected: Vec<_> = expected.into_iter().map(|(n, v)| (n.to_string(), v)).collect();
+        assert_eq!(value, Value::Struct(expected.clone()));
+
+        // Fields can be omitted, except for `name` and `value`, and can be
+        // sequences. Unknown fields are ignored.
+        assert_eq!(from_value::<Cookie>(without(value.clone(), "path")).unwrap().path(), None);
+        assert!(!from_value::<Cookie>(without(value.clone(), "quoted")).unwrap().is_quoted());
+        assert!(from_value::<Cookie>(without(value.clone(), "name")).is_err());
+        assert!(from_value::<Cookie>(without(value.clone(), "value")).is_err());
+        let seq = Value::Seq(expected.iter().map(|(_, v)| v.clone()).collect());
+        assert_eq!(from_value::<Cookie>(seq).unwrap(), cookie);
+        let short = Value::Seq(expected.iter().take(11).map(|(_, v)| v.clone()).collect());
+        assert!(from_value::<Cookie>(short).is_err());
+        let mut fields = expected;
+        fields.push(("unknown".into(), Value::Bool(true)));
+        assert_eq!(from_value::<Cookie>(Value::Struct(fields)).unwrap(), cookie);
+
+        assert!(from_value::<SameSite>(variant("Sometimes", None)).is_err());
+        assert!(from_value::<SameSite>(variant("Other", None)).is_err());
+        let other = SameSite::Other("Sometimes".into());
+        assert_eq!(to_value(&other), variant("Other", Some(string("Sometimes"))));
+        assert_eq!(from_value::<SameSite>(to_value(&other)).unwrap(), other);
+        assert!(from_value::<Priority>(variant("Urgent", None)).is_err());
+        assert!(from_value::<Expiration>(variant("DateTime", Some(Value::I64(0)))).is_err());
+        assert!(from_value::<Expiration>(variant("DateTime", Some(string("1970")))).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "signed")]
+    fn claims_roundtrip() {
+        use crate::Claims;
+
+        let claims = Claims::new().with("user", "1234").with("role", "")
+            .with_expiry(Duration::hours(1));
+        assert_eq!(roundtrip(&claims), claims);
+        assert_eq!(roundtrip(&Claims::new()), Claims::new());
+
+        let string = |s: &str| Value::Str(s.into());
+        let expected = Value::Struct(vec![
+            ("iat".into(), Value::I64(0)),
+            ("exp".into(), Value::Option(Some(Box::new(Value::I64(60))))),
+            ("claims".into(), Value::Seq(vec![Value::Seq(vec![string("a"), string("1")])])),
+        ]);
+
+        let decoded: Claims = from_value(expected.clone()).unwrap();
+        assert_eq!(decoded.issued_at(), OffsetDateTime::UNIX_EPOCH);
+        assert_eq!(decoded.expires(), Some(OffsetDateTime::UNIX_EPOCH + Duration::minutes(1)));
+        assert_eq!(decoded.get("a"), Some("1"));
+        assert_eq!(to_value(&decoded), expected);
+
+        // Only `iat` is required; timestamps must be in range.
+        let claims: Claims = from_value(without(expected.clone(), "exp")).unwrap();
+        assert_eq!(claims.expires(), None);
+        assert!(from_value::<Claims>(without(expected.clone(), "iat")).is_err());
+        let bad = Value::Seq(vec![Value::I64(i64::MAX), Value::Option(None), Value::Seq(vec![])]);
+        assert!(from_value::<Claims>(bad).is_err());
+    }
+
+    #[test]
+    fn jar_roundtrip() {
+        let mut jar = CookieJar::new();
+        jar.add_original(Cookie::new("original", "1"));
+        jar.add_original(Cookie::new("removed", "2"));
+        jar.add_original(Cookie::new("replaced", "3"));
+        jar.add(Cookie::build("added", "4").path("/").finish());
+        jar.add(Cookie::new("replaced", "5"));
+        jar.remove(Cookie::named("removed"));
+
+        let names = |jar: &CookieJar, delta: bool| {
+            let cookies: Box<dyn Iterator<Item = _>> = match delta {
+                true => Box::new(jar.delta()),
+                false => Box::new(jar.iter()),
+            };
+
+            let mut cookies: Vec<_> = cookies.map(|c| c.to_string()).collect();
+            cookies.sort();
+            cookies
+        };
+
+        let decoded: CookieJar = roundtrip(&jar);
+        assert_eq!(names(&decoded, false), names(&jar, false));
+        assert_eq!(names(&decoded, true), names(&jar, true));
+        assert_eq!(decoded.removals().count(), 1);
+
+        let mut reset = decoded.clone();
+        reset.reset_delta();
+        assert_eq!(reset.get("removed").unwrap().value(), "2");
+        assert_eq!(reset.get("replaced").unwrap().value(), "3");
+
+        // Without a delta, or as just the cookies, everything is original.
+        let original = match to_value(&jar) {
+            Value::Struct(fields) => Value::Struct(fields.into_iter().take(1).collect()),
+            _ => unreachable!("a jar is a struct"),
+        };
+
+        let decoded: CookieJar = from_value(original).unwrap();
+        assert_eq!(decoded.iter().count(), 3);
+        assert_eq!(decoded.delta().count(), 0);
+
+        let value = super::cookies_only::serialize(&jar, ValueSerializer).unwrap();
+        let decoded = super::cookies_only::deserialize(value).unwrap();
+        assert_eq!(names(&decoded, false), names(&jar, false));
+        assert_eq!(decoded.delta().count(), 0);
+    }
+}
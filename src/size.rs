@@ -0,0 +1,129 @@
+use std::error::Error;
+use std::fmt;
+
+/// The default limit, in bytes, on the size of a serialized cookie, as
+/// enforced by browsers.
+///
+/// Browsers cap the size of a single cookie at about 4096 bytes and silently
+/// truncate or drop larger cookies. Use [`Cookie::check_size()`] and
+/// [`CookieJar::check_size()`] to detect such cookies before they are sent.
+///
+/// [`Cookie::check_size()`]: crate::Cookie::check_size()
+/// [`CookieJar::check_size()`]: crate::CookieJar::check_size()
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{Cookie, DEFAULT_SIZE_LIMIT};
+///
+/// let c = Cookie::new("name", "value");
+/// assert!(c.check_size(DEFAULT_SIZE_LIMIT).is_ok());
+/// ```
+pub const DEFAULT_SIZE_LIMIT: usize = 4096;
+
+/// Error returned when a serialized cookie exceeds a size limit.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::Cookie;
+///
+/// let c = Cookie::new("name", "a".repeat(100));
+/// let error = c.check_size(64).unwrap_err();
+/// assert_eq!(error.name(), "name");
+/// assert_eq!(error.size(), 105);
+/// assert_eq!(error.limit(), 64);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SizeError {
+    name: String,
+    size: usize,
+    limit: usize,
+}
+
+impl SizeError {
+    /// Creates a new `SizeError` for the cookie named `name`, which serializes
+    /// to `size` bytes, over `limit`.
+    pub(crate) fn new(name: &str, size: usize, limit: usize) -> SizeError {
+        SizeError { name: name.to_string(), size, limit }
+    }
+
+    /// Returns the name of the oversized cookie.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Cookie;
+    ///
+    /// let error = Cookie::new("name", "value").check_size(4).unwrap_err();
+    /// assert_eq!(error.name(), "name");
+    /// ```
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the size, in bytes, of the oversized cookie when serialized.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Cookie;
+    ///
+    /// let error = Cookie::new("name", "value").check_size(4).unwrap_err();
+    /// assert_eq!(error.size(), "name=value".len());
+    /// ```
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the size limit, in bytes, that the cookie exceeds.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::Cookie;
+    ///
+    /// let error = Cookie::new("name", "value").check_size(4).unwrap_err();
+    /// assert_eq!(error.limit(), 4);
+    /// ```
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+impl fmt::Display for SizeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "cookie `{}` is {} bytes, over the {} byte limit",
+            self.name, self.size, self.limit)
+    }
+}
+
+impl Error for SizeError {}
+
+#[cfg(test)]
+mod test {
+    use crate::{Cookie, CookieJar, DEFAULT_SIZE_LIMIT};
+
+    #[test]
+    fn size_limits() {
+        let cookie = Cookie::build("name", "value").path("/").secure(true).finish();
+        let size = cookie.to_string().len();
+        assert_eq!(cookie.serialized_len(), size);
+        assert!(cookie.check_size(size).is_ok());
+        assert_eq!(cookie.check_size(size - 1).unwrap_err().size(), size);
+
+        let value = "v".repeat(DEFAULT_SIZE_LIMIT - "name=".len());
+        assert!(Cookie::new("name", value.clone()).check_size(DEFAULT_SIZE_LIMIT).is_ok());
+        let cookie = Cookie::build("name", value).http_only(true).finish();
+        let error = cookie.check_size(DEFAULT_SIZE_LIMIT).unwrap_err();
+        assert_eq!(error.to_string(), "cookie `name` is 4106 bytes, over the 4096 byte limit");
+
+        let mut jar = CookieJar::new();
+        jar.add_original(cookie.clone());
+        assert!(jar.check_size(DEFAULT_SIZE_LIMIT).is_ok());
+        jar.add(cookie);
+        assert!(jar.check_size(DEFAULT_SIZE_LIMIT).is_err());
+        jar.remove(Cookie::named("name"));
+        assert!(jar.check_size(DEFAULT_SIZE_LIMIT).is_ok());
+    }
+}
@@ -0,0 +1,113 @@
+/// Iterator over the individual `Set-Cookie` values in a folded header.
+///
+/// Some proxies and HTTP/1 client libraries fold multiple `Set-Cookie` headers
+/// into a single header whose values are joined by commas. Because the
+/// `Expires` attribute's date itself contains a comma, the folded value cannot
+/// be split on every comma. Instead, like browsers, a `SetCookieSplitter` only
+/// splits at a comma that is followed by what looks like the start of a new
+/// cookie: a non-empty name followed by `=` before any `;` or `,`.
+///
+/// Each value is yielded with leading and trailing whitespace trimmed; empty
+/// values are skipped. The values are not parsed: pass them to
+/// [`Cookie::parse()`](crate::Cookie::parse()) or a related method.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{Cookie, SetCookieSplitter};
+///
+/// let header = "a=b; Expires=Wed, 21 Oct 2015 07:28:00 GMT, c=d; Path=/";
+/// let values: Vec<_> = SetCookieSplitter::new(header).collect();
+/// assert_eq!(values, ["a=b; Expires=Wed, 21 Oct 2015 07:28:00 GMT", "c=d; Path=/"]);
+///
+/// let cookies: Vec<_> = SetCookieSplitter::new(header)
+///     .map(Cookie::parse)
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+///
+/// assert_eq!(cookies[0].expires_datetime().unwrap().year(), 2015);
+/// assert_eq!(cookies[1].path(), Some("/"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SetCookieSplitter<'a> {
+    rest: &'a str,
+}
+
+impl<'a> SetCookieSplitter<'a> {
+    /// Creates a new `SetCookieSplitter` over the folded header value `header`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::SetCookieSplitter;
+    ///
+    /// let mut splitter = SetCookieSplitter::new("a=b, c=d");
+    /// assert_eq!(splitter.next(), Some("a=b"));
+    /// assert_eq!(splitter.next(), Some("c=d"));
+    /// assert_eq!(splitter.next(), None);
+    /// ```
+    pub fn new(header: &'a str) -> SetCookieSplitter<'a> {
+        SetCookieSplitter { rest: header }
+    }
+}
+
+/// Returns `true` if `s`, the remainder of a header after a comma, starts with
+/// a new cookie: a non-empty name followed by `=` before any `;` or `,`.
+fn starts_cookie(s: &str) -> bool {
+    match s.find(&['=', ';', ','][..]) {
+        Some(i) => s[i..].starts_with('=') && !s[..i].trim().is_empty(),
+        None => false,
+    }
+}
+
+impl<'a> Iterator for SetCookieSplitter<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        while !self.rest.is_empty() {
+            let end = self.rest.match_indices(',')
+                .map(|(i, _)| i)
+                .find(|&i| starts_cookie(&self.rest[(i + 1)..]))
+                .unwrap_or(self.rest.len());
+
+            let value = self.rest[..end].trim();
+            self.rest = self.rest.get((end + 1)..).unwrap_or("");
+            if !value.is_empty() {
+                return Some(value);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SetCookieSplitter;
+
+    fn split(header: &str) -> Vec<&str> {
+        SetCookieSplitter::new(header).collect()
+    }
+
+    #[test]
+    fn splitting() {
+        assert!(split("").is_empty());
+        assert!(split("  ").is_empty());
+        assert_eq!(split("a=b"), ["a=b"]);
+        assert_eq!(split(" a=b ,c=d,  e=f "), ["a=b", "c=d", "e=f"]);
+        assert_eq!(split("a=b; Path=/, c=d; Secure"), ["a=b; Path=/", "c=d; Secure"]);
+
+        // Commas in dates, values, and attributes don't start a new cookie.
+        let expires = "a=b; Expires=Wed, 21 Oct 2015 07:28:00 GMT";
+        assert_eq!(split(expires), [expires]);
+        assert_eq!(split(&format!("{}, c=d", expires)), [expires, "c=d"]);
+        assert_eq!(split(&format!("c=d, {}", expires)), ["c=d", expires]);
+        assert_eq!(split("a=b,c,d; Path=/"), ["a=b,c,d; Path=/"]);
+        assert_eq!(split("a=b; Ext=x,y; Path=/"), ["a=b; Ext=x,y; Path=/"]);
+        assert_eq!(split("a=b, =c"), ["a=b, =c"]);
+
+        // Like in browsers, stray commas before a new cookie are kept.
+        assert_eq!(split("a=, b=, , c="), ["a=", "b=,", "c="]);
+        assert_eq!(split("a=b,, c=d"), ["a=b,", "c=d"]);
+    }
+}
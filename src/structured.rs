@@ -0,0 +1,865 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::encoding::{self, Alphabet};
+
+/// The largest magnitude of an integer that can be serialized.
+const MAX_INTEGER: i64 = 999_999_999_999_999;
+
+/// The most digits in the integer part of a decimal that can be serialized.
+const MAX_DECIMAL_DIGITS: usize = 12;
+
+/// A bare item of an [RFC 8941] structured field: a typed value that can be
+/// stored in, and retrieved from, a cookie value in a form that other tooling
+/// can parse generically.
+///
+/// Items are serialized as RFC 8941 prescribes with
+/// [`StructuredItem::serialize()`] and parsed with [`StructuredItem::parse()`]
+/// or, from a cookie, with [`Cookie::structured_item()`]. Parameters, which
+/// are separated by `;`, can't appear in a cookie value and are not supported.
+///
+/// [RFC 8941]: https://datatracker.ietf.org/doc/html/rfc8941
+/// [`Cookie::structured_item()`]: crate::Cookie::structured_item()
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{Cookie, StructuredItem};
+///
+/// let item = StructuredItem::ByteSequence(vec![0xde, 0xad, 0xbe, 0xef]);
+/// let cookie = Cookie::new("id", item.serialize().unwrap());
+/// assert_eq!(cookie.to_string(), "id=:3q2+7w==:");
+///
+/// let cookie = Cookie::parse(cookie.to_string()).unwrap();
+/// assert_eq!(cookie.structured_item(), Ok(item));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum StructuredItem {
+    /// An integer, of at most 15 digits.
+    Integer(i64),
+    /// A decimal number, with at most 12 integer and 3 fractional digits.
+    /// More fractional digits are rounded away when serialized.
+    Decimal(f64),
+    /// A string of printable ASCII characters, written in double quotes.
+    String(String),
+    /// A token: a short, unquoted textual identifier such as `gzip` or
+    /// `text/html`.
+    Token(String),
+    /// A sequence of bytes, written in base64 between colons.
+    ByteSequence(Vec<u8>),
+    /// A boolean, written as `?1` or `?0`.
+    Boolean(bool),
+}
+
+/// An ordered map from keys to [`StructuredItem`]s, as in the [RFC 8941]
+/// structured field dictionary `a=1,b=?0,c="text"`.
+///
+/// Dictionaries are serialized with [`StructuredDictionary::serialize()`],
+/// without the optional whitespace after each `,`, and parsed with
+/// [`StructuredDictionary::parse()`] or, from a cookie, with
+/// [`Cookie::structured_dictionary()`]. A member whose value is
+/// `Boolean(true)` is written as only its key. Parameters, which are separated
+/// by `;`, can't appear in a cookie value; they and inner lists are not
+/// supported.
+///
+/// [RFC 8941]: https://datatracker.ietf.org/doc/html/rfc8941
+/// [`Cookie::structured_dictionary()`]: crate::Cookie::structured_dictionary()
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{Cookie, StructuredDictionary, StructuredItem};
+///
+/// let mut prefs = StructuredDictionary::new();
+/// prefs.insert("visits", StructuredItem::Integer(42));
+/// prefs.insert("theme", StructuredItem::Token("dark".into()));
+/// prefs.insert("beta", StructuredItem::Boolean(true));
+///
+/// let cookie = Cookie::new("prefs", prefs.serialize().unwrap());
+/// assert_eq!(cookie.value(), "visits=42,theme=dark,beta");
+///
+/// let prefs = cookie.structured_dictionary().unwrap();
+/// assert_eq!(prefs.get("visits"), Some(&StructuredItem::Integer(42)));
+/// assert_eq!(prefs.get("beta").and_then(|b| b.as_bool()), Some(true));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct StructuredDictionary {
+    members: Vec<(String, StructuredItem)>,
+}
+
+/// Error returned when a structured field can't be parsed or serialized.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{StructuredFieldError, StructuredItem};
+///
+/// let error = StructuredItem::parse("?2").unwrap_err();
+/// assert_eq!(error, StructuredFieldError::InvalidSyntax { at: 1 });
+///
+/// let error = StructuredItem::Token("1a".into()).serialize().unwrap_err();
+/// assert_eq!(error, StructuredFieldError::Unserializable);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum StructuredFieldError {
+    /// The string isn't a valid structured field: it's invalid starting at
+    /// byte offset `at`.
+    InvalidSyntax {
+        /// The byte offset at which the string is invalid.
+        at: usize,
+    },
+    /// The string contains a parameter or an inner list, which aren't
+    /// supported, starting at byte offset `at`.
+    Unsupported {
+        /// The byte offset of the parameter or inner list.
+        at: usize,
+    },
+    /// The item or dictionary can't be serialized: a number is out of range,
+    /// a string contains a character other than printable ASCII or a `;`, or a
+    /// token or key is malformed.
+    Unserializable,
+}
+
+impl fmt::Display for StructuredFieldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            StructuredFieldError::InvalidSyntax { at } => {
+                write!(f, "invalid structured field at byte {}", at)
+            }
+            StructuredFieldError::Unsupported { at } => {
+                write!(f, "unsupported structured field parameter or inner list at byte {}", at)
+            }
+            StructuredFieldError::Unserializable => {
+                write!(f, "structured field value can't be serialized")
+            }
+        }
+    }
+}
+
+impl Error for StructuredFieldError {}
+
+impl StructuredItem {
+    /// Parses the structured field item `value`, ignoring surrounding spaces.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::StructuredItem;
+    ///
+    /// assert_eq!(StructuredItem::parse("-17"), Ok(StructuredItem::Integer(-17)));
+    /// assert_eq!(StructuredItem::parse("2.50"), Ok(StructuredItem::Decimal(2.5)));
+    /// assert_eq!(StructuredItem::parse("?1"), Ok(StructuredItem::Boolean(true)));
+    ///
+    /// let item = StructuredItem::parse("\"say \\\"hi\\\"\"").unwrap();
+    /// assert_eq!(item.as_str(), Some("say \"hi\""));
+    ///
+    /// assert!(StructuredItem::parse("1; a=2").is_err());
+    /// ```
+    pub fn parse(value: &str) -> Result<StructuredItem, StructuredFieldError> {
+        let mut parser = Parser::new(value);
+        parser.skip(|b| b == b' ');
+        let item = parser.item()?;
+        parser.skip(|b| b == b' ');
+        parser.end()?;
+        Ok(item)
+    }
+
+    /// Serializes `self` as a structured field item, or returns
+    /// [`StructuredFieldError::Unserializable`] if `self` can't be serialized.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::StructuredItem;
+    ///
+    /// let item = StructuredItem::Decimal(3.14159);
+    /// assert_eq!(item.serialize().unwrap(), "3.142");
+    ///
+    /// let item = StructuredItem::String("a \"quote\"".into());
+    /// assert_eq!(item.serialize().unwrap(), "\"a \\\"quote\\\"\"");
+    ///
+    /// assert!(StructuredItem::String("a;b".into()).serialize().is_err());
+    /// assert!(StructuredItem::Integer(i64::MAX).serialize().is_err());
+    /// ```
+    pub fn serialize(&self) -> Result<String, StructuredFieldError> {
+        let mut out = String::new();
+        self.serialize_to(&mut out)?;
+        Ok(out)
+    }
+
+    /// Returns the value of `self` if it's an integer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::StructuredItem;
+    ///
+    /// assert_eq!(StructuredItem::Integer(7).as_integer(), Some(7));
+    /// assert_eq!(StructuredItem::Decimal(7.0).as_integer(), None);
+    /// ```
+    pub fn as_integer(&self) -> Option<i64> {
+        match *self {
+            StructuredItem::Integer(i) => Some(i),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of `self` if it's a decimal or an integer.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::StructuredItem;
+    ///
+    /// assert_eq!(StructuredItem::Decimal(0.5).as_decimal(), Some(0.5));
+    /// assert_eq!(StructuredItem::Integer(7).as_decimal(), Some(7.0));
+    /// ```
+    pub fn as_decimal(&self) -> Option<f64> {
+        match *self {
+            StructuredItem::Decimal(d) => Some(d),
+            StructuredItem::Integer(i) => Some(i as f64),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of `self` if it's a string or a token.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::StructuredItem;
+    ///
+    /// assert_eq!(StructuredItem::String("a b".into()).as_str(), Some("a b"));
+    /// assert_eq!(StructuredItem::Token("gzip".into()).as_str(), Some("gzip"));
+    /// assert_eq!(StructuredItem::Boolean(true).as_str(), None);
+    /// ```
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            StructuredItem::String(ref s) | StructuredItem::Token(ref s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of `self` if it's a byte sequence.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::StructuredItem;
+    ///
+    /// let item = StructuredItem::parse(":AQID:").unwrap();
+    /// assert_eq!(item.as_bytes(), Some(&[1, 2, 3][..]));
+    /// ```
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match *self {
+            StructuredItem::ByteSequence(ref bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of `self` if it's a boolean.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::StructuredItem;
+    ///
+    /// assert_eq!(StructuredItem::Boolean(false).as_bool(), Some(false));
+    /// assert_eq!(StructuredItem::Integer(0).as_bool(), None);
+    /// ```
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            StructuredItem::Boolean(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    fn serialize_to(&self, out: &mut String) -> Result<(), StructuredFieldError> {
+        use std::fmt::Write;
+
+        let unserializable = StructuredFieldError::Unserializable;
+        match *self {
+            StructuredItem::Integer(i) => {
+                if !(-MAX_INTEGER..=MAX_INTEGER).contains(&i) {
+                    return Err(unserializable);
+                }
+
+                write!(out, "{}", i).expect("write to String");
+            }
+            StructuredItem::Decimal(d) => {
+                let rounded = format!("{:.3}", d);
+                let integer = rounded.trim_start_matches('-').split('.').next().unwrap_or("");
+                if !d.is_finite() || integer.len() > MAX_DECIMAL_DIGITS {
+                    return Err(unserializable);
+                }
+
+                // Keep at least one fractional digit, as in `1.0`.
+                let trimmed = rounded.trim_end_matches('0');
+                out.push_str(trimmed);
+                if trimmed.ends_with('.') {
+                    out.push('0');
+                }
+            }
+            StructuredItem::String(ref s) => {
+                if !s.bytes().all(|b| (0x20..=0x7E).contains(&b) && b != b';') {
+                    return Err(unserializable);
+                }
+
+                out.push('"');
+                for c in s.chars() {
+                    if c == '"' || c == '\\' {
+                        out.push('\\');
+                    }
+
+                    out.push(c);
+                }
+
+                out.push('"');
+            }
+            StructuredItem::Token(ref token) => {
+                let mut bytes = token.bytes();
+                let first = matches!(bytes.next(), Some(b) if b.is_ascii_alphabetic() || b == b'*');
+                if !first || !bytes.all(is_token_char) {
+                    return Err(unserializable);
+                }
+
+                out.push_str(token);
+            }
+            StructuredItem::ByteSequence(ref bytes) => {
+                out.push(':');
+                base64_encode(out, bytes);
+                out.push(':');
+            }
+            StructuredItem::Boolean(b) => out.push_str(if b { "?1" } else { "?0" }),
+        }
+
+        Ok(())
+    }
+}
+
+impl StructuredDictionary {
+    /// Creates a new, empty `StructuredDictionary`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::StructuredDictionary;
+    ///
+    /// let dictionary = StructuredDictionary::new();
+    /// assert!(dictionary.is_empty());
+    /// assert_eq!(dictionary.serialize().unwrap(), "");
+    /// ```
+    pub fn new() -> StructuredDictionary {
+        StructuredDictionary::default()
+    }
+
+    /// Parses the structured field dictionary `value`. Whitespace around the
+    /// members is ignored, and if a key appears more than once, the last value
+    /// wins, in the position of the first.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{StructuredDictionary, StructuredItem};
+    ///
+    /// let dictionary = StructuredDictionary::parse("a=1, b, c=:AA==:, a=2").unwrap();
+    /// let keys: Vec<_> = dictionary.iter().map(|(k, _)| k).collect();
+    /// assert_eq!(keys, ["a", "b", "c"]);
+    /// assert_eq!(dictionary.get("a"), Some(&StructuredItem::Integer(2)));
+    /// assert_eq!(dictionary.get("b"), Some(&StructuredItem::Boolean(true)));
+    ///
+    /// assert!(StructuredDictionary::parse("a=1,").is_err());
+    /// assert!(StructuredDictionary::parse("A=1").is_err());
+    /// ```
+    pub fn parse(value: &str) -> Result<StructuredDictionary, StructuredFieldError> {
+        let mut parser = Parser::new(value);
+        let mut dictionary = StructuredDictionary::new();
+        parser.skip(|b| b == b' ');
+        while !parser.is_empty() {
+            let key = parser.key()?;
+            let item = match parser.peek() {
+                Some(b'=') => {
+                    parser.next();
+                    parser.item()?
+                }
+                _ => StructuredItem::Boolean(true),
+            };
+
+            dictionary.insert(key, item);
+            parser.skip(|b| b == b' ' || b == b'\t');
+            if parser.is_empty() {
+                break;
+            }
+
+            match parser.peek() {
+                Some(b',') => parser.next(),
+                _ => return Err(parser.error()),
+            };
+
+            parser.skip(|b| b == b' ' || b == b'\t');
+            if parser.is_empty() {
+                return Err(parser.error());
+            }
+        }
+
+        Ok(dictionary)
+    }
+
+    /// Serializes `self` as a structured field dictionary, or returns
+    /// [`StructuredFieldError::Unserializable`] if `self` can't be serialized.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{StructuredDictionary, StructuredItem};
+    ///
+    /// let mut dictionary = StructuredDictionary::new();
+    /// dictionary.insert("n", StructuredItem::Integer(1));
+    /// dictionary.insert("s", StructuredItem::String("a b".into()));
+    /// dictionary.insert("f", StructuredItem::Boolean(false));
+    /// assert_eq!(dictionary.serialize().unwrap(), "n=1,s=\"a b\",f=?0");
+    ///
+    /// dictionary.insert("Invalid", StructuredItem::Integer(2));
+    /// assert!(dictionary.serialize().is_err());
+    /// ```
+    pub fn serialize(&self) -> Result<String, StructuredFieldError> {
+        let mut out = String::new();
+        for (i, (key, item)) in self.members.iter().enumerate() {
+            if !is_key(key) {
+                return Err(StructuredFieldError::Unserializable);
+            }
+
+            if i > 0 {
+                out.push(',');
+            }
+
+            out.push_str(key);
+            if *item != StructuredItem::Boolean(true) {
+                out.push('=');
+                item.serialize_to(&mut out)?;
+            }
+        }
+
+        Ok(out)
+    }
+
+    /// Returns the item with key `key`, if there is one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{StructuredDictionary, StructuredItem};
+    ///
+    /// let dictionary = StructuredDictionary::parse("a=1").unwrap();
+    /// assert_eq!(dictionary.get("a"), Some(&StructuredItem::Integer(1)));
+    /// assert_eq!(dictionary.get("b"), None);
+    /// ```
+    pub fn get(&self, key: &str) -> Option<&StructuredItem> {
+        self.members.iter().find(|(k, _)| k == key).map(|(_, item)| item)
+    }
+
+    /// Sets the item with key `key` to `item`, returning the previous item, if
+    /// any. A new member is added after all existing members; an existing
+    /// member keeps its position.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{StructuredDictionary, StructuredItem};
+    ///
+    /// let mut dictionary = StructuredDictionary::parse("a=1,b=2").unwrap();
+    /// assert_eq!(dictionary.insert("a", StructuredItem::Integer(3)),
+    ///     Some(StructuredItem::Integer(1)));
+    ///
+    /// assert_eq!(dictionary.insert("c", StructuredItem::Integer(4)), None);
+    /// assert_eq!(dictionary.serialize().unwrap(), "a=3,b=2,c=4");
+    /// ```
+    pub fn insert<K>(&mut self, key: K, item: StructuredItem) -> Option<StructuredItem>
+        where K: Into<String>
+    {
+        let key = key.into();
+        match self.members.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing)) => Some(std::mem::replace(existing, item)),
+            None => {
+                self.members.push((key, item));
+                None
+            }
+        }
+    }
+
+    /// Removes the member with key `key`, returning its item, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::{StructuredDictionary, StructuredItem};
+    ///
+    /// let mut dictionary = StructuredDictionary::parse("a=1,b=2").unwrap();
+    /// assert_eq!(dictionary.remove("a"), Some(StructuredItem::Integer(1)));
+    /// assert_eq!(dictionary.remove("a"), None);
+    /// assert_eq!(dictionary.serialize().unwrap(), "b=2");
+    /// ```
+    pub fn remove(&mut self, key: &str) -> Option<StructuredItem> {
+        let i = self.members.iter().position(|(k, _)| k == key)?;
+        Some(self.members.remove(i).1)
+    }
+
+    /// Returns an iterator over the keys and items of the members, in order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::StructuredDictionary;
+    ///
+    /// let dictionary = StructuredDictionary::parse("b=2,a=1").unwrap();
+    /// let keys: Vec<_> = dictionary.iter().map(|(k, _)| k).collect();
+    /// assert_eq!(keys, ["b", "a"]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &StructuredItem)> {
+        self.members.iter().map(|(k, item)| (k.as_str(), item))
+    }
+
+    /// Returns the number of members.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::StructuredDictionary;
+    ///
+    /// assert_eq!(StructuredDictionary::parse("a=1,b").unwrap().len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    /// Returns `true` if there are no members.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::StructuredDictionary;
+    ///
+    /// assert!(StructuredDictionary::parse("").unwrap().is_empty());
+    /// assert!(!StructuredDictionary::parse("a").unwrap().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+}
+
+/// Returns `true` if `b` may appear in a token after its first character.
+fn is_token_char(b: u8) -> bool {
+    crate::parse::is_token_char(b as char) || b == b':' || b == b'/'
+}
+
+/// Returns `true` if `key` is a valid dictionary key.
+fn is_key(key: &str) -> bool {
+    let mut bytes = key.bytes();
+    matches!(bytes.next(), Some(b) if b.is_ascii_lowercase() || b == b'*')
+        && bytes.all(|b| {
+            b.is_ascii_lowercase() || b.is_ascii_digit() || matches!(b, b'_' | b'-' | b'.' | b'*')
+        })
+}
+
+/// A parser over the bytes of a structured field.
+struct Parser<'a> {
+    bytes: &'a [u8],
+    i: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(s: &'a str) -> Parser<'a> {
+        Parser { bytes: s.as_bytes(), i: 0 }
+    }
+
+    fn error(&self) -> StructuredFieldError {
+        StructuredFieldError::InvalidSyntax { at: self.i }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.i >= self.bytes.len()
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.i).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let b = self.peek()?;
+        self.i += 1;
+        Some(b)
+    }
+
+    fn skip(&mut self, f: impl Fn(u8) -> bool) {
+        while matches!(self.peek(), Some(b) if f(b)) {
+            self.i += 1;
+        }
+    }
+
+    /// Consumes the longest run of bytes satisfying `f` and returns it.
+    fn take(&mut self, f: impl Fn(u8) -> bool) -> &'a str {
+        let start = self.i;
+        self.skip(f);
+        std::str::from_utf8(&self.bytes[start..self.i]).expect("ASCII")
+    }
+
+    fn end(&self) -> Result<(), StructuredFieldError> {
+        match self.is_empty() {
+            true => Ok(()),
+            false => Err(self.error()),
+        }
+    }
+
+    fn key(&mut self) -> Result<String, StructuredFieldError> {
+        match self.peek() {
+            Some(b) if b.is_ascii_lowercase() || b == b'*' => {}
+            _ => return Err(self.error()),
+        }
+
+        let key = self.take(|b| {
+            b.is_ascii_lowercase() || b.is_ascii_digit() || matches!(b, b'_' | b'-' | b'.' | b'*')
+        });
+
+        Ok(key.to_string())
+    }
+
+    fn item(&mut self) -> Result<StructuredItem, StructuredFieldError> {
+        let item = match self.peek() {
+            Some(b'-') | Some(b'0'..=b'9') => self.number()?,
+            Some(b'"') => self.string()?,
+            Some(b':') => self.byte_sequence()?,
+            Some(b'?') => self.boolean()?,
+            Some(b) if b.is_ascii_alphabetic() || b == b'*' => {
+                StructuredItem::Token(self.take(is_token_char).to_string())
+            }
+            Some(b'(') => return Err(StructuredFieldError::Unsupported { at: self.i }),
+            _ => return Err(self.error()),
+        };
+
+        match self.peek() {
+            Some(b';') => Err(StructuredFieldError::Unsupported { at: self.i }),
+            _ => Ok(item),
+        }
+    }
+
+    fn number(&mut self) -> Result<StructuredItem, StructuredFieldError> {
+        let start = self.i;
+        let negative = self.peek() == Some(b'-');
+        if negative {
+            self.next();
+        }
+
+        let integer = self.take(|b| b.is_ascii_digit());
+        if integer.is_empty() || integer.len() > 15 {
+            return Err(StructuredFieldError::InvalidSyntax { at: start });
+        }
+
+        if self.peek() != Some(b'.') {
+            let value: i64 = integer.parse().expect("at most 15 digits");
+            return Ok(StructuredItem::Integer(if negative { -value } else { value }));
+        }
+
+        self.next();
+        let fraction = self.take(|b| b.is_ascii_digit());
+        if integer.len() > MAX_DECIMAL_DIGITS || fraction.is_empty() || fraction.len() > 3 {
+            return Err(StructuredFieldError::InvalidSyntax { at: start });
+        }
+
+        let value: f64 = format!("{}.{}", integer, fraction).parse().expect("decimal");
+        Ok(StructuredItem::Decimal(if negative { -value } else { value }))
+    }
+
+    fn string(&mut self) -> Result<StructuredItem, StructuredFieldError> {
+        let mut string = String::new();
+        self.next();
+        loop {
+            match self.next() {
+                Some(b'"') => return Ok(StructuredItem::String(string)),
+                Some(b'\\') => match self.next() {
+                    Some(b @ b'"') | Some(b @ b'\\') => string.push(b as char),
+                    _ => {
+                        self.i -= 1;
+                        return Err(self.error());
+                    }
+                },
+                Some(b @ 0x20..=0x7E) => string.push(b as char),
+                Some(_) => {
+                    self.i -= 1;
+                    return Err(self.error());
+                }
+                None => return Err(self.error()),
+            }
+        }
+    }
+
+    fn byte_sequence(&mut self) -> Result<StructuredItem, StructuredFieldError> {
+        self.next();
+        let start = self.i;
+        let encoded = self.take(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'='));
+        if self.next() != Some(b':') {
+            self.i = start + encoded.len();
+            return Err(self.error());
+        }
+
+        base64_decode(encoded)
+            .map(StructuredItem::ByteSequence)
+            .ok_or(StructuredFieldError::InvalidSyntax { at: start })
+    }
+
+    fn boolean(&mut self) -> Result<StructuredItem, StructuredFieldError> {
+        self.next();
+        let item = match self.peek() {
+            Some(b'1') => StructuredItem::Boolean(true),
+            Some(b'0') => StructuredItem::Boolean(false),
+            _ => return Err(self.error()),
+        };
+
+        self.next();
+        Ok(item)
+    }
+}
+
+/// Appends `bytes`, encoded as padded, standard base64, to `out`.
+fn base64_encode(out: &mut String, bytes: &[u8]) {
+    encoding::encode_into(out, bytes, Alphabet::Standard, true)
+}
+
+/// Decodes the standard base64 string `s`, with or without padding, or returns
+/// `None` if it isn't valid base64.
+fn base64_decode(s: &str) -> Option<Vec<u8>> {
+    let unpadded = s.trim_end_matches('=');
+    let padding = s.len() - unpadded.len();
+    if padding > 0 && padding != (4 - unpadded.len() % 4) % 4 {
+        return None;
+    }
+
+    encoding::decode(unpadded, Alphabet::Standard)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{base64_decode, base64_encode};
+    use crate::{Cookie, StructuredDictionary, StructuredFieldError, StructuredItem};
+
+    #[test]
+    fn structured_items() {
+        use StructuredItem::*;
+
+        let items = [
+            (Integer(0), "0"),
+            (Integer(-999_999_999_999_999), "-999999999999999"),
+            (Decimal(1.0), "1.0"),
+            (Decimal(-0.125), "-0.125"),
+            (Decimal(999_999_999_999.999), "999999999999.999"),
+            (String("".into()), "\"\""),
+            (String("a \\ \"b\", c".into()), "\"a \\\\ \\\"b\\\", c\""),
+            (Token("*foo/bar:1.0".into()), "*foo/bar:1.0"),
+            (ByteSequence(vec![]), "::"),
+            (ByteSequence(b"f".to_vec()), ":Zg==:"),
+            (ByteSequence(b"fo".to_vec()), ":Zm8=:"),
+            (ByteSequence(b"foo".to_vec()), ":Zm9v:"),
+            (Boolean(true), "?1"),
+            (Boolean(false), "?0"),
+        ];
+
+        for (item, serialized) in items.iter() {
+            assert_eq!(item.serialize().as_deref(), Ok(*serialized));
+            assert_eq!(StructuredItem::parse(serialized).as_ref(), Ok(item));
+
+            // Items roundtrip through cookies, even when written quoted.
+            let cookie = Cookie::parse(format!("name={}; Path=/", serialized)).unwrap();
+            assert_eq!(cookie.structured_item().as_ref(), Ok(item));
+            let cookie = Cookie::new("name", *serialized);
+            assert_eq!(Cookie::parse(cookie.to_string()).unwrap().structured_item().as_ref(), Ok(item));
+        }
+
+        assert_eq!(Decimal(0.0004).serialize().unwrap(), "0.0");
+        assert_eq!(Decimal(2.5).serialize().unwrap(), "2.5");
+        assert_eq!(StructuredItem::parse("  4.50 "), Ok(Decimal(4.5)));
+        assert_eq!(StructuredItem::parse(":Zg:"), Ok(ByteSequence(b"f".to_vec())));
+
+        for unserializable in [
+            Integer(1_000_000_000_000_000),
+            Decimal(1_000_000_000_000.0),
+            Decimal(f64::NAN),
+            String("é".into()),
+            String("\n".into()),
+            String(";".into()),
+            Token("".into()),
+            Token("a b".into()),
+            Token("1a".into()),
+        ].iter() {
+            assert_eq!(unserializable.serialize(), Err(StructuredFieldError::Unserializable));
+        }
+
+        let invalid = |at| Err(StructuredFieldError::InvalidSyntax { at });
+        let unsupported = |at| Err(StructuredFieldError::Unsupported { at });
+        assert_eq!(StructuredItem::parse(""), invalid(0));
+        assert_eq!(StructuredItem::parse("1 2"), invalid(2));
+        assert_eq!(StructuredItem::parse("-"), invalid(0));
+        assert_eq!(StructuredItem::parse("1234567890123456"), invalid(0));
+        assert_eq!(StructuredItem::parse("1.2345"), invalid(0));
+        assert_eq!(StructuredItem::parse("1."), invalid(0));
+        assert_eq!(StructuredItem::parse("\"abc"), invalid(4));
+        assert_eq!(StructuredItem::parse("\"a\\bc\""), invalid(3));
+        assert_eq!(StructuredItem::parse("\"é\""), invalid(1));
+        assert_eq!(StructuredItem::parse(":Zg=:"), invalid(1));
+        assert_eq!(StructuredItem::parse(":Zm9v"), invalid(5));
+        assert_eq!(StructuredItem::parse("?"), invalid(1));
+        assert_eq!(StructuredItem::parse("@"), invalid(0));
+        assert_eq!(StructuredItem::parse("1;a"), unsupported(1));
+        assert_eq!(StructuredItem::parse("(1 2)"), unsupported(0));
+    }
+
+    #[test]
+    fn structured_dictionaries() {
+        use StructuredItem::*;
+
+        let string = "a=1, b=?0,\tc, d=:AQID:,e=\"x y\" , *f=2.5,g=tok";
+        let dictionary = StructuredDictionary::parse(string).unwrap();
+        assert_eq!(dictionary.iter().collect::<Vec<_>>(), [
+            ("a", &Integer(1)), ("b", &Boolean(false)), ("c", &Boolean(true)),
+            ("d", &ByteSequence(vec![1, 2, 3])), ("e", &String("x y".into())),
+            ("*f", &Decimal(2.5)), ("g", &Token("tok".into())),
+        ]);
+
+        let serialized = dictionary.serialize().unwrap();
+        assert_eq!(serialized, "a=1,b=?0,c,d=:AQID:,e=\"x y\",*f=2.5,g=tok");
+        assert_eq!(StructuredDictionary::parse(&serialized), Ok(dictionary.clone()));
+
+        let cookie = Cookie::new("name", serialized);
+        let cookie = Cookie::parse(cookie.to_string()).unwrap();
+        assert_eq!(cookie.structured_dictionary(), Ok(dictionary));
+
+        let invalid = |at| Err(StructuredFieldError::InvalidSyntax { at });
+        assert_eq!(StructuredDictionary::parse("a=1,"), invalid(4));
+        assert_eq!(StructuredDictionary::parse("a=1 b=2"), invalid(4));
+        assert_eq!(StructuredDictionary::parse("a=1,,b"), invalid(4));
+        assert_eq!(StructuredDictionary::parse("1a=1"), invalid(0));
+        assert_eq!(StructuredDictionary::parse("a="), invalid(2));
+        assert_eq!(StructuredDictionary::parse("a=(1)"),
+            Err(StructuredFieldError::Unsupported { at: 2 }));
+
+        let mut dictionary = StructuredDictionary::new();
+        dictionary.insert("a b", Integer(1));
+        assert_eq!(dictionary.serialize(), Err(StructuredFieldError::Unserializable));
+    }
+
+    #[test]
+    fn base64() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for len in 0..bytes.len() {
+            let mut encoded = String::new();
+            base64_encode(&mut encoded, &bytes[..len]);
+            assert_eq!(encoded.len() % 4, 0);
+            assert_eq!(base64_decode(&encoded).as_deref(), Some(&bytes[..len]));
+            assert_eq!(base64_decode(encoded.trim_end_matches('=')).as_deref(), Some(&bytes[..len]));
+        }
+
+        assert_eq!(base64_decode("A"), None);
+        assert_eq!(base64_decode("AA="), None);
+        assert_eq!(base64_decode("A==="), None);
+        assert_eq!(base64_decode("AA-_"), None);
+    }
+}
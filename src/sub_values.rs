@@ -0,0 +1,287 @@
+use std::fmt;
+use std::iter::FromIterator;
+
+/// An ordered map of fields packed into a single cookie value, as in
+/// `name=a=1&b=2&c=3`.
+///
+/// Fields are separated by `&`, and each key is separated from its value by
+/// `=`. When written out, via the [`Display`](fmt::Display) implementation,
+/// keys and values are percent-encoded so that they can contain any character,
+/// including `&`, `=`, and characters not allowed in cookie values. When
+/// parsed, via [`SubValues::parse()`], keys and values are percent-decoded.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{Cookie, SubValues};
+///
+/// let mut fields = SubValues::new();
+/// fields.insert("user", "Sergio Benitez");
+/// fields.insert("theme", "dark&light");
+///
+/// let cookie = Cookie::new("prefs", fields.to_string());
+/// assert_eq!(cookie.value(), "user=Sergio%20Benitez&theme=dark%26light");
+///
+/// let fields = SubValues::parse(cookie.value());
+/// assert_eq!(fields.get("user"), Some("Sergio Benitez"));
+/// assert_eq!(fields.get("theme"), Some("dark&light"));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct SubValues {
+    fields: Vec<(String, String)>,
+}
+
+impl SubValues {
+    /// Creates a new, empty `SubValues`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::SubValues;
+    ///
+    /// let fields = SubValues::new();
+    /// assert!(fields.is_empty());
+    /// assert_eq!(fields.to_string(), "");
+    /// ```
+    pub fn new() -> SubValues {
+        SubValues::default()
+    }
+
+    /// Parses the fields in the cookie value `value`, percent-decoding each
+    /// key and value. Parsing never fails: empty fields are skipped, a field
+    /// without an `=` has an empty value, malformed percent escapes are kept
+    /// as-is, and if a key appears more than once, the first field wins.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::SubValues;
+    ///
+    /// let fields = SubValues::parse("a=1&&b&c=x%3Dy&a=2");
+    /// let pairs: Vec<_> = fields.iter().collect();
+    /// assert_eq!(pairs, [("a", "1"), ("b", ""), ("c", "x=y")]);
+    /// ```
+    pub fn parse(value: &str) -> SubValues {
+        value.split('&')
+            .filter(|field| !field.is_empty())
+            .map(|field| match field.find('=') {
+                Some(i) => (decode(&field[..i]), decode(&field[(i + 1)..])),
+                None => (decode(field), String::new()),
+            })
+            .collect()
+    }
+
+    /// Returns the value of the field with key `key`, if there is one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::SubValues;
+    ///
+    /// let fields = SubValues::parse("a=1&b=2");
+    /// assert_eq!(fields.get("b"), Some("2"));
+    /// assert_eq!(fields.get("c"), None);
+    /// ```
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    /// Sets the value of the field with key `key` to `value`, returning the
+    /// previous value, if any. A new field is added after all existing fields;
+    /// an existing field keeps its position.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::SubValues;
+    ///
+    /// let mut fields = SubValues::parse("a=1&b=2");
+    /// assert_eq!(fields.insert("a", "3"), Some("1".to_string()));
+    /// assert_eq!(fields.insert("c", "4"), None);
+    /// assert_eq!(fields.to_string(), "a=3&b=2&c=4");
+    /// ```
+    pub fn insert<K, V>(&mut self, key: K, value: V) -> Option<String>
+        where K: Into<String>, V: Into<String>
+    {
+        let (key, value) = (key.into(), value.into());
+        match self.fields.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => Some(std::mem::replace(v, value)),
+            None => {
+                self.fields.push((key, value));
+                None
+            }
+        }
+    }
+
+    /// Removes the field with key `key`, returning its value, if any.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::SubValues;
+    ///
+    /// let mut fields = SubValues::parse("a=1&b=2");
+    /// assert_eq!(fields.remove("a"), Some("1".to_string()));
+    /// assert_eq!(fields.remove("a"), None);
+    /// assert_eq!(fields.to_string(), "b=2");
+    /// ```
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        let i = self.fields.iter().position(|(k, _)| k == key)?;
+        Some(self.fields.remove(i).1)
+    }
+
+    /// Returns an iterator over the keys and values of the fields, in order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::SubValues;
+    ///
+    /// let fields = SubValues::parse("b=2&a=1");
+    /// let keys: Vec<_> = fields.iter().map(|(k, _)| k).collect();
+    /// assert_eq!(keys, ["b", "a"]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.fields.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Returns the number of fields.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::SubValues;
+    ///
+    /// assert_eq!(SubValues::parse("a=1&b=2").len(), 2);
+    /// ```
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Returns `true` if there are no fields.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use cookie::SubValues;
+    ///
+    /// assert!(SubValues::parse("").is_empty());
+    /// assert!(!SubValues::parse("a").is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for SubValues {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut fields = SubValues::new();
+        for (key, value) in iter {
+            let key = key.into();
+            if fields.get(&key).is_none() {
+                fields.fields.push((key, value.into()));
+            }
+        }
+
+        fields
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> Extend<(K, V)> for SubValues {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+/// Writes the fields, percent-encoded, separated by `&`.
+impl fmt::Display for SubValues {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (key, value)) in self.fields.iter().enumerate() {
+            if i > 0 {
+                f.write_str("&")?;
+            }
+
+            encode(f, key)?;
+            f.write_str("=")?;
+            encode(f, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns `true` if the byte `b` is written as-is: it's a `cookie-octet`, as
+/// defined in RFC 6265, other than the field separators and `%` and `+`.
+fn is_unreserved(b: u8) -> bool {
+    matches!(b, 0x21 | 0x23..=0x2B | 0x2D..=0x3A | 0x3C..=0x5B | 0x5D..=0x7E)
+        && !matches!(b, b'&' | b'=' | b'%' | b'+')
+}
+
+/// Writes `s` to `f`, percent-encoding every byte that isn't unreserved.
+fn encode(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    for &b in s.as_bytes() {
+        match is_unreserved(b) {
+            true => f.write_str(std::str::from_utf8(&[b]).expect("ASCII"))?,
+            false => write!(f, "%{:02X}", b)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Percent-decodes `s`, keeping malformed escapes as-is and replacing invalid
+/// UTF-8 in the result with U+FFFD.
+fn decode(s: &str) -> String {
+    let (bytes, mut decoded) = (s.as_bytes(), Vec::with_capacity(s.len()));
+    let mut i = 0;
+    while i < bytes.len() {
+        let escape = bytes.get((i + 1)..(i + 3))
+            .filter(|hex| bytes[i] == b'%' && hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match escape {
+            Some(b) => { decoded.push(b); i += 3; }
+            None => { decoded.push(bytes[i]); i += 1; }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Cookie, SubValues};
+
+    #[test]
+    fn sub_values() {
+        let fields = SubValues::parse("a=1&b=2&c=3");
+        assert_eq!(fields.iter().collect::<Vec<_>>(), [("a", "1"), ("b", "2"), ("c", "3")]);
+        assert_eq!(fields.to_string(), "a=1&b=2&c=3");
+
+        // Every key and value roundtrips, and the encoding is a cookie value.
+        let tricky = ["", " ", "a&b", "a=b", "100%", "1+1", "\"q\"", "a;b,c\\d", "日本", "%zz"];
+        let fields: SubValues = tricky.iter().map(|s| (*s, *s)).collect();
+        assert_eq!(fields.len(), tricky.len());
+        let value = fields.to_string();
+        assert!(Cookie::parse_strict(format!("name={}", value)).is_ok());
+        assert_eq!(SubValues::parse(&value), fields);
+        assert_eq!(fields.get("日本"), Some("日本"));
+        assert_eq!(fields.get("%zz"), Some("%zz"));
+
+        // Parsing is lenient.
+        let fields = SubValues::parse("&a=1=2&&b&=c&a=3&d=%4&e=%e6%97%a5&f=%FF&g=%+5&");
+        assert_eq!(fields.iter().collect::<Vec<_>>(), [
+            ("a", "1=2"), ("b", ""), ("", "c"), ("d", "%4"), ("e", "日"), ("f", "\u{FFFD}"),
+            ("g", "%+5"),
+        ]);
+
+        let mut fields = SubValues::new();
+        fields.extend(vec![("x", "1"), ("y", "2"), ("x", "3")]);
+        assert_eq!(fields.to_string(), "x=3&y=2");
+        assert_eq!(fields.remove("x"), Some("3".into()));
+        assert_eq!(fields.to_string(), "y=2");
+    }
+}
@@ -0,0 +1,327 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::Attribute;
+use crate::parse::is_token_char;
+
+/// Error returned when a cookie would be rejected by browsers.
+///
+/// See [`Cookie::validate()`](crate::Cookie::validate()) for details.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{Cookie, SameSite, ValidationError};
+///
+/// let c = Cookie::build("name", "value")
+///     .same_site(SameSite::None)
+///     .secure(false)
+///     .finish();
+///
+/// assert_eq!(c.validate(), Err(ValidationError::InsecureSameSiteNone));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum ValidationError {
+    /// The cookie is `SameSite=None` but was explicitly marked as not
+    /// `Secure`. Browsers drop such cookies.
+    InsecureSameSiteNone,
+    /// The cookie is `Partitioned` but was explicitly marked as not `Secure`.
+    /// Browsers drop such cookies.
+    InsecurePartitioned,
+    /// The cookie's name starts with `__Secure-` but the cookie isn't `Secure`.
+    InvalidSecurePrefix,
+    /// The cookie's name starts with `__Host-` but the cookie isn't `Secure`,
+    /// its `Path` isn't `/`, or it has a `Domain`.
+    InvalidHostPrefix,
+}
+
+impl ValidationError {
+    /// Returns a description of this error as a string.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            ValidationError::InsecureSameSiteNone => "`SameSite=None` cookie is not `Secure`",
+            ValidationError::InsecurePartitioned => "`Partitioned` cookie is not `Secure`",
+            ValidationError::InvalidSecurePrefix => {
+                "`__Secure-` prefixed cookie is not `Secure`"
+            }
+            ValidationError::InvalidHostPrefix => {
+                "`__Host-` prefixed cookie is not `Secure`, has a `Domain`, or has a `Path` \
+                    other than `/`"
+            }
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Error for ValidationError {}
+
+/// Error returned when a cookie name isn't an RFC 6265 `token`.
+///
+/// See [`Cookie::try_new()`](crate::Cookie::try_new()) for details.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{Cookie, NameError};
+///
+/// assert_eq!(Cookie::try_new("", "value").unwrap_err(), NameError::Empty);
+///
+/// let error = Cookie::try_new("my name", "value").unwrap_err();
+/// assert_eq!(error, NameError::InvalidCharacter(' ', 2));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum NameError {
+    /// The name is empty.
+    Empty,
+    /// The name contains the character, at the byte index, that isn't allowed
+    /// in a `token`: a separator, whitespace, a control character, or a
+    /// non-ASCII character.
+    InvalidCharacter(char, usize),
+}
+
+impl NameError {
+    /// Checks that `name` is a `token`, returning the `NameError` if it isn't.
+    pub(crate) fn check(name: &str) -> Result<(), NameError> {
+        if name.is_empty() {
+            return Err(NameError::Empty);
+        }
+
+        match name.char_indices().find(|&(_, c)| !is_token_char(c)) {
+            Some((i, c)) => Err(NameError::InvalidCharacter(c, i)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for NameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            NameError::Empty => write!(f, "cookie name is empty"),
+            NameError::InvalidCharacter(c, i) => {
+                write!(f, "cookie name contains invalid character {:?} at index {}", c, i)
+            }
+        }
+    }
+}
+
+impl Error for NameError {}
+
+/// Error returned when a cookie can't be written out as a well-formed
+/// `Set-Cookie` header value.
+///
+/// See [`Cookie::to_string_checked()`](crate::Cookie::to_string_checked())
+/// for details.
+///
+/// # Example
+///
+/// ```rust
+/// use cookie::{Attribute, Cookie, FormatError};
+///
+/// let error = Cookie::new("name", "a;b").to_string_checked().unwrap_err();
+/// assert_eq!(error, FormatError::InvalidValue(';', 1));
+///
+/// let c = Cookie::build("name", "value").path("/\r\nX: y").finish();
+/// let error = c.to_string_checked().unwrap_err();
+/// assert_eq!(error, FormatError::InvalidAttribute(Attribute::Path, '\r'));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum FormatError {
+    /// The name isn't a valid cookie name.
+    InvalidName(NameError),
+    /// The value contains the character, at the byte index, that can't be
+    /// written out unencoded: a control character, a `;`, or, in a quoted
+    /// value, a `"`.
+    InvalidValue(char, usize),
+    /// The value of the attribute contains the character, a control character
+    /// or a `;`, that can't be written out. The name of an unknown attribute
+    /// also can't contain a `=`.
+    InvalidAttribute(Attribute, char),
+}
+
+impl FormatError {
+    /// Checks that `value`, quoted if `quoted`, can be written out.
+    pub(crate) fn check_value(value: &str, quoted: bool) -> Result<(), FormatError> {
+        let invalid = |c: char| c.is_control() || c == ';' || (quoted && c == '"');
+        match value.char_indices().find(|&(_, c)| invalid(c)) {
+            Some((i, c)) => Err(FormatError::InvalidValue(c, i)),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks that the value `value` of `attribute` can be written out.
+    pub(crate) fn check_attribute(attribute: Attribute, value: &str) -> Result<(), FormatError> {
+        match value.chars().find(|&c| c.is_control() || c == ';') {
+            Some(c) => Err(FormatError::InvalidAttribute(attribute, c)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl From<NameError> for FormatError {
+    fn from(error: NameError) -> Self {
+        FormatError::InvalidName(error)
+    }
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FormatError::InvalidName(ref e) => e.fmt(f),
+            FormatError::InvalidValue(c, i) => {
+                write!(f, "cookie value contains invalid character {:?} at index {}", c, i)
+            }
+            FormatError::InvalidAttribute(attribute, c) => {
+                write!(f, "cookie attribute {:?} contains invalid character {:?}", attribute, c)
+            }
+        }
+    }
+}
+
+impl Error for FormatError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FormatError::InvalidName(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Attribute, Cookie, FormatError, NameError, SameSite, ValidationError};
+
+    #[test]
+    fn validation() {
+        // `Secure` is written out unless explicitly disabled.
+        let mut cookie = Cookie::build("name", "value").same_site(SameSite::None).finish();
+        assert!(cookie.validate().is_ok());
+
+        cookie.set_secure(false);
+        assert_eq!(cookie.validate(), Err(ValidationError::InsecureSameSiteNone));
+        assert_eq!(cookie.to_string(), "name=value; SameSite=None");
+
+        cookie.set_partitioned(true);
+        assert_eq!(cookie.validate(), Err(ValidationError::InsecureSameSiteNone));
+        cookie.set_same_site(SameSite::Lax);
+        assert_eq!(cookie.validate(), Err(ValidationError::InsecurePartitioned));
+
+        cookie.set_secure(None);
+        assert!(cookie.validate().is_ok());
+        assert_eq!(cookie.to_string(), "name=value; SameSite=Lax; Secure; Partitioned");
+
+        let parsed = Cookie::parse("name=value; SameSite=None").unwrap();
+        assert!(parsed.validate().is_ok());
+        assert!(Cookie::build("name", "value").secure(false).finish().validate().is_ok());
+
+        let error = Cookie::build("name", "value")
+            .same_site(SameSite::None)
+            .secure(false)
+            .finish_checked()
+            .unwrap_err();
+
+        assert_eq!(error.to_string(), "`SameSite=None` cookie is not `Secure`");
+    }
+
+    #[test]
+    fn prefix_validation() {
+        let cookie = Cookie::build("name", "value").host_prefix().finish();
+        assert_eq!(cookie.to_string(), "__Host-name=value; Secure; Path=/");
+        assert!(cookie.validate().is_ok());
+
+        let cookie = Cookie::build("__Host-name", "value").host_prefix().finish();
+        assert_eq!(cookie.name(), "__Host-name");
+
+        let cookie = Cookie::build("name", "value").domain("a.com").secure_prefix().finish();
+        assert_eq!(cookie.to_string(), "__Secure-name=value; Secure; Domain=a.com");
+        assert!(cookie.validate().is_ok());
+
+        let host = ValidationError::InvalidHostPrefix;
+        let secure = ValidationError::InvalidSecurePrefix;
+        let cases = [
+            ("__Host-a=b; Secure; Path=/", Ok(())),
+            ("__host-a=b; Secure; Path=/", Ok(())),
+            ("__Host-a=b; SameSite=None; Path=/", Ok(())),
+            ("__Host-a=b; Path=/", Err(host)),
+            ("__Host-a=b; Secure", Err(host)),
+            ("__Host-a=b; Secure; Path=/a", Err(host)),
+            ("__Host-a=b; Secure; Path=/; Domain=a.com", Err(host)),
+            ("__Secure-a=b; Secure; Path=/a; Domain=a.com", Ok(())),
+            ("__SECURE-a=b", Err(secure)),
+            ("__Secure-a=b; Partitioned", Ok(())),
+            ("_Host-a=b", Ok(())),
+            ("a=__Host-", Ok(())),
+        ];
+
+        for (string, expected) in cases.iter() {
+            assert_eq!(Cookie::parse(*string).unwrap().validate(), *expected, "{}", string);
+        }
+    }
+
+    #[test]
+    fn name_validation() {
+        assert_eq!(Cookie::try_new("name", "value").unwrap(), Cookie::new("name", "value"));
+        assert!(Cookie::try_new("!#$%&'*+-.^_`|~09azAZ", "").is_ok());
+        assert!(Cookie::try_new("name", "any value; at all").is_ok());
+
+        assert_eq!(Cookie::try_new("", "value"), Err(NameError::Empty));
+        let invalid = ["a b", "a\tb", "a=b", "a;b", "a,b", "a\"b", "a(b)", "a/b", "a\x7fb", "añb"];
+        for name in invalid.iter() {
+            let c = name[1..].chars().next().unwrap();
+            assert_eq!(Cookie::try_new(*name, ""), Err(NameError::InvalidCharacter(c, 1)));
+        }
+
+        let error = Cookie::try_new("a\nb", "").unwrap_err();
+        assert_eq!(error.to_string(), "cookie name contains invalid character '\\n' at index 1");
+    }
+
+    #[test]
+    fn format_validation() {
+        let cookie = Cookie::build("name", "a value, with commas").path("/a b").finish();
+        assert_eq!(cookie.to_string_checked().unwrap(), cookie.to_string());
+
+        let mut cookie = Cookie::new("name", "value");
+        cookie.set_quoted_value("a \"b\"");
+        assert_eq!(cookie.to_string_checked(), Err(FormatError::InvalidValue('"', 2)));
+        cookie.set_value("a \"b\"");
+        assert!(cookie.to_string_checked().is_ok());
+        cookie.set_value("a\r\nSet-Cookie: b=c");
+        assert_eq!(cookie.to_string_checked(), Err(FormatError::InvalidValue('\r', 1)));
+
+        let error = Cookie::new("na me", "value").to_string_checked().unwrap_err();
+        assert_eq!(error, FormatError::InvalidName(NameError::InvalidCharacter(' ', 2)));
+        assert_eq!(error.to_string(), "cookie name contains invalid character ' ' at index 2");
+
+        let extension = |name, value: Option<&'static str>| {
+            Cookie::build("a", "b").extension(name, value).finish()
+        };
+
+        let same_site_other = |value: &str| {
+            Cookie::build("a", "b").same_site(SameSite::Other(value.into())).finish()
+        };
+
+        let cases = [
+            (Cookie::build("a", "b").domain("a.com;x").finish(), Attribute::Domain, ';'),
+            (Cookie::build("a", "b").path("/\n").finish(), Attribute::Path, '\n'),
+            (same_site_other("x;y"), Attribute::SameSite, ';'),
+            (extension("X", Some("\0")), Attribute::Extensions, '\0'),
+            (extension("X=Y", None), Attribute::Extensions, '='),
+            (extension("X;", Some("")), Attribute::Extensions, ';'),
+        ];
+
+        for (cookie, attribute, c) in cases.iter() {
+            let error = cookie.to_string_checked().unwrap_err();
+            assert_eq!(error, FormatError::InvalidAttribute(*attribute, *c), "{}", cookie);
+        }
+
+        let error = FormatError::InvalidAttribute(Attribute::Path, '\n');
+        assert_eq!(error.to_string(), "cookie attribute Path contains invalid character '\\n'");
+    }
+}